@@ -7,19 +7,19 @@ mod tests;
 
 use std::{hash::Hash, ops};
 
-use base_db::CrateId;
+use base_db::{AnchoredPath, CrateId, FileId};
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_expand::{
-    attrs::{collect_attrs, Attr, AttrId, RawAttrs},
-    HirFileId, InFile,
+    attrs::{collect_attrs, Attr, AttrId, AttrInput, RawAttrs},
+    hygiene::Hygiene,
+    name, HirFileId, InFile,
 };
-use itertools::Itertools;
 use la_arena::{ArenaMap, Idx, RawIdx};
 use mbe::DelimiterKind;
 use syntax::{
     ast::{self, HasAttrs, IsString},
-    AstPtr, AstToken, SmolStr, TextRange, TextSize,
+    AstNode, AstPtr, AstToken, SmolStr, TextRange, TextSize,
 };
 use triomphe::Arc;
 
@@ -42,11 +42,98 @@ impl Documentation {
         Documentation(s)
     }
 
+    /// Joins doc strings into one markdown document the way rustdoc does. Every doc string starts
+    /// a new line. Single-line doc strings, like `///` comments, are stripped of their common
+    /// indentation, while multi-line ones are unindented on their own, dropping blank first and
+    /// last lines, and for `/** */` comments a leading column of `*`. Included files are taken
+    /// as they are.
+    ///
+    /// `on_line` is called with each line taken from a doc string that isn't an included file,
+    /// given as the index of the doc string, the line's range in it and the line's range in the
+    /// document.
+    fn assemble(
+        docs: &[DocFragment<'_>],
+        mut on_line: impl FnMut(usize, ops::Range<usize>, ops::Range<usize>),
+    ) -> Option<Documentation> {
+        let indent = docs
+            .iter()
+            .filter_map(|doc| doc.written())
+            .filter(|doc| !doc.contains('\n') && !doc.trim().is_empty())
+            .map(|doc| doc.chars().take_while(|c| c.is_whitespace()).count())
+            .min()
+            .unwrap_or(0);
+        let mut buf = String::new();
+        for (idx, fragment) in docs.iter().enumerate() {
+            match fragment.written() {
+                Some(doc) => {
+                    let lines = if doc.contains('\n') {
+                        let is_block_comment = matches!(fragment, DocFragment::BlockComment(_));
+                        block_doc_lines(doc, is_block_comment)
+                    } else {
+                        vec![unindent_line(doc, 0, indent)]
+                    };
+                    for (i, range) in lines.into_iter().enumerate() {
+                        if i != 0 {
+                            buf.push('\n');
+                        }
+                        let start = buf.len();
+                        buf.push_str(&doc[range.clone()]);
+                        on_line(idx, range, start..buf.len());
+                    }
+                }
+                None => buf.push_str(fragment.text().trim_end()),
+            }
+            buf.push('\n');
+        }
+        buf.pop();
+        if buf.is_empty() {
+            None
+        } else {
+            Some(Documentation(buf))
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
 }
 
+/// A doc string [`Documentation::assemble`] joins.
+#[derive(Clone, Copy)]
+enum DocFragment<'a> {
+    /// A `///` comment or a `#[doc = "..."]` attribute.
+    Attr(&'a str),
+    /// A `/** */` comment, whose lines may start with a column of `*`.
+    BlockComment(&'a str),
+    /// A file included with `#[doc = include_str!("path")]`, taken verbatim.
+    Included(&'a str),
+}
+
+impl<'a> DocFragment<'a> {
+    /// The doc string of a doc comment or `#[doc = "..."]` attribute.
+    fn of(attr: &'a Attr) -> Option<DocFragment<'a>> {
+        match attr.input.as_deref()? {
+            AttrInput::Literal(doc) => Some(DocFragment::Attr(doc)),
+            AttrInput::BlockDocComment(doc) => Some(DocFragment::BlockComment(doc)),
+            AttrInput::TokenTree(..) => None,
+        }
+    }
+
+    fn text(self) -> &'a str {
+        match self {
+            DocFragment::Attr(it) | DocFragment::BlockComment(it) | DocFragment::Included(it) => it,
+        }
+    }
+
+    /// The text of the doc string if it is written in the source, not included.
+    fn written(self) -> Option<&'a str> {
+        match self {
+            DocFragment::Included(_) => None,
+            it => Some(it.text()),
+        }
+    }
+}
+
 impl From<Documentation> for String {
     fn from(Documentation(string): Documentation) -> Self {
         string
@@ -70,6 +157,17 @@ impl Attrs {
     pub(crate) fn filter(db: &dyn DefDatabase, krate: CrateId, raw_attrs: RawAttrs) -> Attrs {
         Attrs(raw_attrs.filter(db.upcast(), krate))
     }
+
+    /// Lowers the attributes of `owner` directly from its syntax, for items that have no
+    /// definition to query them through, like `use` items.
+    pub fn from_syntax(
+        db: &dyn DefDatabase,
+        krate: CrateId,
+        owner: &dyn ast::HasAttrs,
+        hygiene: &Hygiene,
+    ) -> Attrs {
+        Attrs::filter(db, krate, RawAttrs::new(db.upcast(), owner, hygiene))
+    }
 }
 
 impl ops::Deref for Attrs {
@@ -218,30 +316,8 @@ impl Attrs {
     }
 
     pub fn docs(&self) -> Option<Documentation> {
-        let docs = self.by_key("doc").attrs().filter_map(|attr| attr.string_value());
-        let indent = doc_indent(self);
-        let mut buf = String::new();
-        for doc in docs {
-            // str::lines doesn't yield anything for the empty string
-            if !doc.is_empty() {
-                buf.extend(Itertools::intersperse(
-                    doc.lines().map(|line| {
-                        line.char_indices()
-                            .nth(indent)
-                            .map_or(line, |(offset, _)| &line[offset..])
-                            .trim_end()
-                    }),
-                    "\n",
-                ));
-            }
-            buf.push('\n');
-        }
-        buf.pop();
-        if buf.is_empty() {
-            None
-        } else {
-            Some(Documentation(buf))
-        }
+        let docs: Vec<_> = self.by_key("doc").attrs().filter_map(DocFragment::of).collect();
+        Documentation::assemble(&docs, |_, _, _| ())
     }
 
     pub fn has_doc_hidden(&self) -> bool {
@@ -468,6 +544,22 @@ impl AttrsWithOwner {
     }
 
     pub fn source_map(&self, db: &dyn DefDatabase) -> AttrSourceMap {
+        let (owner, module_file) = self.syntax_owner(db);
+        let mut map = AttrSourceMap::new(owner.as_ref().map(|node| node as &dyn HasAttrs));
+        if let Some(file) = module_file {
+            map.append_module_inline_attrs(AttrSourceMap::new(
+                file.as_ref().map(|node| node as &dyn HasAttrs),
+            ));
+        }
+        map
+    }
+
+    /// The syntax node the attributes are written on, together with the file of modules declared
+    /// with `mod foo;`, whose attributes follow the ones of the declaration.
+    fn syntax_owner(
+        &self,
+        db: &dyn DefDatabase,
+    ) -> (InFile<ast::AnyHasAttrs>, Option<InFile<ast::AnyHasAttrs>>) {
         let owner = match self.owner {
             AttrDefId::ModuleId(module) => {
                 // Modules can have 2 attribute owners (the `mod x;` item, and the module file itself).
@@ -476,24 +568,22 @@ impl AttrsWithOwner {
                 let mod_data = &def_map[module.local_id];
                 match mod_data.declaration_source(db) {
                     Some(it) => {
-                        let mut map = AttrSourceMap::new(InFile::new(it.file_id, &it.value));
-                        if let InFile { file_id, value: ModuleSource::SourceFile(file) } =
-                            mod_data.definition_source(db)
-                        {
-                            map.append_module_inline_attrs(AttrSourceMap::new(InFile::new(
-                                file_id, &file,
-                            )));
-                        }
-                        return map;
+                        let file = match mod_data.definition_source(db) {
+                            InFile { file_id, value: ModuleSource::SourceFile(file) } => {
+                                Some(InFile::new(file_id, ast::AnyHasAttrs::new(file)))
+                            }
+                            _ => None,
+                        };
+                        return (it.map(ast::AnyHasAttrs::new), file);
                     }
                     None => {
                         let InFile { file_id, value } = mod_data.definition_source(db);
-                        let attrs_owner = match &value {
-                            ModuleSource::SourceFile(file) => file as &dyn ast::HasAttrs,
-                            ModuleSource::Module(module) => module as &dyn ast::HasAttrs,
-                            ModuleSource::BlockExpr(block) => block as &dyn ast::HasAttrs,
+                        let attrs_owner = match value {
+                            ModuleSource::SourceFile(file) => ast::AnyHasAttrs::new(file),
+                            ModuleSource::Module(module) => ast::AnyHasAttrs::new(module),
+                            ModuleSource::BlockExpr(block) => ast::AnyHasAttrs::new(block),
                         };
-                        return AttrSourceMap::new(InFile::new(file_id, attrs_owner));
+                        return (InFile::new(file_id, attrs_owner), None);
                     }
                 }
             }
@@ -547,64 +637,102 @@ impl AttrsWithOwner {
             AttrDefId::ExternBlockId(id) => any_has_attrs(db, id),
         };
 
-        AttrSourceMap::new(owner.as_ref().map(|node| node as &dyn HasAttrs))
+        (owner, None)
+    }
+
+    /// Like [`Attrs::docs`], but also expands `#[doc = include_str!("path")]`, resolving `path`
+    /// relative to the file containing the attribute. Files that can't be loaded are skipped.
+    pub fn docs_with_includes(&self, db: &dyn DefDatabase) -> Option<Documentation> {
+        if self.by_key("doc").attrs().all(|attr| attr.input.is_some()) {
+            return self.docs();
+        }
+        let source_map = self.source_map(db);
+        let docs: Vec<_> = self
+            .by_key("doc")
+            .attrs()
+            .filter_map(|attr| match DocFragment::of(attr) {
+                Some(doc) => Some(Either::Left(doc)),
+                None => included_doc_text(db, source_map.source_of(attr)).map(Either::Right),
+            })
+            .collect();
+        let docs: Vec<_> = docs
+            .iter()
+            .map(|doc| doc.as_ref().either(|it| *it, |it| DocFragment::Included(it)))
+            .collect();
+        Documentation::assemble(&docs, |_, _, _| ())
+    }
+
+    /// The files included by `#[doc = include_str!("path")]` attributes, in the order they are
+    /// written in, for resolving the relative links of their text.
+    pub fn included_doc_files(&self, db: &dyn DefDatabase) -> Vec<FileId> {
+        if self.by_key("doc").attrs().all(|attr| attr.input.is_some()) {
+            return Vec::new();
+        }
+        let source_map = self.source_map(db);
+        self.by_key("doc")
+            .attrs()
+            .filter(|attr| attr.input.is_none())
+            .filter_map(|attr| included_doc_file(db, source_map.source_of(attr)))
+            .map(|(file_id, _)| file_id)
+            .collect()
+    }
+
+    /// Like [`AttrsWithOwner::docs_with_includes`], but also takes the docs of `cfg_attr`s whose
+    /// condition doesn't hold in the current configuration, like
+    /// `#[cfg_attr(feature = "docs", doc = "...")]`, in the order they are written in.
+    pub fn docs_with_inactive_cfg_attrs(&self, db: &dyn DefDatabase) -> Option<Documentation> {
+        let (owner, module_file) = self.syntax_owner(db);
+        let from_syntax = |owner: InFile<ast::AnyHasAttrs>| {
+            RawAttrs::from_attrs_owner(db.upcast(), owner.as_ref().map(|it| it as &dyn HasAttrs))
+        };
+        let mut raw_attrs = from_syntax(owner);
+        if let Some(file) = module_file {
+            raw_attrs = raw_attrs.merge(from_syntax(file));
+        }
+        let is_doc = |attr: &Attr| attr.path.as_ident().map_or(false, |it| *it == name![doc]);
+        let attrs = raw_attrs.filter_keeping_inactive(db.upcast(), self.owner.krate(db), is_doc);
+        AttrsWithOwner { attrs: Attrs(attrs), owner: self.owner }.docs_with_includes(db)
     }
 
     pub fn docs_with_rangemap(
         &self,
         db: &dyn DefDatabase,
     ) -> Option<(Documentation, DocsRangeMap)> {
-        let docs =
-            self.by_key("doc").attrs().filter_map(|attr| attr.string_value().map(|s| (s, attr.id)));
-        let indent = doc_indent(self);
-        let mut buf = String::new();
+        let (ids, docs): (Vec<_>, Vec<_>) = self
+            .by_key("doc")
+            .attrs()
+            .filter_map(|attr| DocFragment::of(attr).map(|doc| (attr.id, doc)))
+            .unzip();
         let mut mapping = Vec::new();
-        for (doc, idx) in docs {
-            if !doc.is_empty() {
-                let mut base_offset = 0;
-                for raw_line in doc.split('\n') {
-                    let line = raw_line.trim_end();
-                    let line_len = line.len();
-                    let (offset, line) = match line.char_indices().nth(indent) {
-                        Some((offset, _)) => (offset, &line[offset..]),
-                        None => (0, line),
-                    };
-                    let buf_offset = buf.len();
-                    buf.push_str(line);
-                    mapping.push((
-                        TextRange::new(buf_offset.try_into().ok()?, buf.len().try_into().ok()?),
-                        idx,
-                        TextRange::at(
-                            (base_offset + offset).try_into().ok()?,
-                            line_len.try_into().ok()?,
-                        ),
-                    ));
-                    buf.push('\n');
-                    base_offset += raw_line.len() + 1;
-                }
-            } else {
-                buf.push('\n');
-            }
-        }
-        buf.pop();
-        if buf.is_empty() {
-            None
-        } else {
-            Some((Documentation(buf), DocsRangeMap { mapping, source_map: self.source_map(db) }))
-        }
+        let docs = Documentation::assemble(&docs, |idx, src, dst| {
+            let (Ok(src_start), Ok(src_end), Ok(dst_start), Ok(dst_end)) = (
+                src.start.try_into(),
+                src.end.try_into(),
+                dst.start.try_into(),
+                dst.end.try_into(),
+            ) else {
+                return;
+            };
+            mapping.push((
+                TextRange::new(dst_start, dst_end),
+                ids[idx],
+                TextRange::new(src_start, src_end),
+            ));
+        })?;
+        Some((docs, DocsRangeMap { mapping, source_map: self.source_map(db) }))
     }
-}
 
-fn doc_indent(attrs: &Attrs) -> usize {
-    attrs
-        .by_key("doc")
-        .attrs()
-        .filter_map(|attr| attr.string_value())
-        .flat_map(|s| s.lines())
-        .filter(|line| !line.chars().all(|c| c.is_whitespace()))
-        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
-        .min()
-        .unwrap_or(0)
+    /// The offset in [`Attrs::docs`] at which the docs written inside the owner, with `//!` or
+    /// `#![doc]`, start. `None` if all docs are written on the owner.
+    pub fn inner_docs_start(&self, db: &dyn DefDatabase) -> Option<TextSize> {
+        let (_, map) = self.docs_with_rangemap(db)?;
+        let (start, ..) =
+            map.mapping.iter().find(|(_, id, _)| match map.source_map.source_of_id(*id).value {
+                Either::Left(attr) => attr.kind().is_inner(),
+                Either::Right(comment) => comment.is_inner(),
+            })?;
+        Some(start.start())
+    }
 }
 
 #[derive(Debug)]
@@ -709,6 +837,103 @@ impl DocsRangeMap {
     }
 }
 
+/// The range of `line`, found at `offset` of its doc string, without trailing whitespace and the
+/// first `indent` characters.
+fn unindent_line(line: &str, offset: usize, indent: usize) -> ops::Range<usize> {
+    let line = line.trim_end();
+    let start = line.char_indices().nth(indent).map_or(0, |(start, _)| start);
+    offset + start..offset + line.len()
+}
+
+/// The ranges of the lines of a multi-line doc string, see [`Documentation::assemble`]. The
+/// column of `*` is only stripped from block comments, rustdoc keeps it in `#[doc]` attributes.
+fn block_doc_lines(doc: &str, is_block_comment: bool) -> Vec<ops::Range<usize>> {
+    let is_blank = |line: &str| line.trim().is_empty();
+    let mut offset = 0;
+    let mut lines: Vec<_> = doc
+        .split('\n')
+        .map(|line| {
+            let res = (offset, line.trim_end());
+            offset += line.len() + 1;
+            res
+        })
+        .collect();
+    if lines.first().map_or(false, |&(_, line)| is_blank(line)) {
+        lines.remove(0);
+    }
+    if lines.last().map_or(false, |&(_, line)| is_blank(line)) {
+        lines.pop();
+    }
+
+    let has_star_column = is_block_comment
+        && lines.iter().any(|&(_, line)| !is_blank(line))
+        && lines.iter().all(|&(_, line)| is_blank(line) || line.trim_start().starts_with('*'));
+    if has_star_column {
+        for (offset, line) in lines.iter_mut().filter(|(_, line)| !is_blank(line)) {
+            let after_star = line.find('*').map_or(0, |it| it + 1);
+            *offset += after_star;
+            *line = &line[after_star..];
+        }
+    }
+
+    let indent = lines
+        .iter()
+        .filter(|&&(_, line)| !is_blank(line))
+        .map(|(_, line)| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|(offset, line)| match is_blank(line) {
+            true => offset..offset,
+            false => unindent_line(line, offset, indent),
+        })
+        .collect()
+}
+
+/// The size of the largest file a `#[doc = include_str!("path")]` attribute includes, larger
+/// ones are replaced by a note saying so.
+const MAX_INCLUDED_DOC_LEN: usize = 1 << 20;
+
+/// Loads the file included by a `#[doc = include_str!("path")]` attribute.
+///
+/// The included text is taken verbatim, so `include_str!`s written in it are not expanded and
+/// files including each other can't make this recurse.
+fn included_doc_text(
+    db: &dyn DefDatabase,
+    attr: InFile<&Either<ast::Attr, ast::Comment>>,
+) -> Option<Arc<str>> {
+    let (file_id, path) = included_doc_file(db, attr)?;
+    let text = db.file_text(file_id);
+    if text.len() > MAX_INCLUDED_DOC_LEN {
+        return Some(Arc::from(format!("*The included file `{path}` is too large to show.*")));
+    }
+    Some(text)
+}
+
+/// Resolves the file included by a `#[doc = include_str!("path")]` attribute, returning it with
+/// the path as written.
+fn included_doc_file(
+    db: &dyn DefDatabase,
+    attr: InFile<&Either<ast::Attr, ast::Comment>>,
+) -> Option<(FileId, String)> {
+    let ast::Expr::MacroExpr(expr) = attr.value.as_ref().left()?.expr()? else { return None };
+    let call = expr.macro_call()?;
+    if call.path()?.segment()?.name_ref()?.text() != "include_str" {
+        return None;
+    }
+    let path = call
+        .token_tree()?
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find_map(ast::String::cast)?;
+    let path = path.value()?;
+    let anchor = attr.file_id.original_file(db.upcast());
+    let file_id = db.resolve_path(AnchoredPath { anchor, path: &path })?;
+    Some((file_id, path.into_owned()))
+}
+
 fn get_doc_string_in_attr(it: &ast::Attr) -> Option<ast::String> {
     match it.expr() {
         // #[doc = lit]
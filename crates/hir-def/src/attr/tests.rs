@@ -1,10 +1,16 @@
-//! This module contains tests for doc-expression parsing.
-//! Currently, it tests `#[doc(hidden)]` and `#[doc(alias)]`.
+//! This module contains tests for doc-expression parsing and documentation assembly.
+//! Currently, it tests `#[doc(hidden)]`, `#[doc(alias)]` and the docs of mixed doc comments and
+//! `#[doc]` attributes.
 
+use expect_test::{expect, Expect};
+use hir_expand::{attrs::RawAttrs, hygiene::Hygiene};
 use mbe::syntax_node_to_token_tree;
 use syntax::{ast, AstNode};
 
-use crate::attr::{DocAtom, DocExpr};
+use crate::{
+    attr::{Attrs, DocAtom, DocExpr},
+    test_db::TestDB,
+};
 
 fn assert_parse_result(input: &str, expected: DocExpr) {
     let (tt, _) = {
@@ -38,3 +44,89 @@ fn test_doc_expr_parser() {
         DocExpr::Alias(["Bar".into(), "Qux".into()].into()),
     );
 }
+
+fn check_docs(ra_fixture: &str, expect: Expect) {
+    let db = TestDB::default();
+    let source_file = ast::SourceFile::parse(ra_fixture).ok().unwrap();
+    let item = source_file.syntax().descendants().find_map(ast::Item::cast).unwrap();
+    let attrs = Attrs(RawAttrs::new(&db, &item, &Hygiene::new_unhygienic()));
+    expect.assert_eq(attrs.docs().as_ref().map_or("", |docs| docs.as_str()));
+}
+
+#[test]
+fn docs_of_line_comments_and_attributes() {
+    check_docs(
+        r#"
+/// Uses [link].
+#[doc = ""]
+#[doc = " [link]: Foo"]
+///
+///     indented code
+struct S;
+"#,
+        expect![[r#"
+            Uses [link].
+
+            [link]: Foo
+
+                indented code"#]],
+    );
+}
+
+#[test]
+fn docs_of_block_comments() {
+    check_docs(
+        r#"
+/// Uses [link].
+/**
+ * Details.
+ *
+ *     indented code
+ */
+/**
+    [link]: Foo
+*/
+struct S;
+"#,
+        expect![[r#"
+            Uses [link].
+            Details.
+
+                indented code
+            [link]: Foo"#]],
+    );
+}
+
+#[test]
+fn docs_of_single_line_block_comment() {
+    check_docs(
+        r#"
+/** Summary. */
+/// More.
+struct S;
+"#,
+        expect![[r#"
+            Summary.
+            More."#]],
+    );
+}
+
+#[test]
+fn docs_of_multi_line_attribute_keep_leading_stars() {
+    check_docs(
+        r#"
+#[doc = "
+ * first
+ * second
+"]
+/**
+ * Details.
+ */
+struct S;
+"#,
+        expect![[r#"
+            * first
+            * second
+            Details."#]],
+    );
+}
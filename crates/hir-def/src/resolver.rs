@@ -399,6 +399,28 @@ impl Resolver {
             .take_macros()
     }
 
+    /// Resolves `name` to a `macro_rules!` defined in the current module or a module containing
+    /// it, wherever in the module it is defined.
+    ///
+    /// Unlike [`Resolver::resolve_path_as_macro`], this ignores the textual scoping of
+    /// `macro_rules!`, like rustdoc does when resolving intra-doc links.
+    pub fn resolve_macro_rules_in_modules(
+        &self,
+        db: &dyn DefDatabase,
+        name: &Name,
+    ) -> Option<MacroId> {
+        let (def_map, module) = self.item_scope();
+        let mut module = def_map.module_id(module);
+        loop {
+            let def_map = module.def_map(db);
+            let macros = def_map[module.local_id].scope.get_legacy_macro(name);
+            if let Some(&mac) = macros.and_then(|it| it.last()) {
+                return Some(mac);
+            }
+            module = def_map.containing_module(module.local_id)?;
+        }
+    }
+
     /// Returns a set of names available in the current scope.
     ///
     /// Note that this is a somewhat fuzzy concept -- internally, the compiler
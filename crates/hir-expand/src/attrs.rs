@@ -45,10 +45,17 @@ impl RawAttrs {
                 Either::Left(attr) => {
                     attr.meta().and_then(|meta| Attr::from_src(db, meta, hygiene, id))
                 }
-                Either::Right(comment) => comment.doc_comment().map(|doc| Attr {
-                    id,
-                    input: Some(Interned::new(AttrInput::Literal(SmolStr::new(doc)))),
-                    path: Interned::new(ModPath::from(crate::name!(doc))),
+                Either::Right(comment) => comment.doc_comment().map(|doc| {
+                    let doc = SmolStr::new(doc);
+                    let input = match comment.kind().shape {
+                        ast::CommentShape::Line => AttrInput::Literal(doc),
+                        ast::CommentShape::Block => AttrInput::BlockDocComment(doc),
+                    };
+                    Attr {
+                        id,
+                        input: Some(Interned::new(input)),
+                        path: Interned::new(ModPath::from(crate::name!(doc))),
+                    }
                 }),
             })
             .collect::<Vec<_>>();
@@ -92,6 +99,17 @@ impl RawAttrs {
     /// Processes `cfg_attr`s, returning the resulting semantic `Attrs`.
     // FIXME: This should return a different type
     pub fn filter(self, db: &dyn ExpandDatabase, krate: CrateId) -> RawAttrs {
+        self.filter_keeping_inactive(db, krate, |_| false)
+    }
+
+    /// Like [`RawAttrs::filter`], but keeps the attributes of `cfg_attr`s whose condition doesn't
+    /// hold that `keep_inactive` accepts.
+    pub fn filter_keeping_inactive(
+        self,
+        db: &dyn ExpandDatabase,
+        krate: CrateId,
+        keep_inactive: impl Fn(&Attr) -> bool,
+    ) -> RawAttrs {
         let has_cfg_attrs = self
             .iter()
             .any(|attr| attr.path.as_ident().map_or(false, |name| *name == crate::name![cfg_attr]));
@@ -135,7 +153,7 @@ impl RawAttrs {
                     let cfg = Subtree { delimiter: subtree.delimiter, token_trees: cfg.to_vec() };
                     let cfg = CfgExpr::parse(&cfg);
                     if cfg_options.check(&cfg) == Some(false) {
-                        smallvec![]
+                        attrs.filter(|attr| keep_inactive(attr)).collect()
                     } else {
                         cov_mark::hit!(cfg_attr_active);
 
@@ -191,6 +209,8 @@ pub struct Attr {
 pub enum AttrInput {
     /// `#[attr = "string"]`
     Literal(SmolStr),
+    /// `/** string */`, a desugared block doc comment, whose lines may start with a column of `*`
+    BlockDocComment(SmolStr),
     /// `#[attr(subtree)]`
     TokenTree(tt::Subtree, mbe::TokenMap),
 }
@@ -198,7 +218,9 @@ pub enum AttrInput {
 impl fmt::Display for AttrInput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            AttrInput::Literal(lit) => write!(f, " = \"{}\"", lit.escape_debug()),
+            AttrInput::Literal(lit) | AttrInput::BlockDocComment(lit) => {
+                write!(f, " = \"{}\"", lit.escape_debug())
+            }
             AttrInput::TokenTree(subtree, _) => subtree.fmt(f),
         }
     }
@@ -248,7 +270,7 @@ impl Attr {
     /// #[path = "string"]
     pub fn string_value(&self) -> Option<&SmolStr> {
         match self.input.as_deref()? {
-            AttrInput::Literal(it) => Some(it),
+            AttrInput::Literal(it) | AttrInput::BlockDocComment(it) => Some(it),
             _ => None,
         }
     }
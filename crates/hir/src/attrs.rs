@@ -4,7 +4,9 @@ use hir_def::{
     attr::{AttrsWithOwner, Documentation},
     item_scope::ItemInNs,
     path::ModPath,
+    per_ns::PerNs,
     resolver::HasResolver,
+    visibility::Visibility,
     AttrDefId, GenericParamId, ModuleDefId,
 };
 use hir_expand::hygiene::Hygiene;
@@ -44,7 +46,7 @@ macro_rules! impl_has_attrs {
             }
             fn docs(self, db: &dyn HirDatabase) -> Option<Documentation> {
                 let def = AttrDefId::$def_id(self.into());
-                db.attrs(def).docs()
+                db.attrs_with_owner(def).docs_with_includes(db.upcast())
             }
             fn resolve_doc_path(self, db: &dyn HirDatabase, link: &str, ns: Option<Namespace>) -> Option<ModuleDef> {
                 let def = AttrDefId::$def_id(self.into());
@@ -161,12 +163,23 @@ fn resolve_doc_path(
         ModPath::from_src(db.upcast(), ast_path, &Hygiene::new_unhygienic())?
     };
 
-    let resolved = resolver.resolve_module_path_in_items(db.upcast(), &modpath);
-    let resolved = if resolved.is_none() {
-        resolver.resolve_module_path_in_trait_assoc_items(db.upcast(), &modpath)?
-    } else {
-        resolved
-    };
+    let mut resolved = resolver.resolve_module_path_in_items(db.upcast(), &modpath);
+    if resolved.is_none() {
+        if let Some(assoc) =
+            resolver.resolve_module_path_in_trait_assoc_items(db.upcast(), &modpath)
+        {
+            resolved = assoc;
+        }
+    }
+    // links to `macro_rules!` resolve wherever they are defined in the module, even after `def`
+    if matches!(ns, None | Some(Namespace::Macros)) && resolved.take_macros().is_none() {
+        let mac = modpath
+            .as_ident()
+            .and_then(|name| resolver.resolve_macro_rules_in_modules(db.upcast(), name));
+        if let Some(mac) = mac {
+            resolved = resolved.or(PerNs::macros(mac, Visibility::Public));
+        }
+    }
     match ns {
         Some(Namespace::Types) => resolved.take_types(),
         Some(Namespace::Values) => resolved.take_values(),
@@ -249,6 +249,11 @@ impl Crate {
         doc_url.map(|s| s.trim_matches('"').trim_end_matches('/').to_owned() + "/")
     }
 
+    /// Whether the crate is `#![no_std]` in its current configuration.
+    pub fn is_no_std(self, db: &dyn HirDatabase) -> bool {
+        db.attrs(AttrDefId::ModuleId(self.root_module(db).into())).by_key("no_std").exists()
+    }
+
     pub fn cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
         db.crate_graph()[self.id].cfg_options.clone()
     }
@@ -331,6 +336,23 @@ impl ModuleDef {
         Some(segments.into_iter().join("::"))
     }
 
+    /// The shortest path to the item from the root of its crate that is public all the way,
+    /// possibly going through re-exports, as recorded in the crate's import map.
+    pub fn public_path(self, db: &dyn HirDatabase) -> Option<Vec<Name>> {
+        self.public_path_in(db, self.module(db)?.krate())
+    }
+
+    /// Like [`ModuleDef::public_path`], but from the root of `krate`, which may re-export the
+    /// item from one of its dependencies.
+    pub fn public_path_in(self, db: &dyn HirDatabase, krate: Crate) -> Option<Vec<Name>> {
+        let item = match self {
+            ModuleDef::Macro(it) => ItemInNs::Macros(it),
+            it => ItemInNs::from(it),
+        };
+        let import_map = db.import_map(krate.into());
+        Some(import_map.path_of(item.into())?.segments.clone())
+    }
+
     pub fn canonical_module_path(
         &self,
         db: &dyn HirDatabase,
@@ -551,6 +573,25 @@ impl Module {
             .collect()
     }
 
+    /// The items of the module disabled by a `#[cfg]` attribute in the current configuration,
+    /// along with the disabling cfg expression.
+    pub fn inactive_items(self, db: &dyn HirDatabase) -> Vec<(InFile<ast::Item>, CfgExpr)> {
+        let def_map = self.id.def_map(db.upcast());
+        def_map
+            .diagnostics()
+            .iter()
+            .filter(|diag| diag.in_module == self.id.local_id)
+            .filter_map(|diag| match &diag.kind {
+                DefDiagnosticKind::UnconfiguredCode { ast, cfg, opts: _ } => {
+                    let node = ast.to_node(db.upcast());
+                    let item = <ast::Item as AstNode>::cast(node.syntax().clone())?;
+                    Some((ast.with_value(item), cfg.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Fills `acc` with the module's diagnostics.
     pub fn diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
         let _p = profile::span("Module::diagnostics").detail(|| {
@@ -2208,6 +2249,13 @@ impl BuiltinType {
         Type::new_for_crate(db.crate_graph().iter().next().unwrap(), TyBuilder::builtin(self.inner))
     }
 
+    pub fn by_name(name: &str) -> Option<BuiltinType> {
+        hir_def::builtin_type::BuiltinType::ALL
+            .iter()
+            .find(|(it, _)| it.to_smol_str() == name)
+            .map(|&(_, inner)| BuiltinType { inner })
+    }
+
     pub fn name(self) -> Name {
         self.inner.as_name()
     }
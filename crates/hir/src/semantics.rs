@@ -33,10 +33,10 @@ use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    Access, Adjust, Adjustment, AutoBorrow, BindingMode, BuiltinAttr, Callable, ConstParam, Crate,
-    DeriveHelper, Field, Function, HasSource, HirFileId, Impl, InFile, Label, LifetimeParam, Local,
-    Macro, Module, ModuleDef, Name, OverloadedDeref, Path, ScopeDef, ToolModule, Trait, Type,
-    TypeAlias, TypeParam, VariantDef,
+    Access, Adjust, Adjustment, Attrs, AutoBorrow, BindingMode, BuiltinAttr, Callable, ConstParam,
+    Crate, DeriveHelper, Field, Function, HasSource, HirFileId, Impl, InFile, Label, LifetimeParam,
+    Local, Macro, Module, ModuleDef, Name, OverloadedDeref, Path, ScopeDef, ToolModule, Trait,
+    Type, TypeAlias, TypeParam, VariantDef,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -475,6 +475,11 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.scope(node)
     }
 
+    /// The attributes, and with that the docs, of a `use` item.
+    pub fn use_attrs(&self, use_: &ast::Use) -> Option<Attrs> {
+        self.imp.use_attrs(use_)
+    }
+
     pub fn scope_at_offset(
         &self,
         node: &SyntaxNode,
@@ -1079,6 +1084,13 @@ impl<'db> SemanticsImpl<'db> {
         Some(Type::new_with_resolver(self.db, &analyze.resolver, ty))
     }
 
+    fn use_attrs(&self, use_: &ast::Use) -> Option<Attrs> {
+        let file_id = self.find_file(use_.syntax()).file_id;
+        let krate = self.scope(use_.syntax())?.krate();
+        let hygiene = hir_expand::hygiene::Hygiene::new(self.db.upcast(), file_id);
+        Some(Attrs::from_syntax(self.db.upcast(), krate.id, use_, &hygiene))
+    }
+
     fn resolve_trait(&self, path: &ast::Path) -> Option<Trait> {
         let analyze = self.analyze(path.syntax())?;
         let hygiene = hir_expand::hygiene::Hygiene::new(self.db.upcast(), analyze.file_id);
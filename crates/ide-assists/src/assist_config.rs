@@ -15,4 +15,6 @@ pub struct AssistConfig {
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
     pub assist_emit_must_use: bool,
+    /// Whether the client can copy text to the clipboard for assists without edits.
+    pub copy_to_clipboard: bool,
 }
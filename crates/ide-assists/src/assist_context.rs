@@ -186,10 +186,12 @@ impl Assists {
         }
 
         let mut trigger_signature_help = false;
+        let mut copy_to_clipboard = None;
         let source_change = if self.resolve.should_resolve(&id) {
             let mut builder = SourceChangeBuilder::new(self.file);
             f(&mut builder);
             trigger_signature_help = builder.trigger_signature_help;
+            copy_to_clipboard = builder.copy_to_clipboard.take();
             Some(builder.finish())
         } else {
             None
@@ -197,7 +199,15 @@ impl Assists {
 
         let label = Label::new(label);
         let group = group.cloned();
-        self.buf.push(Assist { id, label, group, target, source_change, trigger_signature_help });
+        self.buf.push(Assist {
+            id,
+            label,
+            group,
+            target,
+            source_change,
+            trigger_signature_help,
+            copy_to_clipboard,
+        });
         Some(())
     }
 
@@ -0,0 +1,181 @@
+use ide_db::doc_links::{resolve_doc_link, token_as_doc_comment};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_code_span_to_intra_doc_link
+//
+// Turns a code span in documentation naming an item into an intra-doc link to it.
+//
+// ```
+// /// Connects to a `Shard$0`.
+// pub fn connect() {}
+// pub struct Shard;
+// ```
+// ->
+// ```
+// /// Connects to a [`Shard`].
+// pub fn connect() {}
+// pub struct Shard;
+// ```
+pub(crate) fn convert_code_span_to_intra_doc_link(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let doc_comment = ctx.token_at_offset().find_map(|token| token_as_doc_comment(&token))?;
+    let (range, code) = doc_comment.get_code_span_with_descend_at(
+        &ctx.sema,
+        ctx.offset(),
+        |owner, range, code| {
+            resolve_doc_link(ctx.db(), owner, &code)?;
+            Some((range, code))
+        },
+    )?;
+
+    acc.add(
+        AssistId("convert_code_span_to_intra_doc_link", AssistKind::RefactorRewrite),
+        format!("Convert `{code}` to intra-doc link"),
+        range,
+        |builder| {
+            builder.insert(range.start(), "[");
+            builder.insert(range.end(), "]");
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_paths() {
+        check_assist(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Connects to a `gateway::Sh$0ard`.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// Connects to a [`gateway::Shard`].
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_calls() {
+        check_assist(
+            convert_code_span_to_intra_doc_link,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Only valid after `connect()$0` or `Self::start()`.
+    pub fn send(&self) {}
+    pub fn start(&self) {}
+}
+pub fn connect() {}
+"#,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Only valid after [`connect()`] or `Self::start()`.
+    pub fn send(&self) {}
+    pub fn start(&self) {}
+}
+pub fn connect() {}
+"#,
+        );
+        check_assist(
+            convert_code_span_to_intra_doc_link,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Only valid after `connect()` or `Self::start()$0`.
+    pub fn send(&self) {}
+    pub fn start(&self) {}
+}
+pub fn connect() {}
+"#,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Only valid after `connect()` or [`Self::start()`].
+    pub fn send(&self) {}
+    pub fn start(&self) {}
+}
+pub fn connect() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_spans_in_doc_attributes() {
+        check_assist(
+            convert_code_span_to_intra_doc_link,
+            r#"
+#[doc = "Connects to a `$0Shard`."]
+pub fn connect() {}
+pub struct Shard;
+"#,
+            r#"
+#[doc = "Connects to a [`Shard`]."]
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unresolved_code() {
+        check_assist_not_applicable(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Returns `None$0` on failure.
+pub fn connect() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Sends `shard.id + 1$0`.
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_links() {
+        check_assist_not_applicable(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Connects to a [`Shard$0`].
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+        check_assist_not_applicable(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Connects to a [`Shard$0`](Shard).
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+        check_assist_not_applicable(
+            convert_code_span_to_intra_doc_link,
+            r#"
+/// Connects to a $0Shard.
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+}
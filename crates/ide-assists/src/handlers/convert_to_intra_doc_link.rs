@@ -0,0 +1,267 @@
+use std::iter;
+
+use hir::{AsAssocItem, AssocItemContainer};
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::Definition,
+    doc_links::{
+        resolve_doc_html_path, resolve_doc_path_for_def, token_as_doc_comment, DocLinkKind,
+    },
+    RootDatabase,
+};
+use itertools::Itertools;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_to_intra_doc_link
+//
+// Converts a link to a rustdoc HTML page into an intra-doc link.
+//
+// ```
+// /// Connects to a [Shard](gateway/struct.Shard.html$0).
+// pub fn connect() {}
+// pub mod gateway { pub struct Shard; }
+// ```
+// ->
+// ```
+// /// Connects to a [Shard](crate::gateway::Shard).
+// pub fn connect() {}
+// pub mod gateway { pub struct Shard; }
+// ```
+pub(crate) fn convert_to_intra_doc_link(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let doc_comment = ctx.token_at_offset().find_map(|token| token_as_doc_comment(&token))?;
+    let (owner, link) =
+        doc_comment.get_link_with_descend_at(&ctx.sema, ctx.offset(), |owner, _, link| {
+            Some((owner, link))
+        })?;
+    if link.kind != DocLinkKind::Inline || !link.target.split('#').next()?.ends_with(".html") {
+        return None;
+    }
+    let db = ctx.db();
+    let target = resolve_doc_html_path(db, owner, &link.target)?;
+    let path = intra_doc_paths(db, target)?
+        .into_iter()
+        .find(|path| resolve_doc_path_for_def(db, owner, path, None) == Some(target))?;
+
+    let file_text = db.file_text(ctx.file_id());
+    let link_text = file_text.get(link.range.start().into()..link.range.end().into())?;
+    let target_range = link.target_range(link_text)?;
+    acc.add(
+        AssistId("convert_to_intra_doc_link", AssistKind::RefactorRewrite),
+        format!("Convert to intra-doc link `{path}`"),
+        target_range,
+        |builder| builder.replace(target_range, path),
+    )
+}
+
+/// The paths an intra-doc link to `def` can be written as, the name relative to its parent first
+/// and then the path from the crate root.
+fn intra_doc_paths(db: &RootDatabase, def: Definition) -> Option<[String; 2]> {
+    let (item, assoc_name) = match def {
+        Definition::Variant(it) => (Definition::Adt(it.parent_enum(db).into()), Some(it.name(db))),
+        Definition::Field(it) => match it.parent_def(db) {
+            hir::VariantDef::Struct(adt) => (Definition::Adt(adt.into()), Some(it.name(db))),
+            hir::VariantDef::Union(adt) => (Definition::Adt(adt.into()), Some(it.name(db))),
+            hir::VariantDef::Variant(_) => return None,
+        },
+        _ => match def.as_assoc_item(db) {
+            Some(assoc) => {
+                let container = match assoc.container(db) {
+                    AssocItemContainer::Trait(it) => Definition::Trait(it),
+                    AssocItemContainer::Impl(it) => Definition::Adt(it.self_ty(db).as_adt()?),
+                };
+                (container, Some(assoc.name(db)?))
+            }
+            None => (def, None),
+        },
+    };
+
+    let module = match item {
+        Definition::Module(it) => it,
+        _ => item.module(db)?,
+    };
+    let mut path = module
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .filter_map(|it| Some(it.name(db)?.to_string()))
+        .collect_vec();
+    if !matches!(item, Definition::Module(_)) {
+        path.push(item.name(db)?.to_string());
+    }
+
+    let assoc = assoc_name.map(|it| format!("::{it}")).unwrap_or_default();
+    let name = path.last().cloned().unwrap_or_else(|| "crate".to_owned());
+    let full = iter::once("crate".to_owned()).chain(path).join("::");
+    Some([format!("{name}{assoc}"), format!("{full}{assoc}")])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_link_to_item_in_sibling_module() {
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+pub mod client {
+    /// Connects to a [Shard](../gateway/struct.Shard.html$0).
+    pub fn connect() {}
+}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+pub mod client {
+    /// Connects to a [Shard](crate::gateway::Shard).
+    pub fn connect() {}
+}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn prefers_names_in_scope() {
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+/// See [the shard](struct.Shard.html$0).
+pub fn connect() {}
+pub struct Shard;
+"#,
+            r#"
+/// See [the shard](Shard).
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_fragments() {
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn start(&self) {}
+    }
+}
+/// Call [start](gateway/struct.Shard.html#method.start$0) first.
+pub fn connect() {}
+"#,
+            r#"
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn start(&self) {}
+    }
+}
+/// Call [start](crate::gateway::Shard::start) first.
+pub fn connect() {}
+"#,
+        );
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+pub enum Event {
+    Ready,
+}
+/// Waits for [ready](enum.Event.html#variant.Ready$0).
+pub fn connect() {}
+"#,
+            r#"
+pub enum Event {
+    Ready,
+}
+/// Waits for [ready](Event::Ready).
+pub fn connect() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_links_to_modules() {
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+pub mod gateway {}
+/// Connects to the [gateway](../gateway/index.html$0).
+pub mod client {}
+"#,
+            r#"
+pub mod gateway {}
+/// Connects to the [gateway](crate::gateway).
+pub mod client {}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_links_relative_to_primitive_pages() {
+        check_assist(
+            convert_to_intra_doc_link,
+            r#"
+mod primitive_docs {
+    /// Iterate over its [chars](str/struct.Chars.html$0).
+    #[rustc_doc_primitive = "str"]
+    mod prim_str {}
+}
+pub mod str {
+    pub struct Chars;
+}
+"#,
+            r#"
+mod primitive_docs {
+    /// Iterate over its [chars](crate::str::Chars).
+    #[rustc_doc_primitive = "str"]
+    mod prim_str {}
+}
+pub mod str {
+    pub struct Chars;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unresolved_pages() {
+        check_assist_not_applicable(
+            convert_to_intra_doc_link,
+            r#"
+/// Connects to a [Shard](gateway/struct.Shard.html$0).
+pub fn connect() {}
+pub mod gateway {
+    pub enum Shard {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_to_intra_doc_link,
+            r#"
+/// Starts the [shard](struct.Shard.html#method.stop$0).
+pub fn connect() {}
+pub struct Shard;
+impl Shard {
+    pub fn start(&self) {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_to_intra_doc_link,
+            r#"
+/// See [the docs](https://docs.rs/foo/struct.Shard.html$0).
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+}
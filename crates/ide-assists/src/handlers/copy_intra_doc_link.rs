@@ -0,0 +1,408 @@
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef, PrefixKind};
+use ide_db::{
+    defs::{Definition, NameClass, NameRefClass},
+    doc_links::{doc_attributes, is_path_like, resolve_doc_path_for_def},
+    helpers::mod_path_to_ast,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, AstToken, SyntaxNode, TextRange, TextSize};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: copy_intra_doc_link
+//
+// Copies an intra-doc link to the item under the cursor, written as it resolves from the docs of
+// the enclosing item, to the clipboard. On code in a doc comment, like `` `Shard` ``, the code is
+// turned into the link instead.
+//
+// ```
+// pub fn connect() {
+//     gateway::Sha$0rd::open();
+// }
+// pub mod gateway {
+//     pub struct Shard;
+//     impl Shard { pub fn open() {} }
+// }
+// ```
+// ->
+// ```
+// [`gateway::Shard`]
+// ```
+pub(crate) fn copy_intra_doc_link(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    if let Some(comment) = ctx.find_token_at_offset::<ast::Comment>() {
+        return insert_intra_doc_link(acc, ctx, comment);
+    }
+    if !ctx.config.copy_to_clipboard {
+        return None;
+    }
+    let (node, target) = if let Some(name_ref) = ctx.find_node_at_offset::<ast::NameRef>() {
+        let target = match NameRefClass::classify(&ctx.sema, &name_ref)? {
+            NameRefClass::Definition(it) => it,
+            NameRefClass::FieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+        };
+        (name_ref.syntax().clone(), target)
+    } else {
+        let name = ctx.find_node_at_offset::<ast::Name>()?;
+        let target = match NameClass::classify(&ctx.sema, &name)? {
+            NameClass::Definition(it) | NameClass::ConstReference(it) => it,
+            NameClass::PatFieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+        };
+        (name.syntax().clone(), target)
+    };
+    let db = ctx.db();
+    let target = match target {
+        Definition::SelfType(it) => Definition::Adt(it.self_ty(db).as_adt()?),
+        _ => target,
+    };
+    let owner = doc_owner(ctx, &node)?;
+    let path = link_path(ctx, owner, target)?;
+
+    let link = format!("[`{path}`]");
+    acc.add(
+        AssistId("copy_intra_doc_link", AssistKind::None),
+        "Copy intra-doc link",
+        node.text_range(),
+        |builder| builder.copy_to_clipboard(link),
+    )
+}
+
+/// Turns the inline code under the cursor in a doc comment into a link, if it names an item.
+fn insert_intra_doc_link(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+    comment: ast::Comment,
+) -> Option<()> {
+    comment.kind().doc?;
+    let text = comment.text();
+    let comment_start = comment.syntax().text_range().start();
+    let offset = usize::from(ctx.offset() - comment_start);
+    let start = text[..offset].rfind('`')?;
+    let end = offset + text[offset..].find('`')?;
+    let code = &text[start + 1..end];
+    // the code must be a whole code span, not be linked already
+    if text[..start].matches('`').count() % 2 != 0
+        || text[..start].ends_with(['`', '['])
+        || text[end + 1..].starts_with(['`', ']'])
+        || !is_path_like(code)
+    {
+        return None;
+    }
+
+    let owner = doc_owner(ctx, comment.syntax().parent().as_ref()?)?;
+    let target = resolve_doc_path_for_def(ctx.db(), owner, code, None)?;
+    let path = link_path(ctx, owner, target)?;
+
+    let range = TextRange::new(
+        comment_start + TextSize::try_from(start).ok()?,
+        comment_start + TextSize::try_from(end + 1).ok()?,
+    );
+    acc.add(
+        AssistId("copy_intra_doc_link", AssistKind::None),
+        "Insert intra-doc link",
+        range,
+        |builder| builder.replace(range, format!("[`{path}`]")),
+    )
+}
+
+/// The shortest path to write a link to `target` with in the docs of `owner`.
+fn link_path(ctx: &AssistContext<'_>, owner: Definition, target: Definition) -> Option<String> {
+    let db = ctx.db();
+    let prefer_self = match owner {
+        Definition::SelfType(_) => true,
+        _ => matches!(
+            owner.as_assoc_item(db).map(|it| it.container(db)),
+            Some(AssocItemContainer::Impl(_))
+        ),
+    };
+
+    let module = match owner {
+        Definition::Module(it) => it,
+        _ => owner.module(db)?,
+    };
+    link_paths(db, module, target, ctx.config.prefer_no_std)
+        .into_iter()
+        .filter(|path| prefer_self || !path.starts_with("Self"))
+        .find(|path| resolve_doc_path_for_def(db, owner, path, None) == Some(target))
+}
+
+/// The nearest item around `node` that can have docs.
+fn doc_owner(ctx: &AssistContext<'_>, node: &SyntaxNode) -> Option<Definition> {
+    node.ancestors().find_map(|it| doc_attributes(&ctx.sema, &it)).map(|(_, owner)| owner)
+}
+
+/// The paths a link to `target` can be written as from `module`, the `Self` forms first and the
+/// path from the crate root last.
+fn link_paths(
+    db: &RootDatabase,
+    module: hir::Module,
+    target: Definition,
+    prefer_no_std: bool,
+) -> Vec<String> {
+    let parent = parent_of(db, target);
+    let mut paths = vec!["Self".to_owned()];
+    if let Some((_, name)) = &parent {
+        paths.push(format!("Self::{name}"));
+    }
+    for prefix in [None, Some(PrefixKind::ByCrate)] {
+        let find_path = |def: ModuleDef| {
+            let path = match prefix {
+                Some(prefix) => module.find_use_path_prefixed(db, def, prefix, prefer_no_std),
+                None => module.find_use_path(db, def, prefer_no_std),
+            };
+            path.map(|it| mod_path_to_ast(&it).to_string())
+        };
+        if let Some(path) = as_module_def(target).and_then(find_path) {
+            paths.push(path);
+        }
+        if let Some((parent, name)) = &parent {
+            if let Some(path) = as_module_def(*parent).and_then(find_path) {
+                paths.push(format!("{path}::{name}"));
+            }
+        }
+    }
+    paths
+}
+
+/// The type, trait or enum variant `def` is an item of, and its name in there.
+fn parent_of(db: &RootDatabase, def: Definition) -> Option<(Definition, hir::Name)> {
+    match def {
+        Definition::Variant(it) => Some((Definition::Adt(it.parent_enum(db).into()), it.name(db))),
+        Definition::Field(it) => match it.parent_def(db) {
+            hir::VariantDef::Struct(adt) => Some((Definition::Adt(adt.into()), it.name(db))),
+            hir::VariantDef::Union(adt) => Some((Definition::Adt(adt.into()), it.name(db))),
+            hir::VariantDef::Variant(variant) => Some((Definition::Variant(variant), it.name(db))),
+        },
+        _ => {
+            let assoc = def.as_assoc_item(db)?;
+            let parent = match assoc.container(db) {
+                AssocItemContainer::Trait(it) => Definition::Trait(it),
+                AssocItemContainer::Impl(it) => Definition::Adt(it.self_ty(db).as_adt()?),
+            };
+            Some((parent, assoc.name(db)?))
+        }
+    }
+}
+
+fn as_module_def(def: Definition) -> Option<ModuleDef> {
+    Some(match def {
+        Definition::Module(it) => ModuleDef::Module(it),
+        Definition::Function(it) => ModuleDef::Function(it),
+        Definition::Adt(it) => ModuleDef::Adt(it),
+        Definition::Variant(it) => ModuleDef::Variant(it),
+        Definition::Const(it) => ModuleDef::Const(it),
+        Definition::Static(it) => ModuleDef::Static(it),
+        Definition::Trait(it) => ModuleDef::Trait(it),
+        Definition::TraitAlias(it) => ModuleDef::TraitAlias(it),
+        Definition::TypeAlias(it) => ModuleDef::TypeAlias(it),
+        Definition::Macro(it) => ModuleDef::Macro(it),
+        Definition::BuiltinType(it) => ModuleDef::BuiltinType(it),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_clipboard, check_assist_not_applicable,
+        check_assist_not_applicable_no_clipboard,
+    };
+
+    use super::*;
+
+    #[test]
+    fn copies_shortest_path_from_the_enclosing_item() {
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub mod client {
+    use crate::gateway;
+
+    pub fn connect() {
+        let _ = gateway::Sha$0rd;
+    }
+}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            "[`gateway::Shard`]",
+        );
+    }
+
+    #[test]
+    fn copies_paths_of_associated_items() {
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub fn connect() {
+    gateway::Shard::op$0en();
+}
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn open() {}
+    }
+}
+"#,
+            "[`gateway::Shard::open`]",
+        );
+    }
+
+    #[test]
+    fn copies_paths_of_variant_fields() {
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub fn connect(event: Event) {
+    let Event::Ready { session$0_id } = event;
+}
+pub enum Event {
+    Ready { session_id: u32 },
+}
+"#,
+            "[`Event::Ready::session_id`]",
+        );
+    }
+
+    #[test]
+    fn prefers_self_in_impls() {
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub struct Shard;
+impl Shard {
+    pub fn open() {}
+    pub fn reconnect() {
+        Shard::op$0en();
+    }
+}
+"#,
+            "[`Self::open`]",
+        );
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub struct Shard;
+impl Shard {
+    pub fn open() -> Sha$0rd { Shard }
+}
+"#,
+            "[`Self`]",
+        );
+    }
+
+    #[test]
+    fn does_not_use_self_outside_impls() {
+        check_assist_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub trait Connect {
+    fn connect(&self) -> Sha$0rd;
+}
+pub struct Shard;
+"#,
+            "[`Shard`]",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_locals() {
+        check_assist_not_applicable(
+            copy_intra_doc_link,
+            r#"
+pub fn connect() {
+    let shard = 92;
+    let _ = sha$0rd;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_clipboard() {
+        check_assist_not_applicable_no_clipboard(
+            copy_intra_doc_link,
+            r#"
+pub fn connect() {
+    let _ = Sha$0rd;
+}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn inserts_links_for_code_in_docs() {
+        check_assist(
+            copy_intra_doc_link,
+            r#"
+pub mod client {
+    use crate::gateway::Shard;
+
+    /// Connects a `Sha$0rd`.
+    pub fn connect() {}
+}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+pub mod client {
+    use crate::gateway::Shard;
+
+    /// Connects a [`Shard`].
+    pub fn connect() {}
+}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+        check_assist(
+            copy_intra_doc_link,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Opens it, see `Shard::re$0connect`.
+    pub fn open() {}
+    pub fn reconnect() {}
+}
+"#,
+            r#"
+pub struct Shard;
+impl Shard {
+    /// Opens it, see [`Self::reconnect`].
+    pub fn open() {}
+    pub fn reconnect() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_linked_or_unresolved_code_in_docs() {
+        check_assist_not_applicable(
+            copy_intra_doc_link,
+            r#"
+/// Connects a [`Sha$0rd`].
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+        check_assist_not_applicable(
+            copy_intra_doc_link,
+            r#"
+/// Connects a `Miss$0ing`.
+pub fn connect() {}
+"#,
+        );
+        check_assist_not_applicable(
+            copy_intra_doc_link,
+            r#"
+/// Connects `a` Sha$0rd `b`.
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+}
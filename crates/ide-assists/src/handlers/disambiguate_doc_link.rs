@@ -0,0 +1,215 @@
+use hir::Adt;
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::Definition,
+    doc_links::{resolve_doc_path_in_all_namespaces, token_as_doc_comment},
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: disambiguate_doc_link
+//
+// If an intra-doc link resolves to items in multiple namespaces, adds a disambiguator picking one
+// of them.
+//
+// ```
+// /// Calls [parse$0].
+// pub fn run() {}
+// pub mod parse {}
+// pub fn parse() {}
+// ```
+// ->
+// ```
+// /// Calls [mod@parse].
+// pub fn run() {}
+// pub mod parse {}
+// pub fn parse() {}
+// ```
+pub(crate) fn disambiguate_doc_link(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let doc_comment = ctx.token_at_offset().find_map(|token| token_as_doc_comment(&token))?;
+    let (owner, link) =
+        doc_comment.get_link_with_descend_at(&ctx.sema, ctx.offset(), |owner, _, link| {
+            Some((owner, link))
+        })?;
+    if link.ns.is_some() {
+        return None;
+    }
+    let db = ctx.db();
+    let resolutions = resolve_doc_path_in_all_namespaces(db, owner, &link.target);
+    if resolutions.len() < 2 {
+        return None;
+    }
+
+    let file_text = db.file_text(ctx.file_id());
+    let link_text = file_text.get(link.range.start().into()..link.range.end().into())?;
+    let target_range = link.target_range(link_text)?;
+
+    let group_label = GroupLabel(format!("Disambiguate doc link `{}`", link.target));
+    for (ns, def) in resolutions {
+        for disambiguator in disambiguators(ns, def) {
+            let (label, insert_at, insert) = match disambiguator {
+                Disambiguator::Prefix(prefix) => (
+                    format!("{prefix}@{}", link.target),
+                    target_range.start(),
+                    format!("{prefix}@"),
+                ),
+                Disambiguator::Suffix(suffix) => {
+                    (format!("{}{suffix}", link.target), target_range.end(), suffix.to_owned())
+                }
+            };
+            acc.add_group(
+                &group_label,
+                AssistId("disambiguate_doc_link", AssistKind::QuickFix),
+                format!("Disambiguate as `{label}`"),
+                target_range,
+                |builder| builder.insert(insert_at, insert),
+            );
+        }
+    }
+    Some(())
+}
+
+enum Disambiguator {
+    /// `kind@name`
+    Prefix(&'static str),
+    /// `name()` or `name!`
+    Suffix(&'static str),
+}
+
+/// The disambiguators rustdoc accepts for `def`, most specific first.
+fn disambiguators(ns: hir::Namespace, def: Definition) -> Vec<Disambiguator> {
+    let prefix = match def {
+        Definition::Module(_) => "mod",
+        Definition::Adt(Adt::Struct(_)) => "struct",
+        Definition::Adt(Adt::Enum(_)) => "enum",
+        Definition::Adt(Adt::Union(_)) => "union",
+        Definition::Trait(_) => "trait",
+        Definition::Function(_) => "fn",
+        Definition::Const(_) => "const",
+        Definition::Static(_) => "static",
+        Definition::Macro(_) => "macro",
+        _ => match ns {
+            hir::Namespace::Types => "type",
+            hir::Namespace::Values => "value",
+            hir::Namespace::Macros => "macro",
+        },
+    };
+    let mut res = vec![Disambiguator::Prefix(prefix)];
+    match def {
+        Definition::Function(_) => res.push(Disambiguator::Suffix("()")),
+        Definition::Macro(_) => res.push(Disambiguator::Suffix("!")),
+        _ => (),
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    const MOD_AND_FN: &str = r#"
+pub mod parse {}
+pub fn parse() {}
+"#;
+
+    #[test]
+    fn disambiguate_module() {
+        check_assist_by_label(
+            disambiguate_doc_link,
+            &format!("/// Calls [parse$0].\npub fn run() {{}}{MOD_AND_FN}"),
+            &format!("/// Calls [mod@parse].\npub fn run() {{}}{MOD_AND_FN}"),
+            "Disambiguate as `mod@parse`",
+        );
+    }
+
+    #[test]
+    fn disambiguate_function() {
+        check_assist_by_label(
+            disambiguate_doc_link,
+            &format!("/// Calls [parse$0].\npub fn run() {{}}{MOD_AND_FN}"),
+            &format!("/// Calls [fn@parse].\npub fn run() {{}}{MOD_AND_FN}"),
+            "Disambiguate as `fn@parse`",
+        );
+        check_assist_by_label(
+            disambiguate_doc_link,
+            &format!("/// Calls [parse$0].\npub fn run() {{}}{MOD_AND_FN}"),
+            &format!("/// Calls [parse()].\npub fn run() {{}}{MOD_AND_FN}"),
+            "Disambiguate as `parse()`",
+        );
+    }
+
+    #[test]
+    fn disambiguate_backticked_link() {
+        check_assist_by_label(
+            disambiguate_doc_link,
+            &format!("/// Calls [`parse$0`].\npub fn run() {{}}{MOD_AND_FN}"),
+            &format!("/// Calls [`mod@parse`].\npub fn run() {{}}{MOD_AND_FN}"),
+            "Disambiguate as `mod@parse`",
+        );
+    }
+
+    #[test]
+    fn disambiguate_inline_link() {
+        check_assist_by_label(
+            disambiguate_doc_link,
+            &format!("/// Calls [the parser](parse$0).\npub fn run() {{}}{MOD_AND_FN}"),
+            &format!("/// Calls [the parser](parse()).\npub fn run() {{}}{MOD_AND_FN}"),
+            "Disambiguate as `parse()`",
+        );
+    }
+
+    #[test]
+    fn disambiguate_macro() {
+        check_assist_by_label(
+            disambiguate_doc_link,
+            r#"
+/// Calls [run$0].
+pub fn run() {}
+#[macro_export]
+macro_rules! run { () => {} }
+"#,
+            r#"
+/// Calls [run!].
+pub fn run() {}
+#[macro_export]
+macro_rules! run { () => {} }
+"#,
+            "Disambiguate as `run!`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_single_namespace() {
+        check_assist_not_applicable(
+            disambiguate_doc_link,
+            r#"
+/// Calls [parse$0].
+pub fn run() {}
+pub fn parse() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_unit_struct() {
+        // unit structs live in both the type and value namespace, but name a single item
+        check_assist_not_applicable(
+            disambiguate_doc_link,
+            r#"
+/// Returns a [Parser$0].
+pub fn run() {}
+pub struct Parser;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_disambiguated() {
+        check_assist_not_applicable(
+            disambiguate_doc_link,
+            &format!("/// Calls [mod@parse$0].\npub fn run() {{}}{MOD_AND_FN}"),
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use hir::{ItemInNs, ModuleDef, PrefixKind};
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::Definition,
+    doc_links::{
+        is_path_like, resolve_doc_path_for_def, token_as_doc_comment, DocLink, DocLinkKind,
+    },
+    helpers::mod_path_to_ast,
+    imports::import_assets::NameToImport,
+    items_locator::{self, AssocItemSearch, DEFAULT_QUERY_SEARCH_LIMIT},
+};
+use itertools::Itertools;
+use syntax::{TextRange, TextSize};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: qualify_doc_link
+//
+// If an intra-doc link is unresolved, provides all possible qualified paths for it.
+//
+// ```
+// /// Connects to a [Shard$0].
+// pub fn connect() {}
+// pub mod gateway { pub struct Shard; }
+// ```
+// ->
+// ```
+// /// Connects to a [Shard](crate::gateway::Shard).
+// pub fn connect() {}
+// pub mod gateway { pub struct Shard; }
+// ```
+pub(crate) fn qualify_doc_link(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let doc_comment = ctx.token_at_offset().find_map(|token| token_as_doc_comment(&token))?;
+    let (owner, link) =
+        doc_comment.get_link_with_descend_at(&ctx.sema, ctx.offset(), |owner, _, link| {
+            Some((owner, link))
+        })?;
+    let db = ctx.db();
+
+    // Shortcut and inline links carry their target in the link itself, for reference links the
+    // target lives in the reference definition which would need to be edited instead.
+    if !matches!(link.kind, DocLinkKind::Shortcut | DocLinkKind::Inline) {
+        return None;
+    }
+    if !is_path_like(&link.target) {
+        return None;
+    }
+    if link.resolve(db, owner).is_some() {
+        return None;
+    }
+
+    let scope = match owner {
+        Definition::Module(module) => module,
+        _ => owner.module(db)?,
+    };
+    let name = link.target.rsplit("::").next()?;
+    let file_text = db.file_text(ctx.file_id());
+    let mut range = link.range;
+    // the range of collapsed links does not include the trailing `[]`
+    if file_text[usize::from(range.end())..].starts_with("[]") {
+        range = TextRange::new(range.start(), range.end() + TextSize::of("[]"));
+    }
+    let link_text = file_text.get(range.start().into()..range.end().into())?;
+
+    let candidates = items_locator::items_with_name(
+        &ctx.sema,
+        scope.krate(),
+        NameToImport::exact_case_sensitive(name.to_owned()),
+        AssocItemSearch::Exclude,
+        Some(DEFAULT_QUERY_SEARCH_LIMIT.inner()),
+    )
+    .filter(|&item| match link.ns {
+        Some(hir::Namespace::Types) => matches!(item, ItemInNs::Types(_)),
+        Some(hir::Namespace::Values) => matches!(item, ItemInNs::Values(_)),
+        Some(hir::Namespace::Macros) => matches!(item, ItemInNs::Macros(_)),
+        None => true,
+    })
+    .filter_map(|item| {
+        let path = scope.find_use_path_prefixed(
+            db,
+            item,
+            PrefixKind::ByCrate,
+            ctx.config.prefer_no_std,
+        )?;
+        let path = mod_path_to_ast(&path).to_string();
+        // only offer paths that actually resolve to the item from the link's scope
+        let def = match item {
+            ItemInNs::Types(it) | ItemInNs::Values(it) => Definition::from(it),
+            ItemInNs::Macros(it) => Definition::from(ModuleDef::Macro(it)),
+        };
+        let resolved = resolve_doc_path_for_def(db, owner, &path, link.ns);
+        (resolved == Some(def) && link.names(db, def)).then_some(path)
+    })
+    .sorted()
+    .dedup()
+    .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let group_label = GroupLabel(format!("Qualify doc link `{}`", link.target));
+    for path in candidates {
+        let Some(replacement) = qualified_link(&link, link_text, &path) else { continue };
+        acc.add_group(
+            &group_label,
+            AssistId("qualify_doc_link", AssistKind::QuickFix),
+            format!("Qualify as `{path}`"),
+            range,
+            |builder| builder.replace(range, replacement),
+        );
+    }
+    Some(())
+}
+
+/// Rewrites the source of `link` to point at `path`, keeping the displayed text as is.
+///
+/// The disambiguator moves to the target, as explicit link text is shown as written: `[fn@f]`
+/// becomes `[f](fn@crate::f)`, while suffixes like in `[f()]` and `[m!]` show in both.
+fn qualified_link(link: &DocLink, link_text: &str, path: &str) -> Option<String> {
+    let (text, target) = match link.kind {
+        // `[text](target)`
+        DocLinkKind::Inline => {
+            let target_start = link_text.rfind("](")?;
+            (&link_text[1..target_start], link_text[target_start + 2..].strip_suffix(')')?)
+        }
+        // `[target]` or `[target][]`
+        DocLinkKind::Shortcut => {
+            let link_text = link_text.strip_suffix("[]").unwrap_or(link_text);
+            let text = link_text.strip_prefix('[')?.strip_suffix(']')?;
+            (text, text)
+        }
+        DocLinkKind::Reference | DocLinkKind::Autolink | DocLinkKind::Definition => return None,
+    };
+    let written = target.trim_matches('`');
+    let start = match written.split_once(['@', ' ']) {
+        Some((prefix, _))
+            if link.ns.is_some() && prefix.bytes().all(|it| it.is_ascii_alphabetic()) =>
+        {
+            prefix.len() + 1
+        }
+        _ => 0,
+    };
+    let start = start + written[start..].find(&*link.target)?;
+    let prefix: String = written[..start].chars().filter(|&it| it != '`').collect();
+    let suffix: String =
+        written[start + link.target.len()..].chars().filter(|&it| it != '`').collect();
+    let text = match link.kind {
+        DocLinkKind::Shortcut => text.replacen(&prefix, "", 1),
+        _ => text.to_owned(),
+    };
+    Some(format!("[{text}]({prefix}{path}{suffix})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn qualify_shortcut_link() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// [Shard$0]
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// [Shard](crate::gateway::Shard)
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_backticked_shortcut_link() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// See [`Shard$0`][].
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// See [`Shard`](crate::gateway::Shard).
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_inline_link_keeps_text() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// Uses a [shard](Sha$0rd) internally.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// Uses a [shard](crate::gateway::Shard) internally.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_from_dependency() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+//- /main.rs crate:main deps:dep
+/// [Shard$0]
+pub fn connect() {}
+//- /dep.rs crate:dep
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// [Shard](dep::gateway::Shard)
+pub fn connect() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_macro_link() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+//- /main.rs crate:main deps:dep
+/// [connect!$0]
+pub fn connect() {}
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! connect { () => {} }
+"#,
+            r#"
+/// [connect!](dep::connect!)
+pub fn connect() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_disambiguated_shortcut_links() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// [struct@Shard$0]
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// [Shard](struct@crate::gateway::Shard)
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// [`fn@open$0`]
+pub fn connect() {}
+pub mod gateway {
+    pub fn open() {}
+}
+"#,
+            r#"
+/// [`open`](fn@crate::gateway::open)
+pub fn connect() {}
+pub mod gateway {
+    pub fn open() {}
+}
+"#,
+        );
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// [open()$0]
+pub fn connect() {}
+pub mod gateway {
+    pub fn open() {}
+}
+"#,
+            r#"
+/// [open()](crate::gateway::open())
+pub fn connect() {}
+pub mod gateway {
+    pub fn open() {}
+}
+"#,
+        );
+        check_assist(
+            qualify_doc_link,
+            r#"
+//- /main.rs crate:main deps:dep
+/// [macro@connect$0]
+pub fn connect() {}
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! connect { () => {} }
+"#,
+            r#"
+/// [connect](macro@dep::connect)
+pub fn connect() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_disambiguated_inline_link() {
+        check_assist(
+            qualify_doc_link,
+            r#"
+/// Uses a [shard](struct@Sha$0rd) internally.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// Uses a [shard](struct@crate::gateway::Shard) internally.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_one_assist_per_candidate() {
+        let fixture = r#"
+/// [Shard$0]
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+pub mod voice {
+    pub struct Shard;
+}
+"#;
+        check_assist_by_label(
+            qualify_doc_link,
+            fixture,
+            r#"
+/// [Shard](crate::gateway::Shard)
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+pub mod voice {
+    pub struct Shard;
+}
+"#,
+            "Qualify as `crate::gateway::Shard`",
+        );
+        check_assist_by_label(
+            qualify_doc_link,
+            fixture,
+            r#"
+/// [Shard](crate::voice::Shard)
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+pub mod voice {
+    pub struct Shard;
+}
+"#,
+            "Qualify as `crate::voice::Shard`",
+        );
+    }
+
+    #[test]
+    fn qualify_respects_namespace() {
+        check_assist_not_applicable(
+            qualify_doc_link,
+            r#"
+/// [fn@Shard$0]
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_resolved_link() {
+        check_assist_not_applicable(
+            qualify_doc_link,
+            r#"
+/// [Shard$0]
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_reference_link() {
+        check_assist_not_applicable(
+            qualify_doc_link,
+            r#"
+/// [the shard][shard$0]
+///
+/// [shard]: Shard
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_urls() {
+        check_assist_not_applicable(
+            qualify_doc_link,
+            r#"
+/// [Shard](struct.Shard.html$0)
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+}
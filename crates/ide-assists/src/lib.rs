@@ -116,6 +116,7 @@ mod handlers {
     mod auto_import;
     mod change_visibility;
     mod convert_bool_then;
+    mod convert_code_span_to_intra_doc_link;
     mod convert_comment_block;
     mod convert_integer_literal;
     mod convert_into_to_from;
@@ -126,10 +127,13 @@ mod handlers {
     mod convert_tuple_struct_to_named_struct;
     mod convert_named_struct_to_tuple_struct;
     mod convert_to_guarded_return;
+    mod convert_to_intra_doc_link;
     mod convert_two_arm_bool_match_to_matches_macro;
     mod convert_while_to_loop;
+    mod copy_intra_doc_link;
     mod desugar_doc_comment;
     mod destructure_tuple_binding;
+    mod disambiguate_doc_link;
     mod expand_glob_import;
     mod extract_expressions_from_format_string;
     mod extract_function;
@@ -177,6 +181,7 @@ mod handlers {
     mod number_representation;
     mod promote_local_to_const;
     mod pull_assignment_up;
+    mod qualify_doc_link;
     mod qualify_path;
     mod qualify_method_call;
     mod raw_string;
@@ -223,6 +228,7 @@ mod handlers {
             change_visibility::change_visibility,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
+            convert_code_span_to_intra_doc_link::convert_code_span_to_intra_doc_link,
             convert_comment_block::convert_comment_block,
             convert_integer_literal::convert_integer_literal,
             convert_into_to_from::convert_into_to_from,
@@ -233,11 +239,14 @@ mod handlers {
             convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
             convert_nested_function_to_closure::convert_nested_function_to_closure,
             convert_to_guarded_return::convert_to_guarded_return,
+            convert_to_intra_doc_link::convert_to_intra_doc_link,
             convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
             convert_two_arm_bool_match_to_matches_macro::convert_two_arm_bool_match_to_matches_macro,
             convert_while_to_loop::convert_while_to_loop,
+            copy_intra_doc_link::copy_intra_doc_link,
             desugar_doc_comment::desugar_doc_comment,
             destructure_tuple_binding::destructure_tuple_binding,
+            disambiguate_doc_link::disambiguate_doc_link,
             expand_glob_import::expand_glob_import,
             extract_expressions_from_format_string::extract_expressions_from_format_string,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
@@ -282,6 +291,7 @@ mod handlers {
             number_representation::reformat_number_literal,
             pull_assignment_up::pull_assignment_up,
             promote_local_to_const::promote_local_to_const,
+            qualify_doc_link::qualify_doc_link,
             qualify_path::qualify_path,
             qualify_method_call::qualify_method_call,
             raw_string::add_hash,
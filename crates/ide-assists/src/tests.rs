@@ -31,6 +31,7 @@ pub(crate) const TEST_CONFIG: AssistConfig = AssistConfig {
     },
     prefer_no_std: false,
     assist_emit_must_use: false,
+    copy_to_clipboard: true,
 };
 
 pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
@@ -45,6 +46,7 @@ pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
     },
     prefer_no_std: false,
     assist_emit_must_use: false,
+    copy_to_clipboard: true,
 };
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
@@ -98,6 +100,18 @@ pub(crate) fn check_assist_not_applicable(assist: Handler, ra_fixture: &str) {
     check(assist, ra_fixture, ExpectedResult::NotApplicable, None);
 }
 
+/// Check the text an assist without edits copies to the clipboard.
+#[track_caller]
+pub(crate) fn check_assist_clipboard(assist: Handler, ra_fixture: &str, text: &str) {
+    check(assist, ra_fixture, ExpectedResult::Clipboard(text), None);
+}
+
+#[track_caller]
+pub(crate) fn check_assist_not_applicable_no_clipboard(assist: Handler, ra_fixture: &str) {
+    let config = AssistConfig { copy_to_clipboard: false, ..TEST_CONFIG };
+    check_with_config(config, assist, ra_fixture, ExpectedResult::NotApplicable, None);
+}
+
 /// Check assist in unresolved state. Useful to check assists for lazy computation.
 #[track_caller]
 pub(crate) fn check_assist_unresolved(assist: Handler, ra_fixture: &str) {
@@ -126,6 +140,10 @@ fn check_doc_test(assist_id: &str, before: &str, after: &str) {
             )
         });
 
+    if let Some(text) = assist.copy_to_clipboard {
+        assert_eq_text!(after.trim_end(), &text);
+        return;
+    }
     let actual = {
         let source_change = assist
             .source_change
@@ -145,6 +163,7 @@ enum ExpectedResult<'a> {
     Unresolved,
     After(&'a str),
     Target(&'a str),
+    Clipboard(&'a str),
 }
 
 #[track_caller]
@@ -229,6 +248,9 @@ fn check_with_config(
             let range = assist.target;
             assert_eq_text!(&text_without_caret[range], target);
         }
+        (Some(assist), ExpectedResult::Clipboard(text)) => {
+            assert_eq_text!(text, assist.copy_to_clipboard.as_deref().unwrap_or_default());
+        }
         (Some(assist), ExpectedResult::Unresolved) => assert!(
             assist.source_change.is_none(),
             "unresolved assist should not contain source changes"
@@ -236,7 +258,10 @@ fn check_with_config(
         (Some(_), ExpectedResult::NotApplicable) => panic!("assist should not be applicable!"),
         (
             None,
-            ExpectedResult::After(_) | ExpectedResult::Target(_) | ExpectedResult::Unresolved,
+            ExpectedResult::After(_)
+            | ExpectedResult::Target(_)
+            | ExpectedResult::Clipboard(_)
+            | ExpectedResult::Unresolved,
         ) => {
             panic!("code action is not applicable")
         }
@@ -270,6 +295,7 @@ fn assist_order_field_struct() {
     let mut assists = assists.iter();
 
     assert_eq!(assists.next().expect("expected assist").label, "Change visibility to pub(crate)");
+    assert_eq!(assists.next().expect("expected assist").label, "Copy intra-doc link");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a mut getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a setter method");
@@ -389,6 +415,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -405,6 +432,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
@@ -435,6 +463,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -451,6 +480,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
@@ -502,6 +532,7 @@ pub fn test_some_range(a: int) -> bool {
                     },
                 ),
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -518,6 +549,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
@@ -561,6 +593,7 @@ pub fn test_some_range(a: int) -> bool {
                     },
                 ),
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -598,6 +631,7 @@ pub fn test_some_range(a: int) -> bool {
                     },
                 ),
                 trigger_signature_help: false,
+                copy_to_clipboard: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
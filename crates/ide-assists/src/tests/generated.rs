@@ -300,6 +300,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_code_span_to_intra_doc_link() {
+    check_doc_test(
+        "convert_code_span_to_intra_doc_link",
+        r#####"
+/// Connects to a `Shard$0`.
+pub fn connect() {}
+pub struct Shard;
+"#####,
+        r#####"
+/// Connects to a [`Shard`].
+pub fn connect() {}
+pub struct Shard;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_with_for_each() {
     check_doc_test(
@@ -543,6 +560,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_to_intra_doc_link() {
+    check_doc_test(
+        "convert_to_intra_doc_link",
+        r#####"
+/// Connects to a [Shard](gateway/struct.Shard.html$0).
+pub fn connect() {}
+pub mod gateway { pub struct Shard; }
+"#####,
+        r#####"
+/// Connects to a [Shard](crate::gateway::Shard).
+pub fn connect() {}
+pub mod gateway { pub struct Shard; }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_tuple_struct_to_named_struct() {
     check_doc_test(
@@ -628,6 +662,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_copy_intra_doc_link() {
+    check_doc_test(
+        "copy_intra_doc_link",
+        r#####"
+pub fn connect() {
+    gateway::Sha$0rd::open();
+}
+pub mod gateway {
+    pub struct Shard;
+    impl Shard { pub fn open() {} }
+}
+"#####,
+        r#####"
+[`gateway::Shard`]
+"#####,
+    )
+}
+
 #[test]
 fn doctest_destructure_tuple_binding() {
     check_doc_test(
@@ -662,6 +715,25 @@ comment"]
     )
 }
 
+#[test]
+fn doctest_disambiguate_doc_link() {
+    check_doc_test(
+        "disambiguate_doc_link",
+        r#####"
+/// Calls [parse$0].
+pub fn run() {}
+pub mod parse {}
+pub fn parse() {}
+"#####,
+        r#####"
+/// Calls [mod@parse].
+pub fn run() {}
+pub mod parse {}
+pub fn parse() {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -1966,6 +2038,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_qualify_doc_link() {
+    check_doc_test(
+        "qualify_doc_link",
+        r#####"
+/// Connects to a [Shard$0].
+pub fn connect() {}
+pub mod gateway { pub struct Shard; }
+"#####,
+        r#####"
+/// Connects to a [Shard](crate::gateway::Shard).
+pub fn connect() {}
+pub mod gateway { pub struct Shard; }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_qualify_method_call() {
     check_doc_test(
@@ -1,6 +1,7 @@
 //! This module defines an accumulator for completions which are going to be presented to user.
 
 pub(crate) mod attribute;
+pub(crate) mod doc_link;
 pub(crate) mod dot;
 pub(crate) mod expr;
 pub(crate) mod extern_abi;
@@ -39,7 +40,8 @@ use crate::{
         literal::{render_struct_literal, render_variant_lit},
         macro_::render_macro,
         pattern::{render_struct_pat, render_variant_pat},
-        render_field, render_path_resolution, render_pattern_resolution, render_tuple_field,
+        render_field, render_path_resolution, render_pattern_resolution, render_resolution_simple,
+        render_tuple_field,
         type_alias::{render_type_alias, render_type_alias_with_eq},
         union_literal::render_union_literal,
         RenderContext,
@@ -194,6 +196,33 @@ impl Completions {
         );
     }
 
+    pub(crate) fn add_doc_link_resolution(
+        &mut self,
+        ctx: &CompletionContext<'_>,
+        local_name: hir::Name,
+        resolution: hir::ScopeDef,
+        doc_aliases: Vec<syntax::SmolStr>,
+    ) {
+        if !ctx.check_stability(resolution.attrs(ctx.db).as_deref()) {
+            return;
+        }
+        let is_private_editable = match ctx.def_is_visible(&resolution) {
+            Visible::Yes => false,
+            Visible::Editable => true,
+            Visible::No => return,
+        };
+        self.add(
+            render_resolution_simple(
+                RenderContext::new(ctx)
+                    .private_editable(is_private_editable)
+                    .doc_aliases(doc_aliases),
+                local_name,
+                resolution,
+            )
+            .build(),
+        );
+    }
+
     pub(crate) fn add_pattern_resolution(
         &mut self,
         ctx: &CompletionContext<'_>,
@@ -1,5 +1,5 @@
 //! Completion for derives
-use hir::{HasAttrs, ScopeDef};
+use hir::ScopeDef;
 use ide_db::SymbolKind;
 use itertools::Itertools;
 use syntax::SmolStr;
@@ -86,9 +86,7 @@ pub(crate) fn complete_derive_path(
                             ctx.source_range(),
                             SmolStr::from_iter(label),
                         );
-                        if let Some(docs) = mac.docs(ctx.db) {
-                            item.documentation(docs);
-                        }
+                        item.docs_of(ctx.db, mac);
                         item.lookup_by(lookup);
                         item.add_to(acc);
                     }
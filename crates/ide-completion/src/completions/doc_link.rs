@@ -0,0 +1,236 @@
+//! Completes paths in the targets of reference link definitions in documentation.
+
+use hir::ScopeDef;
+use ide_db::{
+    defs::Definition,
+    doc_links::{assoc_items_of, resolve_doc_link_path},
+};
+
+use crate::{
+    context::{CompletionContext, DocLinkDefinitionContext},
+    Completions,
+};
+
+/// Completes the path of a reference link definition like `/// [shard]: gateway::$0`.
+///
+/// The names in scope of the docs are offered for the first segment, the items of the module,
+/// enum, type or trait the qualifier resolves to for the others.
+pub(crate) fn complete_doc_link_definition(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    DocLinkDefinitionContext { scope, qualifier }: &DocLinkDefinitionContext,
+) {
+    let Some(qualifier) = qualifier else {
+        ctx.process_all_names(&mut |name, def, doc_aliases| {
+            acc.add_doc_link_resolution(ctx, name, def, doc_aliases)
+        });
+        return;
+    };
+    let Some((qualifier, _)) =
+        resolve_doc_link_path(ctx.db, *scope, qualifier, Some(hir::Namespace::Types))
+    else {
+        return;
+    };
+
+    if let Definition::Module(module) = qualifier {
+        for (name, def) in module.scope(ctx.db, None) {
+            let doc_aliases = ctx.doc_aliases_in_scope(def);
+            acc.add_doc_link_resolution(ctx, name, def, doc_aliases);
+        }
+        return;
+    }
+    if let Definition::Adt(hir::Adt::Enum(e)) = qualifier {
+        for variant in e.variants(ctx.db) {
+            let def = ScopeDef::ModuleDef(variant.into());
+            acc.add_doc_link_resolution(ctx, variant.name(ctx.db), def, Vec::new());
+        }
+    }
+    for item in assoc_items_of(ctx.db, qualifier).unwrap_or_default() {
+        let Some(name) = item.name(ctx.db) else { continue };
+        let def = ScopeDef::ModuleDef(match item {
+            hir::AssocItem::Function(it) => it.into(),
+            hir::AssocItem::Const(it) => it.into(),
+            hir::AssocItem::TypeAlias(it) => it.into(),
+        });
+        acc.add_doc_link_resolution(ctx, name, def, Vec::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list_no_kw};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list_no_kw(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_names_in_scope_of_the_docs() {
+        check(
+            r#"
+/// Connects to a [shard].
+///
+/// [shard]: $0
+pub fn connect() {}
+pub struct Shard;
+mod gateway {}
+"#,
+            expect![[r#"
+                fn connect
+                md gateway
+                st Shard
+                bt u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_items_of_the_qualifier() {
+        check(
+            r#"
+/// [shard]: gateway::Sh$0
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+    pub fn start() {}
+}
+"#,
+            expect![[r#"
+                fn start
+                st Shard
+            "#]],
+        );
+        check(
+            r#"
+/// [start]: fn@crate::Shard::$0
+pub fn connect() {}
+pub enum Shard { Idle }
+impl Shard {
+    pub fn start(&self) {}
+    pub const MAX: u8 = 0;
+}
+"#,
+            expect![[r#"
+                ct MAX
+                fn start
+                ev Idle
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_in_doc_attributes() {
+        check(
+            r#"
+/// Connects to a [shard].
+#[doc = "[shard]: gateway::$0"]
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            expect![[r#"
+                st Shard
+            "#]],
+        );
+        check(
+            r#"
+#[doc = "Connects to a [shard].
+
+  [shard]: gateway::$0"]
+/// More docs.
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            expect![[r#"
+                st Shard
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_in_the_scope_of_module_docs() {
+        check(
+            r#"
+pub struct Shard;
+/// [shard]: $0
+pub mod gateway {
+    pub struct Inner;
+}
+"#,
+            expect![[r#"
+                md gateway
+                st Shard
+                bt u32
+            "#]],
+        );
+        check(
+            r#"
+//- /lib.rs
+pub struct Shard;
+pub mod gateway;
+//- /gateway.rs
+//! [inner]: $0
+pub struct Inner;
+"#,
+            expect![[r#"
+                st Inner
+                bt u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn replaces_the_typed_segment() {
+        check_edit(
+            "Shard",
+            r#"
+/// [shard]: gateway::Sh$0
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// [shard]: gateway::Shard
+pub fn connect() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_completions_outside_of_definition_targets() {
+        check(
+            r#"
+/// See [shard]($0)
+pub fn connect() {}
+pub struct Shard;
+"#,
+            expect![[r#""#]],
+        );
+        check(
+            r#"
+/// [shard]: Shard "the $0"
+pub fn connect() {}
+pub struct Shard;
+"#,
+            expect![[r#""#]],
+        );
+        check(
+            r#"
+// [shard]: Sh$0
+pub fn connect() {}
+pub struct Shard;
+"#,
+            expect![[r#""#]],
+        );
+    }
+}
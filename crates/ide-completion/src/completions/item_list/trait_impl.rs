@@ -194,7 +194,7 @@ fn add_function_impl(
 
     let mut item = CompletionItem::new(completion_kind, replacement_range, label);
     item.lookup_by(format!("fn {fn_name}"))
-        .set_documentation(func.docs(ctx.db))
+        .docs_of(ctx.db, func)
         .set_relevance(CompletionRelevance { is_item_from_trait: true, ..Default::default() });
 
     if let Some(source) = ctx.sema.source(func) {
@@ -256,7 +256,7 @@ fn add_type_alias_impl(
 
     let mut item = CompletionItem::new(SymbolKind::TypeAlias, replacement_range, label);
     item.lookup_by(format!("type {alias_name}"))
-        .set_documentation(type_alias.docs(ctx.db))
+        .docs_of(ctx.db, type_alias)
         .set_relevance(CompletionRelevance { is_item_from_trait: true, ..Default::default() });
 
     if let Some(source) = ctx.sema.source(type_alias) {
@@ -328,7 +328,7 @@ fn add_const_impl(
 
                 let mut item = CompletionItem::new(SymbolKind::Const, replacement_range, label);
                 item.lookup_by(format!("const {const_name}"))
-                    .set_documentation(const_.docs(ctx.db))
+                    .docs_of(ctx.db, const_)
                     .set_relevance(CompletionRelevance {
                         is_item_from_trait: true,
                         ..Default::default()
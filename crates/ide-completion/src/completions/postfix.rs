@@ -2,7 +2,6 @@
 
 mod format_like;
 
-use hir::{Documentation, HasAttrs};
 use ide_db::{imports::insert_use::ImportScope, ty_filter::TryEnum, SnippetCap};
 use syntax::{
     ast::{self, make, AstNode, AstToken},
@@ -63,7 +62,7 @@ pub(crate) fn complete_postfix(
                     "fn drop(&mut self)",
                     &format!("drop($0{receiver_text})"),
                 );
-                item.set_documentation(drop_fn.docs(ctx.db));
+                item.docs_of(ctx.db, drop_fn);
                 item.add_to(acc);
             }
         }
@@ -185,9 +184,18 @@ pub(crate) fn complete_postfix(
         }
     }
 
-    postfix_snippet("box", "Box::new(expr)", &format!("Box::new({receiver_text})")).add_to(acc);
-    postfix_snippet("dbg", "dbg!(expr)", &format!("dbg!({receiver_text})")).add_to(acc); // fixme
-    postfix_snippet("dbgr", "dbg!(&expr)", &format!("dbg!(&{receiver_text})")).add_to(acc);
+    let mut item = postfix_snippet("box", "Box::new(expr)", &format!("Box::new({receiver_text})"));
+    item.documentation(
+        ctx,
+        "Moves the expression to the heap, see [`Box::new`](std::boxed::Box::new).",
+    );
+    item.add_to(acc);
+    let mut item = postfix_snippet("dbg", "dbg!(expr)", &format!("dbg!({receiver_text})")); // fixme
+    item.documentation(ctx, "Prints the expression and its value to stderr, see [`std::dbg!`].");
+    item.add_to(acc);
+    let mut item = postfix_snippet("dbgr", "dbg!(&expr)", &format!("dbg!(&{receiver_text})"));
+    item.documentation(ctx, "Like `dbg`, but not moving the expression, see [`std::dbg!`].");
+    item.add_to(acc);
     postfix_snippet("call", "function(expr)", &format!("${{1}}({receiver_text})")).add_to(acc);
 
     if let Some(parent) = dot_receiver.syntax().parent().and_then(|p| p.parent()) {
@@ -311,7 +319,7 @@ fn add_custom_postfix_completions(
             let body = snippet.postfix_snippet(receiver_text);
             let mut builder =
                 postfix_snippet(trigger, snippet.description.as_deref().unwrap_or_default(), &body);
-            builder.documentation(Documentation::new(format!("```rust\n{body}\n```")));
+            builder.documentation(ctx, format!("```rust\n{body}\n```"));
             for import in imports.into_iter() {
                 builder.add_import(import);
             }
@@ -11,6 +11,8 @@ use hir::{
 };
 use ide_db::{
     base_db::{FilePosition, SourceDatabase},
+    defs::Definition,
+    doc_links::token_as_doc_comment,
     famous_defs::FamousDefs,
     helpers::is_editable_crate,
     FxHashMap, FxHashSet, RootDatabase,
@@ -321,6 +323,19 @@ pub(super) enum CompletionAnalysis {
         colon_prefix: bool,
         fake_attribute_under_caret: Option<ast::Attr>,
     },
+    /// The target of a reference link definition in documentation the cursor is currently in
+    DocLinkDefinition(DocLinkDefinitionContext),
+}
+
+/// The target of a reference link definition in documentation we are completing, like
+/// `[shard]: gateway::$0` on a line of a doc comment or `#[doc]` attribute.
+#[derive(Debug)]
+pub(crate) struct DocLinkDefinitionContext {
+    /// The definition the path resolves in, the owner of the documentation or, for the outer
+    /// docs of a module, its parent.
+    pub(crate) scope: Definition,
+    /// The path before the segment being completed, `gateway` above.
+    pub(crate) qualifier: Option<String>,
 }
 
 /// Information about the field or method access we are completing.
@@ -393,6 +408,22 @@ impl CompletionContext<'_> {
     pub(crate) fn source_range(&self) -> TextRange {
         let kind = self.original_token.kind();
         match kind {
+            // in documentation the segment being typed is part of a comment or string token
+            COMMENT | STRING if token_as_doc_comment(&self.original_token).is_some() => {
+                let token_start = self.original_token.text_range().start();
+                let before_cursor =
+                    &self.original_token.text()[..usize::from(self.position.offset - token_start)];
+                let segment_len: usize = before_cursor
+                    .chars()
+                    .rev()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .map(char::len_utf8)
+                    .sum();
+                TextRange::new(
+                    self.position.offset - TextSize::from(segment_len as u32),
+                    self.position.offset,
+                )
+            }
             CHAR => {
                 // assume we are completing a lifetime but the user has only typed the '
                 cov_mark::hit!(completes_if_lifetime_without_idents);
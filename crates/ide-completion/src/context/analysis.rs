@@ -2,19 +2,24 @@
 use std::iter;
 
 use hir::{Semantics, Type, TypeInfo, Variant};
-use ide_db::{active_parameter::ActiveParameter, RootDatabase};
+use ide_db::{
+    active_parameter::ActiveParameter, defs::Definition, doc_links::doc_attributes, RootDatabase,
+};
 use syntax::{
     algo::{find_node_at_offset, non_trivia_sibling},
-    ast::{self, AttrKind, HasArgList, HasLoopBody, HasName, NameOrNameRef},
+    ast::{
+        self, AttrKind, CommentPlacement, HasArgList, HasLoopBody, HasName, IsString, NameOrNameRef,
+    },
     match_ast, AstNode, AstToken, Direction, NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode,
     SyntaxToken, TextRange, TextSize, T,
 };
 
 use crate::context::{
-    AttrCtx, CompletionAnalysis, DotAccess, DotAccessKind, ExprCtx, ItemListKind, LifetimeContext,
-    LifetimeKind, NameContext, NameKind, NameRefContext, NameRefKind, ParamContext, ParamKind,
-    PathCompletionCtx, PathKind, PatternContext, PatternRefutability, Qualified, QualifierCtx,
-    TypeAscriptionTarget, TypeLocation, COMPLETION_MARKER,
+    AttrCtx, CompletionAnalysis, DocLinkDefinitionContext, DotAccess, DotAccessKind, ExprCtx,
+    ItemListKind, LifetimeContext, LifetimeKind, NameContext, NameKind, NameRefContext,
+    NameRefKind, ParamContext, ParamKind, PathCompletionCtx, PathKind, PatternContext,
+    PatternRefutability, Qualified, QualifierCtx, TypeAscriptionTarget, TypeLocation,
+    COMPLETION_MARKER,
 };
 
 struct ExpansionResult {
@@ -242,11 +247,12 @@ fn analyze(
     }
 
     let Some(name_like) = find_node_at_offset(&speculative_file, offset) else {
-        let analysis = if let Some(original) = ast::String::cast(original_token.clone()) {
-            CompletionAnalysis::String {
-                original,
-                expanded: ast::String::cast(self_token.clone()),
-            }
+        let analysis = if let Some(doc_link_ctx) =
+            classify_doc_link_definition(sema, original_token, &fake_ident_token)
+        {
+            CompletionAnalysis::DocLinkDefinition(doc_link_ctx)
+        } else if let Some(original) = ast::String::cast(original_token.clone()) {
+            CompletionAnalysis::String { original, expanded: ast::String::cast(self_token.clone()) }
         } else {
             // Fix up trailing whitespace problem
             // #[attr(foo = $0
@@ -518,6 +524,58 @@ fn classify_lifetime(
     Some(LifetimeContext { lifetime, kind })
 }
 
+/// Checks whether the cursor is in the target of a reference link definition, like
+/// `/// [shard]: gateway::$0`, in a doc comment or the string of a `#[doc]` attribute.
+///
+/// Only the line up to the cursor is looked at, the target is the path written after the colon.
+fn classify_doc_link_definition(
+    sema: &Semantics<'_, RootDatabase>,
+    original_token: &SyntaxToken,
+    fake_ident_token: &SyntaxToken,
+) -> Option<DocLinkDefinitionContext> {
+    let (contents_start, owner_node, is_inner) = match_ast! {
+        match original_token {
+            ast::Comment(comment) => {
+                let is_inner = comment.kind().doc? == CommentPlacement::Inner;
+                (comment.prefix().len(), original_token.parent()?, is_inner)
+            },
+            ast::String(string) => {
+                let attr = original_token
+                    .parent_ancestors()
+                    .find_map(ast::Attr::cast)
+                    .filter(|attr| attr.simple_name().as_deref() == Some("doc"))?;
+                let contents_start =
+                    string.text_range_between_quotes()?.start() - original_token.text_range().start();
+                (contents_start.into(), attr.syntax().parent()?, attr.kind() == AttrKind::Inner)
+            },
+            _ => return None,
+        }
+    };
+    let text = fake_ident_token.text();
+    let before_cursor = text.get(contents_start..text.find(COMPLETION_MARKER)?)?;
+    let line = before_cursor.rsplit('\n').next()?.trim_start();
+    let (label, target) = line.strip_prefix('[')?.split_once("]:")?;
+    if label.is_empty() || label.contains(['[', ']']) {
+        return None;
+    }
+    let path = target.trim_start();
+    let path = path.split_once('@').map_or(path, |(_, path)| path);
+    if !path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+        return None;
+    }
+
+    let (_, owner) = doc_attributes(sema, &owner_node)?;
+    // like rustdoc, the outer docs of a module resolve in its parent
+    let scope = match owner {
+        Definition::Module(module) if !is_inner => {
+            module.parent(sema.db).map_or(owner, Definition::Module)
+        }
+        _ => owner,
+    };
+    let qualifier = path.rsplit_once("::").map(|(qualifier, _)| qualifier.to_owned());
+    Some(DocLinkDefinitionContext { scope, qualifier })
+}
+
 fn classify_name(
     sema: &Semantics<'_, RootDatabase>,
     original_file: &SyntaxNode,
@@ -1,9 +1,15 @@
 //! See `CompletionItem` structure.
 
-use std::fmt;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
 
 use hir::{Documentation, Mutability};
-use ide_db::{imports::import_assets::LocatedImport, SnippetCap, SymbolKind};
+use ide_db::{
+    defs::Definition, imports::import_assets::LocatedImport, RootDatabase, SnippetCap, SymbolKind,
+};
 use itertools::Itertools;
 use smallvec::SmallVec;
 use stdx::{impl_from, never};
@@ -51,6 +57,13 @@ pub struct CompletionItem {
     /// Additional info to show in the UI pop up.
     pub detail: Option<String>,
     pub documentation: Option<Documentation>,
+    /// The item `documentation` was taken from, used to resolve the intra-doc links in it.
+    ///
+    /// For the documentation rust-analyzer writes itself, like the one of snippets, this is the
+    /// module of the completion, see `builtin_docs`.
+    pub doc_owner: Option<Definition>,
+    /// Whether `documentation` was written by rust-analyzer rather than taken from `doc_owner`.
+    pub builtin_docs: bool,
 
     /// Whether this item is marked as deprecated
     pub deprecated: bool,
@@ -346,6 +359,8 @@ impl CompletionItem {
             trait_name: None,
             detail: None,
             documentation: None,
+            doc_owner: None,
+            builtin_docs: false,
             lookup: None,
             kind: kind.into(),
             text_edit: None,
@@ -363,6 +378,14 @@ impl CompletionItem {
         self.lookup.as_str()
     }
 
+    /// Identifies `doc_owner` for as long as the database isn't changed, for the completion to be
+    /// found again when its documentation is resolved.
+    pub fn doc_owner_key(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.doc_owner?.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     pub fn ref_match(&self) -> Option<(String, text_edit::Indel, CompletionRelevance)> {
         // Relevance of the ref match should be the same as the original
         // match, but with exact type match set because self.ref_match
@@ -393,6 +416,8 @@ pub(crate) struct Builder {
     is_snippet: bool,
     detail: Option<String>,
     documentation: Option<Documentation>,
+    doc_owner: Option<Definition>,
+    builtin_docs: bool,
     lookup: Option<SmolStr>,
     kind: CompletionItemKind,
     text_edit: Option<TextEdit>,
@@ -451,6 +476,8 @@ impl Builder {
             is_snippet: self.is_snippet,
             detail: self.detail,
             documentation: self.documentation,
+            doc_owner: self.doc_owner,
+            builtin_docs: self.builtin_docs,
             lookup,
             kind: self.kind,
             deprecated: self.deprecated,
@@ -509,11 +536,31 @@ impl Builder {
         }
         self
     }
-    #[allow(unused)]
-    pub(crate) fn documentation(&mut self, docs: Documentation) -> &mut Builder {
-        self.set_documentation(Some(docs))
+    /// Sets documentation written by rust-analyzer, linking from the module of the completion.
+    pub(crate) fn documentation(
+        &mut self,
+        ctx: &CompletionContext<'_>,
+        docs: impl Into<String>,
+    ) -> &mut Builder {
+        self.documentation = Some(Documentation::new(docs.into()));
+        self.doc_owner = Some(Definition::Module(ctx.module));
+        self.builtin_docs = true;
+        self
+    }
+    /// Sets the documentation of `def`, linking from the item it is written on.
+    pub(crate) fn docs_of(
+        &mut self,
+        db: &RootDatabase,
+        def: impl Into<Definition>,
+    ) -> &mut Builder {
+        self.set_docs(ide_db::documentation::Documentation::for_definition(db, &def.into()))
     }
-    pub(crate) fn set_documentation(&mut self, docs: Option<Documentation>) -> &mut Builder {
+    pub(crate) fn set_docs(
+        &mut self,
+        docs: Option<ide_db::documentation::Documentation>,
+    ) -> &mut Builder {
+        self.doc_owner = docs.as_ref().map(|it| it.owner());
+        self.builtin_docs = false;
         self.documentation = docs.map(Into::into);
         self
     }
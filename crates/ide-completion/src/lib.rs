@@ -197,6 +197,9 @@ pub fn completions(
                     attr,
                 );
             }
+            CompletionAnalysis::DocLinkDefinition(doc_link_ctx) => {
+                completions::doc_link::complete_doc_link_definition(acc, ctx, doc_link_ctx);
+            }
             CompletionAnalysis::UnexpandedAttrTT { .. } | CompletionAnalysis::String { .. } => (),
         }
     }
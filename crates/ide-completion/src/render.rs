@@ -112,11 +112,6 @@ impl<'a> RenderContext<'a> {
                 .map(|trait_| self.is_deprecated(trait_))
                 .unwrap_or(false)
     }
-
-    // FIXME: remove this
-    fn docs(&self, def: impl HasAttrs) -> Option<hir::Documentation> {
-        def.docs(self.db())
-    }
 }
 
 pub(crate) fn render_field(
@@ -140,7 +135,7 @@ pub(crate) fn render_field(
         ..CompletionRelevance::default()
     });
     item.detail(ty.display(ctx.db()).to_string())
-        .set_documentation(field.docs(ctx.db()))
+        .docs_of(ctx.db(), field)
         .set_deprecated(is_deprecated)
         .lookup_by(name);
     item.insert_text(field_with_receiver(receiver.as_ref(), &escaped_name));
@@ -202,6 +197,14 @@ pub(crate) fn render_pattern_resolution(
     render_resolution_pat(ctx, pattern_ctx, local_name, None, resolution)
 }
 
+pub(crate) fn render_resolution_simple(
+    ctx: RenderContext<'_>,
+    local_name: hir::Name,
+    resolution: ScopeDef,
+) -> Builder {
+    render_resolution_simple_(ctx, &local_name, None, resolution)
+}
+
 pub(crate) fn render_resolution_with_import(
     ctx: RenderContext<'_>,
     path_ctx: &PathCompletionCtx,
@@ -356,8 +359,10 @@ fn render_resolution_simple_(
     let mut item =
         CompletionItem::new(kind, ctx.source_range(), local_name.unescaped().to_smol_str());
     item.set_relevance(ctx.completion_relevance())
-        .set_documentation(scope_def_docs(db, resolution))
         .set_deprecated(scope_def_is_deprecated(&ctx, resolution));
+    if let ScopeDef::ModuleDef(def) = resolution {
+        item.docs_of(db, def);
+    }
 
     if let Some(import_to_add) = ctx.import_to_add {
         item.add_import(import_to_add);
@@ -401,20 +406,6 @@ fn res_to_kind(resolution: ScopeDef) -> CompletionItemKind {
     }
 }
 
-fn scope_def_docs(db: &RootDatabase, resolution: ScopeDef) -> Option<hir::Documentation> {
-    use hir::ModuleDef::*;
-    match resolution {
-        ScopeDef::ModuleDef(Module(it)) => it.docs(db),
-        ScopeDef::ModuleDef(Adt(it)) => it.docs(db),
-        ScopeDef::ModuleDef(Variant(it)) => it.docs(db),
-        ScopeDef::ModuleDef(Const(it)) => it.docs(db),
-        ScopeDef::ModuleDef(Static(it)) => it.docs(db),
-        ScopeDef::ModuleDef(Trait(it)) => it.docs(db),
-        ScopeDef::ModuleDef(TypeAlias(it)) => it.docs(db),
-        _ => None,
-    }
-}
-
 fn scope_def_is_deprecated(ctx: &RenderContext<'_>, resolution: ScopeDef) -> bool {
     match resolution {
         ScopeDef::ModuleDef(it) => ctx.is_deprecated_assoc_item(it),
@@ -584,6 +575,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn documented_items_record_doc_owner() {
+        let items = get_all_items(
+            TEST_CONFIG,
+            r#"
+/// A function.
+fn func() {}
+/// A tuple struct.
+struct Tuple(u32);
+/// A constant.
+const CONST: () = ();
+/// A macro.
+macro_rules! mac { () => {} }
+
+fn main() {
+    $0
+}
+"#,
+            None,
+        );
+        let actual = items
+            .iter()
+            .filter(|it| it.documentation.is_some())
+            .map(|it| format!("{} {}\n", it.label, it.doc_owner.is_some()))
+            .collect::<String>();
+        expect![[r#"
+            CONST true
+            Tuple true
+            func() true
+            mac!(…) true
+        "#]]
+        .assert_eq(&actual);
+    }
+
     #[test]
     fn enum_detail_includes_record_fields() {
         check(
@@ -17,7 +17,7 @@ fn render(ctx: RenderContext<'_>, const_: hir::Const) -> Option<CompletionItem>
     let detail = const_.display(db).to_string();
 
     let mut item = CompletionItem::new(SymbolKind::Const, ctx.source_range(), name);
-    item.set_documentation(ctx.docs(const_))
+    item.docs_of(ctx.db(), const_)
         .set_deprecated(ctx.is_deprecated(const_) || ctx.is_deprecated_assoc_item(const_))
         .detail(detail)
         .set_relevance(ctx.completion_relevance());
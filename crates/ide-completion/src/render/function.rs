@@ -93,7 +93,7 @@ fn render(
         _ => (),
     }
 
-    item.set_documentation(ctx.docs(func))
+    item.docs_of(ctx.db(), func)
         .set_deprecated(ctx.is_deprecated(func) || ctx.is_deprecated_assoc_item(func))
         .detail(detail(db, func))
         .lookup_by(name.unescaped().to_smol_str());
@@ -1,7 +1,7 @@
 //! Renderer for `enum` variants.
 
-use hir::{db::HirDatabase, Documentation, HasAttrs, StructKind};
-use ide_db::SymbolKind;
+use hir::{db::HirDatabase, StructKind};
+use ide_db::{defs::Definition, SymbolKind};
 
 use crate::{
     context::{CompletionContext, PathCompletionCtx, PathKind},
@@ -117,7 +117,7 @@ fn render(
         None => item.insert_text(rendered.literal),
     };
 
-    item.set_documentation(thing.docs(db)).set_deprecated(thing.is_deprecated(&ctx));
+    item.docs_of(db, thing.definition()).set_deprecated(thing.is_deprecated(&ctx));
 
     let ty = thing.ty(db);
     item.set_relevance(CompletionRelevance {
@@ -170,10 +170,10 @@ impl Variant {
         }
     }
 
-    fn docs(self, db: &dyn HirDatabase) -> Option<Documentation> {
+    fn definition(self) -> Definition {
         match self {
-            Variant::Struct(it) => it.docs(db),
-            Variant::EnumVariant(it) => it.docs(db),
+            Variant::Struct(it) => it.into(),
+            Variant::EnumVariant(it) => it.into(),
         }
     }
 
@@ -1,7 +1,7 @@
 //! Renderer for macro invocations.
 
-use hir::{Documentation, HirDisplay};
-use ide_db::SymbolKind;
+use hir::HirDisplay;
+use ide_db::{documentation::Documentation, SymbolKind};
 use syntax::SmolStr;
 
 use crate::{
@@ -47,7 +47,7 @@ fn render(
     };
 
     let (name, escaped_name) = (name.unescaped().to_smol_str(), name.to_smol_str());
-    let docs = ctx.docs(macro_);
+    let docs = Documentation::for_definition(ctx.db(), &macro_.into());
     let docs_str = docs.as_ref().map(Documentation::as_str).unwrap_or_default();
     let is_fn_like = macro_.is_fn_like(completion.db);
     let (bra, ket) = if is_fn_like { guess_macro_braces(&name, docs_str) } else { ("", "") };
@@ -61,7 +61,7 @@ fn render(
     );
     item.set_deprecated(ctx.is_deprecated(macro_))
         .detail(macro_.display(completion.db).to_string())
-        .set_documentation(docs)
+        .set_docs(docs)
         .set_relevance(ctx.completion_relevance());
 
     match ctx.snippet_cap() {
@@ -1,7 +1,7 @@
 //! Renderer for patterns.
 
 use hir::{db::HirDatabase, HasAttrs, Name, StructKind};
-use ide_db::SnippetCap;
+use ide_db::{defs::Definition, SnippetCap};
 use itertools::Itertools;
 use syntax::SmolStr;
 
@@ -100,7 +100,7 @@ fn build_completion(
     label: SmolStr,
     lookup: SmolStr,
     pat: String,
-    def: impl HasAttrs + Copy,
+    def: impl HasAttrs + Into<Definition> + Copy,
     adt_ty: hir::Type,
     // Missing in context of match statement completions
     is_variant_missing: bool,
@@ -112,7 +112,7 @@ fn build_completion(
     }
 
     let mut item = CompletionItem::new(CompletionItemKind::Binding, ctx.source_range(), label);
-    item.set_documentation(ctx.docs(def))
+    item.docs_of(ctx.db(), def)
         .set_deprecated(ctx.is_deprecated(def))
         .detail(&pat)
         .lookup_by(lookup)
@@ -66,7 +66,7 @@ pub(crate) fn render_union_literal(
         if fields_omitted { ", .." } else { "" }
     );
 
-    item.set_documentation(ctx.docs(un))
+    item.docs_of(ctx.db(), un)
         .set_deprecated(ctx.is_deprecated(un))
         .detail(detail)
         .set_relevance(ctx.completion_relevance());
@@ -172,6 +172,14 @@ impl RootDatabase {
             // LineIndexDatabase
             crate::LineIndexQuery
 
+            // DocLinksDatabase
+            crate::doc_links::DocLinkResolutionsQuery
+            crate::doc_links::OwnerDocsQuery
+            crate::doc_links::ModuleImportsQuery
+            crate::doc_links::CrateDocBaseUrlQuery
+            crate::doc_links::DocCoverageQuery
+            crate::doc_links::DocLinkResolutionBudgetQuery
+
             // InternDatabase
             hir::db::InternFunctionQuery
             hir::db::InternStructQuery
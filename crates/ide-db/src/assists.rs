@@ -30,6 +30,9 @@ pub struct Assist {
     /// structure, such as a diagnostic.
     pub source_change: Option<SourceChange>,
     pub trigger_signature_help: bool,
+    /// Text the client should copy to the clipboard once the assist is applied, only known when
+    /// `source_change` is.
+    pub copy_to_clipboard: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -7,10 +7,10 @@
 
 use arrayvec::ArrayVec;
 use hir::{
-    Adt, AsAssocItem, AssocItem, BuiltinAttr, BuiltinType, Const, Crate, DeriveHelper, Field,
-    Function, GenericParam, HasVisibility, Impl, Label, Local, Macro, Module, ModuleDef, Name,
-    PathResolution, Semantics, Static, ToolModule, Trait, TraitAlias, TypeAlias, Variant,
-    Visibility,
+    Adt, AsAssocItem, AssocItem, AttrsWithOwner, BuiltinAttr, BuiltinType, Const, Crate,
+    DeriveHelper, Enum, Field, Function, GenericParam, HasAttrs, HasVisibility, Impl, Label, Local,
+    Macro, Module, ModuleDef, Name, PathResolution, Semantics, Static, Struct, ToolModule, Trait,
+    TraitAlias, TypeAlias, Union, Variant, Visibility,
 };
 use stdx::impl_from;
 use syntax::{
@@ -130,6 +130,31 @@ impl Definition {
         };
         Some(name)
     }
+
+    pub fn attrs(&self, db: &dyn hir::db::HirDatabase) -> Option<AttrsWithOwner> {
+        let attrs = match self {
+            Definition::Macro(it) => it.attrs(db),
+            Definition::Field(it) => it.attrs(db),
+            Definition::Module(it) => it.attrs(db),
+            Definition::Function(it) => it.attrs(db),
+            Definition::Adt(it) => it.attrs(db),
+            Definition::Variant(it) => it.attrs(db),
+            Definition::Const(it) => it.attrs(db),
+            Definition::Static(it) => it.attrs(db),
+            Definition::Trait(it) => it.attrs(db),
+            Definition::TraitAlias(it) => it.attrs(db),
+            Definition::TypeAlias(it) => it.attrs(db),
+            Definition::SelfType(it) => it.attrs(db),
+            Definition::GenericParam(it) => it.attrs(db),
+            Definition::BuiltinType(_)
+            | Definition::Local(_)
+            | Definition::Label(_)
+            | Definition::DeriveHelper(_)
+            | Definition::BuiltinAttr(_)
+            | Definition::ToolModule(_) => return None,
+        };
+        Some(attrs)
+    }
 }
 
 // FIXME: IdentClass as a name no longer fits
@@ -558,8 +583,8 @@ impl NameRefClass {
 }
 
 impl_from!(
-    Field, Module, Function, Adt, Variant, Const, Static, Trait, TraitAlias, TypeAlias, BuiltinType, Local,
-    GenericParam, Label, Macro
+    Field, Module, Function, Adt(Struct, Enum, Union), Variant, Const, Static, Trait, TraitAlias,
+    TypeAlias, BuiltinType, Local, GenericParam, Label, Macro
     for Definition
 );
 
@@ -0,0 +1,1668 @@
+//! Extraction and resolution of intra-doc links in documentation.
+//!
+//! This is shared by the IDE features that need to know what a doc link points to, URL
+//! generation for the links lives in the `ide` crate, except for the documentation roots of
+//! crates.
+
+pub mod coverage;
+pub mod intra_doc_links;
+
+use std::fmt::Display;
+
+use base_db::{
+    salsa, CrateOrigin, FileId, LangCrateOrigin, ReleaseChannel, SourceDatabaseExt, Upcast,
+};
+use hir::{db::HirDatabase, AsAssocItem, HasAttrs, Semantics};
+use memchr::memmem::Finder;
+use nohash_hasher::IntMap;
+use once_cell::unsync::Lazy;
+use pulldown_cmark::{BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag};
+use syntax::{
+    ast::{self, HasModuleItem, HasName, IsString},
+    match_ast, AstNode, AstToken, SyntaxKind,
+    SyntaxKind::*,
+    SyntaxNode, SyntaxToken, TextRange, TextSize,
+};
+use triomphe::Arc;
+use url::Url;
+
+use crate::{
+    defs::Definition,
+    doc_links::intra_doc_links::{parse_function_kind, parse_intra_doc_link, FunctionKind},
+    search::SearchScope,
+    FxHashMap, RootDatabase,
+};
+
+pub const MARKDOWN_OPTIONS: Options =
+    Options::ENABLE_FOOTNOTES.union(Options::ENABLE_TABLES).union(Options::ENABLE_TASKLISTS);
+
+/// How a link was written in the markdown source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocLinkKind {
+    /// `[text](target)`
+    Inline,
+    /// A link whose target comes from a reference definition, `[text][label]`, `[label][]` or
+    /// `[label]` with a `[label]: target` line elsewhere in the docs.
+    Reference,
+    /// `[target]` or `[target][]` without a matching reference definition.
+    Shortcut,
+    /// `<https://example.com>` or `<mail@example.com>`
+    Autolink,
+    /// `[label]: target`, the reference definition the reference links with that label link to.
+    Definition,
+}
+
+impl From<LinkType> for DocLinkKind {
+    fn from(link_type: LinkType) -> Self {
+        match link_type {
+            LinkType::Inline => DocLinkKind::Inline,
+            LinkType::Reference
+            | LinkType::ReferenceUnknown
+            | LinkType::Collapsed
+            | LinkType::Shortcut => DocLinkKind::Reference,
+            LinkType::CollapsedUnknown | LinkType::ShortcutUnknown => DocLinkKind::Shortcut,
+            LinkType::Autolink | LinkType::Email => DocLinkKind::Autolink,
+        }
+    }
+}
+
+/// How the target of a link is resolved, decided from its shape alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocLinkTargetKind {
+    /// `https://example.com`, used as is.
+    Url,
+    /// A path to a rustdoc page like `struct.Foo.html`, `../index.html` or `#method.new`,
+    /// relative to the page of the documented item.
+    Page,
+    /// An intra-doc link like `Foo::new` or `fn@bar`.
+    Intra,
+}
+
+impl DocLinkTargetKind {
+    /// Classifies `target`. Paths can't contain `.`, `/` or `#`, so no target could be both a page
+    /// and an intra-doc link.
+    pub fn of(target: &str) -> DocLinkTargetKind {
+        if target.contains("://") {
+            DocLinkTargetKind::Url
+        } else if target.contains(['.', '/', '#']) {
+            DocLinkTargetKind::Page
+        } else {
+            DocLinkTargetKind::Intra
+        }
+    }
+}
+
+/// Whether `target` could be the path of an intra-doc link, like `crate::Foo::new` or
+/// `<Foo as Default>::default`.
+pub fn is_path_like(target: &str) -> bool {
+    if let Some((ty, trait_, name)) = split_qualified_path(target) {
+        return [ty, trait_, name].into_iter().all(is_path_like);
+    }
+    let first = target.trim_start_matches(':').chars().next();
+    first.map_or(false, |it| it.is_alphabetic() || it == '_')
+        && target.chars().all(|it| it.is_alphanumeric() || matches!(it, '_' | ':'))
+}
+
+/// Splits the qualified path `<Type as Trait>::name` into its type, trait and item name.
+fn split_qualified_path(path: &str) -> Option<(&str, &str, &str)> {
+    let (qself, name) = path.strip_prefix('<')?.split_once(">::")?;
+    let (ty, trait_) = qself.split_once(" as ")?;
+    Some((ty.trim(), trait_.trim(), name))
+}
+
+/// A link found in a documentation string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLink {
+    /// The range of the whole link syntax, i.e. `[text](target)`.
+    pub range: TextRange,
+    /// The link target with its disambiguator stripped.
+    pub target: String,
+    /// The namespace requested by the disambiguator, if any.
+    pub ns: Option<hir::Namespace>,
+    /// The functions a `function@` or `method@` disambiguator restricts the link to.
+    pub functions: Option<FunctionKind>,
+    pub kind: DocLinkKind,
+    /// The title of an inline or reference link, like `docs` in `[text](target "docs")`.
+    pub title: String,
+}
+
+impl DocLink {
+    /// Resolves the link written in the docs of `owner`, see [`resolve_doc_link`].
+    pub fn resolve(&self, db: &dyn HirDatabase, owner: Definition) -> Option<Definition> {
+        resolve_doc_path_for_def(db, owner, &self.target, self.ns).filter(|&it| self.names(db, it))
+    }
+
+    /// Whether the disambiguator of the link allows it to name `def`.
+    pub fn names(&self, db: &dyn HirDatabase, def: Definition) -> bool {
+        self.functions.map_or(true, |it| it.matches(db, def))
+    }
+
+    /// Returns the range of the target in the link's source text, `link_source` being the text
+    /// covered by `self.range`.
+    ///
+    /// Only links that spell out their target, shortcut and inline ones, and reference
+    /// definitions have such a range.
+    pub fn target_range(&self, link_source: &str) -> Option<TextRange> {
+        let target_start = match self.kind {
+            // `[text](target)`
+            DocLinkKind::Inline => link_source.rfind("](")? + "](".len(),
+            // `[target]` or `[target][]`
+            DocLinkKind::Shortcut => "[".len(),
+            // `[label]: target`
+            DocLinkKind::Definition => {
+                let start = link_source.find("]:")? + "]:".len();
+                let target = link_source.get(start..)?;
+                start + (target.len() - target.trim_start().len())
+            }
+            DocLinkKind::Reference | DocLinkKind::Autolink => return None,
+        };
+        let source = link_source.get(target_start..)?;
+        // the target comes after the disambiguator, which could contain it as in `fn@f`
+        let spelled = source.trim_start_matches('`');
+        let spelled = match spelled.split_once(['@', ' ']) {
+            Some((prefix, rest))
+                if !prefix.is_empty() && prefix.bytes().all(|it| it.is_ascii_alphabetic()) =>
+            {
+                rest
+            }
+            _ => spelled,
+        };
+        let target_start = target_start + (source.len() - spelled.len());
+        let target_start = target_start + spelled.find(&*self.target)?;
+        let target_start = self.range.start() + TextSize::try_from(target_start).ok()?;
+        Some(TextRange::at(target_start, TextSize::of(&*self.target)))
+    }
+}
+
+/// A doc link resolving to a searched-for definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLinkReference {
+    /// The range of the whole link in the original file.
+    pub range: TextRange,
+    /// The range of the final segment of the link's target path in the original file.
+    pub name_range: TextRange,
+}
+
+/// Takes the text of shortcut and collapsed links without a reference definition, like
+/// ``[`Foo`]``, as their target, for them to be resolved as intra-doc links.
+///
+/// Bracketed text that can't be the path of an intra-doc link, like `[0..n]`, `[T; N]` or
+/// `['a]`, stays text as in rustdoc, without being resolved.
+pub fn broken_link_clone_cb(link: BrokenLink<'_>) -> Option<(CowStr<'_>, CowStr<'_>)> {
+    let (path, _) = parse_intra_doc_link(&link.reference);
+    if !is_path_like(path) {
+        return None;
+    }
+    Some((/*url*/ link.reference.clone(), /*title*/ link.reference))
+}
+
+/// Whether `docs` define the reference `[label]: target`, which the shortcut link `[label]` links
+/// to rather than resolving as an intra-doc link.
+pub fn defines_reference(docs: &str, label: &str) -> bool {
+    // reference definitions apply to the whole document, also to a link before them
+    let text = format!("[{label}]\n\n{docs}");
+    let mut cb = |_: BrokenLink<'_>| Some((CowStr::Borrowed(""), CowStr::Borrowed("")));
+    let first_link = Parser::new_with_broken_link_callback(&text, MARKDOWN_OPTIONS, Some(&mut cb))
+        .find_map(|event| match event {
+            Event::Start(Tag::Link(link_type, ..)) => Some(link_type),
+            _ => None,
+        });
+    first_link == Some(LinkType::Shortcut)
+}
+
+/// Extracts all links from a given markdown text.
+pub fn extract_links_from_docs(docs: &hir::Documentation) -> Vec<DocLink> {
+    Parser::new_with_broken_link_callback(
+        docs.as_str(),
+        MARKDOWN_OPTIONS,
+        Some(&mut broken_link_clone_cb),
+    )
+    .into_offset_iter()
+    .filter_map(|(event, range)| match event {
+        Event::Start(Tag::Link(link_type, target, title)) => {
+            let functions = parse_function_kind(&target);
+            let (target, ns) = parse_intra_doc_link(&target);
+            let kind = link_type.into();
+            // the title of shortcut links is their text, see `broken_link_clone_cb`
+            let title = match kind {
+                DocLinkKind::Shortcut => String::new(),
+                _ => title.to_string(),
+            };
+            Some(DocLink {
+                range: TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?),
+                target: target.to_string(),
+                ns,
+                functions,
+                kind,
+                title,
+            })
+        }
+        _ => None,
+    })
+    .collect()
+}
+
+/// Extracts the reference definitions, like `[label]: target`, from a given markdown text, as
+/// links of the [`DocLinkKind::Definition`] kind covering the definition.
+pub fn extract_reference_definitions_from_docs(docs: &hir::Documentation) -> Vec<DocLink> {
+    let parser = Parser::new_ext(docs.as_str(), MARKDOWN_OPTIONS);
+    let mut definitions: Vec<_> = parser
+        .reference_definitions()
+        .iter()
+        .filter_map(|(_, def)| {
+            let functions = parse_function_kind(&def.dest);
+            let (target, ns) = parse_intra_doc_link(&def.dest);
+            Some(DocLink {
+                range: TextRange::new(
+                    def.span.start.try_into().ok()?,
+                    def.span.end.try_into().ok()?,
+                ),
+                target: target.to_string(),
+                ns,
+                functions,
+                kind: DocLinkKind::Definition,
+                title: def.title.as_deref().unwrap_or_default().to_owned(),
+            })
+        })
+        .collect();
+    definitions.sort_by_key(|it| it.range.start());
+    definitions
+}
+
+/// Extracts the code spans outside of links from a given markdown text, returning their range
+/// including the backticks and the code they contain.
+pub fn extract_code_spans_from_docs(docs: &hir::Documentation) -> Vec<(TextRange, String)> {
+    let mut in_link = 0usize;
+    Parser::new_with_broken_link_callback(
+        docs.as_str(),
+        MARKDOWN_OPTIONS,
+        Some(&mut broken_link_clone_cb),
+    )
+    .into_offset_iter()
+    .filter_map(|(event, range)| match event {
+        Event::Start(Tag::Link(..)) => {
+            in_link += 1;
+            None
+        }
+        Event::End(Tag::Link(..)) => {
+            in_link -= 1;
+            None
+        }
+        Event::Code(code) if in_link == 0 => Some((
+            TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?),
+            code.to_string(),
+        )),
+        _ => None,
+    })
+    .collect()
+}
+
+/// Extracts all links from a given markdown text returning the definition text range, link-text
+/// and the namespace if known.
+pub fn extract_definitions_from_docs(
+    docs: &hir::Documentation,
+) -> Vec<(TextRange, String, Option<hir::Namespace>)> {
+    extract_links_from_docs(docs)
+        .into_iter()
+        .map(|DocLink { range, target, ns, .. }| (range, target, ns))
+        .collect()
+}
+
+#[salsa::query_group(DocLinksDatabaseStorage)]
+pub trait DocLinksDatabase: HirDatabase + Upcast<dyn HirDatabase> {
+    /// How the intra-doc links in the documentation of `owner` resolve, by the definition whose
+    /// scope they resolve in, see [`doc_link_scope`], and link target.
+    ///
+    /// Hover and completion render the same documentation over and over, this spares them
+    /// resolving its links every time.
+    ///
+    /// Only the first [`DocLinksDatabase::doc_link_resolution_budget`] distinct targets are
+    /// resolved, in the order of the documentation, the others are missing from the map.
+    fn doc_link_resolutions(
+        &self,
+        owner: Definition,
+    ) -> Arc<FxHashMap<(Definition, String), DocLinkResolution>>;
+
+    /// The documentation written in the attributes of `owner`.
+    ///
+    /// [`DocLinksDatabase::doc_link_resolutions`] depends on this instead of all attributes of
+    /// `owner`, so editing its other attributes keeps the resolutions.
+    fn owner_docs(&self, owner: Definition) -> Option<hir::Documentation>;
+
+    /// The imports written in `module`, see [`ModuleImports`].
+    ///
+    /// These are read from the syntax of the module, going through this query the resolutions of
+    /// the links in its docs only depend on the file of the module once its imports change.
+    fn module_imports(&self, module: hir::Module) -> Arc<ModuleImports>;
+
+    /// How many link targets are resolved for one piece of documentation at most.
+    ///
+    /// Machine-generated documentation can have thousands of links, resolving all of them would
+    /// make a single hover take seconds.
+    #[salsa::input]
+    fn doc_link_resolution_budget(&self) -> usize;
+
+    /// Whether intra-doc links naming nothing resolve to the item they name by one of its
+    /// `#[doc(alias)]`es, see [`DocLinkResolutionFailure::DocAlias`]. rustdoc doesn't do this,
+    /// so it's off by default.
+    #[salsa::input]
+    fn doc_link_alias_resolution(&self) -> bool;
+
+    /// The root URL of the web documentation of `krate`, like `https://docs.rs/foo/0.1.0/foo/`.
+    ///
+    /// Rewriting the links of many items points to the same few crates again and again, this
+    /// computes the root of each of their documentation once per revision.
+    fn crate_doc_base_url(&self, krate: hir::Crate) -> Option<Arc<Url>>;
+
+    /// How much of the public API of `krate` is documented and how many of the intra-doc links
+    /// in its documentation resolve.
+    ///
+    /// This walks all public items of the crate, so it is computed once per revision.
+    #[salsa::invoke(coverage::doc_coverage)]
+    fn doc_coverage(&self, krate: hir::Crate) -> Arc<coverage::DocCoverage>;
+}
+
+/// The default of [`DocLinksDatabase::doc_link_resolution_budget`].
+pub const DEFAULT_DOC_LINK_RESOLUTION_BUDGET: usize = 500;
+
+/// How an intra-doc link resolves, see [`doc_link_resolution`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DocLinkResolution {
+    /// The definition the link names along with the namespace it was found in.
+    pub resolved: Option<(Definition, hir::Namespace)>,
+    /// Whether the link has no disambiguator and names different items in different namespaces.
+    pub ambiguous: bool,
+    /// Whether the link names an associated item of a type that several impls of the type
+    /// define, without naming the trait as in `<Foo as Trait>::item`. Like rustdoc, the link
+    /// then resolves to the item of the inherent impl.
+    pub impl_ambiguous: bool,
+    /// The items the link names through different glob imports, like `Error` with both
+    /// `use std::io::*;` and `use std::fmt::*;` in scope, see [`glob_import_candidates`]. The
+    /// link resolves to the first one. Empty unless the link names more than one item.
+    pub candidates: Vec<Definition>,
+    /// Why the link names nothing, `None` if it resolved.
+    pub failure: Option<DocLinkResolutionFailure>,
+}
+
+fn doc_link_resolutions(
+    db: &dyn DocLinksDatabase,
+    owner: Definition,
+) -> Arc<FxHashMap<(Definition, String), DocLinkResolution>> {
+    let _p = profile::span("doc_link_resolutions");
+    cov_mark::hit!(doc_link_resolutions_computed);
+    let Some(docs) = db.owner_docs(owner) else {
+        return Default::default();
+    };
+    let budget = db.doc_link_resolution_budget();
+    let scope_at = doc_link_scope(db.upcast(), owner, docs.as_str());
+    let mut res = FxHashMap::default();
+    let mut cb = broken_link_clone_cb;
+    let doc = Parser::new_with_broken_link_callback(docs.as_str(), MARKDOWN_OPTIONS, Some(&mut cb));
+    for (event, range) in doc.into_offset_iter() {
+        if res.len() >= budget {
+            break;
+        }
+        if let Event::Start(Tag::Link(_, target, _)) = event {
+            let Ok(offset) = TextSize::try_from(range.start) else { continue };
+            let key = (scope_at(offset), target.to_string());
+            if res.contains_key(&key) {
+                continue;
+            }
+            // page links count towards the budget too, but cost nothing to resolve here
+            let resolution = match DocLinkTargetKind::of(&target) {
+                DocLinkTargetKind::Url => continue,
+                DocLinkTargetKind::Page => DocLinkResolution::default(),
+                DocLinkTargetKind::Intra => doc_link_resolution(db, key.0, &target),
+            };
+            res.insert(key, resolution);
+        }
+    }
+    Arc::new(res)
+}
+
+fn owner_docs(db: &dyn DocLinksDatabase, owner: Definition) -> Option<hir::Documentation> {
+    owner.attrs(db.upcast())?.docs()
+}
+
+/// The imports written in a module, by the paths they are written with.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ModuleImports {
+    /// The paths of the modules the glob imports import from.
+    pub globs: Vec<String>,
+    /// The names the other imports import.
+    pub names: Vec<String>,
+}
+
+fn module_imports(db: &dyn DocLinksDatabase, module: hir::Module) -> Arc<ModuleImports> {
+    let mut imports = ModuleImports::default();
+    for item in module_items(module.definition_source(db.upcast()).value) {
+        if let Some(tree) = ast::Use::cast(item.syntax().clone()).and_then(|it| it.use_tree()) {
+            collect_use_tree(tree, "", &mut imports.globs, &mut imports.names);
+        }
+    }
+    Arc::new(imports)
+}
+
+/// The definition in whose scope the intra-doc link at an offset of `markdown`, a rendering of
+/// the docs of `owner`, resolves.
+///
+/// Like rustdoc, this is the parent module for the docs on a `mod` item, while the `//!` docs
+/// written inside the module resolve in the module itself. The links of other owners, and of
+/// modules whose docs `markdown` doesn't contain verbatim, resolve in `owner`.
+pub fn doc_link_scope(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    markdown: &str,
+) -> impl Fn(TextSize) -> Definition {
+    let outer = match owner {
+        Definition::Module(module) => module.parent(db).and_then(|parent| {
+            let attrs = module.attrs(db);
+            let docs = attrs.docs()?;
+            let start = TextSize::try_from(markdown.find(docs.as_str())?).ok()?;
+            let inner_start = attrs.inner_docs_start(db.upcast()).map(|it| start + it);
+            Some((Definition::Module(parent), inner_start))
+        }),
+        _ => None,
+    };
+    move |offset| match outer {
+        Some((parent, inner_start)) if inner_start.map_or(true, |start| offset < start) => parent,
+        _ => owner,
+    }
+}
+
+fn crate_doc_base_url(db: &dyn DocLinksDatabase, krate: hir::Crate) -> Option<Arc<Url>> {
+    let _p = profile::span("crate_doc_base_url");
+    let db = db.upcast();
+    let display_name = krate.display_name(db)?;
+    let crate_data = &db.crate_graph()[krate.into()];
+    let channel = crate_data.channel.map_or("nightly", ReleaseChannel::as_str);
+
+    let web_base = match &crate_data.origin {
+        // std and co do not specify `html_root_url` any longer so we gotta handwrite this ourself.
+        // FIXME: Use the toolchains channel instead of nightly
+        CrateOrigin::Lang(
+            origin @ (LangCrateOrigin::Alloc
+            | LangCrateOrigin::Core
+            | LangCrateOrigin::ProcMacro
+            | LangCrateOrigin::Std
+            | LangCrateOrigin::Test),
+        ) => format!("https://doc.rust-lang.org/{channel}/{origin}"),
+        CrateOrigin::Lang(_) => return None,
+        CrateOrigin::Rustc { name: _ } => {
+            format!("https://doc.rust-lang.org/{channel}/nightly-rustc/")
+        }
+        // FIXME: These should not attempt to link to docs.rs!
+        CrateOrigin::Local { repo: _, name: _ } => {
+            html_root_url_or_docs_rs(db, krate, &display_name)
+        }
+        CrateOrigin::Library { repo: _, name } => html_root_url_or_docs_rs(db, krate, name),
+    };
+    let web_base = Url::parse(&web_base).ok()?.join(&format!("{display_name}/")).ok()?;
+    Some(Arc::new(web_base))
+}
+
+fn html_root_url_or_docs_rs(
+    db: &dyn HirDatabase,
+    krate: hir::Crate,
+    name: &impl Display,
+) -> String {
+    krate.get_html_root_url(db).unwrap_or_else(|| {
+        let version = krate.version(db);
+        // Fallback to docs.rs. This uses the name of the crate and can never be
+        // correct, but that's what fallbacks are about.
+        //
+        // FIXME: clicking on the link should just open the file in the editor,
+        // instead of falling back to external urls.
+        format!("https://docs.rs/{name}/{}/", version.as_deref().unwrap_or("*"))
+    })
+}
+
+/// Resolves the intra-doc link `link` written in the docs of `owner`, use
+/// [`DocLinksDatabase::doc_link_resolutions`] for links that are part of the docs of `owner`.
+pub fn doc_link_resolution(
+    doc_db: &dyn DocLinksDatabase,
+    owner: Definition,
+    link: &str,
+) -> DocLinkResolution {
+    let db = doc_db.upcast();
+    let functions = parse_function_kind(link);
+    let (path, ns) = parse_intra_doc_link(link);
+    let mut resolved = resolve_doc_link_path(db, owner, path, ns)
+        .filter(|&(def, _)| functions.map_or(true, |it| it.matches(db, def)));
+    let candidates = match resolved {
+        Some(resolved) => glob_import_candidates(doc_db, owner, path, resolved)
+            .into_iter()
+            .filter(|&def| functions.map_or(true, |it| it.matches(db, def)))
+            .collect(),
+        None => Vec::new(),
+    };
+    if let (Some((def, _)), Some(&primary)) = (&mut resolved, candidates.first()) {
+        *def = primary;
+    }
+    let ambiguous = resolved.is_some()
+        && ns.is_none()
+        && resolve_doc_path_in_all_namespaces(db, owner, path).len() > 1;
+    let impl_ambiguous = match resolved {
+        Some((def, ns)) if split_qualified_path(path).is_none() => impls_defining(db, def, ns) > 1,
+        _ => false,
+    };
+    let failure = match resolved {
+        Some(_) => None,
+        None => doc_link_resolution_failure(db, owner, path, ns, functions),
+    };
+    DocLinkResolution { resolved, ambiguous, impl_ambiguous, candidates, failure }
+}
+
+/// Resolves the target of an intra-doc link written in the docs of `owner`, e.g. `fn@Foo::new`.
+///
+/// Returns the definition the link names along with the namespace it was found in. Links with a
+/// `method@` disambiguator only resolve to associated functions, and ones with `function@` only
+/// to free functions.
+pub fn resolve_doc_link(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    link: &str,
+) -> Option<(Definition, hir::Namespace)> {
+    let functions = parse_function_kind(link);
+    let (path, ns) = parse_intra_doc_link(link);
+    resolve_doc_link_path(db, owner, path, ns)
+        .filter(|&(def, _)| functions.map_or(true, |it| it.matches(db, def)))
+}
+
+/// Resolves the path of an intra-doc link, with its disambiguator already stripped, in the scope
+/// of the docs of `owner`.
+///
+/// Besides the paths the item scope knows about, this resolves `Self` and the associated items
+/// of types and traits. The generic parameters of `owner` and of the impl or trait it is an item
+/// of come first, then, like rustdoc, the docs of the items of trait impls see the items of the
+/// impl and then of the trait by their names, before the ones of the item scope.
+///
+/// Paths starting with the name of the crate of `owner` that name nothing otherwise, like
+/// `mycrate::Config` in the docs of `mycrate`, resolve like the path starting with `crate`.
+/// rustdoc only resolves them with an `extern crate self as mycrate;`.
+pub fn resolve_doc_link_path(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    path: &str,
+    ns: Option<hir::Namespace>,
+) -> Option<(Definition, hir::Namespace)> {
+    resolve_doc_link_path_as_written(db, owner, path, ns).or_else(|| {
+        let path = strip_own_crate_name(db, owner, path)?;
+        resolve_doc_link_path_as_written(db, owner, &path, ns)
+    })
+}
+
+/// Replaces the first segment of `path` with `crate` if it is the name of the crate of `owner`.
+fn strip_own_crate_name(db: &dyn HirDatabase, owner: Definition, path: &str) -> Option<String> {
+    let Definition::Module(root) =
+        resolve_in_item_scope(db, owner, "crate", hir::Namespace::Types)?
+    else {
+        return None;
+    };
+    let name = root.krate().display_name(db)?;
+    let path = path.strip_prefix("::").unwrap_or(path);
+    let (first, rest) = match path.split_once("::") {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    };
+    (first == &**name.crate_name()).then(|| match rest {
+        Some(rest) => format!("crate::{rest}"),
+        None => "crate".to_owned(),
+    })
+}
+
+fn resolve_doc_link_path_as_written(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    path: &str,
+    ns: Option<hir::Namespace>,
+) -> Option<(Definition, hir::Namespace)> {
+    let namespaces = match ns {
+        Some(ns) => vec![ns],
+        None => vec![hir::Namespace::Types, hir::Namespace::Values, hir::Namespace::Macros],
+    };
+    if let Some((ty, trait_, name)) = split_qualified_path(path) {
+        return resolve_qualified_assoc_item(db, owner, ty, trait_, name, &namespaces);
+    }
+    if let Some(res) = resolve_generic_param(db, owner, path, &namespaces) {
+        return Some(res);
+    }
+    if let Some(res) = resolve_in_trait_impl(db, owner, path, &namespaces) {
+        return Some(res);
+    }
+    if let Some(res) =
+        namespaces.iter().find_map(|&ns| Some((resolve_in_item_scope(db, owner, path, ns)?, ns)))
+    {
+        return Some(res);
+    }
+
+    if path == "Self" {
+        if !namespaces.contains(&hir::Namespace::Types) {
+            return None;
+        }
+        return Some((self_type_of(db, owner)?, hir::Namespace::Types));
+    }
+    let (qualifier, name) = path.rsplit_once("::")?;
+    let field = || match namespaces.contains(&hir::Namespace::Values) {
+        true => Some((resolve_field(db, owner, qualifier, name)?, hir::Namespace::Values)),
+        false => None,
+    };
+    if name.bytes().all(|it| it.is_ascii_digit()) {
+        return field();
+    }
+    // the item scope doesn't know the variants of `Self`
+    if let ("Self", Some(Definition::Adt(hir::Adt::Enum(it)))) =
+        (qualifier, self_type_of(db, owner))
+    {
+        let variant = it.variants(db).into_iter().find(|it| it.name(db).to_smol_str() == name);
+        let ns = namespaces.iter().find(|&&it| it != hir::Namespace::Macros);
+        if let (Some(variant), Some(&ns)) = (variant, ns) {
+            return Some((Definition::Variant(variant), ns));
+        }
+    }
+    let qualifiers: Vec<Vec<hir::AssocItem>> = match qualifier {
+        "Self" => match self_type_of(db, owner) {
+            Some(it) => vec![assoc_items_of(db, it)?],
+            None => vec![items_of_type(db, primitive_self_type_of(db, owner)?)],
+        },
+        // like rustdoc, fall back to primitives shadowed by modules of the same name, as with
+        // `str::len` in std or `slice::iter` in core
+        _ => resolve_in_item_scope(db, owner, qualifier, hir::Namespace::Types)
+            .into_iter()
+            .chain(hir::BuiltinType::by_name(qualifier).map(Definition::BuiltinType))
+            .filter_map(|it| assoc_items_of(db, it))
+            .chain(primitive_type_by_name(db, qualifier).map(|it| items_of_type(db, it)))
+            .collect(),
+    };
+    // like rustdoc, associated items win over fields of the same name
+    qualifiers
+        .into_iter()
+        .find_map(|items| find_assoc_item(db, items, name, &namespaces))
+        .or_else(field)
+}
+
+/// The items of the trait `def`, or of all the impls of the type `def`.
+pub fn assoc_items_of(db: &dyn HirDatabase, def: Definition) -> Option<Vec<hir::AssocItem>> {
+    let ty = match def {
+        Definition::Trait(it) => return Some(it.items(db)),
+        Definition::Adt(it) => it.ty(db),
+        Definition::BuiltinType(it) => it.ty(db),
+        _ => return None,
+    };
+    Some(items_of_type(db, ty))
+}
+
+/// The items of the impls of `ty`, the ones of inherent impls first so that they win over trait
+/// items of the same name, like in rustdoc.
+fn items_of_type(db: &dyn HirDatabase, ty: hir::Type) -> Vec<hir::AssocItem> {
+    let mut impls = hir::Impl::all_for_type(db, ty);
+    impls.sort_by_key(|it| it.trait_(db).is_some());
+    impls.into_iter().flat_map(|impl_| impl_.items(db)).collect()
+}
+
+/// How many impls of the type the impl item `def` belongs to define an item of its name in `ns`.
+fn impls_defining(db: &dyn HirDatabase, def: Definition, ns: hir::Namespace) -> usize {
+    let Some(assoc) = def.as_assoc_item(db) else { return 0 };
+    let (hir::AssocItemContainer::Impl(impl_), Some(name)) = (assoc.container(db), assoc.name(db))
+    else {
+        return 0;
+    };
+    let defines = |item: hir::AssocItem| {
+        assoc_item_namespace(item) == ns && item.name(db).as_ref() == Some(&name)
+    };
+    let impls = hir::Impl::all_for_type(db, impl_.self_ty(db));
+    impls.into_iter().filter(|it| it.items(db).into_iter().any(defines)).count()
+}
+
+/// The items the intra-doc link `path`, which resolved to `resolved`, names through the glob
+/// imports of the module of the docs of `owner`, if it names more than one.
+///
+/// rustc rejects such names as ambiguous where they are used, while the resolver takes the item
+/// of one of the globs. The candidates are ordered by the crate defining the item the first
+/// segment of `path` names instead: the crate of `owner` first, then its dependencies in the
+/// order of the crate graph.
+fn glob_import_candidates(
+    doc_db: &dyn DocLinksDatabase,
+    owner: Definition,
+    path: &str,
+    (resolved, ns): (Definition, hir::Namespace),
+) -> Vec<Definition> {
+    let db = doc_db.upcast();
+    let first = path.split("::").next().unwrap_or(path);
+    if matches!(first, "" | "crate" | "self" | "super" | "Self") {
+        return Vec::new();
+    }
+    let Some(Definition::Module(module)) =
+        resolve_in_item_scope(db, owner, "self", hir::Namespace::Types)
+    else {
+        return Vec::new();
+    };
+    let imports = doc_db.module_imports(module);
+    // items declared or imported by name shadow the ones of glob imports. The declarations are
+    // looked up by name in the item scope, their own names would depend on all their attributes
+    let declarations = module.declarations(db);
+    let declared = module.scope(db, None).into_iter().any(|(name, def)| match def {
+        hir::ScopeDef::ModuleDef(def) => name.to_smol_str() == first && declarations.contains(&def),
+        _ => false,
+    });
+    if declared || imports.names.iter().any(|it| it == first) {
+        return Vec::new();
+    }
+
+    let krate = module.krate();
+    let dependencies = krate.dependencies(db);
+    let rank = |def: hir::ModuleDef| match def.module(db).map(|it| it.krate()) {
+        Some(it) if it == krate => 0,
+        it => {
+            dependencies.iter().position(|dep| Some(dep.krate) == it).map_or(usize::MAX, |i| i + 1)
+        }
+    };
+    let mut candidates: Vec<(usize, Definition)> = Vec::new();
+    for glob in &imports.globs {
+        let Some(Definition::Module(from)) =
+            resolve_in_item_scope(db, Definition::Module(module), glob, hir::Namespace::Types)
+        else {
+            continue;
+        };
+        let Some(named) =
+            from.scope(db, Some(module)).into_iter().find_map(|(name, def)| match def {
+                hir::ScopeDef::ModuleDef(def) if name.to_smol_str() == first => Some(def),
+                _ => None,
+            })
+        else {
+            continue;
+        };
+        let found = resolve_doc_link_path_as_written(db, Definition::Module(from), path, Some(ns));
+        if let Some((def, _)) = found.filter(|(def, _)| candidates.iter().all(|(_, it)| it != def))
+        {
+            candidates.push((rank(named), def));
+        }
+    }
+    if candidates.len() < 2 || candidates.iter().all(|&(_, it)| it != resolved) {
+        return Vec::new();
+    }
+    candidates.sort_by_key(|&(rank, _)| rank);
+    candidates.into_iter().map(|(_, it)| it).collect()
+}
+
+/// The items written in the module `source` is the definition of.
+fn module_items(source: hir::ModuleSource) -> Vec<ast::Item> {
+    match source {
+        hir::ModuleSource::SourceFile(it) => it.items().collect(),
+        hir::ModuleSource::Module(it) => {
+            it.item_list().into_iter().flat_map(|it| it.items()).collect()
+        }
+        hir::ModuleSource::BlockExpr(it) => it
+            .statements()
+            .filter_map(|it| match it {
+                ast::Stmt::Item(it) => Some(it),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+/// Collects the paths of the modules the glob imports of `tree` import from into `globs` and the
+/// names it imports otherwise into `names`.
+fn collect_use_tree(
+    tree: ast::UseTree,
+    prefix: &str,
+    globs: &mut Vec<String>,
+    names: &mut Vec<String>,
+) {
+    let path = match tree.path() {
+        Some(path) if prefix.is_empty() => path.to_string(),
+        Some(path) => format!("{prefix}::{path}"),
+        None => prefix.to_owned(),
+    };
+    if tree.star_token().is_some() {
+        globs.push(path);
+    } else if let Some(list) = tree.use_tree_list() {
+        for tree in list.use_trees() {
+            collect_use_tree(tree, &path, globs, names);
+        }
+    } else if let Some(rename) = tree.rename() {
+        names.extend(rename.name().map(|it| it.text().to_string()));
+    } else {
+        let name = path.rsplit("::").find(|&it| it != "self");
+        names.extend(name.map(str::to_owned));
+    }
+}
+
+/// Resolves `<ty as trait_>::name` to the item of the impl of the trait for the type, or to the
+/// item of the trait if the impl leaves it out.
+fn resolve_qualified_assoc_item(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    ty: &str,
+    trait_: &str,
+    name: &str,
+    namespaces: &[hir::Namespace],
+) -> Option<(Definition, hir::Namespace)> {
+    let Definition::Trait(trait_) =
+        resolve_in_item_scope(db, owner, trait_, hir::Namespace::Types)?
+    else {
+        return None;
+    };
+    let ty = match ty {
+        "Self" => self_type_of(db, owner)?,
+        _ => resolve_in_item_scope(db, owner, ty, hir::Namespace::Types)
+            .or_else(|| hir::BuiltinType::by_name(ty).map(Definition::BuiltinType))?,
+    };
+    let ty = match ty {
+        Definition::Adt(it) => it.ty(db),
+        Definition::BuiltinType(it) => it.ty(db),
+        _ => return None,
+    };
+    let impl_items = hir::Impl::all_for_type(db, ty)
+        .into_iter()
+        .filter(|it| it.trait_(db) == Some(trait_))
+        .flat_map(|it| it.items(db))
+        .collect();
+    find_assoc_item(db, impl_items, name, namespaces)
+        .or_else(|| find_assoc_item(db, trait_.items(db), name, namespaces))
+}
+
+/// The name of the page rustdoc documents the primitive type `ty` on, for the primitives that
+/// have no [`hir::BuiltinType`], like `slice` for `[T]`.
+pub fn primitive_type_name(ty: &hir::Type) -> Option<&'static str> {
+    Some(if ty.is_slice() {
+        "slice"
+    } else if ty.is_array() {
+        "array"
+    } else if ty.is_raw_ptr() {
+        "pointer"
+    } else if ty.is_reference() {
+        "reference"
+    } else if ty.is_never() {
+        "never"
+    } else {
+        return None;
+    })
+}
+
+/// The primitive type named `name` in links, for the primitives that have no
+/// [`hir::BuiltinType`] and whose impls can be looked up without knowing more about the type.
+fn primitive_type_by_name(db: &dyn HirDatabase, name: &str) -> Option<hir::Type> {
+    match name {
+        // the element type doesn't matter to finding the impls of slices
+        "slice" => Some(hir::Type::new_slice(hir::BuiltinType::by_name("u8")?.ty(db))),
+        _ => None,
+    }
+}
+
+/// The type `Self` refers to in the docs of `def` when it is a primitive without a
+/// [`hir::BuiltinType`], as in the docs of the items of `impl<T> [T]`.
+fn primitive_self_type_of(db: &dyn HirDatabase, def: Definition) -> Option<hir::Type> {
+    let impl_ = match def {
+        Definition::SelfType(it) => it,
+        _ => match def.as_assoc_item(db)?.container(db) {
+            hir::AssocItemContainer::Impl(it) => it,
+            hir::AssocItemContainer::Trait(_) => return None,
+        },
+    };
+    let ty = impl_.self_ty(db);
+    primitive_type_name(&ty).map(|_| ty)
+}
+
+/// Resolves the field `name` of the struct, union or enum variant `qualifier`, as in `Foo::id`,
+/// `Event::Ready::session_id` or, for tuple structs and variants, `Foo::0`.
+fn resolve_field(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    qualifier: &str,
+    name: &str,
+) -> Option<Definition> {
+    let (qualifier, _) =
+        resolve_doc_link_path_as_written(db, owner, qualifier, Some(hir::Namespace::Types))?;
+    let (kind, fields) = match qualifier {
+        Definition::Adt(hir::Adt::Struct(it)) => (it.kind(db), it.fields(db)),
+        Definition::Adt(hir::Adt::Union(it)) => (hir::StructKind::Record, it.fields(db)),
+        Definition::Variant(it) => (it.kind(db), it.fields(db)),
+        _ => return None,
+    };
+    if name.bytes().all(|it| it.is_ascii_digit()) {
+        if kind != hir::StructKind::Tuple {
+            return None;
+        }
+        return fields.into_iter().nth(name.parse().ok()?).map(Definition::Field);
+    }
+    fields.into_iter().find(|it| it.name(db).to_smol_str() == name).map(Definition::Field)
+}
+
+/// Resolves `name` among the type and const parameters of `owner`, then of the impl or trait it
+/// is an item of. Lifetimes aren't paths, so links can't name them.
+fn resolve_generic_param(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    name: &str,
+    namespaces: &[hir::Namespace],
+) -> Option<(Definition, hir::Namespace)> {
+    // the implicit `Self` parameter of traits is resolved as the trait
+    if name == "Self" {
+        return None;
+    }
+    let generic_def = |def: Definition| -> Option<hir::GenericDef> {
+        Some(match def {
+            Definition::Function(it) => it.into(),
+            Definition::Adt(it) => it.into(),
+            Definition::Variant(it) => it.into(),
+            Definition::Trait(it) => it.into(),
+            Definition::TraitAlias(it) => it.into(),
+            Definition::TypeAlias(it) => it.into(),
+            Definition::Const(it) => it.into(),
+            Definition::SelfType(it) => it.into(),
+            Definition::Field(it) => match it.parent_def(db) {
+                hir::VariantDef::Struct(it) => hir::Adt::from(it).into(),
+                hir::VariantDef::Union(it) => hir::Adt::from(it).into(),
+                hir::VariantDef::Variant(it) => it.into(),
+            },
+            _ => return None,
+        })
+    };
+    let container = owner.as_assoc_item(db).map(|it| match it.container(db) {
+        hir::AssocItemContainer::Trait(it) => hir::GenericDef::from(it),
+        hir::AssocItemContainer::Impl(it) => hir::GenericDef::from(it),
+    });
+    generic_def(owner).into_iter().chain(container).flat_map(|it| it.params(db)).find_map(|param| {
+        let ns = match param {
+            hir::GenericParam::TypeParam(_) => hir::Namespace::Types,
+            hir::GenericParam::ConstParam(_) => hir::Namespace::Values,
+            hir::GenericParam::LifetimeParam(_) => return None,
+        };
+        let found = namespaces.contains(&ns) && param.name(db).to_smol_str() == name;
+        found.then_some((Definition::GenericParam(param), ns))
+    })
+}
+
+/// Resolves `name` among the items of the trait impl `owner` is an item of, then among the items
+/// of the implemented trait.
+fn resolve_in_trait_impl(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    name: &str,
+    namespaces: &[hir::Namespace],
+) -> Option<(Definition, hir::Namespace)> {
+    let hir::AssocItemContainer::Impl(impl_) = owner.as_assoc_item(db)?.container(db) else {
+        return None;
+    };
+    let trait_ = impl_.trait_(db)?;
+    find_assoc_item(db, impl_.items(db), name, namespaces)
+        .or_else(|| find_assoc_item(db, trait_.items(db), name, namespaces))
+}
+
+/// Finds the item named `name` in one of `namespaces` among `items`.
+fn find_assoc_item(
+    db: &dyn HirDatabase,
+    items: Vec<hir::AssocItem>,
+    name: &str,
+    namespaces: &[hir::Namespace],
+) -> Option<(Definition, hir::Namespace)> {
+    items.into_iter().find_map(|item| {
+        let item_ns = assoc_item_namespace(item);
+        let matches = namespaces.contains(&item_ns) && item.name(db)?.to_smol_str() == name;
+        matches.then_some((Definition::from(item), item_ns))
+    })
+}
+
+fn assoc_item_namespace(item: hir::AssocItem) -> hir::Namespace {
+    match item {
+        hir::AssocItem::TypeAlias(_) => hir::Namespace::Types,
+        hir::AssocItem::Function(_) | hir::AssocItem::Const(_) => hir::Namespace::Values,
+    }
+}
+
+pub fn resolve_doc_path_for_def(
+    db: &dyn HirDatabase,
+    def: Definition,
+    link: &str,
+    ns: Option<hir::Namespace>,
+) -> Option<Definition> {
+    resolve_doc_link_path(db, def, link, ns).map(|(def, _)| def)
+}
+
+/// Resolves `path` through the items visible from the docs of `def`.
+fn resolve_in_item_scope(
+    db: &dyn HirDatabase,
+    def: Definition,
+    path: &str,
+    ns: hir::Namespace,
+) -> Option<Definition> {
+    let ns = Some(ns);
+    match def {
+        Definition::Module(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Function(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Adt(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Variant(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Const(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Static(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Trait(it) => it.resolve_doc_path(db, path, ns),
+        Definition::TraitAlias(it) => it.resolve_doc_path(db, path, ns),
+        Definition::TypeAlias(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Macro(it) => it.resolve_doc_path(db, path, ns),
+        Definition::Field(it) => it.resolve_doc_path(db, path, ns),
+        Definition::SelfType(it) => it.resolve_doc_path(db, path, ns),
+        Definition::BuiltinAttr(_)
+        | Definition::ToolModule(_)
+        | Definition::BuiltinType(_)
+        | Definition::Local(_)
+        | Definition::GenericParam(_)
+        | Definition::Label(_)
+        | Definition::DeriveHelper(_) => None,
+    }
+    .map(Definition::from)
+}
+
+/// The type `Self` refers to in the docs of `def`.
+fn self_type_of(db: &dyn HirDatabase, def: Definition) -> Option<Definition> {
+    match def {
+        Definition::Adt(_) | Definition::Trait(_) => Some(def),
+        Definition::SelfType(it) => it.self_ty(db).as_adt().map(Definition::Adt),
+        Definition::Variant(it) => Some(Definition::Adt(it.parent_enum(db).into())),
+        Definition::Field(it) => Some(Definition::Adt(match it.parent_def(db) {
+            hir::VariantDef::Struct(it) => it.into(),
+            hir::VariantDef::Union(it) => it.into(),
+            hir::VariantDef::Variant(it) => it.parent_enum(db).into(),
+        })),
+        _ => match def.as_assoc_item(db)?.container(db) {
+            hir::AssocItemContainer::Trait(it) => Some(Definition::Trait(it)),
+            hir::AssocItemContainer::Impl(it) => it.self_ty(db).as_adt().map(Definition::Adt),
+        },
+    }
+}
+
+/// Resolves a path-based link to a rustdoc page, like `../gateway/struct.Shard.html#method.new`,
+/// written in the docs of `owner`.
+///
+/// The path is taken relative to the directory of the page documenting `owner`, only pages of
+/// `owner`'s crate can be resolved.
+pub fn resolve_doc_html_path(
+    db: &RootDatabase,
+    owner: Definition,
+    path: &str,
+) -> Option<Definition> {
+    if path.contains("://") {
+        return None;
+    }
+    let (path, fragment) = match path.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (path, None),
+    };
+    let (dirs, file) = match path.rsplit_once('/') {
+        Some((dirs, file)) => (Some(dirs), file),
+        None => (None, path),
+    };
+
+    let mut module = doc_page_module(db, owner)?;
+    for segment in dirs.into_iter().flat_map(|it| it.split('/')) {
+        module = match segment {
+            "" | "." => module,
+            ".." => module.parent(db)?,
+            _ => module
+                .children(db)
+                .find(|it| it.name(db).map_or(false, |it| it.to_smol_str() == segment))?,
+        };
+    }
+    let def = match file {
+        "" | "index.html" => Definition::Module(module),
+        _ => {
+            let (kind, name) = file.strip_suffix(".html")?.split_once('.')?;
+            module.scope(db, None).into_iter().find_map(|(it, def)| {
+                let hir::ScopeDef::ModuleDef(def) = def else { return None };
+                let matches = it.to_smol_str() == name
+                    && match def {
+                        hir::ModuleDef::Adt(hir::Adt::Struct(_)) => kind == "struct",
+                        hir::ModuleDef::Adt(hir::Adt::Enum(_)) => kind == "enum",
+                        hir::ModuleDef::Adt(hir::Adt::Union(_)) => kind == "union",
+                        hir::ModuleDef::Trait(_) => kind == "trait",
+                        hir::ModuleDef::TraitAlias(_) => kind == "traitalias",
+                        hir::ModuleDef::Function(_) => kind == "fn",
+                        hir::ModuleDef::TypeAlias(_) => kind == "type",
+                        hir::ModuleDef::Const(_) => kind == "const",
+                        hir::ModuleDef::Static(_) => kind == "static",
+                        hir::ModuleDef::Macro(it) => match it.kind(db) {
+                            hir::MacroKind::Derive => kind == "derive",
+                            hir::MacroKind::Attr => kind == "attr",
+                            _ => kind == "macro",
+                        },
+                        hir::ModuleDef::Module(_)
+                        | hir::ModuleDef::Variant(_)
+                        | hir::ModuleDef::BuiltinType(_) => false,
+                    };
+                matches.then(|| Definition::from(def))
+            })?
+        }
+    };
+
+    match fragment {
+        None => Some(def),
+        Some(fragment) => resolve_doc_page_fragment(db, def, fragment),
+    }
+}
+
+/// The module whose directory contains the page documenting `def`.
+fn doc_page_module(db: &RootDatabase, def: Definition) -> Option<hir::Module> {
+    match def {
+        // primitives are documented by a page at the root of the crate
+        Definition::Module(it) if primitive_of_module(db, it).is_some() => {
+            Some(it.krate().root_module(db))
+        }
+        // a module is documented by the `index.html` in its own directory
+        Definition::Module(it) => Some(it),
+        Definition::SelfType(it) => Some(it.self_ty(db).as_adt()?.module(db)),
+        _ => match def.as_assoc_item(db).map(|it| it.container(db)) {
+            Some(hir::AssocItemContainer::Trait(it)) => Some(it.module(db)),
+            Some(hir::AssocItemContainer::Impl(it)) => Some(it.self_ty(db).as_adt()?.module(db)),
+            None => def.module(db),
+        },
+    }
+}
+
+/// The primitive type a `#[doc(primitive = "...")]` or `#[rustc_doc_primitive = "..."]` module
+/// documents.
+pub fn primitive_of_module(db: &dyn HirDatabase, module: hir::Module) -> Option<String> {
+    let attrs = module.attrs(db);
+    let primitive = attrs
+        .by_key("doc")
+        .find_string_value_in_tt("primitive")
+        .or_else(|| attrs.by_key("rustc_doc_primitive").string_value())?;
+    Some(primitive.trim_matches('"').to_owned())
+}
+
+/// Resolves a fragment of the page of `def`, like `method.new` or `variant.Some`.
+fn resolve_doc_page_fragment(
+    db: &dyn HirDatabase,
+    def: Definition,
+    fragment: &str,
+) -> Option<Definition> {
+    let (kind, name) = fragment.split_once('.')?;
+    match (kind, def) {
+        ("variant", Definition::Adt(hir::Adt::Enum(it))) => it
+            .variants(db)
+            .into_iter()
+            .find(|it| it.name(db).to_smol_str() == name)
+            .map(Definition::Variant),
+        ("structfield", Definition::Adt(it @ (hir::Adt::Struct(_) | hir::Adt::Union(_)))) => {
+            let fields = match it {
+                hir::Adt::Struct(it) => it.fields(db),
+                hir::Adt::Union(it) => it.fields(db),
+                hir::Adt::Enum(_) => return None,
+            };
+            fields.into_iter().find(|it| it.name(db).to_smol_str() == name).map(Definition::Field)
+        }
+        ("method" | "tymethod" | "associatedconstant" | "associatedtype", _) => {
+            let items = match def {
+                Definition::Trait(it) => it.items(db),
+                Definition::Adt(it) => hir::Impl::all_for_type(db, it.ty(db))
+                    .into_iter()
+                    .filter(|it| it.trait_(db).is_none())
+                    .flat_map(|it| it.items(db))
+                    .collect(),
+                _ => return None,
+            };
+            items.into_iter().find_map(|item| {
+                let matches = match item {
+                    hir::AssocItem::Function(_) => matches!(kind, "method" | "tymethod"),
+                    hir::AssocItem::Const(_) => kind == "associatedconstant",
+                    hir::AssocItem::TypeAlias(_) => kind == "associatedtype",
+                };
+                (matches && item.name(db)?.to_smol_str() == name).then(|| Definition::from(item))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Resolves `link` in each namespace separately, returning the distinct definitions it names.
+///
+/// More than one result means the link is ambiguous unless it carries a disambiguator.
+pub fn resolve_doc_path_in_all_namespaces(
+    db: &dyn HirDatabase,
+    def: Definition,
+    link: &str,
+) -> Vec<(hir::Namespace, Definition)> {
+    let mut res: Vec<(hir::Namespace, Definition)> = Vec::new();
+    for ns in [hir::Namespace::Types, hir::Namespace::Values, hir::Namespace::Macros] {
+        if let Some(resolved) = resolve_doc_path_for_def(db, def, link, Some(ns)) {
+            if res.iter().all(|&(_, it)| it != resolved) {
+                res.push((ns, resolved));
+            }
+        }
+    }
+    res
+}
+
+/// Why an intra-doc link does not name a single item, see [`doc_link_resolution_failure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocLinkResolutionFailure {
+    /// Nothing of the link's name, or of the first segment of its path, is in scope.
+    NotInScope,
+    /// The path resolves up to `resolved`, which has no item named `segment`.
+    NoSuchItem { resolved: String, segment: String },
+    /// The qualifier `qualifier` of the path names no type or module, only an item in another
+    /// namespace, like a function, which has no items of its own.
+    QualifierNotAType { qualifier: String },
+    /// The link names an item, just not in the namespace its disambiguator asks for.
+    WrongNamespace { expected: hir::Namespace },
+    /// The link names a value that isn't a function of the kind its `function@` or `method@`
+    /// disambiguator asks for.
+    WrongFunctionKind { expected: FunctionKind },
+    /// The link has no disambiguator and names items in several namespaces.
+    Ambiguous(Vec<hir::Namespace>),
+    /// The link names an item disabled by a `#[cfg]` attribute in the current configuration.
+    Inactive(InactiveDocLinkTarget),
+    /// The last segment of the link's path names nothing, but is a `#[doc(alias)]` of `def`,
+    /// named `name`, found in `ns`. Such links resolve to `def` when
+    /// [`DocLinksDatabase::doc_link_alias_resolution`] is on, rustdoc doesn't resolve them.
+    DocAlias { def: Definition, ns: hir::Namespace, name: String },
+}
+
+/// An item disabled by a `#[cfg]` attribute in the current configuration, like one behind a
+/// feature that is off, that an intra-doc link names. Documentation built with all features
+/// enabled, as on docs.rs, still has its page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InactiveDocLinkTarget {
+    /// The module declaring the item.
+    pub module: hir::Module,
+    /// The kind of the item, like `STRUCT`.
+    pub kind: SyntaxKind,
+    pub name: String,
+    /// The cfg expression disabling the item.
+    pub cfg: hir::CfgExpr,
+}
+
+/// Returns why the intra-doc link `path`, written in the docs of `owner` with a disambiguator
+/// asking for `ns` and, for `function@` and `method@`, for `functions`, does not resolve to a
+/// single item, or `None` if it does.
+pub fn doc_link_resolution_failure(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    path: &str,
+    ns: Option<hir::Namespace>,
+    functions: Option<FunctionKind>,
+) -> Option<DocLinkResolutionFailure> {
+    match ns {
+        Some(ns) => {
+            if let Some((def, _)) = resolve_doc_link_path(db, owner, path, Some(ns)) {
+                return match functions {
+                    Some(expected) if !expected.matches(db, def) => {
+                        Some(DocLinkResolutionFailure::WrongFunctionKind { expected })
+                    }
+                    _ => None,
+                };
+            }
+            if resolve_doc_link_path(db, owner, path, None).is_some() {
+                return Some(DocLinkResolutionFailure::WrongNamespace { expected: ns });
+            }
+        }
+        None => match &*resolve_doc_path_in_all_namespaces(db, owner, path) {
+            [] => (),
+            [_] => return None,
+            resolutions => {
+                let namespaces = resolutions.iter().map(|&(ns, _)| ns).collect();
+                return Some(DocLinkResolutionFailure::Ambiguous(namespaces));
+            }
+        },
+    }
+
+    if let Some(target) = resolve_inactive_item(db, owner, path) {
+        return Some(DocLinkResolutionFailure::Inactive(target));
+    }
+    if let Some((def, ns, name)) = resolve_doc_alias(db, owner, path, ns) {
+        return Some(DocLinkResolutionFailure::DocAlias { def, ns, name });
+    }
+
+    // find the longest prefix of the path that does resolve
+    let mut prefix = path;
+    while let Some((qualifier, segment)) = prefix.rsplit_once("::") {
+        if resolve_doc_link_path(db, owner, qualifier, Some(hir::Namespace::Types)).is_some() {
+            return Some(DocLinkResolutionFailure::NoSuchItem {
+                resolved: qualifier.to_owned(),
+                segment: segment.to_owned(),
+            });
+        }
+        if resolve_doc_link_path(db, owner, qualifier, None).is_some() {
+            return Some(DocLinkResolutionFailure::QualifierNotAType {
+                qualifier: qualifier.to_owned(),
+            });
+        }
+        prefix = qualifier;
+    }
+    Some(DocLinkResolutionFailure::NotInScope)
+}
+
+/// Finds the item `path` names among the items disabled by `#[cfg]` attributes in the module its
+/// qualifier resolves to, or in the module of the docs of `owner` if it has none.
+fn resolve_inactive_item(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    path: &str,
+) -> Option<InactiveDocLinkTarget> {
+    let (qualifier, name) = path.rsplit_once("::").unwrap_or(("self", path));
+    let Definition::Module(module) =
+        resolve_in_item_scope(db, owner, qualifier, hir::Namespace::Types)?
+    else {
+        return None;
+    };
+    module.inactive_items(db).into_iter().find_map(|(item, cfg)| {
+        let item_name = match &item.value {
+            ast::Item::Const(it) => it.name(),
+            ast::Item::Enum(it) => it.name(),
+            ast::Item::Fn(it) => it.name(),
+            ast::Item::MacroDef(it) => it.name(),
+            ast::Item::MacroRules(it) => it.name(),
+            ast::Item::Module(it) => it.name(),
+            ast::Item::Static(it) => it.name(),
+            ast::Item::Struct(it) => it.name(),
+            ast::Item::Trait(it) => it.name(),
+            ast::Item::TraitAlias(it) => it.name(),
+            ast::Item::TypeAlias(it) => it.name(),
+            ast::Item::Union(it) => it.name(),
+            _ => None,
+        }?;
+        let item_name = item_name.text();
+        let item_name = item_name.trim_start_matches("r#");
+        (item_name == name).then(|| InactiveDocLinkTarget {
+            module,
+            kind: item.value.syntax().kind(),
+            name: item_name.to_owned(),
+            cfg,
+        })
+    })
+}
+
+/// Finds the item that has the last segment of `path` as a `#[doc(alias)]`, in one of `ns` or in
+/// any namespace if `None`, returning it along with its namespace and name.
+///
+/// Single segments are looked up among the items of the module of the docs of `owner`, the others
+/// among the items of the module, type or trait their qualifier resolves to.
+fn resolve_doc_alias(
+    db: &dyn HirDatabase,
+    owner: Definition,
+    path: &str,
+    ns: Option<hir::Namespace>,
+) -> Option<(Definition, hir::Namespace, String)> {
+    let (qualifier, alias) = path.rsplit_once("::").unwrap_or(("self", path));
+    let qualifier = match qualifier {
+        "Self" => self_type_of(db, owner)?,
+        _ => resolve_in_item_scope(db, owner, qualifier, hir::Namespace::Types)?,
+    };
+    let candidates: Vec<(Definition, hir::Namespace, String)> = match qualifier {
+        Definition::Module(module) => module
+            .scope(db, None)
+            .into_iter()
+            .filter_map(|(name, def)| {
+                let hir::ScopeDef::ModuleDef(def) = def else { return None };
+                let def_ns = match def {
+                    hir::ModuleDef::Function(_)
+                    | hir::ModuleDef::Variant(_)
+                    | hir::ModuleDef::Const(_)
+                    | hir::ModuleDef::Static(_) => hir::Namespace::Values,
+                    hir::ModuleDef::Macro(_) => hir::Namespace::Macros,
+                    hir::ModuleDef::Module(_)
+                    | hir::ModuleDef::Adt(_)
+                    | hir::ModuleDef::Trait(_)
+                    | hir::ModuleDef::TraitAlias(_)
+                    | hir::ModuleDef::TypeAlias(_)
+                    | hir::ModuleDef::BuiltinType(_) => hir::Namespace::Types,
+                };
+                Some((Definition::from(def), def_ns, name.to_smol_str().to_string()))
+            })
+            .collect(),
+        _ => assoc_items_of(db, qualifier)?
+            .into_iter()
+            .filter_map(|item| {
+                let name = item.name(db)?.to_smol_str().to_string();
+                Some((Definition::from(item), assoc_item_namespace(item), name))
+            })
+            .collect(),
+    };
+    candidates.into_iter().find(|&(def, def_ns, _)| {
+        ns.map_or(true, |ns| ns == def_ns)
+            && def.attrs(db).map_or(false, |attrs| attrs.doc_aliases().any(|it| it == alias))
+    })
+}
+
+/// Finds the doc links resolving to `def` in all the files that can refer to it, optionally
+/// limited to `scope`.
+///
+/// Only doc comments containing the name of `def` are inspected, and only links that spell out
+/// their target ending in that name are returned. The target of a reference link is found in
+/// its reference definition, `[label]: path`, which is returned instead.
+pub fn doc_link_references(
+    sema: &Semantics<'_, RootDatabase>,
+    def: Definition,
+    scope: Option<&SearchScope>,
+) -> IntMap<FileId, Vec<DocLinkReference>> {
+    let _p = profile::span("doc_link_references");
+    let db = sema.db;
+    let mut res = IntMap::default();
+    let Some(name) = def.name(db) else { return res };
+    let name = name.unescaped().to_smol_str();
+    let finder = Finder::new(name.as_str());
+    let search_scope = match scope {
+        Some(scope) => def.search_scope(db).intersection(scope),
+        None => def.search_scope(db),
+    };
+
+    for (file_id, search_range) in search_scope {
+        let text = db.file_text(file_id);
+        let search_range = search_range.unwrap_or_else(|| TextRange::up_to(TextSize::of(&*text)));
+        let tree = Lazy::new(|| sema.parse(file_id).syntax().clone());
+        let mut references: Vec<DocLinkReference> = Vec::new();
+
+        for idx in finder.find_iter(text.as_bytes()) {
+            let Ok(offset) = TextSize::try_from(idx) else { continue };
+            if !search_range.contains_inclusive(offset)
+                || references.iter().any(|it| it.range.contains(offset))
+            {
+                continue;
+            }
+            let Some(doc_comment) =
+                tree.token_at_offset(offset).find_map(|token| token_as_doc_comment(&token))
+            else {
+                continue;
+            };
+            let reference = doc_comment.get_link_or_reference_definition_with_descend_at(
+                sema,
+                offset,
+                |owner, _, link| {
+                    if link.target.rsplit("::").next() != Some(&*name)
+                        || link.resolve(db, owner) != Some(def)
+                    {
+                        return None;
+                    }
+                    let target_range = link.target_range(
+                        text.get(usize::from(link.range.start())..usize::from(link.range.end()))?,
+                    )?;
+                    let name_range = TextRange::new(
+                        target_range.end() - TextSize::of(&*name),
+                        target_range.end(),
+                    );
+                    Some(DocLinkReference { range: link.range, name_range })
+                },
+            );
+            references.extend(reference);
+        }
+        if !references.is_empty() {
+            res.insert(file_id, references);
+        }
+    }
+    res
+}
+
+pub fn doc_attributes(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<(hir::AttrsWithOwner, Definition)> {
+    match_ast! {
+        match node {
+            ast::SourceFile(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
+            ast::Module(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
+            ast::Fn(it)          => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Function(def))),
+            ast::Struct(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Struct(def)))),
+            ast::Union(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Union(def)))),
+            ast::Enum(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Enum(def)))),
+            ast::Variant(it)     => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Variant(def))),
+            ast::Trait(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Trait(def))),
+            ast::Static(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Static(def))),
+            ast::Const(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Const(def))),
+            ast::TypeAlias(it)   => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::TypeAlias(def))),
+            ast::Impl(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::SelfType(def))),
+            ast::RecordField(it) => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
+            ast::TupleField(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
+            ast::Macro(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Macro(def))),
+            // ast::Use(it) => sema.to_def(&it).map(|def| (Box::new(it) as _, def.attrs(sema.db))),
+            _ => None
+        }
+    }
+}
+
+pub struct DocCommentToken {
+    doc_token: SyntaxToken,
+    prefix_len: TextSize,
+}
+
+pub fn token_as_doc_comment(doc_token: &SyntaxToken) -> Option<DocCommentToken> {
+    (match_ast! {
+        match doc_token {
+            ast::Comment(comment) => TextSize::try_from(comment.prefix().len()).ok(),
+            ast::String(string) => {
+                doc_token.parent_ancestors().find_map(ast::Attr::cast).filter(|attr| attr.simple_name().as_deref() == Some("doc"))?;
+                if doc_token.parent_ancestors().find_map(ast::MacroCall::cast).filter(|mac| mac.path().and_then(|p| p.segment()?.name_ref()).as_ref().map(|n| n.text()).as_deref() == Some("include_str")).is_some() {
+                    return None;
+                }
+                string.open_quote_text_range().map(|it| it.len())
+            },
+            _ => None,
+        }
+    }).map(|prefix_len| DocCommentToken { prefix_len, doc_token: doc_token.clone() })
+}
+
+impl DocCommentToken {
+    pub fn get_definition_with_descend_at<T>(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        offset: TextSize,
+        // Definition, CommentOwner, range of intra doc link in original file
+        mut cb: impl FnMut(Definition, SyntaxNode, TextRange) -> Option<T>,
+    ) -> Option<T> {
+        self.get_link_with_descend_at(sema, offset, |owner, node, link| {
+            let def = link.resolve(sema.db, owner)?;
+            cb(def, node, link.range)
+        })
+    }
+
+    /// Finds the link under `offset` without resolving it.
+    ///
+    /// The callback receives the owner of the documentation, the owner's syntax node and the link,
+    /// with the link's range mapped back into the original file.
+    pub fn get_link_with_descend_at<T>(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        offset: TextSize,
+        mut cb: impl FnMut(Definition, SyntaxNode, DocLink) -> Option<T>,
+    ) -> Option<T> {
+        self.find_in_docs_with_descend_at(
+            sema,
+            offset,
+            extract_links_from_docs,
+            |def, node, range, link| cb(def, node, DocLink { range, ..link }),
+        )
+    }
+
+    /// Like [`DocCommentToken::get_link_with_descend_at`], but also finds the reference
+    /// definition under `offset`, as a link of the [`DocLinkKind::Definition`] kind.
+    pub fn get_link_or_reference_definition_with_descend_at<T>(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        offset: TextSize,
+        mut cb: impl FnMut(Definition, SyntaxNode, DocLink) -> Option<T>,
+    ) -> Option<T> {
+        self.find_in_docs_with_descend_at(
+            sema,
+            offset,
+            |docs| {
+                let mut links = extract_links_from_docs(docs);
+                links.extend(extract_reference_definitions_from_docs(docs));
+                links
+            },
+            |def, node, range, link| cb(def, node, DocLink { range, ..link }),
+        )
+    }
+
+    /// Finds the code span under `offset`, outside of any link.
+    ///
+    /// The callback receives the owner of the documentation, the range of the code span including
+    /// its backticks in the original file and the code it contains.
+    pub fn get_code_span_with_descend_at<T>(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        offset: TextSize,
+        mut cb: impl FnMut(Definition, TextRange, String) -> Option<T>,
+    ) -> Option<T> {
+        self.find_in_docs_with_descend_at(
+            sema,
+            offset,
+            extract_code_spans_from_docs,
+            |def, _, range, (_, code)| cb(def, range, code),
+        )
+    }
+
+    /// Finds the element `extract` returns from the docs of the comment's owner that covers
+    /// `offset`, passing its range mapped back into the original file to `cb`.
+    fn find_in_docs_with_descend_at<E: HasDocRange, T>(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        offset: TextSize,
+        extract: impl Fn(&hir::Documentation) -> Vec<E>,
+        mut cb: impl FnMut(Definition, SyntaxNode, TextRange, E) -> Option<T>,
+    ) -> Option<T> {
+        let DocCommentToken { prefix_len, doc_token } = self;
+        // offset relative to the comments contents
+        let original_start = doc_token.text_range().start();
+        let relative_comment_offset = offset - original_start - prefix_len;
+
+        sema.descend_into_macros(doc_token).into_iter().find_map(|t| {
+            let (node, descended_prefix_len) = match_ast! {
+                match t {
+                    ast::Comment(comment) => (t.parent()?, TextSize::try_from(comment.prefix().len()).ok()?),
+                    ast::String(string) => (t.parent_ancestors().skip_while(|n| n.kind() != ATTR).nth(1)?, string.open_quote_text_range()?.len()),
+                    _ => return None,
+                }
+            };
+            let token_start = t.text_range().start();
+            let abs_in_expansion_offset = token_start + relative_comment_offset + descended_prefix_len;
+
+            let (attributes, def) = doc_attributes(sema, &node)?;
+            let (docs, doc_mapping) = attributes.docs_with_rangemap(sema.db)?;
+            let (in_expansion_range, element) =
+                extract(&docs).into_iter().find_map(|element| {
+                    let mapped = doc_mapping.map(element.doc_range())?;
+                    (mapped.value.contains(abs_in_expansion_offset)).then_some((mapped.value, element))
+                })?;
+            // get the relative range to the doc/attribute in the expansion
+            let in_expansion_relative_range = in_expansion_range - descended_prefix_len - token_start;
+            // Apply relative range to the original input comment
+            let absolute_range = in_expansion_relative_range + original_start + prefix_len;
+            cb(def, node, absolute_range, element)
+        })
+    }
+}
+
+/// Something found in a documentation string at a range of it.
+trait HasDocRange {
+    fn doc_range(&self) -> TextRange;
+}
+
+impl HasDocRange for DocLink {
+    fn doc_range(&self) -> TextRange {
+        self.range
+    }
+}
+
+impl HasDocRange for (TextRange, String) {
+    fn doc_range(&self) -> TextRange {
+        self.0
+    }
+}
@@ -0,0 +1,165 @@
+//! How much of the public API of a crate is documented, see
+//! [`DocLinksDatabase::doc_coverage`].
+
+use hir::{db::HirDatabase, Adt, HasVisibility, ModuleDef, Visibility};
+use triomphe::Arc;
+
+use crate::{
+    defs::Definition,
+    doc_links::{
+        doc_link_resolution_failure, extract_links_from_docs, is_path_like, DocLinkKind,
+        DocLinksDatabase,
+    },
+};
+
+/// The documentation coverage of a crate.
+///
+/// Like rustdoc's `missing_docs` lint, the public items are the ones reachable through `pub`
+/// modules, excluding items hidden with `#[doc(hidden)]` and the items of trait impls, which
+/// are documented by the trait.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DocCoverage {
+    /// The public items, the crate root included.
+    pub items: usize,
+    /// The public items with documentation of their own.
+    pub documented: usize,
+    /// The intra-doc links in the documentation of the public items.
+    pub links: usize,
+    /// The links of `links` that don't name a single item.
+    pub broken_links: usize,
+}
+
+pub(super) fn doc_coverage(db: &dyn DocLinksDatabase, krate: hir::Crate) -> Arc<DocCoverage> {
+    let _p = profile::span("doc_coverage");
+    let db = db.upcast();
+    let mut res = DocCoverage::default();
+    for def in public_items(db, krate) {
+        res.items += 1;
+        let Some(attrs) = def.attrs(db) else { continue };
+        let Some(docs) = attrs.docs_with_includes(db) else { continue };
+        res.documented += 1;
+        // counted like the broken links `rust-analyzer doc-links` reports
+        let links = extract_links_from_docs(&docs)
+            .into_iter()
+            .filter(|link| link.kind != DocLinkKind::Autolink && is_path_like(&link.target));
+        for link in links {
+            res.links += 1;
+            if doc_link_resolution_failure(db, def, &link.target, link.ns, link.functions).is_some()
+            {
+                res.broken_links += 1;
+            }
+        }
+    }
+    Arc::new(res)
+}
+
+/// The public items of `krate`, see [`DocCoverage`].
+fn public_items(db: &dyn HirDatabase, krate: hir::Crate) -> Vec<Definition> {
+    let is_shown = |def: Definition| def.attrs(db).map_or(true, |it| !it.has_doc_hidden());
+    let mut res = Vec::new();
+    let mut modules = vec![krate.root_module(db)];
+    while let Some(module) = modules.pop() {
+        res.push(Definition::Module(module));
+        for decl in module.declarations(db) {
+            if decl.visibility(db) != Visibility::Public || !is_shown(decl.into()) {
+                continue;
+            }
+            match decl {
+                ModuleDef::Module(it) => modules.push(it),
+                ModuleDef::Adt(it) => {
+                    res.push(it.into());
+                    let fields = match it {
+                        Adt::Struct(it) => it.fields(db),
+                        Adt::Union(it) => it.fields(db),
+                        Adt::Enum(it) => it
+                            .variants(db)
+                            .into_iter()
+                            .filter(|&it| is_shown(it.into()))
+                            .flat_map(|variant| {
+                                res.push(variant.into());
+                                variant.fields(db)
+                            })
+                            .collect(),
+                    };
+                    let fields = fields.into_iter().filter(|it| {
+                        it.visibility(db) == Visibility::Public && is_shown((*it).into())
+                    });
+                    res.extend(fields.map(Definition::from));
+                }
+                ModuleDef::Trait(it) => {
+                    res.push(it.into());
+                    let items = it.items(db).into_iter().map(Definition::from);
+                    res.extend(items.filter(|&it| is_shown(it)));
+                }
+                _ => res.push(decl.into()),
+            }
+        }
+        for impl_def in module.impl_defs(db) {
+            if impl_def.trait_(db).is_some() || !is_shown(Definition::SelfType(impl_def)) {
+                continue;
+            }
+            let items = impl_def
+                .items(db)
+                .into_iter()
+                .filter(|it| it.visibility(db) == Visibility::Public && is_shown((*it).into()));
+            res.extend(items.map(Definition::from));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::fixture::WithFixture;
+
+    use super::*;
+    use crate::RootDatabase;
+
+    #[track_caller]
+    fn check(ra_fixture: &str, expect: DocCoverage) {
+        let db = RootDatabase::with_files(ra_fixture);
+        let krate = *hir::Crate::all(&db).first().unwrap();
+        assert_eq!(*db.doc_coverage(krate), expect);
+    }
+
+    #[test]
+    fn counts_public_items_and_their_links() {
+        check(
+            r#"
+//! The crate, see [`Client`].
+/// Connects, see [`Client::connect`] and [`Missing`].
+pub struct Client { pub id: u32, secret: u32 }
+impl Client {
+    /// Opens the [connection](Self).
+    pub fn connect() {}
+    fn retry() {}
+}
+impl Clone for Client { fn clone(&self) -> Self { loop {} } }
+pub enum Kind { A, #[doc(hidden)] B }
+pub mod gateway {
+    #[doc = "The [gateway](https://example.com)."]
+    pub fn open() {}
+}
+mod private {
+    pub fn hidden() {}
+}
+#[doc(hidden)]
+pub fn internal() {}
+"#,
+            DocCoverage { items: 8, documented: 4, links: 4, broken_links: 1 },
+        );
+    }
+    #[test]
+    fn counts_the_links_of_included_docs() {
+        check(
+            r#"
+//- /lib.rs crate:foo
+#[doc = include_str!("open.md")]
+pub fn open() {}
+//- /open.md
+Opens the [`Missing`] gateway, see [`open`].
+"#,
+            DocCoverage { items: 2, documented: 1, links: 2, broken_links: 1 },
+        );
+    }
+}
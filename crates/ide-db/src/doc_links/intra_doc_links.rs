@@ -0,0 +1,161 @@
+//! Helper tools for intra doc links.
+
+use hir::{db::HirDatabase, AsAssocItem};
+
+use crate::defs::Definition;
+
+const TYPES: ([&str; 9], [&str; 0]) =
+    (["type", "struct", "enum", "mod", "trait", "union", "module", "prim", "primitive"], []);
+const VALUES: ([&str; 8], [&str; 1]) =
+    (["value", "function", "fn", "method", "const", "static", "mod", "module"], ["()"]);
+const MACROS: ([&str; 2], [&str; 1]) = (["macro", "derive"], ["!"]);
+
+/// Extract the specified namespace from an intra-doc-link if one exists.
+///
+/// # Examples
+///
+/// * `struct MyStruct` -> ("MyStruct", `Namespace::Types`)
+/// * `panic!` -> ("panic", `Namespace::Macros`)
+/// * `fn@from_intra_spec` -> ("from_intra_spec", `Namespace::Values`)
+/// * ``struct `MyStruct` `` -> ("MyStruct", `Namespace::Types`)
+/// * ```` `` `MyStruct` `` ```` -> ("MyStruct", None)
+/// * `&dyn Handler` -> ("Handler", None)
+pub fn parse_intra_doc_link(s: &str) -> (&str, Option<hir::Namespace>) {
+    // the content of a code span delimited by several backticks is padded with spaces when it
+    // starts or ends with a backtick itself
+    let s = s.trim_matches('`').trim_matches(' ').trim_matches('`');
+
+    let (s, ns) = [
+        (hir::Namespace::Types, (TYPES.0.iter(), TYPES.1.iter())),
+        (hir::Namespace::Values, (VALUES.0.iter(), VALUES.1.iter())),
+        (hir::Namespace::Macros, (MACROS.0.iter(), MACROS.1.iter())),
+    ]
+    .into_iter()
+    .find_map(|(ns, (mut prefixes, mut suffixes))| {
+        if let Some(prefix) = prefixes.find(|&&prefix| {
+            s.starts_with(prefix)
+                && s.chars().nth(prefix.len()).map_or(false, |c| c == '@' || c == ' ')
+        }) {
+            Some((&s[prefix.len() + 1..], ns))
+        } else {
+            suffixes.find_map(|&suffix| s.strip_suffix(suffix).zip(Some(ns)))
+        }
+    })
+    // the disambiguator may be written outside of the code span, as in ``struct `MyStruct` ``
+    .map_or((s, None), |(s, ns)| (s.trim_matches('`'), Some(ns)));
+    (strip_type_syntax(s), ns)
+}
+
+/// The functions a `function@` or `method@` disambiguator narrows a link down to, finer than
+/// the value namespace these ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    /// `function@`, functions that aren't associated items.
+    Free,
+    /// `method@`, the associated functions of types and traits.
+    Associated,
+}
+
+impl FunctionKind {
+    /// Whether `def` is a function of this kind.
+    pub fn matches(self, db: &dyn HirDatabase, def: Definition) -> bool {
+        match def {
+            Definition::Function(it) => {
+                it.as_assoc_item(db).is_some() == (self == FunctionKind::Associated)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the functions the disambiguator of an intra-doc link like `method@Shard::connect`
+/// asks for, if it is `function@` or `method@`.
+///
+/// `fn@` and the `()` suffix ask for any function, like all other disambiguators their
+/// namespace is returned by [`parse_intra_doc_link`].
+pub fn parse_function_kind(s: &str) -> Option<FunctionKind> {
+    let s = s.trim_matches('`').trim_matches(' ').trim_matches('`');
+    [("function", FunctionKind::Free), ("method", FunctionKind::Associated)].into_iter().find_map(
+        |(prefix, kind)| {
+            let rest = s.strip_prefix(prefix)?;
+            rest.starts_with(['@', ' ']).then_some(kind)
+        },
+    )
+}
+
+/// Strips the syntax around the path of a link written as a type, like rustdoc: `dyn Trait` and
+/// `impl Trait` link to `Trait`, `&T` and `&mut T` to `T` and `Box<T>` to `Box`.
+fn strip_type_syntax(s: &str) -> &str {
+    let s = s.strip_prefix("&mut ").or_else(|| s.strip_prefix('&')).unwrap_or(s);
+    let s = s.strip_prefix("dyn ").or_else(|| s.strip_prefix("impl ")).unwrap_or(s);
+    match s.strip_suffix('>').and_then(|it| it.split_once('<')) {
+        Some((outer, _)) if !outer.is_empty() => outer,
+        _ => s,
+    }
+}
+
+pub fn strip_prefixes_suffixes(s: &str) -> &str {
+    [
+        (TYPES.0.iter(), TYPES.1.iter()),
+        (VALUES.0.iter(), VALUES.1.iter()),
+        (MACROS.0.iter(), MACROS.1.iter()),
+    ]
+    .into_iter()
+    .find_map(|(mut prefixes, mut suffixes)| {
+        if let Some(prefix) = prefixes.find(|&&prefix| {
+            s.starts_with(prefix)
+                && s.chars().nth(prefix.len()).map_or(false, |c| c == '@' || c == ' ')
+        }) {
+            Some(&s[prefix.len() + 1..])
+        } else {
+            suffixes.find_map(|&suffix| s.strip_suffix(suffix))
+        }
+    })
+    .unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::*;
+
+    fn check(link: &str, expected: Expect) {
+        let (l, a) = parse_intra_doc_link(link);
+        let a = a.map_or_else(String::new, |a| format!(" ({a:?})"));
+        expected.assert_eq(&format!("{l}{a}"));
+    }
+
+    #[test]
+    fn test_name() {
+        check("foo", expect![[r#"foo"#]]);
+        check("struct Struct", expect![[r#"Struct (Types)"#]]);
+        check("makro!", expect![[r#"makro (Macros)"#]]);
+        check("fn@function", expect![[r#"function (Values)"#]]);
+        check("`struct Struct`", expect![[r#"Struct (Types)"#]]);
+        check("struct `Struct`", expect![[r#"Struct (Types)"#]]);
+        check("`function`()", expect![[r#"function (Values)"#]]);
+        check("```struct Struct```", expect![[r#"Struct (Types)"#]]);
+        check("`` `Struct` ``", expect![[r#"Struct"#]]);
+    }
+
+    #[test]
+    fn test_function_kind() {
+        assert_eq!(parse_function_kind("method@Shard::connect"), Some(FunctionKind::Associated));
+        assert_eq!(parse_function_kind("`function@parse`"), Some(FunctionKind::Free));
+        assert_eq!(parse_function_kind("function `parse`"), Some(FunctionKind::Free));
+        assert_eq!(parse_function_kind("fn@parse"), None);
+        assert_eq!(parse_function_kind("parse()"), None);
+        assert_eq!(parse_function_kind("methods"), None);
+    }
+
+    #[test]
+    fn test_type_syntax() {
+        check("dyn Handler", expect![[r#"Handler"#]]);
+        check("impl Future", expect![[r#"Future"#]]);
+        check("&dyn Handler", expect![[r#"Handler"#]]);
+        check("&mut Vec", expect![[r#"Vec"#]]);
+        check("`Box<dyn Error>`", expect![[r#"Box"#]]);
+        check("trait@dyn Handler", expect![[r#"Handler (Types)"#]]);
+    }
+}
@@ -0,0 +1,153 @@
+//! The documentation of definitions, retrieved the same way by all features showing it.
+
+use std::{fmt, sync::Mutex};
+
+use hir::{AsAssocItem, HasAttrs};
+use triomphe::Arc;
+
+use crate::{defs::Definition, RootDatabase};
+
+/// The documentation of a definition, together with the item it is written on.
+///
+/// The intra-doc links in the documentation resolve from that item, its owner. That is the
+/// definition itself, except for `Self` types, which are documented by their type, and for the
+/// items of trait impls without documentation of their own, which show the documentation of the
+/// trait's item.
+#[derive(Clone)]
+pub struct Documentation {
+    text: Arc<str>,
+    owner: Definition,
+    inherited: bool,
+    rewritten: RewrittenDocs,
+}
+
+/// The documentation rewritten by each [`RewriteDocLinks::cache_key`], shared by the clones.
+type RewrittenDocs = Arc<Mutex<Vec<(u64, Arc<str>)>>>;
+
+impl PartialEq for Documentation {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.text, self.owner, self.inherited) == (&other.text, other.owner, other.inherited)
+    }
+}
+
+impl Eq for Documentation {}
+
+impl fmt::Debug for Documentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Documentation")
+            .field("text", &self.text)
+            .field("owner", &self.owner)
+            .field("inherited", &self.inherited)
+            .finish()
+    }
+}
+
+/// Rewrites the links in documentation, implemented by the link options of the IDE layer.
+pub trait RewriteDocLinks {
+    /// Rewrites the links in `docs`, the documentation of `owner`.
+    fn rewrite_doc_links(&self, db: &RootDatabase, docs: &str, owner: Definition) -> String;
+
+    /// Tells rewriters apart, two rewriters with the same key rewrite documentation the same.
+    fn cache_key(&self) -> u64;
+}
+
+impl Documentation {
+    /// Retrieves the documentation of `def`.
+    ///
+    /// The doc comments and `#[doc = "..."]` attributes of the owner are joined like rustdoc
+    /// does, expanding `#[doc = include_str!("...")]`. Doc attributes of `#[cfg_attr]`s only
+    /// count when their condition holds.
+    pub fn for_definition(db: &RootDatabase, def: &Definition) -> Option<Documentation> {
+        Documentation::retrieve(db, def, false)
+    }
+
+    /// Like [`Documentation::for_definition`], but also taking the doc attributes of
+    /// `#[cfg_attr]`s whose condition doesn't hold, like
+    /// `#[cfg_attr(feature = "docs", doc = "...")]`.
+    pub fn with_inactive_cfg_attrs(db: &RootDatabase, def: &Definition) -> Option<Documentation> {
+        Documentation::retrieve(db, def, true)
+    }
+
+    fn retrieve(db: &RootDatabase, def: &Definition, inactive_cfg: bool) -> Option<Documentation> {
+        let owner = match *def {
+            Definition::SelfType(it) => Definition::Adt(it.self_ty(db).as_adt()?),
+            def => def,
+        };
+        let attrs = owner.attrs(db)?;
+        let docs = match inactive_cfg {
+            true => attrs.docs_with_inactive_cfg_attrs(db),
+            false => attrs.docs_with_includes(db),
+        };
+        let (owner, docs, inherited) = match docs {
+            Some(docs) => (owner, docs, false),
+            None => {
+                let (item, docs) = trait_item_docs(db, owner, inactive_cfg)?;
+                (item, docs, true)
+            }
+        };
+        Some(Documentation {
+            text: docs.as_str().into(),
+            owner,
+            inherited,
+            rewritten: Default::default(),
+        })
+    }
+
+    /// The item the documentation is written on.
+    pub fn owner(&self) -> Definition {
+        self.owner
+    }
+
+    /// Whether the documentation is the one of the trait item the definition implements.
+    pub fn is_inherited(&self) -> bool {
+        self.inherited
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn text(&self) -> Arc<str> {
+        self.text.clone()
+    }
+
+    /// The documentation with its links rewritten by `rewriter`, resolving them from the owner.
+    ///
+    /// The result is kept for the next calls with the same rewriter, on this documentation and
+    /// its clones, which are retrieved for a single request.
+    pub fn rewritten(&self, db: &RootDatabase, rewriter: &dyn RewriteDocLinks) -> Arc<str> {
+        let key = rewriter.cache_key();
+        let cached = self.rewritten.lock().unwrap().iter().find(|(it, _)| *it == key).cloned();
+        if let Some((_, docs)) = cached {
+            return docs;
+        }
+        let docs: Arc<str> = rewriter.rewrite_doc_links(db, &self.text, self.owner).into();
+        self.rewritten.lock().unwrap().push((key, docs.clone()));
+        docs
+    }
+}
+
+impl From<Documentation> for hir::Documentation {
+    fn from(docs: Documentation) -> Self {
+        hir::Documentation::new(docs.as_str().to_owned())
+    }
+}
+
+/// The documentation of the trait item `def` implements, if it is an item of a trait impl,
+/// retrieved like the one of `def`.
+fn trait_item_docs(
+    db: &RootDatabase,
+    def: Definition,
+    inactive_cfg: bool,
+) -> Option<(Definition, hir::Documentation)> {
+    let assoc = def.as_assoc_item(db)?;
+    let trait_ = assoc.containing_trait_impl(db)?;
+    let name = Some(assoc.name(db)?);
+    let item = trait_.items(db).into_iter().find(|it| it.name(db) == name)?;
+    let attrs = item.attrs(db);
+    let docs = match inactive_cfg {
+        true => attrs.docs_with_inactive_cfg_attrs(db),
+        false => attrs.docs_with_includes(db),
+    };
+    Some((item.into(), docs?))
+}
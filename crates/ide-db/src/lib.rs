@@ -9,6 +9,8 @@ mod apply_change;
 pub mod active_parameter;
 pub mod assists;
 pub mod defs;
+pub mod doc_links;
+pub mod documentation;
 pub mod famous_defs;
 pub mod helpers;
 pub mod items_locator;
@@ -51,7 +53,7 @@ use base_db::{
 use hir::db::{DefDatabase, ExpandDatabase, HirDatabase};
 use triomphe::Arc;
 
-use crate::{line_index::LineIndex, symbol_index::SymbolsDatabase};
+use crate::{doc_links::DocLinksDatabase, line_index::LineIndex, symbol_index::SymbolsDatabase};
 pub use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
 
 pub use ::line_index;
@@ -71,7 +73,8 @@ pub type FxIndexMap<K, V> =
     hir::db::HirDatabaseStorage,
     hir::db::InternDatabaseStorage,
     LineIndexDatabaseStorage,
-    symbol_index::SymbolsDatabaseStorage
+    symbol_index::SymbolsDatabaseStorage,
+    doc_links::DocLinksDatabaseStorage
 )]
 pub struct RootDatabase {
     // We use `ManuallyDrop` here because every codegen unit that contains a
@@ -139,6 +142,11 @@ impl RootDatabase {
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_expand_proc_attr_macros_with_durability(false, Durability::HIGH);
+        db.set_doc_link_resolution_budget_with_durability(
+            doc_links::DEFAULT_DOC_LINK_RESOLUTION_BUDGET,
+            Durability::HIGH,
+        );
+        db.set_doc_link_alias_resolution_with_durability(false, Durability::HIGH);
         db.update_parse_query_lru_capacity(lru_capacity);
         db
     }
@@ -147,6 +155,14 @@ impl RootDatabase {
         self.set_expand_proc_attr_macros_with_durability(true, Durability::HIGH);
     }
 
+    pub fn update_doc_link_resolution_budget(&mut self, budget: usize) {
+        self.set_doc_link_resolution_budget_with_durability(budget, Durability::HIGH);
+    }
+
+    pub fn update_doc_link_alias_resolution(&mut self, enable: bool) {
+        self.set_doc_link_alias_resolution_with_durability(enable, Durability::HIGH);
+    }
+
     pub fn update_parse_query_lru_capacity(&mut self, lru_capacity: Option<usize>) {
         let lru_capacity = lru_capacity.unwrap_or(base_db::DEFAULT_PARSE_LRU_CAP);
         base_db::ParseQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
@@ -298,6 +314,13 @@ impl RootDatabase {
             // LineIndexDatabase
             crate::LineIndexQuery
 
+            // DocLinksDatabase
+            doc_links::DocLinkResolutionsQuery
+            doc_links::CrateDocBaseUrlQuery
+            doc_links::DocCoverageQuery
+            // doc_links::DocLinkResolutionBudgetQuery
+            // doc_links::DocLinkAliasResolutionQuery
+
             // InternDatabase
             // hir_db::InternFunctionQuery
             // hir_db::InternStructQuery
@@ -324,6 +347,26 @@ impl salsa::ParallelDatabase for RootDatabase {
     }
 }
 
+/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
+pub struct Snap<DB>(DB);
+impl<DB: salsa::ParallelDatabase> Snap<salsa::Snapshot<DB>> {
+    pub fn new(db: &DB) -> Self {
+        Self(db.snapshot())
+    }
+}
+impl<DB: salsa::ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
+    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
+        Snap(self.0.snapshot())
+    }
+}
+impl<DB> std::ops::Deref for Snap<DB> {
+    type Target = DB;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[salsa::query_group(LineIndexDatabaseStorage)]
 pub trait LineIndexDatabase: base_db::SourceDatabase {
     fn line_index(&self, file_id: FileId) -> Arc<LineIndex>;
@@ -34,6 +34,7 @@ use text_edit::{TextEdit, TextEditBuilder};
 
 use crate::{
     defs::Definition,
+    doc_links::{doc_link_references, DocLinkReference},
     search::FileReference,
     source_change::{FileSystemEdit, SourceChange},
     syntax_helpers::node_ext::expr_as_name_ref,
@@ -246,6 +247,7 @@ fn rename_mod(
         (file_id, source_edit_from_references(references, def, new_name))
     });
     source_change.extend(ref_edits);
+    source_change.extend(doc_link_edits(sema, def, new_name));
 
     Ok(source_change)
 }
@@ -289,6 +291,7 @@ fn rename_reference(
     source_change.extend(usages.iter().map(|(&file_id, references)| {
         (file_id, source_edit_from_references(references, def, new_name))
     }));
+    source_change.extend(doc_link_edits(sema, def, new_name));
 
     let mut insert_def_edit = |def| {
         let (file_id, edit) = source_edit_from_def(sema, def, new_name)?;
@@ -343,6 +346,22 @@ pub fn source_edit_from_references(
     edit.finish()
 }
 
+/// Renames the final path segment of every intra-doc link that resolves to `def`.
+fn doc_link_edits(
+    sema: &Semantics<'_, RootDatabase>,
+    def: Definition,
+    new_name: &str,
+) -> impl Iterator<Item = (FileId, TextEdit)> {
+    let new_name = new_name.trim_start_matches("r#").to_owned();
+    doc_link_references(sema, def, None).into_iter().map(move |(file_id, references)| {
+        let mut edit = TextEdit::builder();
+        for DocLinkReference { name_range, .. } in references {
+            edit.replace(name_range, new_name.clone());
+        }
+        (file_id, edit.finish())
+    })
+}
+
 fn source_edit_from_name(edit: &mut TextEditBuilder, name: &ast::Name, new_name: &str) -> bool {
     if ast::RecordPatField::for_field_name(name).is_some() {
         if let Some(ident_pat) = name.syntax().parent().and_then(ast::IdentPat::cast) {
@@ -73,9 +73,8 @@ pub enum ReferenceCategory {
     Write,
     Read,
     Import,
-    // FIXME: Some day should be able to search in doc comments. Would probably
-    // need to switch from enum to bitflags then?
-    // DocComment
+    /// An intra-doc link in a doc comment, see [`crate::doc_links::doc_link_references`].
+    DocLink,
 }
 
 /// Generally, `search_scope` returns files that might contain references for the element.
@@ -215,7 +214,7 @@ impl IntoIterator for SearchScope {
 }
 
 impl Definition {
-    fn search_scope(&self, db: &RootDatabase) -> SearchScope {
+    pub(crate) fn search_scope(&self, db: &RootDatabase) -> SearchScope {
         let _p = profile::span("search_scope");
 
         if let Definition::BuiltinType(_) = self {
@@ -96,6 +96,7 @@ pub struct SourceChangeBuilder {
     pub file_id: FileId,
     pub source_change: SourceChange,
     pub trigger_signature_help: bool,
+    pub copy_to_clipboard: Option<String>,
 
     /// Maps the original, immutable `SyntaxNode` to a `clone_for_update` twin.
     pub mutated_tree: Option<TreeMutator>,
@@ -138,6 +139,7 @@ impl SourceChangeBuilder {
             file_id,
             source_change: SourceChange::default(),
             trigger_signature_help: false,
+            copy_to_clipboard: None,
             mutated_tree: None,
             snippet_builder: None,
         }
@@ -233,6 +235,9 @@ impl SourceChangeBuilder {
     pub fn trigger_signature_help(&mut self) {
         self.trigger_signature_help = true;
     }
+    pub fn copy_to_clipboard(&mut self, text: impl Into<String>) {
+        self.copy_to_clipboard = Some(text.into());
+    }
 
     /// Adds a tabstop snippet to place the cursor before `node`
     pub fn add_tabstop_before(&mut self, _cap: SnippetCap, node: impl AstNode) {
@@ -27,10 +27,7 @@ use std::{
     mem,
 };
 
-use base_db::{
-    salsa::{self, ParallelDatabase},
-    SourceDatabaseExt, SourceRootId, Upcast,
-};
+use base_db::{salsa, SourceDatabaseExt, SourceRootId, Upcast};
 use fst::{self, Streamer};
 use hir::{
     db::HirDatabase,
@@ -41,7 +38,7 @@ use rayon::prelude::*;
 use rustc_hash::FxHashSet;
 use triomphe::Arc;
 
-use crate::RootDatabase;
+use crate::{RootDatabase, Snap};
 
 #[derive(Debug)]
 pub struct Query {
@@ -143,26 +140,6 @@ pub fn crate_symbols(db: &dyn SymbolsDatabase, krate: Crate) -> Box<[Arc<SymbolI
     krate.modules(db.upcast()).into_iter().map(|module| db.module_symbols(module)).collect()
 }
 
-/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
-struct Snap<DB>(DB);
-impl<DB: ParallelDatabase> Snap<salsa::Snapshot<DB>> {
-    fn new(db: &DB) -> Self {
-        Self(db.snapshot())
-    }
-}
-impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
-    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
-        Snap(self.0.snapshot())
-    }
-}
-impl<DB> std::ops::Deref for Snap<DB> {
-    type Target = DB;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
 // Feature: Workspace Symbol
 //
 // Uses fuzzy-search to find types, modules and functions by name across your
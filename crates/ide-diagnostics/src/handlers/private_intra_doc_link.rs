@@ -0,0 +1,331 @@
+use hir::{AsAssocItem, AssocItemContainer, HasAttrs, HasVisibility, Semantics, Visibility};
+use ide_db::{
+    base_db::FileId,
+    defs::Definition,
+    doc_links::{doc_attributes, extract_links_from_docs, resolve_doc_link_path, DocLink},
+    RootDatabase,
+};
+use syntax::{SyntaxNode, TextRange};
+
+use crate::{Diagnostic, Severity};
+
+// Diagnostic: private-intra-doc-link
+//
+// This diagnostic is shown when the documentation of a public item links to an item
+// rustdoc will not document, which makes the rendered link dead.
+pub(crate) fn private_intra_doc_link(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (_, owner) = doc_attributes(sema, node)?;
+    if !is_documented_publicly(sema.db, owner) {
+        return None;
+    }
+    for (link, target, range) in resolved_doc_links(sema, file_id, node)? {
+        let Some(reason) = undocumented_reason(sema.db, target) else { continue };
+        let message = reason.describe(sema.db, &link.target);
+        acc.push(
+            Diagnostic::new("private-intra-doc-link", message, range)
+                .severity(Severity::WeakWarning),
+        );
+    }
+    Some(())
+}
+
+/// Resolves the intra-doc links in the docs of the item `node` defines.
+///
+/// Returns the links with what they resolve to and the range of their target in `file_id`, or
+/// of the whole link if its target is defined elsewhere. Links that don't resolve, or come from
+/// another file, are skipped.
+fn resolved_doc_links(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<Vec<(DocLink, Definition, TextRange)>> {
+    let (attrs, owner) = doc_attributes(sema, node)?;
+    let (docs, mapping) = attrs.docs_with_rangemap(sema.db)?;
+    let links = extract_links_from_docs(&docs)
+        .into_iter()
+        .filter_map(|link| {
+            let (target, _) = resolve_doc_link_path(sema.db, owner, &link.target, link.ns)?;
+            let range = link
+                .target_range(docs.as_str().get(std::ops::Range::<usize>::from(link.range))?)
+                .unwrap_or(link.range);
+            let range = mapping.map(range).filter(|it| it.file_id == file_id.into())?.value;
+            Some((link, target, range))
+        })
+        .collect();
+    Some(links)
+}
+
+/// Whether `def` is documented when documenting its crate without private items.
+fn is_documented_publicly(db: &RootDatabase, def: Definition) -> bool {
+    undocumented_reason(db, def).is_none()
+}
+
+enum Undocumented {
+    Private,
+    Hidden,
+    InPrivateModule(hir::Module),
+    InHiddenModule(hir::Module),
+    InUndocumentedItem(Definition, bool),
+}
+
+impl Undocumented {
+    fn describe(&self, db: &RootDatabase, target: &str) -> String {
+        let name = |it: Option<hir::Name>| it.map(|it| it.to_string()).unwrap_or_default();
+        match self {
+            Undocumented::Private => {
+                format!("public documentation links to private item `{target}`")
+            }
+            Undocumented::Hidden => {
+                format!("public documentation links to `#[doc(hidden)]` item `{target}`")
+            }
+            Undocumented::InPrivateModule(module) => format!(
+                "public documentation links to `{target}`, which is inside the private module `{}`",
+                name(module.name(db)),
+            ),
+            Undocumented::InHiddenModule(module) => format!(
+                "public documentation links to `{target}`, which is inside the hidden module `{}`",
+                name(module.name(db)),
+            ),
+            Undocumented::InUndocumentedItem(parent, hidden) => format!(
+                "public documentation links to `{target}`, which belongs to the {} item `{}`",
+                if *hidden { "`#[doc(hidden)]`" } else { "private" },
+                name(parent.name(db)),
+            ),
+        }
+    }
+}
+
+/// Returns why rustdoc would not document `def`, if it doesn't.
+///
+/// Fields, variants and associated items are only documented with their parent, and items
+/// only if all their modules are public. Re-exports are not considered.
+fn undocumented_reason(db: &RootDatabase, def: Definition) -> Option<Undocumented> {
+    let (visibility, attrs) = match def {
+        Definition::Module(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Function(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Adt(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Variant(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Field(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Const(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Static(it) => (it.visibility(db), it.attrs(db)),
+        Definition::Trait(it) => (it.visibility(db), it.attrs(db)),
+        Definition::TraitAlias(it) => (it.visibility(db), it.attrs(db)),
+        Definition::TypeAlias(it) => (it.visibility(db), it.attrs(db)),
+        // `macro_rules!` macros are documented when exported, which their visibility reflects
+        Definition::Macro(it) => (it.visibility(db), it.attrs(db)),
+        _ => return None,
+    };
+    // variants and items of traits and trait impls have the visibility of their parent, which is
+    // checked below
+    let own_visibility = match def.as_assoc_item(db) {
+        Some(it) => it.containing_trait_or_trait_impl(db).is_none(),
+        None => !matches!(def, Definition::Variant(_)),
+    };
+    if own_visibility && visibility != Visibility::Public {
+        return Some(Undocumented::Private);
+    }
+    if attrs.has_doc_hidden() {
+        return Some(Undocumented::Hidden);
+    }
+    if let Some(parent) = parent_item(db, def) {
+        match undocumented_reason(db, parent) {
+            Some(Undocumented::Private) => {
+                return Some(Undocumented::InUndocumentedItem(parent, false))
+            }
+            Some(Undocumented::Hidden) => {
+                return Some(Undocumented::InUndocumentedItem(parent, true))
+            }
+            Some(reason) => return Some(reason),
+            None => (),
+        }
+    }
+    // exported macros are documented at the crate root, wherever they are defined
+    if matches!(def, Definition::Macro(it) if it.is_macro_export(db)) {
+        return None;
+    }
+    let mut module = def.module(db);
+    while let Some(it) = module {
+        if it.attrs(db).has_doc_hidden() {
+            return Some(Undocumented::InHiddenModule(it));
+        }
+        if it.parent(db).is_some() && it.visibility(db) != Visibility::Public {
+            return Some(Undocumented::InPrivateModule(it));
+        }
+        module = it.parent(db);
+    }
+    None
+}
+
+/// The item `def` is documented with, like the ADT of a field or the self type of an impl.
+fn parent_item(db: &RootDatabase, def: Definition) -> Option<Definition> {
+    match def {
+        Definition::Field(it) => Some(match it.parent_def(db) {
+            hir::VariantDef::Struct(it) => Definition::Adt(it.into()),
+            hir::VariantDef::Union(it) => Definition::Adt(it.into()),
+            hir::VariantDef::Variant(it) => Definition::Variant(it),
+        }),
+        Definition::Variant(it) => Some(Definition::Adt(it.parent_enum(db).into())),
+        _ => match def.as_assoc_item(db)?.container(db) {
+            AssocItemContainer::Trait(it) => Some(Definition::Trait(it)),
+            AssocItemContainer::Impl(it) => it.self_ty(db).as_adt().map(Definition::Adt),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    // Doc comments look like annotations to the test fixture, so these use block doc comments
+    // and `#[doc]` attributes.
+
+    #[test]
+    fn links_to_private_items() {
+        check_diagnostics(
+            r#"
+/** Calls [internal::do_it] and [`Helper`]. */
+         //^^^^^^^^^^^^^^^ weak: public documentation links to private item `internal::do_it`
+                                //^^^^^^ weak: public documentation links to private item `Helper`
+pub fn run() {}
+
+#[doc = "See [`Helper`]."]
+             //^^^^^^ weak: public documentation links to private item `Helper`
+pub fn see() {}
+
+#[doc = "Links to [`Public`](Public) and [`run`]."]
+                //^^^^^^^^^^^^^^^^^^ 💡 weak: redundant explicit link target, `[`Public`]` links to `Public` already
+pub struct Public;
+
+struct Helper;
+
+mod internal {
+    pub(crate) fn do_it() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn links_to_hidden_items() {
+        check_diagnostics(
+            r#"
+#[doc = "See [`Hidden`] and [`hidden::Item`]."]
+             //^^^^^^ weak: public documentation links to `#[doc(hidden)]` item `Hidden`
+                            //^^^^^^^^^^^^ weak: public documentation links to `hidden::Item`, which is inside the hidden module `hidden`
+pub fn run() {}
+
+#[doc = "See [`hidden::Item`]."]
+             //^^^^^^^^^^^^ weak: public documentation links to `hidden::Item`, which is inside the hidden module `hidden`
+pub fn see() {}
+
+#[doc(hidden)]
+pub struct Hidden;
+
+#[doc(hidden)]
+pub mod hidden {
+    pub struct Item;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn private_items_are_not_flagged() {
+        check_diagnostics(
+            r#"
+#[doc = "Calls [`helper`]."]
+fn private() {}
+
+#[doc = "Calls [`helper`]."]
+#[doc(hidden)]
+pub fn hidden() {}
+
+pub(crate) mod internal {
+    #[doc = "Calls [`super::helper`]."]
+    pub fn run() {}
+}
+
+fn helper() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn links_to_members_of_private_items() {
+        check_diagnostics(
+            r#"
+#[doc = "See [`Helper::field`], [`Helper::new`] and [`Kind::A`]."]
+             //^^^^^^^^^^^^^ weak: public documentation links to `Helper::field`, which belongs to the private item `Helper`
+                                //^^^^^^^^^^^ weak: public documentation links to `Helper::new`, which belongs to the private item `Helper`
+                                                    //^^^^^^^ weak: public documentation links to `Kind::A`, which belongs to the private item `Kind`
+pub fn run() {}
+
+#[doc = "See [`Public::field`] and [`Public::new`]."]
+pub fn see() {}
+
+struct Helper {
+    pub field: u32,
+}
+
+impl Helper {
+    pub fn new() -> Self { loop {} }
+}
+
+enum Kind {
+    A,
+}
+
+pub struct Public {
+    pub field: u32,
+}
+
+impl Public {
+    pub fn new() -> Self { loop {} }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn links_to_items_in_private_modules() {
+        check_diagnostics(
+            r#"
+#[doc = "See [`internal::Item`], [`internal::Item::new`] and [`exported!`]."]
+             //^^^^^^^^^^^^^^ weak: public documentation links to `internal::Item`, which is inside the private module `internal`
+                                 //^^^^^^^^^^^^^^^^^^^ weak: public documentation links to `internal::Item::new`, which is inside the private module `internal`
+pub fn run() {}
+
+mod internal {
+    pub struct Item;
+
+    impl Item {
+        pub fn new() -> Self { loop {} }
+    }
+
+    #[macro_export]
+    macro_rules! exported { () => {} }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reference_links_cover_the_whole_link() {
+        check_diagnostics(
+            r#"
+#[doc = "Calls [the helper]."]
+             //^^^^^^^^^^^^ weak: public documentation links to private item `helper`
+#[doc = ""]
+#[doc = "[the helper]: helper"]
+pub fn run() {}
+
+fn helper() {}
+"#,
+        );
+    }
+}
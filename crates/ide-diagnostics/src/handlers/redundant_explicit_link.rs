@@ -0,0 +1,202 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::FileId,
+    doc_links::{
+        defines_reference, doc_attributes, doc_link_scope, extract_links_from_docs,
+        intra_doc_links::parse_intra_doc_link, resolve_doc_link_path, DocLinkKind,
+    },
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::SyntaxNode;
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: redundant-explicit-link
+//
+// This diagnostic is shown for inline doc links like `[Foo](crate::Foo)` whose text is a path
+// naming the same item as their target, the shortcut link `[Foo]` says the same.
+pub(crate) fn redundant_explicit_link(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (attrs, owner) = doc_attributes(sema, node)?;
+    let (docs, mapping) = attrs.docs_with_rangemap(sema.db)?;
+    let scope_at = doc_link_scope(sema.db, owner, docs.as_str());
+    for link in extract_links_from_docs(&docs) {
+        if link.kind != DocLinkKind::Inline {
+            continue;
+        }
+        let Some(source) = docs.as_str().get(std::ops::Range::<usize>::from(link.range)) else {
+            continue;
+        };
+        let Some(text) = source.rfind("](").and_then(|end| source.get(1..end)) else { continue };
+        let Some(text_path) = link_text_path(text) else { continue };
+        // `[text]` would link to the reference definition
+        if defines_reference(docs.as_str(), text) {
+            continue;
+        }
+
+        let scope = scope_at(link.range.start());
+        let Some((target, _)) = resolve_doc_link_path(sema.db, scope, &link.target, link.ns) else {
+            continue;
+        };
+        let (path, ns) = parse_intra_doc_link(text_path);
+        match resolve_doc_link_path(sema.db, scope, path, ns) {
+            Some((it, _)) if it == target => (),
+            _ => continue,
+        }
+
+        let Some(range) = mapping.map(link.range).filter(|it| it.file_id == file_id.into()) else {
+            continue;
+        };
+        let range = range.value;
+        let shortcut = format!("[{text}]");
+        let message = format!(
+            "redundant explicit link target, `{shortcut}` links to `{}` already",
+            link.target
+        );
+        // shortcut links can't have a title
+        let fixes = link.title.is_empty().then(|| {
+            let edit = TextEdit::replace(range, shortcut);
+            vec![fix(
+                "use_shortcut_link",
+                "Use a shortcut link",
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )]
+        });
+        acc.push(
+            Diagnostic::new("redundant-explicit-link", message, range)
+                .severity(Severity::WeakWarning)
+                .with_fixes(fixes),
+        );
+    }
+    Some(())
+}
+
+/// The path the text of a link spells, if it is nothing but a path, like `crate::Foo`, in or out
+/// of backticks and with or without a disambiguator like `fn@` or `()`. Any other text is prose,
+/// even if it happens to resolve.
+fn link_text_path(text: &str) -> Option<&str> {
+    let path = match text.strip_prefix('`') {
+        Some(it) => it.strip_suffix('`')?,
+        None => text,
+    };
+    let (stripped, _) = parse_intra_doc_link(path);
+    let is_path = !stripped.is_empty()
+        && stripped.split("::").all(|segment| {
+            let mut chars = segment.chars();
+            chars.next().map_or(false, |c| c.is_alphabetic() || c == '_')
+                && chars.all(|c| c.is_alphanumeric() || c == '_')
+        });
+    is_path.then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_no_fix};
+
+    // Doc comments look like annotations to the test fixture, so these use block doc comments
+    // and `#[doc]` attributes.
+
+    #[test]
+    fn links_whose_text_names_the_target() {
+        check_diagnostics(
+            r#"
+/** See [Shard](Shard) and [`Shard`](crate::Shard). */
+      //^^^^^^^^^^^^^^ 💡 weak: redundant explicit link target, `[Shard]` links to `Shard` already
+                         //^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: redundant explicit link target, `[`Shard`]` links to `crate::Shard` already
+pub fn connect() {}
+
+#[doc = "Calls [`gateway::start()`](fn@gateway::start)."]
+             //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: redundant explicit link target, `[`gateway::start()`]` links to `gateway::start` already
+pub fn run() {}
+
+pub struct Shard;
+pub mod gateway {
+    pub fn start() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn links_with_prose_or_other_targets_are_not_flagged() {
+        check_diagnostics(
+            r#"
+#[doc = "The [shard](Shard), [the `Shard`](Shard), [*Shard*](Shard) and [`start`](gateway::start)."]
+pub fn connect() {}
+
+#[doc = "Links to [Shard](gateway::Shard), [`Missing`](Missing) and [Shard](https://docs.rs)."]
+pub fn run() {}
+
+pub struct Shard;
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn text_resolving_in_another_namespace_is_not_flagged() {
+        check_diagnostics(
+            r#"
+#[doc = "The [`shard`](fn@shard) and [shard](macro@shard)."]
+pub fn connect() {}
+
+pub mod shard {}
+pub fn shard() {}
+#[macro_export]
+macro_rules! shard { () => {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn links_with_a_reference_definition_for_their_text_are_not_flagged() {
+        check_diagnostics(
+            r#"
+/**
+See [Shard](Shard).
+
+[Shard]: https://example.com/shard
+*/
+pub fn connect() {}
+
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn links_with_a_title_have_no_fix() {
+        check_no_fix(
+            r#"
+#[doc = "See [`Shard`](crate::Shard$0 \"the shard\")."]
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_uses_shortcut_links() {
+        check_fix(
+            r#"
+#[doc = "See [`Shard`](crate::Shard$0)."]
+pub fn connect() {}
+pub struct Shard;
+"#,
+            r#"
+#[doc = "See [`Shard`]."]
+pub fn connect() {}
+pub struct Shard;
+"#,
+        );
+    }
+}
@@ -63,6 +63,7 @@ fn method_fix(
             TextEdit::insert(range.end(), "()".to_owned()),
         )),
         trigger_signature_help: false,
+        copy_to_clipboard: None,
     }])
 }
 #[cfg(test)]
@@ -118,6 +118,7 @@ mod baz {}
                                         },
                                     ),
                                     trigger_signature_help: false,
+                                    copy_to_clipboard: None,
                                 },
                                 Assist {
                                     id: AssistId(
@@ -145,6 +146,7 @@ mod baz {}
                                         },
                                     ),
                                     trigger_signature_help: false,
+                                    copy_to_clipboard: None,
                                 },
                             ],
                         ),
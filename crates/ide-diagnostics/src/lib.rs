@@ -42,6 +42,8 @@ mod handlers {
     pub(crate) mod no_such_field;
     pub(crate) mod private_assoc_item;
     pub(crate) mod private_field;
+    pub(crate) mod private_intra_doc_link;
+    pub(crate) mod redundant_explicit_link;
     pub(crate) mod replace_filter_map_next_with_find_map;
     pub(crate) mod type_mismatch;
     pub(crate) mod unimplemented_builtin_macro;
@@ -238,6 +240,8 @@ pub fn diagnostics(
         handlers::useless_braces::useless_braces(&mut res, file_id, &node);
         handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
         handlers::json_is_not_rust::json_in_items(&sema, &mut res, file_id, &node, config);
+        handlers::private_intra_doc_link::private_intra_doc_link(&sema, &mut res, file_id, &node);
+        handlers::redundant_explicit_link::redundant_explicit_link(&sema, &mut res, file_id, &node);
     }
 
     let module = sema.to_module_def(file_id);
@@ -328,6 +332,7 @@ fn unresolved_fix(id: &'static str, label: &str, target: TextRange) -> Assist {
         target,
         source_change: None,
         trigger_signature_help: false,
+        copy_to_clipboard: None,
     }
 }
 
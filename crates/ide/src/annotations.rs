@@ -1,4 +1,4 @@
-use hir::{HasSource, InFile, Semantics};
+use hir::{HasAttrs, HasSource, HasVisibility, InFile, ModuleDef, Semantics, Visibility};
 use ide_db::{
     base_db::{FileId, FilePosition, FileRange},
     defs::Definition,
@@ -9,7 +9,9 @@ use syntax::{ast::HasName, AstNode, TextRange};
 
 use crate::{
     annotations::fn_references::find_all_methods,
+    doc_links::{has_web_docs, web_doc_url},
     goto_implementation::goto_implementation,
+    navigation_target::TryToNav,
     references::find_all_refs,
     runnables::{runnables, Runnable},
     NavigationTarget, RunnableKind,
@@ -19,8 +21,8 @@ mod fn_references;
 
 // Feature: Annotations
 //
-// Provides user with annotations above items for looking up references or impl blocks
-// and running/debugging binaries.
+// Provides user with annotations above items for looking up references or impl blocks,
+// running/debugging binaries and opening the web documentation of public items.
 //
 // image::https://user-images.githubusercontent.com/48062697/113020672-b7c34f00-917a-11eb-8f6e-858735660a0e.png[]
 #[derive(Debug)]
@@ -34,6 +36,7 @@ pub enum AnnotationKind {
     Runnable(Runnable),
     HasImpls { pos: FilePosition, data: Option<Vec<NavigationTarget>> },
     HasReferences { pos: FilePosition, data: Option<Vec<FileRange>> },
+    HasDocs { pos: FilePosition, data: Option<String> },
 }
 
 pub struct AnnotationConfig {
@@ -43,6 +46,7 @@ pub struct AnnotationConfig {
     pub annotate_references: bool,
     pub annotate_method_references: bool,
     pub annotate_enum_variant_references: bool,
+    pub annotate_docs: bool,
     pub location: AnnotationLocation,
 }
 
@@ -162,6 +166,25 @@ pub(crate) fn annotations(
         }
     });
 
+    let sema = Semantics::new(db);
+    let has_docs = |module: hir::Module| has_web_docs(db, module.krate());
+    if config.annotate_docs && sema.to_module_def(file_id).map_or(false, has_docs) {
+        visit_file_defs(&sema, file_id, &mut |def| {
+            if !is_documented(db, def) {
+                return;
+            }
+            let Some(nav) = def.try_to_nav(db) else { return };
+            if nav.file_id != file_id {
+                return;
+            }
+            let (annotation_range, target_pos) = mk_ranges((nav.full_range, nav.focus_range));
+            annotations.push(Annotation {
+                range: annotation_range,
+                kind: AnnotationKind::HasDocs { pos: target_pos, data: None },
+            });
+        });
+    }
+
     if config.annotate_method_references {
         annotations.extend(find_all_methods(db, file_id).into_iter().map(|range| {
             let (annotation_range, target_range) = mk_ranges(range);
@@ -181,7 +204,7 @@ pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation)
             *data = goto_implementation(db, pos).map(|range| range.info);
         }
         AnnotationKind::HasReferences { pos, ref mut data } => {
-            *data = find_all_refs(&Semantics::new(db), pos, None).map(|result| {
+            *data = find_all_refs(&Semantics::new(db), pos, None, false).map(|result| {
                 result
                     .into_iter()
                     .flat_map(|res| res.references)
@@ -191,12 +214,49 @@ pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation)
                     .collect()
             });
         }
+        AnnotationKind::HasDocs { pos, ref mut data } => {
+            *data = web_doc_url(db, &pos).ok();
+        }
         _ => {}
     };
 
     annotation
 }
 
+/// Whether `def` is part of the rendered documentation of its crate, that is it is public, lives
+/// in public modules only and neither it nor any of these modules is `#[doc(hidden)]`.
+fn is_documented(db: &RootDatabase, def: Definition) -> bool {
+    let module_def = match def {
+        Definition::Module(it) => ModuleDef::Module(it),
+        Definition::Function(it) => ModuleDef::Function(it),
+        Definition::Adt(it) => ModuleDef::Adt(it),
+        Definition::Const(it) => ModuleDef::Const(it),
+        Definition::Static(it) => ModuleDef::Static(it),
+        Definition::Trait(it) => ModuleDef::Trait(it),
+        Definition::TraitAlias(it) => ModuleDef::TraitAlias(it),
+        Definition::TypeAlias(it) => ModuleDef::TypeAlias(it),
+        Definition::Macro(it) => ModuleDef::Macro(it),
+        _ => return false,
+    };
+    if module_def.attrs(db).map_or(false, |attrs| attrs.has_doc_hidden()) {
+        return false;
+    }
+    let parent = match module_def {
+        // declarative macros are only documented when exported, and then at the crate root
+        ModuleDef::Macro(it) if it.kind(db) == hir::MacroKind::Declarative => {
+            return it.is_macro_export(db)
+        }
+        _ if module_def.visibility(db) != Visibility::Public => return false,
+        _ => module_def.module(db),
+    };
+    parent.map_or(true, |parent| {
+        parent.path_to_root(db).into_iter().all(|module| {
+            (module.is_crate_root(db) || module.visibility(db) == Visibility::Public)
+                && !module.attrs(db).has_doc_hidden()
+        })
+    })
+}
+
 fn should_skip_runnable(kind: &RunnableKind, binary_target: bool) -> bool {
     match kind {
         RunnableKind::Bin => !binary_target,
@@ -219,6 +279,7 @@ mod tests {
         annotate_references: true,
         annotate_method_references: true,
         annotate_enum_variant_references: true,
+        annotate_docs: false,
         location: AnnotationLocation::AboveName,
     };
 
@@ -909,4 +970,99 @@ struct Foo;
             &AnnotationConfig { location: AnnotationLocation::AboveWholeItem, ..DEFAULT_CONFIG },
         );
     }
+
+    #[test]
+    fn docs_annotations() {
+        check_with_config(
+            r#"
+pub fn public() {}
+fn private() {}
+pub(crate) fn crate_visible() {}
+#[doc(hidden)]
+pub struct Hidden;
+pub mod public_mod {
+    pub struct InPublicMod;
+}
+mod private_mod {
+    pub struct InPrivateMod;
+}
+#[macro_export]
+macro_rules! exported {
+    () => {};
+}
+macro_rules! unexported {
+    () => {};
+}
+"#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 7..13,
+                        kind: HasDocs {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 7,
+                            },
+                            data: Some(
+                                "https://docs.rs/test/*/test/fn.public.html",
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 110..120,
+                        kind: HasDocs {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 110,
+                            },
+                            data: Some(
+                                "https://docs.rs/test/*/test/public_mod/index.html",
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 231..239,
+                        kind: HasDocs {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 231,
+                            },
+                            data: Some(
+                                "https://docs.rs/test/*/test/macro.exported.html",
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 138..149,
+                        kind: HasDocs {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 138,
+                            },
+                            data: Some(
+                                "https://docs.rs/test/*/test/public_mod/struct.InPublicMod.html",
+                            ),
+                        },
+                    },
+                ]
+            "#]],
+            &AnnotationConfig {
+                annotate_runnables: false,
+                annotate_impls: false,
+                annotate_references: false,
+                annotate_method_references: false,
+                annotate_enum_variant_references: false,
+                annotate_docs: true,
+                ..DEFAULT_CONFIG
+            },
+        );
+    }
 }
@@ -3,33 +3,58 @@
 #[cfg(test)]
 mod tests;
 
-mod intra_doc_links;
+use std::{
+    collections::hash_map::DefaultHasher,
+    ffi::OsStr,
+    fmt,
+    hash::{Hash, Hasher},
+    iter,
+    path::Path,
+};
 
-use std::ffi::OsStr;
+use either::Either;
+use itertools::Itertools;
 
-use pulldown_cmark::{BrokenLink, CowStr, Event, InlineStr, LinkType, Options, Parser, Tag};
+use pulldown_cmark::{BrokenLink, CowStr, Event, HeadingLevel, InlineStr, LinkType, Parser, Tag};
 use pulldown_cmark_to_cmark::{cmark_resume_with_options, Options as CMarkOptions};
+use rayon::prelude::*;
+use smallvec::{smallvec, SmallVec};
 use stdx::format_to;
 use url::Url;
 
-use hir::{db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, HasAttrs};
+use hir::{
+    db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, Crate, HasAttrs,
+    HasVisibility, ModuleDef, ModuleSource, Name, PathResolution, ScopeDef, Visibility,
+};
 use ide_db::{
-    base_db::{CrateOrigin, LangCrateOrigin, ReleaseChannel, SourceDatabase},
+    base_db::{
+        salsa::Database, CrateOrigin, LangCrateOrigin, SourceDatabase, SourceDatabaseExt,
+        SourceRoot, VfsPath,
+    },
     defs::{Definition, NameClass, NameRefClass},
+    doc_links::{
+        broken_link_clone_cb, doc_link_resolution, doc_link_resolution_failure, doc_link_scope,
+        extract_links_from_docs,
+        intra_doc_links::{parse_intra_doc_link, strip_prefixes_suffixes, FunctionKind},
+        is_path_like, primitive_of_module, primitive_type_name, DocLinkKind, DocLinkResolution,
+        DocLinkResolutionFailure, DocLinkTargetKind, DocLinksDatabase, InactiveDocLinkTarget,
+        MARKDOWN_OPTIONS,
+    },
+    documentation::RewriteDocLinks,
     helpers::pick_best_token,
-    RootDatabase,
+    FxHashMap, LineIndexDatabase, RootDatabase, Snap,
 };
 use syntax::{
-    ast::{self, IsString},
+    ast::{self, HasAttrs as _, HasModuleItem, HasVisibility as _, VisibilityKind},
     match_ast, AstNode, AstToken,
     SyntaxKind::*,
-    SyntaxNode, SyntaxToken, TextRange, TextSize, T,
+    SyntaxNode, TextRange, TextSize, T,
 };
+use triomphe::Arc;
 
-use crate::{
-    doc_links::intra_doc_links::{parse_intra_doc_link, strip_prefixes_suffixes},
-    FilePosition, Semantics,
-};
+use crate::{CompletionItem, FileId, FilePosition, FileRange, Semantics, TryToNav};
+
+pub(crate) use ide_db::doc_links::{doc_attributes, token_as_doc_comment};
 
 /// Web and local links to an item's documentation.
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -42,79 +67,1307 @@ pub struct DocumentationLinks {
     pub local_url: Option<String>,
 }
 
-const MARKDOWN_OPTIONS: Options =
-    Options::ENABLE_FOOTNOTES.union(Options::ENABLE_TABLES).union(Options::ENABLE_TASKLISTS);
+/// A link in markdown documentation together with what it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDocLink {
+    /// The byte range of the whole link in the markdown.
+    pub range: TextRange,
+    /// The URL the link points to, `None` if it could not be resolved. Shared by the links with
+    /// the same target.
+    pub url: Option<Arc<str>>,
+    /// How the target was resolved, decided from its shape.
+    pub target_kind: DocLinkTargetKind,
+    /// The definition an intra-doc link resolved to.
+    pub definition: Option<Definition>,
+    /// The displayed text of the link, with intra-doc link disambiguators stripped.
+    pub text: String,
+    /// Whether the intra-doc link names different items in different namespaces and has no
+    /// disambiguator picking one of them.
+    pub ambiguous: bool,
+    /// Whether the intra-doc link names an associated item that several impls of its type
+    /// define without naming the trait, see [`DocLinkResolution::impl_ambiguous`].
+    pub impl_ambiguous: bool,
+    /// The items the intra-doc link names through different glob imports, the one it links to
+    /// first, see [`DocLinkResolution::candidates`]. Empty unless it names more than one.
+    pub candidates: Vec<DocLinkCandidate>,
+    /// Why the link has no URL, `None` if it has one.
+    pub error: Option<ResolutionError>,
+    /// The cfg expression disabling the item the intra-doc link names in the current
+    /// configuration. The link then has no definition, its URL points to the documentation built
+    /// with that item, like docs.rs does with all features enabled.
+    pub inactive_cfg: Option<hir::CfgExpr>,
+    /// Whether the intra-doc link names its definition by one of its `#[doc(alias)]`es rather
+    /// than its name, see [`DocLinksDatabase::doc_link_alias_resolution`]. The link keeps the
+    /// alias as its text.
+    pub doc_alias: bool,
+    /// Whether this is the `[label]: target` reference definition of reference links elsewhere
+    /// in the markdown, which resolves like them.
+    pub reference_definition: bool,
+    /// Whether the URL points to the source of the definition rather than its documentation, see
+    /// [`DocLinksMode::Navigate`] and [`DocLinkOptions::source_fallback`].
+    pub source_link: bool,
+    /// Whether rewriting removes the link, keeping its text, see
+    /// [`DocLinkOptions::broken_links`].
+    pub removed: bool,
+}
+
+/// One of the items an ambiguous intra-doc link names, see [`ResolvedDocLink::candidates`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLinkCandidate {
+    pub definition: Definition,
+    /// The path of the item, see [`full_name`].
+    pub path: String,
+    /// The URL of the documentation of the item, `None` if it has none.
+    pub url: Option<Arc<str>>,
+}
+
+/// Why a link in documentation could not be given a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionError {
+    /// The target is neither a URL nor the path of a rustdoc page or an item.
+    UnparsablePath,
+    /// The segment at `index` of the intra-doc link's path names nothing.
+    UnresolvedSegment { index: usize, segment: String },
+    /// The intra-doc link names an item, just not in the namespace its disambiguator asks for.
+    WrongNamespace { expected: hir::Namespace },
+    /// The intra-doc link names a value that isn't a function of the kind its `function@` or
+    /// `method@` disambiguator asks for.
+    WrongFunctionKind { expected: FunctionKind },
+    /// The crate named `krate` has no documentation to link to.
+    NoDocBaseUrl { krate: String },
+    /// The link names something rustdoc doesn't generate documentation for, like a local.
+    UnsupportedDefinition,
+    /// The link was not resolved because the documentation has more links than
+    /// [`DocLinksDatabase::doc_link_resolution_budget`].
+    BudgetExceeded,
+}
+
+impl ResolutionError {
+    /// The error for the intra-doc link `target` that didn't resolve because of `failure`.
+    fn unresolved(target: &str, failure: Option<DocLinkResolutionFailure>) -> ResolutionError {
+        let (path, _) = parse_intra_doc_link(target);
+        if !is_path_like(path) {
+            return ResolutionError::UnparsablePath;
+        }
+        match failure {
+            Some(DocLinkResolutionFailure::WrongNamespace { expected }) => {
+                ResolutionError::WrongNamespace { expected }
+            }
+            Some(DocLinkResolutionFailure::WrongFunctionKind { expected }) => {
+                ResolutionError::WrongFunctionKind { expected }
+            }
+            Some(DocLinkResolutionFailure::NoSuchItem { resolved, segment }) => {
+                ResolutionError::UnresolvedSegment { index: resolved.split("::").count(), segment }
+            }
+            // items are looked up in the types and modules their qualifier names
+            Some(DocLinkResolutionFailure::QualifierNotAType { .. }) => {
+                ResolutionError::WrongNamespace { expected: hir::Namespace::Types }
+            }
+            // doc aliases name nothing when not resolved
+            Some(DocLinkResolutionFailure::DocAlias { .. }) => {
+                let segments = path.trim_start_matches("::").split("::");
+                let index = segments.clone().count() - 1;
+                let segment = segments.last().unwrap_or(path);
+                ResolutionError::UnresolvedSegment { index, segment: segment.to_owned() }
+            }
+            // ambiguous links resolve to one of the items they name and links to inactive items
+            // are given a URL instead of the error
+            Some(
+                DocLinkResolutionFailure::NotInScope
+                | DocLinkResolutionFailure::Ambiguous(_)
+                | DocLinkResolutionFailure::Inactive(_),
+            )
+            | None => {
+                let segment = path.trim_start_matches("::").split("::").next().unwrap_or(path);
+                ResolutionError::UnresolvedSegment { index: 0, segment: segment.to_owned() }
+            }
+        }
+    }
+
+    /// The error for links to `def`, whose crate has no documentation.
+    fn no_doc_base_url(db: &RootDatabase, def: Definition) -> ResolutionError {
+        let krate = def.krate(db).and_then(|it| it.display_name(db));
+        ResolutionError::NoDocBaseUrl { krate: krate.map(|it| it.to_string()).unwrap_or_default() }
+    }
+}
+
+impl fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolutionError::UnparsablePath => f.write_str("not a URL, page or item path"),
+            ResolutionError::UnresolvedSegment { index, segment } => {
+                write!(f, "segment {index} of the path, `{segment}`, names nothing")
+            }
+            ResolutionError::WrongNamespace { expected } => {
+                let ns = match expected {
+                    hir::Namespace::Types => "type",
+                    hir::Namespace::Values => "value",
+                    hir::Namespace::Macros => "macro",
+                };
+                write!(f, "names no {ns}")
+            }
+            ResolutionError::WrongFunctionKind { expected } => match expected {
+                FunctionKind::Free => f.write_str("names no free function"),
+                FunctionKind::Associated => f.write_str("names no method"),
+            },
+            ResolutionError::NoDocBaseUrl { krate } => {
+                write!(f, "crate `{krate}` has no documentation to link to")
+            }
+            ResolutionError::UnsupportedDefinition => {
+                f.write_str("names an item without documentation")
+            }
+            ResolutionError::BudgetExceeded => f.write_str("the documentation has too many links"),
+        }
+    }
+}
+
+/// An intra-doc link that does not name a single item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenDocLink {
+    /// The range of the link's target, or of the whole link if the target is defined elsewhere.
+    /// `None` if the documentation doesn't come from a file, e.g. from a macro expansion.
+    pub range: Option<FileRange>,
+    /// The link's target with its disambiguator stripped.
+    pub target: String,
+    pub reason: DocLinkResolutionFailure,
+}
+
+/// Returns the intra-doc links in the documentation of `def` that don't resolve to a single item.
+///
+/// Only links whose target looks like a path are considered intra-doc links, so URLs, links to
+/// rustdoc pages and bracketed text that isn't a path are skipped.
+pub(crate) fn broken_doc_links(db: &RootDatabase, def: Definition) -> Vec<BrokenDocLink> {
+    let Some((docs, mapping)) = def.attrs(db).and_then(|it| it.docs_with_rangemap(db)) else {
+        return Vec::new();
+    };
+    extract_links_from_docs(&docs)
+        .into_iter()
+        .filter(|link| link.kind != DocLinkKind::Autolink && is_path_like(&link.target))
+        .filter_map(|link| {
+            let reason =
+                doc_link_resolution_failure(db, def, &link.target, link.ns, link.functions)?;
+            let link_source = docs.as_str().get(std::ops::Range::<usize>::from(link.range));
+            let range = link_source.and_then(|it| link.target_range(it)).unwrap_or(link.range);
+            let range = mapping
+                .map(range)
+                .and_then(|it| Some(FileRange { file_id: it.file_id.file_id()?, range: it.value }));
+            Some(BrokenDocLink { range, target: link.target, reason })
+        })
+        .collect()
+}
+
+/// The links in the documentation of an item, see [`file_doc_links`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemDocLinks {
+    pub definition: Definition,
+    /// The path of the item, see [`full_name`].
+    pub path: String,
+    /// The range of the doc comments and `#[doc]` attributes of the item.
+    pub range: TextRange,
+    /// The links along with their range in the file, `None` for links that aren't written in
+    /// it, like the ones of `include_str!`ed docs, or that span several lines.
+    pub links: Vec<(Option<TextRange>, ResolvedDocLink)>,
+}
+
+/// Resolves the links in the documentation of every documented item written in `file_id`, in
+/// source order.
+pub(crate) fn file_doc_links(db: &RootDatabase, file_id: FileId) -> Vec<ItemDocLinks> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(file_id);
+    source_file
+        .syntax()
+        .descendants()
+        .filter_map(|node| {
+            let range = doc_syntax_range(&node)?;
+            let (attrs, definition) = doc_attributes(&sema, &node)?;
+            let (docs, mapping) = attrs.docs_with_rangemap(db)?;
+            let links = resolve_doc_links(db, definition, docs.as_str(), &Default::default(), None)
+                .into_iter()
+                .map(|link| {
+                    let range = mapping.map(link.range).filter(|it| it.file_id == file_id.into());
+                    (range.map(|it| it.value), link)
+                })
+                .collect();
+            Some(ItemDocLinks { definition, path: full_name(db, definition), range, links })
+        })
+        .collect()
+}
+
+/// The range covering the doc comments and `#[doc]` attributes of `node`, including the inner
+/// ones of a module's item list.
+fn doc_syntax_range(node: &SyntaxNode) -> Option<TextRange> {
+    let item_list = ast::Module::cast(node.clone()).and_then(|it| it.item_list());
+    let nodes = iter::once(node.clone()).chain(item_list.map(|it| it.syntax().clone()));
+    nodes
+        .flat_map(|it| ast::AttrDocCommentIter::from_syntax_node(&it))
+        .filter_map(|it| match it {
+            Either::Left(attr) => {
+                (attr.simple_name().as_deref() == Some("doc")).then(|| attr.syntax().text_range())
+            }
+            Either::Right(comment) => Some(comment.syntax().text_range()),
+        })
+        .reduce(TextRange::cover)
+}
+
+/// The path of `def` starting with its crate's name, going through the parent of fields, variants
+/// and associated items.
+pub fn full_name(db: &RootDatabase, def: Definition) -> String {
+    let parent = match def {
+        Definition::Field(it) => Some(it.parent_def(db).name(db)),
+        Definition::Variant(it) => Some(it.parent_enum(db).name(db)),
+        _ => def.as_assoc_item(db).and_then(|it| match it.container(db) {
+            AssocItemContainer::Trait(it) => Some(it.name(db)),
+            AssocItemContainer::Impl(it) => it.self_ty(db).as_adt().map(|it| it.name(db)),
+        }),
+    };
+    let krate = def.krate(db).and_then(|it| it.display_name(db)).map(|it| it.to_string());
+    let modules = def.module(db).into_iter().flat_map(|it| it.path_to_root(db).into_iter().rev());
+    let names = modules.filter_map(|it| it.name(db)).chain(parent).chain(def.name(db));
+    krate.into_iter().chain(names.map(|it| it.to_string())).join("::")
+}
+
+/// Strips the disambiguator off `text` if it is the path of an intra-doc link like `fn@foo`, or
+/// the keyword in front of one written as code like ``struct `Foo` ``.
+fn strip_disambiguator(text: CowStr<'_>) -> CowStr<'_> {
+    let stripped = strip_prefixes_suffixes(&text);
+    if stripped.len() != text.len() && (stripped.is_empty() || is_path_like(stripped)) {
+        stripped.to_owned().into()
+    } else {
+        text
+    }
+}
+
+/// Whether the text of a link of `link_type` is its target as well, as in `[fn@foo]` and
+/// ``[`Foo`][]`` without a reference definition. Like rustdoc, only such links show their text
+/// without the disambiguator, the text of `[fn pointers](fn@foo)` stays as written.
+fn text_is_target(link_type: LinkType) -> bool {
+    matches!(link_type, LinkType::ShortcutUnknown | LinkType::CollapsedUnknown)
+}
+
+/// Resolves all links in the markdown documentation of `definition`.
+///
+/// The links are in the order of their ranges, which don't overlap. Every link is reported at its
+/// own range, repeated links included. The `[label]: target` reference definition used by
+/// reference links is reported once more at its own range, however many links use it.
+///
+/// With [`DocLinksMode::Navigate`], intra-doc links to items defined in the workspace point to
+/// their source instead of the online documentation, as do the ones to items of workspace crates
+/// without published documentation with [`DocLinkOptions::source_fallback`]. `documented_in` is
+/// the crate inlining the documentation from a re-export, if it is shown there, see
+/// [`inlining_crate`].
+pub(crate) fn resolve_doc_links(
+    db: &RootDatabase,
+    definition: Definition,
+    markdown: &str,
+    options: &DocLinkOptions,
+    documented_in: Option<Crate>,
+) -> Vec<ResolvedDocLink> {
+    let mut _p = profile::span("resolve_doc_links");
+    let mut cb = broken_link_clone_cb;
+    let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+    // by normalized label, the first definition of a label is the one that counts
+    let mut reference_definitions: FxHashMap<String, Option<TextRange>> = doc
+        .reference_definitions()
+        .iter()
+        .map(|(label, def)| (normalize_label(label), text_range(def.span.clone())))
+        .collect();
+
+    let resolutions = db.doc_link_resolutions(definition);
+    let scope_at = doc_link_scope(db, definition, markdown);
+    // what's left of the budget goes to the targets the cached resolutions don't cover, in order
+    let mut budget = db.doc_link_resolution_budget().saturating_sub(resolutions.len());
+    // links repeating a target in the same scope share its resolution and URL, the range and
+    // text are filled in for each of them
+    let mut targets: FxHashMap<(Definition, String), ResolvedDocLink> = FxHashMap::default();
+    let mut urls = DocUrlCache::new(db, definition, documented_in);
+    let mut links = Vec::new();
+    let mut definitions = Vec::new();
+    let mut current: Option<ResolvedDocLink> = None;
+    let mut strip_text = false;
+    for (event, range) in cancellable(db, doc.into_offset_iter()) {
+        match event {
+            Event::Start(Tag::Link(link_type, target, _)) => {
+                let Some(range) = text_range(range) else { continue };
+                strip_text = text_is_target(link_type);
+                let used_definition = reference_label(link_type, &markdown[range])
+                    .and_then(|label| reference_definitions.remove(&label)?);
+                let key = (scope_at(range.start()), target.to_string());
+                let link = match targets.get(&key) {
+                    Some(it) => it.clone(),
+                    None => {
+                        let link = resolve_target(
+                            db,
+                            &mut urls,
+                            definition,
+                            &key,
+                            &resolutions,
+                            &mut budget,
+                            options,
+                        );
+                        targets.insert(key, link.clone());
+                        link
+                    }
+                };
+                if let Some(range) = used_definition {
+                    let text = markdown[range].strip_prefix('[').and_then(|it| it.split_once("]:"));
+                    definitions.push(ResolvedDocLink {
+                        range,
+                        text: text.map_or_else(String::new, |(label, _)| label.to_owned()),
+                        reference_definition: true,
+                        ..link.clone()
+                    });
+                }
+                current = Some(ResolvedDocLink { range, ..link });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(link) = &mut current {
+                    let is_intra = link.definition.is_some() || link.inactive_cfg.is_some();
+                    match is_intra && strip_text {
+                        true => link.text.push_str(strip_prefixes_suffixes(&text)),
+                        false => link.text.push_str(&text),
+                    }
+                }
+            }
+            Event::End(Tag::Link(..)) => links.extend(current.take()),
+            _ => (),
+        }
+    }
+    links.extend(definitions);
+    links.sort_by_key(|it| it.range.start());
+    let mut end = TextSize::from(0);
+    links.retain(|it| {
+        let keep = it.range.start() >= end;
+        if keep {
+            end = it.range.end();
+        }
+        keep
+    });
+    _p = _p.detail(|| {
+        let resolved = links.iter().filter(|it| it.url.is_some()).count();
+        format!("{} links, {resolved} resolved", links.len())
+    });
+    links
+}
+
+/// Resolves a link target of the documentation of `def` in the scope of the definition it is
+/// paired with, using its cached resolution from `resolutions` if there is one and taking from
+/// the `budget` otherwise.
+///
+/// The returned link has an empty range and text.
+fn resolve_target(
+    db: &RootDatabase,
+    urls: &mut DocUrlCache,
+    def: Definition,
+    key @ (scope, target): &(Definition, String),
+    resolutions: &FxHashMap<(Definition, String), DocLinkResolution>,
+    budget: &mut usize,
+    options: &DocLinkOptions,
+) -> ResolvedDocLink {
+    cov_mark::hit!(doc_link_target_resolved);
+    let kind = DocLinkTargetKind::of(target);
+    // links outside of the docs of `def`, e.g. in inherited docs, are not cached
+    let resolution = match resolutions.get(key).cloned() {
+        _ if kind == DocLinkTargetKind::Url => Some(DocLinkResolution::default()),
+        Some(it) => Some(it),
+        None if *budget > 0 => {
+            *budget -= 1;
+            Some(match kind {
+                DocLinkTargetKind::Intra => doc_link_resolution(db, *scope, target),
+                _ => DocLinkResolution::default(),
+            })
+        }
+        None => None,
+    };
+    let ambiguous = resolution.as_ref().map_or(false, |it| it.ambiguous);
+    let impl_ambiguous = resolution.as_ref().map_or(false, |it| it.impl_ambiguous);
+    let candidates = resolution.iter().flat_map(|it| &it.candidates);
+    let candidates = candidates
+        .map(|&definition| {
+            let url = rewrite_intra_doc_link(db, urls, def, definition).ok().map(Arc::from);
+            DocLinkCandidate { definition, path: full_name(db, definition), url }
+        })
+        .collect();
+    let inactive_cfg = match resolution.as_ref().and_then(|it| it.failure.as_ref()) {
+        Some(DocLinkResolutionFailure::Inactive(item)) => Some(item.cfg.clone()),
+        _ => None,
+    };
+    let resolution = resolution.map(|it| resolve_by_doc_alias(db, it));
+    let doc_alias = resolution.as_ref().map_or(false, |it| {
+        it.resolved.is_some()
+            && matches!(it.failure, Some(DocLinkResolutionFailure::DocAlias { .. }))
+    });
+    let resolved = match resolution {
+        Some(resolution) => resolve_link(db, urls, def, target, resolution, options),
+        None => Err(ResolutionError::BudgetExceeded),
+    };
+    let removed = matches!(options.mode, DocLinksMode::Rewrite | DocLinksMode::Navigate)
+        && match &resolved {
+            Ok(_) => ambiguous && options.broken_links == BrokenLinksMode::StripWithAmbiguous,
+            // links past the budget are not known to be broken
+            Err(ResolutionError::BudgetExceeded) => false,
+            Err(_) => options.broken_links != BrokenLinksMode::Keep,
+        };
+    let (url, definition, source_link, error) = match resolved {
+        Ok((url, definition, source_link)) => (Some(Arc::from(url)), definition, source_link, None),
+        Err(error) => {
+            tracing::debug!("unresolved doc link `{target}` in the docs of {def:?}: {error}");
+            (None, None, false, Some(error))
+        }
+    };
+    ResolvedDocLink {
+        range: TextRange::empty(0.into()),
+        url,
+        target_kind: kind,
+        definition,
+        text: String::new(),
+        ambiguous,
+        impl_ambiguous,
+        candidates,
+        error,
+        inactive_cfg,
+        doc_alias,
+        reference_definition: false,
+        source_link,
+        removed,
+    }
+}
+
+/// The label of the reference definition the reference link `source` uses, normalized like
+/// [`normalize_label`].
+fn reference_label(link_type: LinkType, source: &str) -> Option<String> {
+    let label = match link_type {
+        // `[text][label]`
+        LinkType::Reference => source.strip_suffix(']')?.rsplit_once("][")?.1,
+        // `[label][]`, whose range leaves out the `[]`, or `[label]`
+        LinkType::Collapsed | LinkType::Shortcut => {
+            source.strip_suffix("[]").unwrap_or(source).strip_prefix('[')?.strip_suffix(']')?
+        }
+        _ => return None,
+    };
+    Some(normalize_label(label))
+}
+
+/// Reference labels match case-insensitively and with their whitespace collapsed.
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Resolves the link `resolution` is of to the item it names by a `#[doc(alias)]`, if
+/// [`DocLinksDatabase::doc_link_alias_resolution`] is on.
+fn resolve_by_doc_alias(db: &RootDatabase, resolution: DocLinkResolution) -> DocLinkResolution {
+    match resolution.failure {
+        Some(DocLinkResolutionFailure::DocAlias { def, ns, .. })
+            if resolution.resolved.is_none() && db.doc_link_alias_resolution() =>
+        {
+            DocLinkResolution { resolved: Some((def, ns)), ..resolution }
+        }
+        _ => resolution,
+    }
+}
+
+/// Resolves a link target to a URL and, for intra-doc links, the definition it names and whether
+/// the URL points to its source.
+fn resolve_link(
+    db: &RootDatabase,
+    urls: &mut DocUrlCache,
+    def: Definition,
+    target: &str,
+    resolution: DocLinkResolution,
+    options: &DocLinkOptions,
+) -> Result<(String, Option<Definition>, bool), ResolutionError> {
+    match DocLinkTargetKind::of(target) {
+        DocLinkTargetKind::Url => Ok((target.to_owned(), None, false)),
+        // path-based links: `../../module/struct.MyStruct.html`, or `docs/guide.md` in an
+        // included README
+        DocLinkTargetKind::Page => match included_file_url(db, urls, def, target, false) {
+            Some(url) => Ok((url, None, false)),
+            None => Ok((rewrite_url_link(db, urls, def, target)?, None, false)),
+        },
+        // module-based links (AKA intra-doc links): `super::super::module::MyStruct`
+        DocLinkTargetKind::Intra => {
+            let Some((resolved, _)) = resolution.resolved else {
+                if let Some(DocLinkResolutionFailure::Inactive(item)) = &resolution.failure {
+                    return Ok((rewrite_inactive_item_link(db, urls, def, item)?, None, false));
+                }
+                return Err(ResolutionError::unresolved(target, resolution.failure));
+            };
+            let link_to_source = match options.mode {
+                DocLinksMode::Navigate => true,
+                _ => options.source_fallback && !has_published_docs(db, resolved),
+            };
+            match link_to_source.then(|| source_url(db, resolved)).flatten() {
+                Some(url) => Ok((url, Some(resolved), true)),
+                None => {
+                    Ok((rewrite_intra_doc_link(db, urls, def, resolved)?, Some(resolved), false))
+                }
+            }
+        }
+    }
+}
+
+/// How links in documentation are presented to the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DocLinksMode {
+    /// Rewrite links to point to the online documentation.
+    Rewrite,
+    /// Remove links, keeping their text.
+    Strip,
+    /// Leave the markdown untouched.
+    Raw,
+    /// Like [`DocLinksMode::Rewrite`], but links to items defined in the workspace point to
+    /// their source as `file://` URIs with a `#L<line>` fragment.
+    Navigate,
+}
+
+/// How links to rustdoc pages, like `struct.Foo.html`, are presented when the other links are
+/// rewritten.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PageLinksMode {
+    /// Rewrite them to point to the page in the online documentation.
+    #[default]
+    Rewrite,
+    /// Leave them as written, for clients resolving them against a local `cargo doc` output.
+    Ignore,
+    /// Remove them, keeping their text.
+    Strip,
+}
+
+/// How links that could not be resolved are presented when the other links are rewritten. Links
+/// with a URL as their target are always kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BrokenLinksMode {
+    /// Keep them as written.
+    #[default]
+    Keep,
+    /// Remove them, keeping their text. Links that were not resolved because the documentation
+    /// has too many links are kept.
+    Strip,
+    /// Like [`BrokenLinksMode::Strip`], also removing the ambiguous intra-doc links, which
+    /// resolve to one of the items they name.
+    StripWithAmbiguous,
+}
+
+/// How the links in documentation are processed, built once per request from the client's
+/// configuration.
+///
+/// The default rewrites all links to point to the online documentation, without a link to the
+/// full documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DocLinkOptions {
+    pub mode: DocLinksMode,
+    /// How links to rustdoc pages are presented when `mode` rewrites links.
+    pub page_links: PageLinksMode,
+    /// Whether long or truncated documentation ends with a link to the item's documentation
+    /// page. Hover and completion add it, as only they know whether the docs were cut off.
+    pub full_docs_link: bool,
+    /// Whether intra-doc links to items of workspace crates without an `html_root_url`, whose
+    /// documentation is only available locally, point to their source like with
+    /// [`DocLinksMode::Navigate`] instead of a guessed docs.rs page.
+    pub source_fallback: bool,
+    /// How links that could not be resolved are presented when `mode` rewrites links.
+    pub broken_links: BrokenLinksMode,
+}
+
+impl Default for DocLinkOptions {
+    fn default() -> Self {
+        DocLinkOptions {
+            mode: DocLinksMode::Rewrite,
+            page_links: PageLinksMode::Rewrite,
+            full_docs_link: false,
+            source_fallback: false,
+            broken_links: BrokenLinksMode::Keep,
+        }
+    }
+}
+
+impl DocLinkOptions {
+    pub fn with_mode(mut self, mode: DocLinksMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_page_links(mut self, page_links: PageLinksMode) -> Self {
+        self.page_links = page_links;
+        self
+    }
+
+    pub fn with_full_docs_link(mut self, full_docs_link: bool) -> Self {
+        self.full_docs_link = full_docs_link;
+        self
+    }
+
+    pub fn with_source_fallback(mut self, source_fallback: bool) -> Self {
+        self.source_fallback = source_fallback;
+        self
+    }
+
+    pub fn with_broken_links(mut self, broken_links: BrokenLinksMode) -> Self {
+        self.broken_links = broken_links;
+        self
+    }
+}
+
+/// Processes the links in `markdown`, the documentation of `definition`, according to `options`.
+///
+/// `documented_in` is the crate inlining the documentation, see [`resolve_doc_links`].
+pub(crate) fn process_links(
+    db: &RootDatabase,
+    markdown: &str,
+    definition: Definition,
+    options: &DocLinkOptions,
+    documented_in: Option<Crate>,
+) -> String {
+    match options.mode {
+        DocLinksMode::Rewrite | DocLinksMode::Navigate => {
+            rewrite_links_(db, markdown, definition, options, documented_in)
+        }
+        DocLinksMode::Strip => remove_links(markdown),
+        DocLinksMode::Raw => markdown.to_owned(),
+    }
+}
 
 /// Rewrite documentation links in markdown to point to an online host (e.g. docs.rs)
-pub(crate) fn rewrite_links(db: &RootDatabase, markdown: &str, definition: Definition) -> String {
+pub fn rewrite_links(db: &RootDatabase, markdown: &str, definition: Definition) -> String {
+    process_links(db, markdown, definition, &DocLinkOptions::default(), None)
+}
+
+impl RewriteDocLinks for DocLinkOptions {
+    fn rewrite_doc_links(&self, db: &RootDatabase, docs: &str, owner: Definition) -> String {
+        process_links(db, docs, owner, self, None)
+    }
+
+    fn cache_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn rewrite_links_(
+    db: &RootDatabase,
+    markdown: &str,
+    definition: Definition,
+    options: &DocLinkOptions,
+    documented_in: Option<Crate>,
+) -> String {
+    let _p = profile::span("rewrite_links").detail(|| format!("{} bytes", markdown.len()));
+    let links = resolve_doc_links(db, definition, markdown, options, documented_in);
     let mut cb = broken_link_clone_cb;
     let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+    let doc = rewritten_link_events(db, markdown, doc, definition, options, documented_in, &links);
+    let mut out = write_markdown(markdown, doc);
+    if links.iter().any(|it| it.error == Some(ResolutionError::BudgetExceeded)) {
+        out.push_str(
+            "\n\n*Some links were not resolved, this documentation has too many of them.*",
+        );
+    }
+    out
+}
 
-    let doc = map_links(doc, |target, title| {
-        // This check is imperfect, there's some overlap between valid intra-doc links
-        // and valid URLs so we choose to be too eager to try to resolve what might be
-        // a URL.
+/// The events of `doc`, the parser of `markdown`, the documentation of `definition`, with its
+/// links rewritten to the URLs they were resolved to in `links`, see [`resolve_doc_links`].
+///
+/// Links that are kept, like the ones to URLs, keep their borrowed target and text.
+fn rewritten_link_events<'a>(
+    db: &'a RootDatabase,
+    markdown: &'a str,
+    doc: Parser<'a, 'a>,
+    definition: Definition,
+    options: &DocLinkOptions,
+    documented_in: Option<Crate>,
+    links: &'a [ResolvedDocLink],
+) -> impl Iterator<Item = Event<'a>> + 'a {
+    let page_links = options.page_links;
+
+    // `links` are in the order of the markdown, so don't scan them for every link
+    let link_at = move |range: TextRange| {
+        links
+            .binary_search_by_key(&range.start(), |link| link.range.start())
+            .ok()
+            .map(|idx| &links[idx])
+            .filter(|link| link.range == range)
+    };
+    // shortcut links without a reference definition that don't resolve show as their plain text
+    // instead of linking to a bogus relative target, links with written targets are kept, as are
+    // page links unless they are to be stripped, and broken links if they are to be removed
+    let is_page_link = |target: &str| DocLinkTargetKind::of(target) == DocLinkTargetKind::Page;
+    let mut in_unlinked = false;
+    let doc = keep_setext_headings(markdown, cancellable(db, doc.into_offset_iter()));
+    let doc = keep_brackets(markdown, doc);
+    let doc = doc.filter(move |(event, range)| match event {
+        Event::Start(Tag::Link(_, target, _))
+            if page_links == PageLinksMode::Strip && is_page_link(target) =>
+        {
+            in_unlinked = true;
+            false
+        }
+        Event::Start(Tag::Link(..))
+            if text_range(range.clone()).and_then(link_at).map_or(false, |it| it.removed) =>
+        {
+            in_unlinked = true;
+            false
+        }
+        Event::Start(Tag::Link(kind, ..)) if text_is_target(*kind) => {
+            let link = text_range(range.clone()).and_then(link_at);
+            in_unlinked = link.map_or(true, |it| it.url.is_none());
+            !in_unlinked
+        }
+        Event::End(Tag::Link(..)) if in_unlinked => {
+            in_unlinked = false;
+            false
+        }
+        _ => true,
+    });
+
+    // images aren't links to resolve, only the relative ones of included files are rewritten
+    let mut image_urls = DocUrlCache::new(db, definition, documented_in);
+    let doc = doc.map(move |(event, range)| match event {
+        Event::End(Tag::Image(kind, target, title)) => {
+            let target = match included_file_url(db, &mut image_urls, definition, &target, true) {
+                Some(url) => url.into(),
+                None => target,
+            };
+            (Event::End(Tag::Image(kind, target, title)), range)
+        }
+        event => (event, range),
+    });
+
+    map_links(doc, move |range, kind, target, title| {
+        // Targets that are URLs already, including the ones rewritten for earlier parts of the
+        // link's text, are kept as is.
         if target.contains("://") {
-            (Some(LinkType::Inline), target.to_string(), title.to_string())
-        } else {
-            // Two possibilities:
-            // * path-based links: `../../module/struct.MyStruct.html`
-            // * module-based links (AKA intra-doc links): `super::super::module::MyStruct`
-            if let Some((target, title)) = rewrite_intra_doc_link(db, definition, target, title) {
-                return (None, target, title);
+            return LinkRewrite::Unchanged { link_type: Some(LinkType::Inline) };
+        }
+        match link_at(range) {
+            Some(ResolvedDocLink { url: Some(url), definition, inactive_cfg, .. })
+                if definition.is_some() || inactive_cfg.is_some() =>
+            {
+                LinkRewrite::Rewritten {
+                    link_type: None,
+                    target: url.to_string(),
+                    text: match text_is_target(kind) {
+                        true => strip_prefixes_suffixes(title).to_owned(),
+                        false => title.to_owned(),
+                    },
+                }
+            }
+            Some(ResolvedDocLink { url: Some(url), definition: None, .. })
+                if page_links == PageLinksMode::Rewrite =>
+            {
+                LinkRewrite::Rewritten {
+                    link_type: Some(LinkType::Inline),
+                    target: url.to_string(),
+                    text: title.to_owned(),
+                }
             }
-            if let Some(target) = rewrite_url_link(db, definition, target) {
-                return (Some(LinkType::Inline), target, title.to_string());
+            _ => LinkRewrite::Unchanged { link_type: None },
+        }
+    })
+}
+
+/// The number of markdown events processed between two checks for cancellation.
+const CANCELLATION_CHECK_INTERVAL: usize = 1024;
+
+/// Checks for cancellation every [`CANCELLATION_CHECK_INTERVAL`] events, so that rewriting huge
+/// documents doesn't hold up the requests following a cancelled one.
+fn cancellable<'a, T>(
+    db: &'a RootDatabase,
+    events: impl Iterator<Item = T> + 'a,
+) -> impl Iterator<Item = T> + 'a {
+    events.enumerate().map(move |(i, event)| {
+        if i % CANCELLATION_CHECK_INTERVAL == 0 {
+            #[cfg(test)]
+            if let Some(hook) = CANCELLATION_CHECK_HOOK.with(|it| it.get()) {
+                hook(db);
             }
+            db.unwind_if_cancelled();
+        }
+        event
+    })
+}
 
-            (None, target.to_string(), title.to_string())
+#[cfg(test)]
+thread_local! {
+    /// Called before each check of [`cancellable`] on this thread, so tests can wait for a
+    /// cancellation to be requested right in the middle of a rewrite.
+    pub(crate) static CANCELLATION_CHECK_HOOK: std::cell::Cell<Option<fn(&RootDatabase)>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Remove all links in markdown documentation.
+pub(crate) fn remove_links(markdown: &str) -> String {
+    let mut drop_link = false;
+    let mut strip_text = false;
+
+    let mut cb = |link: BrokenLink<'_>| {
+        let (path, _) = parse_intra_doc_link(&link.reference);
+        if !is_path_like(path) {
+            return None;
         }
+        let empty = InlineStr::try_from("").unwrap();
+        Some((CowStr::Inlined(empty), CowStr::Inlined(empty)))
+    };
+    let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+    let doc = keep_setext_headings(markdown, doc.into_offset_iter());
+    let doc = keep_brackets(markdown, doc).map(|(event, _)| event);
+    let doc = doc.filter_map(move |evt| match evt {
+        Event::Start(Tag::Link(link_type, target, title)) => {
+            if link_type == LinkType::Inline && target.contains("://") {
+                Some(Event::Start(Tag::Link(link_type, target, title)))
+            } else {
+                drop_link = true;
+                strip_text = text_is_target(link_type);
+                None
+            }
+        }
+        Event::End(_) if drop_link => {
+            drop_link = false;
+            None
+        }
+        // show intra-doc links like `[fn@foo]` the way rewriting them does
+        Event::Text(text) if strip_text && drop_link => {
+            Some(Event::Text(strip_disambiguator(text)))
+        }
+        Event::Code(text) if strip_text && drop_link => {
+            Some(Event::Code(strip_disambiguator(text)))
+        }
+        _ => Some(evt),
     });
+
+    write_markdown(markdown, doc)
+}
+
+/// Demotes the level 1 and 2 headings of markdown documentation by one level, so the headings
+/// of crate docs like `# Examples` don't dwarf the rest of a hover.
+pub(crate) fn demote_headings(markdown: &str) -> String {
+    let mut cb = broken_link_clone_cb;
+    let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+    let demote = |level| match level {
+        HeadingLevel::H1 => HeadingLevel::H2,
+        HeadingLevel::H2 => HeadingLevel::H3,
+        level => level,
+    };
+    let doc = doc.into_offset_iter().map(|(event, range)| match event {
+        Event::Start(Tag::Heading(level, id, classes)) => {
+            (Event::Start(Tag::Heading(demote(level), id, classes)), range)
+        }
+        Event::End(Tag::Heading(level, id, classes)) => {
+            (Event::End(Tag::Heading(demote(level), id, classes)), range)
+        }
+        _ => (event, range),
+    });
+    let doc = keep_brackets(markdown, keep_setext_headings(markdown, doc)).map(|(event, _)| event);
+
+    write_markdown(markdown, doc)
+}
+
+/// Writes the `events` of `markdown` back as markdown.
+///
+/// The serializer numbers all items of an ordered list with the list's start and writes all
+/// bullets as `*`, indenting the content of items by the width of that marker. The markers of
+/// the list items of `markdown` are written as they are instead, like `10.`, `11.` or `-`, with
+/// the indentation of the items and their content kept. Likewise, code spans keep the number
+/// of backticks delimiting them, like ``` `` `raw` `` ```, which the serializer writes as one.
+fn write_markdown<'e>(markdown: &str, events: impl Iterator<Item = Event<'e>>) -> String {
+    let options = CMarkOptions { code_block_token_count: 3, ..Default::default() };
+    // only documentation with lists or code spans is parsed for their syntax
+    let mut syntax: Option<SourceSyntax> = None;
     let mut out = String::new();
-    cmark_resume_with_options(
-        doc,
-        &mut out,
-        None,
-        CMarkOptions { code_block_token_count: 3, ..Default::default() },
-    )
-    .ok();
+    let mut state = None;
+    for event in events {
+        let is_item = matches!(event, Event::Start(Tag::Item));
+        let bracket = match &event {
+            Event::Html(text) if matches!(&**text, "[" | "]") => Some(text.clone()),
+            _ => None,
+        };
+        // code spans are written as an empty one, `` `` ``, which is replaced afterwards
+        let (event, code) = match event {
+            Event::Code(text) => (Event::Code(CowStr::Borrowed("")), Some(text)),
+            event => (event, None),
+        };
+        let Ok(mut next) =
+            cmark_resume_with_options(std::iter::once(event), &mut out, state, options.clone())
+        else {
+            return out;
+        };
+        // brackets kept by `keep_brackets` are text, not HTML followed by a newline
+        if let Some(text) = bracket {
+            next.last_was_html = false;
+            let written = [next.current_shortcut_text.as_mut(), next.text_for_header.as_mut()];
+            written.into_iter().flatten().for_each(|it| it.push_str(&text));
+        }
+        if let Some(text) = code {
+            let syntax = syntax.get_or_insert_with(|| SourceSyntax::of(markdown));
+            let span = code_span(&text, syntax.code_delimiters.next());
+            let written = [next.current_shortcut_text.as_mut(), next.text_for_header.as_mut()];
+            for it in written.into_iter().flatten().chain([&mut out]) {
+                if it.ends_with("``") {
+                    it.truncate(it.len() - 2);
+                    it.push_str(&span);
+                }
+            }
+        }
+        if is_item {
+            let syntax = syntax.get_or_insert_with(|| SourceSyntax::of(markdown));
+            let written = match next.list_stack.last() {
+                Some(Some(n)) => format!("{n}. "),
+                _ => format!("{} ", options.list_token),
+            };
+            if let Some(marker) = syntax.item_markers.next().filter(|_| out.ends_with(&written)) {
+                out.truncate(out.len() - written.len());
+                out.push_str(&marker);
+                if let Some(padding) = next.padding.last_mut() {
+                    *padding = " ".repeat(marker.len()).into();
+                }
+            }
+        }
+        state = Some(next);
+    }
     out
 }
 
-/// Remove all links in markdown documentation.
-pub(crate) fn remove_links(markdown: &str) -> String {
-    let mut drop_link = false;
+/// The syntax of markdown that the serializer doesn't write back as it is, in the order of the
+/// events it belongs to.
+struct SourceSyntax {
+    /// The markers of the list items, with the indentation in front of them and the spaces up to
+    /// their content, like `"10. "` or `"  - "`.
+    item_markers: std::vec::IntoIter<String>,
+    /// The number of backticks delimiting the code spans and whether their content is
+    /// surrounded by spaces.
+    code_delimiters: std::vec::IntoIter<(usize, bool)>,
+}
+
+impl SourceSyntax {
+    fn of(markdown: &str) -> SourceSyntax {
+        let mut item_markers = Vec::new();
+        let mut code_delimiters = Vec::new();
+        for (event, range) in Parser::new_ext(markdown, MARKDOWN_OPTIONS).into_offset_iter() {
+            match event {
+                Event::Start(Tag::Item) => item_markers.push(item_marker(&markdown[range])),
+                Event::Code(_) => {
+                    let source = &markdown[range];
+                    let len = source.bytes().take_while(|&it| it == b'`').count();
+                    let content = source.get(len..source.len().saturating_sub(len));
+                    let padded = content.map_or(false, |it| {
+                        it.len() > 1 && it.starts_with(' ') && it.ends_with(' ')
+                    });
+                    code_delimiters.push((len, padded));
+                }
+                _ => (),
+            }
+        }
+        SourceSyntax {
+            item_markers: item_markers.into_iter(),
+            code_delimiters: code_delimiters.into_iter(),
+        }
+    }
+}
+
+/// The marker of the list item `source` is the markdown of, like [`SourceSyntax::item_markers`].
+fn item_marker(source: &str) -> String {
+    // the range of an item starts where the content of the items around it does
+    let line = source.lines().next().unwrap_or_default();
+    let marker = line.trim_start_matches(' ');
+    let indent = line.len() - marker.len();
+    let marker_len = marker.bytes().take_while(u8::is_ascii_digit).count() + 1;
+    let rest = marker.get(marker_len..).unwrap_or_default();
+    let spaces = rest.len() - rest.trim_start_matches(' ').len();
+    // content starting on the next line or as an indented code block is written one space
+    // after the marker
+    let spaces = match spaces {
+        1..=4 if spaces < rest.len() => spaces,
+        _ => 1,
+    };
+    let marker = marker.get(..marker_len).unwrap_or_default();
+    format!("{}{marker}{}", " ".repeat(indent), " ".repeat(spaces))
+}
+
+/// Writes a code span with the content `text`, delimited like in the source if `delimiter` still
+/// delimits it and by the fewest backticks that do otherwise.
+fn code_span(text: &str, delimiter: Option<(usize, bool)>) -> String {
+    let runs: SmallVec<[usize; 2]> =
+        text.split(|it| it != '`').map(str::len).filter(|&it| it > 0).collect();
+    let (len, padded) = delimiter
+        .filter(|(len, _)| *len > 0 && !runs.contains(len))
+        .unwrap_or_else(|| ((1..).find(|it| !runs.contains(it)).unwrap_or(1), false));
+    // a backtick at either end would extend the delimiter, and a space at both ends would be
+    // stripped as padding
+    let padded = padded
+        || text.starts_with('`')
+        || text.ends_with('`')
+        || (text.starts_with(' ') && text.ends_with(' ') && !text.trim().is_empty());
+    let delimiter = "`".repeat(len);
+    let padding = if padded { " " } else { "" };
+    format!("{delimiter}{padding}{text}{padding}{delimiter}")
+}
+
+/// Keeps the setext headings of `markdown`, like `Title\n=====`, in that form when its events
+/// are written back as markdown, which writes all headings as ATX headings like `# Title`.
+///
+/// Such a heading is written as a paragraph followed by its underline as HTML, which is written
+/// as is. The underline is `=` or `-` by the heading's level, so headings whose level changed
+/// stay setext headings of levels 1 and 2 only. Headings in block quotes, lists and footnotes
+/// are written as ATX headings, the underline would lose their indentation.
+fn keep_setext_headings<'e>(
+    markdown: &'e str,
+    events: impl Iterator<Item = (Event<'e>, std::ops::Range<usize>)>,
+) -> impl Iterator<Item = (Event<'e>, std::ops::Range<usize>)> {
+    let mut nesting = 0usize;
+    let mut underline = None;
+    events.flat_map(move |(event, range)| -> SmallVec<[_; 2]> {
+        match event {
+            Event::Start(Tag::BlockQuote | Tag::List(_) | Tag::FootnoteDefinition(_)) => {
+                nesting += 1;
+            }
+            Event::End(Tag::BlockQuote | Tag::List(_) | Tag::FootnoteDefinition(_)) => {
+                nesting = nesting.saturating_sub(1);
+            }
+            Event::Start(Tag::Heading(level, ..)) if nesting == 0 => {
+                let underline_char = match level {
+                    HeadingLevel::H1 => '=',
+                    HeadingLevel::H2 => '-',
+                    _ => return smallvec![(event, range)],
+                };
+                let source = markdown.get(range.clone()).unwrap_or_default().trim_end();
+                let last_line = source.rsplit_once('\n').map(|(_, it)| it.trim());
+                let is_underline = |line: &&str| {
+                    !line.is_empty()
+                        && (line.bytes().all(|c| c == b'=') || line.bytes().all(|c| c == b'-'))
+                };
+                if let Some(last_line) = last_line.filter(is_underline) {
+                    underline = Some(underline_char.to_string().repeat(last_line.len()));
+                    return smallvec![(Event::Start(Tag::Paragraph), range)];
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some(underline) = underline.take() {
+                    return smallvec![
+                        (Event::Html(format!("\n{underline}").into()), range.clone()),
+                        (Event::End(Tag::Paragraph), range),
+                    ];
+                }
+            }
+            _ => (),
+        }
+        smallvec![(event, range)]
+    })
+}
+
+/// Keeps the brackets that don't belong to a link, like the ones of `[0..n]` or `[T; N]`, as they
+/// are written.
+///
+/// The serializer escapes a bracket at the start of text, so they would show as `\[0..n\]` in
+/// clients not rendering markdown. Brackets written unescaped are passed on as HTML instead,
+/// which is written verbatim, see [`write_markdown`]. A closing bracket is only kept if its
+/// opening one was or it has none, so `\[foo]` stays `\[foo\]`.
+fn keep_brackets<'e>(
+    markdown: &'e str,
+    events: impl Iterator<Item = (Event<'e>, std::ops::Range<usize>)>,
+) -> impl Iterator<Item = (Event<'e>, std::ops::Range<usize>)> {
+    let mut in_code_block = false;
+    // whether each open bracket was kept as written
+    let mut open_brackets = Vec::new();
+    events.flat_map(move |(event, range)| -> SmallVec<[_; 2]> {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(text) if !in_code_block => {
+                // the text of an escaped bracket starts after the backslash
+                let before = markdown.get(..range.start).unwrap_or_default();
+                let backslashes = before.len() - before.trim_end_matches('\\').len();
+                let bracket = match text.chars().next() {
+                    Some('[') if backslashes % 2 == 0 => Some('['),
+                    Some(']') if backslashes % 2 == 0 && open_brackets.last() != Some(&false) => {
+                        Some(']')
+                    }
+                    _ => None,
+                };
+                let mut chars = text.chars();
+                if let Some(bracket) = bracket {
+                    chars.next();
+                    match bracket {
+                        '[' => open_brackets.push(true),
+                        _ => drop(open_brackets.pop()),
+                    }
+                }
+                for c in chars {
+                    match c {
+                        '[' => open_brackets.push(false),
+                        ']' => drop(open_brackets.pop()),
+                        _ => (),
+                    }
+                }
+                let Some(bracket) = bracket else {
+                    return smallvec![(Event::Text(text), range)];
+                };
+                let html = Event::Html(CowStr::Inlined(bracket.into()));
+                let rest = &text[1..];
+                if rest.is_empty() {
+                    return smallvec![(html, range)];
+                }
+                let rest = Event::Text(CowStr::Boxed(rest.into()));
+                return smallvec![(html, range.clone()), (rest, range)];
+            }
+            _ => (),
+        }
+        smallvec![(event, range)]
+    })
+}
+
+/// Documentation longer than this many bytes is considered long, see [`append_full_docs_link`].
+const LONG_DOCS_LEN: usize = 1000;
+
+/// Ends `docs` with a link to the documentation page of `def` if they were `truncated` or are
+/// long, so the whole documentation is a click away. Nothing is added if the page has no URL.
+pub(crate) fn append_full_docs_link(
+    db: &RootDatabase,
+    docs: &mut String,
+    def: Definition,
+    truncated: bool,
+) {
+    if !truncated && docs.len() <= LONG_DOCS_LEN {
+        return;
+    }
+    if let Some(url) = doc_url(db, &def) {
+        format_to!(docs, "\n\n— [view full documentation]({url})");
+    }
+}
+
+/// Completion lists longer than this have the documentation of their items processed in
+/// parallel, see [`completion_list_docs`].
+const PARALLEL_COMPLETION_DOCS_CUTOFF: usize = 64;
+
+/// The documentation of a completion item, with its links processed according to `options`.
+pub(crate) fn completion_docs(
+    db: &RootDatabase,
+    item: &CompletionItem,
+    options: &DocLinkOptions,
+) -> Option<String> {
+    let docs = item.documentation.as_ref()?;
+    Some(match item.doc_owner {
+        Some(def) => {
+            let mut docs = docs.as_str().to_owned();
+            // the page of the module has nothing on the docs of snippets
+            if options.full_docs_link && !item.builtin_docs {
+                append_full_docs_link(db, &mut docs, def, false);
+            }
+            process_links(db, &docs, def, options, None)
+        }
+        None => docs.as_str().to_owned(),
+    })
+}
+
+/// The [`completion_docs`] of each item of a completion list, in order.
+///
+/// Long lists are split across the rayon thread pool, each thread reading its own snapshot of
+/// the database.
+pub(crate) fn completion_list_docs(
+    db: &RootDatabase,
+    items: &[CompletionItem],
+    options: &DocLinkOptions,
+) -> Vec<Option<String>> {
+    if items.len() <= PARALLEL_COMPLETION_DOCS_CUTOFF {
+        return items.iter().map(|it| completion_docs(db, it, options)).collect();
+    }
+    items
+        .par_iter()
+        .map_with(Snap::new(db), |db, item| completion_docs(db, item, options))
+        .collect()
+}
+
+/// Turns bare URLs in the text of markdown documentation into autolinks.
+///
+/// URLs in code, links and HTML are left alone.
+pub(crate) fn linkify_urls(markdown: &str) -> String {
+    let mut cb = broken_link_clone_cb;
+    let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+
+    // the parser splits text at some special characters, merge it back so URLs are found whole,
+    // except at escaped brackets, which the serializer only escapes at the start of text
+    let mut events: Vec<Event<'_>> = Vec::new();
+    let doc = keep_setext_headings(markdown, doc.into_offset_iter());
+    for (event, _) in keep_brackets(markdown, doc) {
+        match (events.last_mut(), event) {
+            (Some(Event::Text(prev)), Event::Text(text)) if !text.starts_with(['[', ']']) => {
+                *prev = CowStr::Boxed(format!("{prev}{text}").into());
+            }
+            (_, event) => events.push(event),
+        }
+    }
+
+    let mut in_link_or_code = 0usize;
+    let mut out_events = Vec::with_capacity(events.len());
+    for event in events {
+        match event {
+            Event::Start(Tag::Link(..) | Tag::Image(..) | Tag::CodeBlock(_)) => {
+                in_link_or_code += 1;
+                out_events.push(event);
+            }
+            Event::End(Tag::Link(..) | Tag::Image(..) | Tag::CodeBlock(_)) => {
+                in_link_or_code = in_link_or_code.saturating_sub(1);
+                out_events.push(event);
+            }
+            Event::Text(text) if in_link_or_code == 0 => {
+                let mut rest = &*text;
+                while let Some((start, end)) = find_bare_url(rest) {
+                    if start > 0 {
+                        out_events.push(Event::Text(CowStr::Boxed(rest[..start].into())));
+                    }
+                    let url = CowStr::Boxed(rest[start..end].into());
+                    let tag = Tag::Link(LinkType::Autolink, url.clone(), CowStr::Borrowed(""));
+                    out_events.push(Event::Start(tag.clone()));
+                    out_events.push(Event::Text(url));
+                    out_events.push(Event::End(tag));
+                    rest = &rest[end..];
+                }
+                if !rest.is_empty() {
+                    out_events.push(Event::Text(CowStr::Boxed(rest.into())));
+                }
+            }
+            _ => out_events.push(event),
+        }
+    }
+
+    write_markdown(markdown, out_events.into_iter())
+}
+
+/// Finds the first `http://` or `https://` URL in `text`, returning its byte range.
+///
+/// Trailing punctuation is not considered part of the URL, neither is a closing parenthesis
+/// without a matching opening one in the URL, so `(see https://example.com).` works.
+fn find_bare_url(text: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    loop {
+        let start = offset + text[offset..].find("http")?;
+        let rest = &text[start..];
+        let is_word_start =
+            text[..start].chars().next_back().map_or(true, |c| !c.is_alphanumeric());
+        let scheme = ["https://", "http://"].into_iter().find(|it| rest.starts_with(it));
+        let Some(scheme) = scheme.filter(|_| is_word_start) else {
+            offset = start + "http".len();
+            continue;
+        };
 
-    let mut cb = |_: BrokenLink<'_>| {
-        let empty = InlineStr::try_from("").unwrap();
-        Some((CowStr::Inlined(empty), CowStr::Inlined(empty)))
-    };
-    let doc = Parser::new_with_broken_link_callback(markdown, MARKDOWN_OPTIONS, Some(&mut cb));
-    let doc = doc.filter_map(move |evt| match evt {
-        Event::Start(Tag::Link(link_type, target, title)) => {
-            if link_type == LinkType::Inline && target.contains("://") {
-                Some(Event::Start(Tag::Link(link_type, target, title)))
-            } else {
-                drop_link = true;
-                None
+        let mut url = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`'))
+            .map_or(rest, |end| &rest[..end]);
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ':', ';', '!', '?']);
+            let trimmed = trimmed.trim_end_matches(['\'', '*', '_']);
+            let trimmed = match trimmed.strip_suffix(')') {
+                Some(it) if trimmed.matches('(').count() < trimmed.matches(')').count() => it,
+                _ => trimmed,
+            };
+            if trimmed.len() == url.len() {
+                break;
             }
+            url = trimmed;
         }
-        Event::End(_) if drop_link => {
-            drop_link = false;
-            None
+        if url.len() > scheme.len() {
+            return Some((start, start + url.len()));
         }
-        _ => Some(evt),
-    });
-
-    let mut out = String::new();
-    cmark_resume_with_options(
-        doc,
-        &mut out,
-        None,
-        CMarkOptions { code_block_token_count: 3, ..Default::default() },
-    )
-    .ok();
-    out
+        offset = start + scheme.len();
+    }
 }
 
 // Feature: Open Docs
@@ -135,6 +1388,34 @@ pub(crate) fn external_docs(
     target_dir: Option<&OsStr>,
     sysroot: Option<&OsStr>,
 ) -> Option<DocumentationLinks> {
+    let definition = definition_at(db, position)?;
+    Some(get_doc_links(db, definition, target_dir, sysroot))
+}
+
+/// Retrieve the web URL of the documentation of the definition at `position`.
+///
+/// The error describes why there is no URL, to be shown to the user.
+pub(crate) fn web_doc_url(db: &RootDatabase, position: &FilePosition) -> Result<String, String> {
+    let definition =
+        definition_at(db, position).ok_or_else(|| "No item found at the cursor".to_owned())?;
+    if let Some(url) = doc_url(db, &definition) {
+        return Ok(url.into());
+    }
+    let krate_name = definition.krate(db).and_then(|krate| krate.display_name(db));
+    match krate_name {
+        Some(name) if get_doc_base_urls(db, definition, None, None).0.is_none() => {
+            Err(format!("Crate `{name}` has no documentation URL"))
+        }
+        _ => Err("The item at the cursor has no documentation page".to_owned()),
+    }
+}
+
+/// Whether the items of `krate` have documentation on the web, see [`get_doc_links`].
+pub(crate) fn has_web_docs(db: &RootDatabase, krate: Crate) -> bool {
+    get_doc_base_urls(db, Definition::Module(krate.root_module(db)), None, None).0.is_some()
+}
+
+fn definition_at(db: &RootDatabase, position: &FilePosition) -> Option<Definition> {
     let sema = &Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
     let token = pick_best_token(file.token_at_offset(position.offset), |kind| match kind {
@@ -161,158 +1442,29 @@ pub(crate) fn external_docs(
             _ => return None
         }
     };
-
-    Some(get_doc_links(db, definition, target_dir, sysroot))
+    Some(definition)
 }
 
-/// Extracts all links from a given markdown text returning the definition text range, link-text
-/// and the namespace if known.
-pub(crate) fn extract_definitions_from_docs(
-    docs: &hir::Documentation,
-) -> Vec<(TextRange, String, Option<hir::Namespace>)> {
-    Parser::new_with_broken_link_callback(
-        docs.as_str(),
-        MARKDOWN_OPTIONS,
-        Some(&mut broken_link_clone_cb),
-    )
-    .into_offset_iter()
-    .filter_map(|(event, range)| match event {
-        Event::Start(Tag::Link(_, target, _)) => {
-            let (link, ns) = parse_intra_doc_link(&target);
-            Some((
-                TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?),
-                link.to_string(),
-                ns,
-            ))
-        }
-        _ => None,
-    })
-    .collect()
+/// The URL of the web documentation of `def`, the page of its own or the anchor of `def` on the
+/// page of its parent, see [`get_doc_links`].
+pub fn doc_url(db: &RootDatabase, def: &Definition) -> Option<Url> {
+    doc_urls(db, *def, None, None).0
 }
 
-pub(crate) fn resolve_doc_path_for_def(
-    db: &dyn HirDatabase,
+pub(crate) fn get_doc_links(
+    db: &RootDatabase,
     def: Definition,
-    link: &str,
-    ns: Option<hir::Namespace>,
-) -> Option<Definition> {
-    match def {
-        Definition::Module(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Function(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Adt(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Variant(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Const(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Static(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Trait(it) => it.resolve_doc_path(db, link, ns),
-        Definition::TraitAlias(it) => it.resolve_doc_path(db, link, ns),
-        Definition::TypeAlias(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Macro(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Field(it) => it.resolve_doc_path(db, link, ns),
-        Definition::SelfType(it) => it.resolve_doc_path(db, link, ns),
-        Definition::BuiltinAttr(_)
-        | Definition::ToolModule(_)
-        | Definition::BuiltinType(_)
-        | Definition::Local(_)
-        | Definition::GenericParam(_)
-        | Definition::Label(_)
-        | Definition::DeriveHelper(_) => None,
-    }
-    .map(Definition::from)
-}
-
-pub(crate) fn doc_attributes(
-    sema: &Semantics<'_, RootDatabase>,
-    node: &SyntaxNode,
-) -> Option<(hir::AttrsWithOwner, Definition)> {
-    match_ast! {
-        match node {
-            ast::SourceFile(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
-            ast::Module(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
-            ast::Fn(it)          => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Function(def))),
-            ast::Struct(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Struct(def)))),
-            ast::Union(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Union(def)))),
-            ast::Enum(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Enum(def)))),
-            ast::Variant(it)     => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Variant(def))),
-            ast::Trait(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Trait(def))),
-            ast::Static(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Static(def))),
-            ast::Const(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Const(def))),
-            ast::TypeAlias(it)   => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::TypeAlias(def))),
-            ast::Impl(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::SelfType(def))),
-            ast::RecordField(it) => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
-            ast::TupleField(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
-            ast::Macro(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Macro(def))),
-            // ast::Use(it) => sema.to_def(&it).map(|def| (Box::new(it) as _, def.attrs(sema.db))),
-            _ => None
-        }
-    }
-}
-
-pub(crate) struct DocCommentToken {
-    doc_token: SyntaxToken,
-    prefix_len: TextSize,
-}
-
-pub(crate) fn token_as_doc_comment(doc_token: &SyntaxToken) -> Option<DocCommentToken> {
-    (match_ast! {
-        match doc_token {
-            ast::Comment(comment) => TextSize::try_from(comment.prefix().len()).ok(),
-            ast::String(string) => {
-                doc_token.parent_ancestors().find_map(ast::Attr::cast).filter(|attr| attr.simple_name().as_deref() == Some("doc"))?;
-                if doc_token.parent_ancestors().find_map(ast::MacroCall::cast).filter(|mac| mac.path().and_then(|p| p.segment()?.name_ref()).as_ref().map(|n| n.text()).as_deref() == Some("include_str")).is_some() {
-                    return None;
-                }
-                string.open_quote_text_range().map(|it| it.len())
-            },
-            _ => None,
-        }
-    }).map(|prefix_len| DocCommentToken { prefix_len, doc_token: doc_token.clone() })
-}
-
-impl DocCommentToken {
-    pub(crate) fn get_definition_with_descend_at<T>(
-        self,
-        sema: &Semantics<'_, RootDatabase>,
-        offset: TextSize,
-        // Definition, CommentOwner, range of intra doc link in original file
-        mut cb: impl FnMut(Definition, SyntaxNode, TextRange) -> Option<T>,
-    ) -> Option<T> {
-        let DocCommentToken { prefix_len, doc_token } = self;
-        // offset relative to the comments contents
-        let original_start = doc_token.text_range().start();
-        let relative_comment_offset = offset - original_start - prefix_len;
-
-        sema.descend_into_macros(doc_token).into_iter().find_map(|t| {
-            let (node, descended_prefix_len) = match_ast! {
-                match t {
-                    ast::Comment(comment) => (t.parent()?, TextSize::try_from(comment.prefix().len()).ok()?),
-                    ast::String(string) => (t.parent_ancestors().skip_while(|n| n.kind() != ATTR).nth(1)?, string.open_quote_text_range()?.len()),
-                    _ => return None,
-                }
-            };
-            let token_start = t.text_range().start();
-            let abs_in_expansion_offset = token_start + relative_comment_offset + descended_prefix_len;
-
-            let (attributes, def) = doc_attributes(sema, &node)?;
-            let (docs, doc_mapping) = attributes.docs_with_rangemap(sema.db)?;
-            let (in_expansion_range, link, ns) =
-                extract_definitions_from_docs(&docs).into_iter().find_map(|(range, link, ns)| {
-                    let mapped = doc_mapping.map(range)?;
-                    (mapped.value.contains(abs_in_expansion_offset)).then_some((mapped.value, link, ns))
-                })?;
-            // get the relative range to the doc/attribute in the expansion
-            let in_expansion_relative_range = in_expansion_range - descended_prefix_len - token_start;
-            // Apply relative range to the original input comment
-            let absolute_range = in_expansion_relative_range + original_start + prefix_len;
-            let def = resolve_doc_path_for_def(sema.db, def, &link, ns)?;
-            cb(def, node, absolute_range)
-        })
+    target_dir: Option<&OsStr>,
+    sysroot: Option<&OsStr>,
+) -> DocumentationLinks {
+    let _p = profile::span("get_doc_links");
+    let (web_url, local_url) = doc_urls(db, def, target_dir, sysroot);
+    DocumentationLinks {
+        web_url: web_url.map(|it| it.into()),
+        local_url: local_url.map(|it| it.into()),
     }
 }
 
-fn broken_link_clone_cb(link: BrokenLink<'_>) -> Option<(CowStr<'_>, CowStr<'_>)> {
-    Some((/*url*/ link.reference.clone(), /*title*/ link.reference))
-}
-
 // FIXME:
 // BUG: For Option::Some
 // Returns https://doc.rust-lang.org/nightly/core/prelude/v1/enum.Option.html#variant.Some
@@ -320,21 +1472,25 @@ fn broken_link_clone_cb(link: BrokenLink<'_>) -> Option<(CowStr<'_>, CowStr<'_>)
 //
 // This should cease to be a problem if RFC2988 (Stable Rustdoc URLs) is implemented
 // https://github.com/rust-lang/rfcs/pull/2988
-fn get_doc_links(
+/// The web and local documentation URLs of `def`: the base URL of its crate joined with the
+/// directory and file of its page and the fragment for `def` on it.
+fn doc_urls(
     db: &RootDatabase,
     def: Definition,
     target_dir: Option<&OsStr>,
     sysroot: Option<&OsStr>,
-) -> DocumentationLinks {
+) -> (Option<Url>, Option<Url>) {
     let join_url = |base_url: Option<Url>, path: &str| -> Option<Url> {
         base_url.and_then(|url| url.join(path).ok())
     };
 
-    let Some((target, file, frag)) = filename_and_frag_for_def(db, def) else { return Default::default(); };
+    let Some((target, file, frag)) = filename_and_frag_for_def(db, def) else {
+        return Default::default();
+    };
 
     let (mut web_url, mut local_url) = get_doc_base_urls(db, target, target_dir, sysroot);
 
-    if let Some(path) = mod_path_of_def(db, target) {
+    if let Some(path) = doc_dir_path(db, target) {
         web_url = join_url(web_url, &path);
         local_url = join_url(local_url, &path);
     }
@@ -342,113 +1498,665 @@ fn get_doc_links(
     web_url = join_url(web_url, &file);
     local_url = join_url(local_url, &file);
 
-    web_url.as_mut().map(|url| url.set_fragment(frag.as_deref()));
-    local_url.as_mut().map(|url| url.set_fragment(frag.as_deref()));
+    if let Some(url) = &mut web_url {
+        url.set_fragment(frag.as_deref());
+    }
+    if let Some(url) = &mut local_url {
+        url.set_fragment(frag.as_deref());
+    }
 
-    DocumentationLinks {
-        web_url: web_url.map(|it| it.into()),
-        local_url: local_url.map(|it| it.into()),
+    (web_url, local_url)
+}
+
+/// Whether the documentation of `def` is published somewhere, which is assumed for all crates
+/// but the workspace members without an `html_root_url`.
+fn has_published_docs(db: &RootDatabase, def: Definition) -> bool {
+    def.krate(db).map_or(true, |krate| {
+        !matches!(krate.origin(db), CrateOrigin::Local { .. })
+            || krate.get_html_root_url(db).is_some()
+    })
+}
+
+/// Returns a `file://` URI pointing at the line `def` is defined on, if it is in the workspace.
+fn source_url(db: &RootDatabase, def: Definition) -> Option<String> {
+    let nav = def.try_to_nav(db)?;
+    let source_root = db.source_root(db.file_source_root(nav.file_id));
+    if source_root.is_library {
+        return None;
     }
+    let path = source_root.path_for_file(&nav.file_id)?.as_path()?;
+    let mut url = Url::from_file_path(path).ok()?;
+    let line = db.line_index(nav.file_id).line_col(nav.focus_or_full_range().start()).line;
+    url.set_fragment(Some(&format!("L{}", line + 1)));
+    Some(url.into())
 }
 
-fn rewrite_intra_doc_link(
+/// Returns a link to the lines `def` is defined on in its crate's source repository.
+///
+/// This is only computed for library dependencies, the package's `repository` field has to point
+/// to GitHub or GitLab. The revision is guessed as the `v{version}` tag, as we can't query the
+/// repository for its tags.
+pub(crate) fn repository_url(db: &RootDatabase, def: Definition) -> Option<String> {
+    let krate = def.krate(db)?;
+    let nav = def.try_to_nav(db)?;
+    let source_root = db.source_root(db.file_source_root(nav.file_id));
+    if !source_root.is_library {
+        // path and workspace dependencies are better served by `source_url`
+        return None;
+    }
+    let path = source_root.path_for_file(&nav.file_id)?;
+    let line_index = db.line_index(nav.file_id);
+    let start = line_index.line_col(nav.full_range.start()).line + 1;
+    let end = line_index.line_col(nav.full_range.end()).line + 1;
+    repository_file_url(db, krate, &source_root, path, RepositoryView::Lines(start, end))
+}
+
+/// Resolves the relative link or image `target` in the documentation of `def` against the file
+/// the documentation includes with `#[doc = include_str!("path")]`, like READMEs link to the
+/// files next to them.
+///
+/// Workspace files are linked to as `file://` URIs, the files of library dependencies in their
+/// crate's repository like with [`repository_url`], images to their raw content. Targets of
+/// rustdoc pages are left to [`rewrite_url_link`].
+fn included_file_url(
     db: &RootDatabase,
+    urls: &mut DocUrlCache,
     def: Definition,
     target: &str,
-    title: &str,
-) -> Option<(String, String)> {
-    let (link, ns) = parse_intra_doc_link(target);
+    image: bool,
+) -> Option<String> {
+    if DocLinkTargetKind::of(target) == DocLinkTargetKind::Url || target.starts_with(['/', '#']) {
+        return None;
+    }
+    let (path, fragment) = match target.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (target, None),
+    };
+    if path.ends_with(".html") {
+        return None;
+    }
+    let included = urls.included_doc_file(db, def)?;
+    let source_root = db.source_root(db.file_source_root(included));
+    let file = source_root.path_for_file(&included)?.parent()?.join(path)?;
+    let mut url = match source_root.is_library {
+        true => {
+            let view = if image { RepositoryView::Raw } else { RepositoryView::Blob };
+            repository_file_url(db, def.krate(db)?, &source_root, &file, view)?
+        }
+        false => Url::from_file_path(file.as_path()?).ok()?.into(),
+    };
+    if let Some(fragment) = fragment {
+        format_to!(url, "#{fragment}");
+    }
+    Some(url)
+}
+
+/// How [`repository_file_url`] shows a file.
+enum RepositoryView {
+    /// The lines `start..=end` of the file.
+    Lines(u32, u32),
+    /// The whole file.
+    Blob,
+    /// The content of the file, for images.
+    Raw,
+}
+
+/// Returns a link to `file` of the library crate `krate` in its source repository.
+///
+/// The package's `repository` field has to point to GitHub or GitLab. The revision is guessed as
+/// the `v{version}` tag, as we can't query the repository for its tags.
+fn repository_file_url(
+    db: &RootDatabase,
+    krate: Crate,
+    source_root: &SourceRoot,
+    file: &VfsPath,
+    view: RepositoryView,
+) -> Option<String> {
+    let repo = match krate.origin(db) {
+        CrateOrigin::Local { repo, .. } | CrateOrigin::Library { repo, .. } => repo?,
+        CrateOrigin::Rustc { .. } | CrateOrigin::Lang(_) => return None,
+    };
+
+    // assume the standard package layout, with the crate root in `src/`
+    let crate_root = source_root.path_for_file(&krate.root_file(db))?;
+    let mut package_root = crate_root.parent()?;
+    if package_root.name_and_extension() == Some(("src", None)) {
+        package_root.pop();
+    }
+    let path = file.strip_prefix(&package_root)?;
+    let path = AsRef::<Path>::as_ref(path)
+        .components()
+        .map(|it| it.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?
+        .join("/");
 
-    let resolved = resolve_doc_path_for_def(db, def, link, ns)?;
-    let mut url = get_doc_base_urls(db, resolved, None, None).0?;
+    let rev = match krate.version(db) {
+        Some(version) => format!("v{version}"),
+        None => "HEAD".to_owned(),
+    };
+    repository_blob_url(&repo, &rev, &path, view)
+}
 
-    let (_, file, frag) = filename_and_frag_for_def(db, resolved)?;
-    if let Some(path) = mod_path_of_def(db, resolved) {
-        url = url.join(&path).ok()?;
+/// Builds a GitHub or GitLab URL showing `path` at `rev` like `view` says.
+fn repository_blob_url(repo: &str, rev: &str, path: &str, view: RepositoryView) -> Option<String> {
+    let repo = repo.trim_start_matches("git+");
+    let repo = repo.strip_prefix("https://").or_else(|| repo.strip_prefix("http://"))?;
+    let repo = repo.strip_prefix("www.").unwrap_or(repo);
+    let (host, project) = repo.split_once('/')?;
+    match host {
+        "github.com" => {
+            // ignore trailing `/tree/<branch>/...` segments
+            let mut segments = project.split('/');
+            let owner = segments.next().filter(|it| !it.is_empty())?;
+            let name = segments.next().filter(|it| !it.is_empty())?;
+            let name = name.strip_suffix(".git").unwrap_or(name);
+            let base = format!("https://github.com/{owner}/{name}");
+            Some(match view {
+                RepositoryView::Lines(start, end) => {
+                    format!("{base}/blob/{rev}/{path}#L{start}-L{end}")
+                }
+                RepositoryView::Blob => format!("{base}/blob/{rev}/{path}"),
+                RepositoryView::Raw => format!("{base}/raw/{rev}/{path}"),
+            })
+        }
+        "gitlab.com" => {
+            // GitLab projects can be nested in groups, `/-/` starts the non-project part
+            let project = project.split("/-/").next()?.trim_end_matches('/');
+            let project = project.strip_suffix(".git").unwrap_or(project);
+            if !project.contains('/') {
+                return None;
+            }
+            let base = format!("https://gitlab.com/{project}/-");
+            Some(match view {
+                RepositoryView::Lines(start, end) => {
+                    format!("{base}/blob/{rev}/{path}#L{start}-{end}")
+                }
+                RepositoryView::Blob => format!("{base}/blob/{rev}/{path}"),
+                RepositoryView::Raw => format!("{base}/raw/{rev}/{path}"),
+            })
+        }
+        _ => None,
     }
+}
 
-    url = url.join(&file).ok()?;
+/// Computes the URL to the documentation of the intra-doc link target `resolved`.
+fn rewrite_intra_doc_link(
+    db: &RootDatabase,
+    urls: &mut DocUrlCache,
+    def: Definition,
+    resolved: Definition,
+) -> Result<String, ResolutionError> {
+    let _p = profile::span("rewrite_intra_doc_link");
+    // generic parameters have no page, links to them go to the item declaring them
+    let resolved = match resolved {
+        Definition::GenericParam(param) => match param.parent() {
+            hir::GenericDef::Function(it) => Definition::Function(it),
+            hir::GenericDef::Adt(it) => Definition::Adt(it),
+            hir::GenericDef::Trait(it) => Definition::Trait(it),
+            hir::GenericDef::TraitAlias(it) => Definition::TraitAlias(it),
+            hir::GenericDef::TypeAlias(it) => Definition::TypeAlias(it),
+            hir::GenericDef::Impl(it) => Definition::SelfType(it),
+            hir::GenericDef::Variant(it) => Definition::Variant(it),
+            hir::GenericDef::Const(it) => Definition::Const(it),
+        },
+        _ => resolved,
+    };
+    // associated items are documented on the page of their container, which may live in another
+    // module than the impl (or the macro call expanding to it)
+    let (target, file, frag) =
+        filename_and_frag_for_def(db, resolved).ok_or(ResolutionError::UnsupportedDefinition)?;
+    let mut url = urls.dir_url(db, def, resolved, target)?;
+    url = url.join(&file).map_err(|_| ResolutionError::UnparsablePath)?;
     url.set_fragment(frag.as_deref());
 
-    Some((url.into(), strip_prefixes_suffixes(title).to_string()))
+    Ok(url.into())
+}
+
+/// Computes the URL to the documentation of `item`, which is disabled in the current
+/// configuration and so has no definition.
+fn rewrite_inactive_item_link(
+    db: &RootDatabase,
+    urls: &mut DocUrlCache,
+    def: Definition,
+    item: &InactiveDocLinkTarget,
+) -> Result<String, ResolutionError> {
+    let name = &item.name;
+    let file = match item.kind {
+        CONST => format!("constant.{name}.html"),
+        ENUM => format!("enum.{name}.html"),
+        FN => format!("fn.{name}.html"),
+        MACRO_DEF | MACRO_RULES => format!("macro.{name}.html"),
+        MODULE => format!("{name}/index.html"),
+        STATIC => format!("static.{name}.html"),
+        STRUCT => format!("struct.{name}.html"),
+        TRAIT => format!("trait.{name}.html"),
+        TRAIT_ALIAS => format!("traitalias.{name}.html"),
+        TYPE_ALIAS => format!("type.{name}.html"),
+        UNION => format!("union.{name}.html"),
+        _ => return Err(ResolutionError::UnsupportedDefinition),
+    };
+    // the items of a module are documented next to its `index.html`
+    let module = Definition::Module(item.module);
+    let (_, index, _) =
+        filename_and_frag_for_def(db, module).ok_or(ResolutionError::UnsupportedDefinition)?;
+    let url = urls.dir_url(db, def, module, module)?;
+    let url = url.join(&index).and_then(|it| it.join(&file));
+    url.map(Into::into).map_err(|_| ResolutionError::UnparsablePath)
 }
 
 /// Try to resolve path to local documentation via path-based links (i.e. `../gateway/struct.Shard.html`).
-fn rewrite_url_link(db: &RootDatabase, def: Definition, target: &str) -> Option<String> {
+fn rewrite_url_link(
+    db: &RootDatabase,
+    urls: &mut DocUrlCache,
+    def: Definition,
+    target: &str,
+) -> Result<String, ResolutionError> {
     if !(target.contains('#') || target.contains(".html")) {
+        return Err(ResolutionError::UnparsablePath);
+    }
+    let _p = profile::span("rewrite_url_link");
+
+    let (page, file, frag) =
+        filename_and_frag_for_def(db, def).ok_or(ResolutionError::UnsupportedDefinition)?;
+    let mut url = urls.dir_url(db, def, def, page)?;
+    url = url.join(&file).map_err(|_| ResolutionError::UnparsablePath)?;
+    url.set_fragment(frag.as_deref());
+    url.join(target).map(Into::into).map_err(|_| ResolutionError::UnparsablePath)
+}
+
+fn mod_path_of_def(db: &RootDatabase, def: Definition) -> Option<String> {
+    def.module(db).map(|it| module_path(db, it))
+}
+
+/// The path of the directory rustdoc puts the page of `def` in, relative to the documentation
+/// root of its crate, like `gateway/shard/`.
+///
+/// This follows [`canonical_doc_path`] where it applies and falls back to the path of the module
+/// defining `def`.
+fn doc_dir_path(db: &RootDatabase, def: Definition) -> Option<String> {
+    // rustdoc puts the pages of primitives at the root of the crate, wherever their module or
+    // impls are
+    match def {
+        Definition::Module(module) if primitive_of_module(db, module).is_some() => {
+            return Some(String::new());
+        }
+        Definition::SelfType(impl_) if primitive_type_name(&impl_.self_ty(db)).is_some() => {
+            return Some(String::new());
+        }
+        _ => (),
+    }
+    let Some(module_def) = page_item(db, def) else {
+        return mod_path_of_def(db, def);
+    };
+    let Some(path) = canonical_doc_path(db, module_def) else {
+        return mod_path_of_def(db, def);
+    };
+    Some(dir_of_path(path))
+}
+
+/// The path of the directory containing the page of `def` in the documentation of `krate`,
+/// which inlines it from a re-export of one of its dependencies.
+fn inlined_doc_dir_path(db: &RootDatabase, krate: Crate, def: Definition) -> Option<String> {
+    let module_def = page_item(db, def)?;
+    let name = module_def.name(db)?;
+    let path = module_def.public_path_in(db, krate).filter(|path| path.last() == Some(&name))?;
+    Some(dir_of_path(path))
+}
+
+/// The item whose page documents `def`, if it has a page of its own.
+fn page_item(db: &RootDatabase, def: Definition) -> Option<ModuleDef> {
+    let module_def = match def {
+        Definition::Module(it) => ModuleDef::Module(it),
+        Definition::Function(it) => it.into(),
+        Definition::Adt(it) => it.into(),
+        // variants are documented on the page of their enum
+        Definition::Variant(it) => Adt::from(it.parent_enum(db)).into(),
+        Definition::Const(it) => it.into(),
+        Definition::Static(it) => it.into(),
+        Definition::Trait(it) => it.into(),
+        Definition::TraitAlias(it) => it.into(),
+        Definition::TypeAlias(it) => it.into(),
+        Definition::Macro(it) => it.into(),
+        _ => return None,
+    };
+    Some(module_def)
+}
+
+/// The directory of the page at `path`, like `gateway/shard/` for `gateway::shard::Shard`.
+fn dir_of_path(mut path: Vec<Name>) -> String {
+    path.pop();
+    let mut res = String::new();
+    path.iter().for_each(|name| format_to!(res, "{}/", name));
+    res
+}
+
+/// The path rustdoc documents `def` at, from the root of its crate to the name of `def`.
+///
+/// Public items in public modules are documented where they are defined. Other items are
+/// inlined where a documented ancestor module re-exports them with a `pub use` that is neither
+/// `#[doc(no_inline)]` nor `#[doc(hidden)]`. Failing that, the shortest public path from the
+/// import map is used. Renaming re-exports are not followed, as the page would be named after the
+/// new name.
+fn canonical_doc_path(db: &RootDatabase, def: ModuleDef) -> Option<Vec<Name>> {
+    let name = def.name(db)?;
+    documented_path(db, def, &name)
+        .or_else(|| def.public_path(db).filter(|path| path.last() == Some(&name)))
+}
+
+/// The path of the page rustdoc generates for `def` named `name`, without falling back to the
+/// import map like [`canonical_doc_path`] does.
+fn documented_path(db: &RootDatabase, def: ModuleDef, name: &Name) -> Option<Vec<Name>> {
+    if def.attrs(db).map_or(false, |it| it.has_doc_hidden()) {
         return None;
     }
+    let module = def.module(db)?;
+    let at_definition = match def.visibility(db) {
+        Visibility::Public => documented_module_path(db, module),
+        _ => None,
+    };
+    let mut path = match at_definition {
+        Some(it) => it,
+        None => module.path_to_root(db).into_iter().find_map(|ancestor| {
+            inlines_reexport(db, ancestor, def, name)
+                .then(|| documented_module_path(db, ancestor))
+                .flatten()
+        })?,
+    };
+    path.push(name.clone());
+    Some(path)
+}
 
-    let mut url = get_doc_base_urls(db, def, None, None).0?;
-    let (def, file, frag) = filename_and_frag_for_def(db, def)?;
+/// The path of the page rustdoc generates for `module`, empty for the crate root.
+fn documented_module_path(db: &RootDatabase, module: hir::Module) -> Option<Vec<Name>> {
+    if module.is_crate_root(db) {
+        return Some(Vec::new());
+    }
+    documented_path(db, ModuleDef::Module(module), &module.name(db)?)
+}
 
-    if let Some(path) = mod_path_of_def(db, def) {
-        url = url.join(&path).ok()?;
+/// Whether `module` re-exports `def` as `name` with a `pub use` that rustdoc inlines the
+/// documentation of `def` at.
+fn inlines_reexport(db: &RootDatabase, module: hir::Module, def: ModuleDef, name: &Name) -> bool {
+    // glob imports would match the items declared in `module`
+    if def.module(db) == Some(module) {
+        return false;
     }
+    let items = match module.definition_source(db).value {
+        ModuleSource::SourceFile(it) => it.items(),
+        ModuleSource::Module(it) => match it.item_list() {
+            Some(it) => it.items(),
+            None => return false,
+        },
+        ModuleSource::BlockExpr(_) => return false,
+    };
+    let name_text = name.to_smol_str();
+    let reexported = items
+        .filter_map(|it| match it {
+            ast::Item::Use(it) => Some(it),
+            _ => None,
+        })
+        .filter(|it| it.visibility().map_or(false, |vis| matches!(vis.kind(), VisibilityKind::Pub)))
+        .filter(|it| !it.attrs().any(|attr| is_doc_attr_with(&attr, &["no_inline", "hidden"])))
+        .filter_map(|it| it.use_tree())
+        .any(|tree| use_tree_imports(&tree, None, &name_text));
+    // the name may be imported from elsewhere in another namespace
+    reexported
+        && module
+            .scope(db, None)
+            .into_iter()
+            .any(|(it, scope_def)| it == *name && scope_def == ScopeDef::ModuleDef(def))
+}
 
-    url = url.join(&file).ok()?;
-    url.set_fragment(frag.as_deref());
-    url.join(target).ok().map(Into::into)
+/// The `std` crate whose re-exports of `core` and `alloc` items the links in the documentation of
+/// `def` point to.
+///
+/// `#![no_std]` crates keep linking to `core` and `alloc`, which is what they use, as do the
+/// standard library crates themselves.
+fn std_docs_of(db: &RootDatabase, def: Definition) -> Option<Crate> {
+    let krate = def.krate(db)?;
+    if krate.is_builtin(db) || krate.is_no_std(db) {
+        return None;
+    }
+    krate
+        .dependencies(db)
+        .into_iter()
+        .map(|dep| dep.krate)
+        .find(|dep| matches!(dep.origin(db), CrateOrigin::Lang(LangCrateOrigin::Std)))
 }
 
-fn mod_path_of_def(db: &RootDatabase, def: Definition) -> Option<String> {
-    def.canonical_module_path(db).map(|it| {
-        let mut path = String::new();
-        it.flat_map(|it| it.name(db)).for_each(|name| format_to!(path, "{}/", name));
-        path
-    })
+/// Whether the items of `krate` are re-exported by `std` with the same documentation.
+fn is_std_reexported(db: &RootDatabase, krate: Crate) -> bool {
+    matches!(krate.origin(db), CrateOrigin::Lang(LangCrateOrigin::Core | LangCrateOrigin::Alloc))
+}
+
+/// The crate documenting `def` when it is referred to at `node` through a re-export of another
+/// crate, with a path like `facade::Shard`.
+///
+/// rustdoc shows the documentation of items inlined from dependencies on the pages of the
+/// re-exporting crate, which is the one to link to when the defining crate isn't published.
+pub(crate) fn inlining_crate(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+    def: Definition,
+) -> Option<Crate> {
+    let db = sema.db;
+    let module_def = page_item(db, def)?;
+    let name = module_def.name(db)?;
+    let path = ast::PathSegment::cast(node.parent()?)?.parent_path();
+    let module = match sema.resolve_path(&path.qualifier()?)? {
+        PathResolution::Def(ModuleDef::Module(it)) => it,
+        _ => return None,
+    };
+    let krate = module.krate();
+    let inlined =
+        krate != module_def.module(db)?.krate() && inlines_reexport(db, module, module_def, &name);
+    inlined.then_some(krate)
+}
+
+/// Whether `tree`, nested in a tree with the path `prefix`, imports something named `name`
+/// without renaming it, or everything from a module.
+fn use_tree_imports(tree: &ast::UseTree, prefix: Option<&ast::Path>, name: &str) -> bool {
+    if let Some(list) = tree.use_tree_list() {
+        let path = tree.path();
+        return list.use_trees().any(|it| use_tree_imports(&it, path.as_ref().or(prefix), name));
+    }
+    if tree.star_token().is_some() {
+        return true;
+    }
+    // `{self}` imports the module the list is nested in
+    let is_self = |path: &ast::Path| path.segment().map_or(false, |it| it.self_token().is_some());
+    let path = tree.path().filter(|it| !is_self(it));
+    let segment = path.as_ref().or(prefix).and_then(|it| it.segment()).and_then(|it| it.name_ref());
+    tree.rename().is_none()
+        && segment.map_or(false, |it| it.text().trim_start_matches("r#") == name)
+}
+
+/// Whether `attr` is a `#[doc(...)]` attribute with one of `flags` in it.
+fn is_doc_attr_with(attr: &ast::Attr, flags: &[&str]) -> bool {
+    let Some((name, tt)) = attr.as_simple_call() else { return false };
+    name == "doc"
+        && tt.syntax().children_with_tokens().any(|it| {
+            it.as_token().map_or(false, |it| it.kind() == IDENT && flags.contains(&it.text()))
+        })
+}
+
+fn module_path(db: &RootDatabase, module: hir::Module) -> String {
+    let mut path = String::new();
+    let modules = module.path_to_root(db).into_iter().rev();
+    modules.flat_map(|it| it.name(db)).for_each(|name| format_to!(path, "{}/", name));
+    path
+}
+
+/// The parts of documentation URLs computed while rewriting the links of one document, whose
+/// links often point to the same few items.
+#[derive(Default)]
+struct DocUrlCache {
+    /// The crate inlining the documentation the links are in, see [`inlining_crate`].
+    documented_in: Option<Crate>,
+    /// The `std` documenting the items of `core` and `alloc` linked to, see [`std_docs_of`].
+    std: Option<Crate>,
+    dir_paths: FxHashMap<Definition, Option<String>>,
+    inlined_dir_paths: FxHashMap<Definition, Option<String>>,
+    /// The file the documentation includes, see [`included_file_url`].
+    included_doc_file: Option<Option<FileId>>,
+}
+
+impl DocUrlCache {
+    fn new(db: &RootDatabase, def: Definition, documented_in: Option<Crate>) -> DocUrlCache {
+        DocUrlCache { documented_in, std: std_docs_of(db, def), ..Default::default() }
+    }
+
+    /// The URL of the directory containing the page of `page`, under the documentation root of
+    /// the crate of `base_of`.
+    ///
+    /// When the documentation of `def` is inlined into another crate, the items of the crate of
+    /// `def` that crate re-exports are documented there too, so they are linked to there. Items
+    /// of `core` and `alloc` are linked to where `std` re-exports them, unless the crate of `def`
+    /// is `#![no_std]`.
+    fn dir_url(
+        &mut self,
+        db: &RootDatabase,
+        def: Definition,
+        base_of: Definition,
+        page: Definition,
+    ) -> Result<Url, ResolutionError> {
+        let page_crate = page.krate(db);
+        let inlined_in = match self.documented_in {
+            Some(krate) if page_crate == def.krate(db) => Some(krate),
+            _ if page_crate.map_or(false, |it| is_std_reexported(db, it)) => self.std,
+            _ => None,
+        };
+        if let Some(krate) = inlined_in {
+            let path = self
+                .inlined_dir_paths
+                .entry(page)
+                .or_insert_with(|| inlined_doc_dir_path(db, krate, page));
+            let base = get_doc_base_urls(db, Definition::Module(krate.root_module(db)), None, None);
+            if let (Some(base), Some(path)) = (base.0, path) {
+                return base.join(path).map_err(|_| ResolutionError::UnparsablePath);
+            }
+        }
+        let url = get_doc_base_urls(db, base_of, None, None)
+            .0
+            .ok_or_else(|| ResolutionError::no_doc_base_url(db, base_of))?;
+        match self.dir_path(db, page) {
+            Some(path) => url.join(path).map_err(|_| ResolutionError::UnparsablePath),
+            None => Ok(url),
+        }
+    }
+
+    /// The path of the directory containing the page of `def` relative to the crate's
+    /// documentation root, see [`doc_dir_path`].
+    fn dir_path(&mut self, db: &RootDatabase, def: Definition) -> Option<&str> {
+        self.dir_paths.entry(def).or_insert_with(|| doc_dir_path(db, def)).as_deref()
+    }
+
+    /// The first file included by a `#[doc = include_str!("path")]` attribute of `def`, whose
+    /// documentation the links are in.
+    fn included_doc_file(&mut self, db: &RootDatabase, def: Definition) -> Option<FileId> {
+        *self
+            .included_doc_file
+            .get_or_insert_with(|| def.attrs(db)?.included_doc_files(db).first().copied())
+    }
+}
+
+/// How [`map_links`] changes a link.
+enum LinkRewrite {
+    /// Keep the target and text of the link, changing its type if one is given.
+    Unchanged { link_type: Option<LinkType> },
+    /// Replace the target and text of the link, changing its type if one is given.
+    Rewritten { link_type: Option<LinkType>, target: String, text: String },
 }
 
 /// Rewrites a markdown document, applying 'callback' to each link.
+///
+/// The callback receives the range of the link in the markdown, its type as written, its target
+/// and its text.
 fn map_links<'e>(
-    events: impl Iterator<Item = Event<'e>>,
-    callback: impl Fn(&str, &str) -> (Option<LinkType>, String, String),
+    events: impl Iterator<Item = (Event<'e>, std::ops::Range<usize>)>,
+    callback: impl Fn(TextRange, LinkType, &str, &str) -> LinkRewrite,
 ) -> impl Iterator<Item = Event<'e>> {
-    let mut in_link = false;
-    // holds the origin link target on start event and the rewritten one on end event
-    let mut end_link_target: Option<CowStr<'_>> = None;
-    // normally link's type is determined by the type of link tag in the end event,
-    // however in some cases we want to change the link type, for example,
-    // `Shortcut` type parsed from Start/End tags doesn't make sense for url links
-    let mut end_link_type: Option<LinkType> = None;
-
-    events.map(move |evt| match evt {
+    // nothing carries over from one link to the next, the state lives from the start event of a
+    // link to its end event
+    let mut current: Option<MappedLink<'e>> = None;
+
+    events.map(move |(evt, range)| match evt {
         Event::Start(Tag::Link(link_type, ref target, _)) => {
-            in_link = true;
-            end_link_target = Some(target.clone());
-            end_link_type = Some(link_type);
+            current = Some(MappedLink {
+                range: text_range(range).unwrap_or_default(),
+                link_type,
+                target: target.clone(),
+                end_link_type: Some(link_type),
+            });
             evt
         }
         Event::End(Tag::Link(link_type, target, _)) => {
-            in_link = false;
+            let link = current.take();
+            // the events don't include reference definitions, so links that refer to one carry
+            // its target themselves, dropping the definitions including unused ones
+            let link_type = match link.as_ref().and_then(|it| it.end_link_type).unwrap_or(link_type)
+            {
+                LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => LinkType::Inline,
+                it => it,
+            };
             Event::End(Tag::Link(
-                end_link_type.unwrap_or(link_type),
-                end_link_target.take().unwrap_or(target),
+                link_type,
+                link.map_or(target, |it| it.target),
                 CowStr::Borrowed(""),
             ))
         }
-        Event::Text(s) if in_link => {
-            let (link_type, link_target_s, link_name) =
-                callback(&end_link_target.take().unwrap(), &s);
-            end_link_target = Some(CowStr::Boxed(link_target_s.into()));
-            if !matches!(end_link_type, Some(LinkType::Autolink)) {
-                end_link_type = link_type;
-            }
-            Event::Text(CowStr::Boxed(link_name.into()))
-        }
-        Event::Code(s) if in_link => {
-            let (link_type, link_target_s, link_name) =
-                callback(&end_link_target.take().unwrap(), &s);
-            end_link_target = Some(CowStr::Boxed(link_target_s.into()));
-            if !matches!(end_link_type, Some(LinkType::Autolink)) {
-                end_link_type = link_type;
-            }
-            Event::Code(CowStr::Boxed(link_name.into()))
-        }
+        Event::Text(s) => match &mut current {
+            Some(link) => Event::Text(link.rewrite_text(&callback, s)),
+            None => Event::Text(s),
+        },
+        Event::Code(s) => match &mut current {
+            Some(link) => Event::Code(link.rewrite_text(&callback, s)),
+            None => Event::Code(s),
+        },
         _ => evt,
     })
 }
 
+/// The link [`map_links`] is rewriting, between its start and end events.
+struct MappedLink<'e> {
+    range: TextRange,
+    /// The type of the link as written.
+    link_type: LinkType,
+    /// The original target, replaced by the rewritten one once a text of the link is rewritten.
+    target: CowStr<'e>,
+    /// The type the end event gets, of the end tag if `None`. Normally that type determines the
+    /// link's type, however in some cases we want to change it, for example, the `Shortcut` type
+    /// parsed from Start/End tags doesn't make sense for url links.
+    end_link_type: Option<LinkType>,
+}
+
+impl<'e> MappedLink<'e> {
+    /// Applies `callback` to a text of the link, returning the new text and storing the new
+    /// target and type of the link. Unchanged targets and texts are kept as they are, without
+    /// allocating.
+    fn rewrite_text(
+        &mut self,
+        callback: &impl Fn(TextRange, LinkType, &str, &str) -> LinkRewrite,
+        text: CowStr<'e>,
+    ) -> CowStr<'e> {
+        let (new_type, text) = match callback(self.range, self.link_type, &self.target, &text) {
+            LinkRewrite::Unchanged { link_type } => (link_type, text),
+            LinkRewrite::Rewritten { link_type, target, text } => {
+                self.target = target.into();
+                (link_type, text.into())
+            }
+        };
+        if !matches!(self.end_link_type, Some(LinkType::Autolink)) {
+            self.end_link_type = new_type;
+        }
+        text
+    }
+}
+
+fn text_range(range: std::ops::Range<usize>) -> Option<TextRange> {
+    Some(TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?))
+}
+
 /// Get the root URL for the documentation of a definition.
 ///
 /// ```ignore
@@ -482,64 +2190,20 @@ fn get_doc_base_urls(
 
     let Some(krate) = def.krate(db) else { return Default::default() };
     let Some(display_name) = krate.display_name(db) else { return Default::default() };
-    let crate_data = &db.crate_graph()[krate.into()];
-    let channel = crate_data.channel.map_or("nightly", ReleaseChannel::as_str);
-
-    let (web_base, local_base) = match &crate_data.origin {
-        // std and co do not specify `html_root_url` any longer so we gotta handwrite this ourself.
-        // FIXME: Use the toolchains channel instead of nightly
+    let web_base = db.crate_doc_base_url(krate).map(|it| Url::clone(&it));
+    let local_base = match &db.crate_graph()[krate.into()].origin {
         CrateOrigin::Lang(
             origin @ (LangCrateOrigin::Alloc
             | LangCrateOrigin::Core
             | LangCrateOrigin::ProcMacro
             | LangCrateOrigin::Std
             | LangCrateOrigin::Test),
-        ) => {
-            let system_url = system_doc.and_then(|it| it.join(&format!("{origin}")).ok());
-            let web_url = format!("https://doc.rust-lang.org/{channel}/{origin}");
-            (Some(web_url), system_url)
-        }
-        CrateOrigin::Lang(_) => return (None, None),
-        CrateOrigin::Rustc { name: _ } => {
-            (Some(format!("https://doc.rust-lang.org/{channel}/nightly-rustc/")), None)
-        }
-        CrateOrigin::Local { repo: _, name: _ } => {
-            // FIXME: These should not attempt to link to docs.rs!
-            let weblink = krate.get_html_root_url(db).or_else(|| {
-                let version = krate.version(db);
-                // Fallback to docs.rs. This uses `display_name` and can never be
-                // correct, but that's what fallbacks are about.
-                //
-                // FIXME: clicking on the link should just open the file in the editor,
-                // instead of falling back to external urls.
-                Some(format!(
-                    "https://docs.rs/{krate}/{version}/",
-                    krate = display_name,
-                    version = version.as_deref().unwrap_or("*")
-                ))
-            });
-            (weblink, local_doc)
-        }
-        CrateOrigin::Library { repo: _, name } => {
-            let weblink = krate.get_html_root_url(db).or_else(|| {
-                let version = krate.version(db);
-                // Fallback to docs.rs. This uses `display_name` and can never be
-                // correct, but that's what fallbacks are about.
-                //
-                // FIXME: clicking on the link should just open the file in the editor,
-                // instead of falling back to external urls.
-                Some(format!(
-                    "https://docs.rs/{krate}/{version}/",
-                    krate = name,
-                    version = version.as_deref().unwrap_or("*")
-                ))
-            });
-            (weblink, local_doc)
+        ) => system_doc.and_then(|it| it.join(&format!("{origin}")).ok()),
+        CrateOrigin::Lang(_) | CrateOrigin::Rustc { name: _ } => None,
+        CrateOrigin::Local { repo: _, name: _ } | CrateOrigin::Library { repo: _, name: _ } => {
+            local_doc
         }
     };
-    let web_base = web_base
-        .and_then(|it| Url::parse(&it).ok())
-        .and_then(|it| it.join(&format!("{display_name}/")).ok());
     let local_base = local_base.and_then(|it| it.join(&format!("{display_name}/")).ok());
 
     (web_base, local_base)
@@ -558,10 +2222,25 @@ fn filename_and_frag_for_def(
     if let Some(assoc_item) = def.as_assoc_item(db) {
         let def = match assoc_item.container(db) {
             AssocItemContainer::Trait(t) => t.into(),
-            AssocItemContainer::Impl(i) => i.self_ty(db).as_adt()?.into(),
+            AssocItemContainer::Impl(i) => {
+                let self_ty = i.self_ty(db);
+                match (self_ty.as_adt(), self_ty.as_builtin()) {
+                    (Some(adt), _) => adt.into(),
+                    (None, Some(builtin)) => Definition::BuiltinType(builtin),
+                    // the impls of the other primitives, like slices, are documented on the page
+                    // of the primitive in the crate of the impl
+                    (None, None) => {
+                        primitive_type_name(&self_ty)?;
+                        Definition::SelfType(i)
+                    }
+                }
+            }
         };
         let (_, file, _) = filename_and_frag_for_def(db, def)?;
-        let frag = get_assoc_item_fragment(db, assoc_item)?;
+        let frag = match assoc_item.container(db) {
+            AssocItemContainer::Trait(_) => get_assoc_item_fragment(db, assoc_item)?,
+            AssocItemContainer::Impl(impl_) => impl_item_fragment(db, impl_, assoc_item)?,
+        };
         return Some((def, file, Some(frag)));
     }
 
@@ -577,7 +2256,11 @@ fn filename_and_frag_for_def(
                 Some(kw) => {
                     format!("keyword.{}.html", kw.trim_matches('"'))
                 }
-                None => format!("{name}/index.html"),
+                // so is `#[doc(primitive = "...")]`, the module's docs end up on the primitive's page
+                None => match primitive_of_module(db, m) {
+                    Some(primitive) => format!("primitive.{primitive}.html"),
+                    None => format!("{name}/index.html"),
+                },
             },
             None => String::from("index.html"),
         },
@@ -587,11 +2270,18 @@ fn filename_and_frag_for_def(
         Definition::BuiltinType(t) => format!("primitive.{}.html", t.name()),
         Definition::Function(f) => format!("fn.{}.html", f.name(db)),
         Definition::Variant(ev) => {
-            format!("enum.{}.html#variant.{}", ev.parent_enum(db).name(db), ev.name(db))
+            let file = format!("enum.{}.html", ev.parent_enum(db).name(db));
+            return Some((def, file, Some(doc_fragment(Fragment::Variant(&ev.name(db))))));
         }
-        Definition::Const(c) => format!("const.{}.html", c.name(db)?),
+        Definition::Const(c) => format!("constant.{}.html", c.name(db)?),
         Definition::Static(s) => format!("static.{}.html", s.name(db)),
-        Definition::Macro(mac) => format!("macro.{}.html", mac.name(db)),
+        Definition::Macro(mac) => match mac.kind(db) {
+            hir::MacroKind::Derive => format!("derive.{}.html", mac.name(db)),
+            hir::MacroKind::Attr => format!("attr.{}.html", mac.name(db)),
+            hir::MacroKind::Declarative | hir::MacroKind::BuiltIn | hir::MacroKind::ProcMacro => {
+                format!("macro.{}.html", mac.name(db))
+            }
+        },
         Definition::Field(field) => {
             let def = match field.parent_def(db) {
                 hir::VariantDef::Struct(it) => Definition::Adt(it.into()),
@@ -599,13 +2289,26 @@ fn filename_and_frag_for_def(
                 hir::VariantDef::Variant(it) => Definition::Variant(it),
             };
             let (_, file, _) = filename_and_frag_for_def(db, def)?;
-            return Some((def, file, Some(format!("structfield.{}", field.name(db)))));
+            let fragment = match def {
+                Definition::Variant(it) => Fragment::VariantField(&it.name(db), &field.name(db)),
+                _ => Fragment::StructField(&field.name(db)),
+            };
+            return Some((def, file, Some(doc_fragment(fragment))));
         }
         Definition::SelfType(impl_) => {
-            let adt = impl_.self_ty(db).as_adt()?.into();
-            let (_, file, _) = filename_and_frag_for_def(db, adt)?;
-            // FIXME fragment numbering
-            return Some((adt, file, Some(String::from("impl"))));
+            let self_ty = impl_.self_ty(db);
+            let (page, file) = match self_ty.as_adt() {
+                Some(adt) => {
+                    let adt = adt.into();
+                    (adt, filename_and_frag_for_def(db, adt)?.1)
+                }
+                None => (def, format!("primitive.{}.html", primitive_type_name(&self_ty)?)),
+            };
+            let fragment = match impl_.trait_(db) {
+                Some(_) => Fragment::TraitImpls,
+                None => Fragment::InherentImpls,
+            };
+            return Some((page, file, Some(doc_fragment(fragment))));
         }
         Definition::Local(_)
         | Definition::GenericParam(_)
@@ -633,12 +2336,84 @@ fn get_assoc_item_fragment(db: &dyn HirDatabase, assoc_item: hir::AssocItem) ->
             // Rustdoc makes this decision based on whether a method 'has defaultness'.
             // Currently this is only the case for provided trait methods.
             if is_trait_method && !function.has_body(db) {
-                format!("tymethod.{}", function.name(db))
+                doc_fragment(Fragment::TyMethod(&function.name(db)))
             } else {
-                format!("method.{}", function.name(db))
+                doc_fragment(Fragment::Method(&function.name(db)))
             }
         }
-        AssocItem::Const(constant) => format!("associatedconstant.{}", constant.name(db)?),
-        AssocItem::TypeAlias(ty) => format!("associatedtype.{}", ty.name(db)),
+        AssocItem::Const(constant) => {
+            doc_fragment(Fragment::AssociatedConstant(&constant.name(db)?))
+        }
+        AssocItem::TypeAlias(ty) => doc_fragment(Fragment::AssociatedType(&ty.name(db))),
+    })
+}
+
+/// The fragment of the item `assoc_item` of `impl_` on the page of the impl's self type.
+///
+/// Like rustdoc, items whose anchor an item shown before them on the page already has get a
+/// numbered one, as with a trait method shadowed by an inherent one, `method.len-1`. The items
+/// of inherent impls are shown first.
+fn impl_item_fragment(
+    db: &dyn HirDatabase,
+    impl_: hir::Impl,
+    assoc_item: hir::AssocItem,
+) -> Option<String> {
+    let frag = get_assoc_item_fragment(db, assoc_item)?;
+    let mut impls = hir::Impl::all_for_type(db, impl_.self_ty(db));
+    if !impls.contains(&impl_) {
+        return Some(frag);
+    }
+    impls.sort_by_key(|it| it.trait_(db).is_some());
+    let name = assoc_item.name(db);
+    let earlier = impls
+        .into_iter()
+        .flat_map(|it| it.items(db))
+        .take_while(|&it| it != assoc_item)
+        .filter(|&it| it.name(db) == name && get_assoc_item_fragment(db, it) == Some(frag.clone()))
+        .count();
+    Some(match earlier {
+        0 => frag,
+        n => format!("{frag}-{n}"),
     })
 }
+
+/// A part of the page of an item that rustdoc generates an anchor for.
+#[derive(Clone, Copy, Debug)]
+enum Fragment<'a> {
+    /// A provided method of a trait or a method of an impl.
+    Method(&'a Name),
+    /// A required method of a trait.
+    TyMethod(&'a Name),
+    AssociatedConstant(&'a Name),
+    AssociatedType(&'a Name),
+    Variant(&'a Name),
+    StructField(&'a Name),
+    /// A field of the variant of an enum, named by the variant and then the field.
+    VariantField(&'a Name, &'a Name),
+    /// The section listing the inherent impls of a type. The anchors of single impls depend on
+    /// how rustdoc renders their headers.
+    InherentImpls,
+    /// The section listing the trait impls of a type.
+    TraitImpls,
+}
+
+/// The id rustdoc gives the anchor of `fragment`, like `method.try_into` or
+/// `variant.Some.field.0`. Raw identifiers are written without their `r#` prefix, as in
+/// `structfield.type`. Other identifiers are kept as they are, URLs percent-encode their
+/// non-ASCII characters, which browsers decode before looking for the anchor.
+fn doc_fragment(fragment: Fragment<'_>) -> String {
+    let name = |it: &Name| it.unescaped().to_smol_str();
+    match fragment {
+        Fragment::Method(it) => format!("method.{}", name(it)),
+        Fragment::TyMethod(it) => format!("tymethod.{}", name(it)),
+        Fragment::AssociatedConstant(it) => format!("associatedconstant.{}", name(it)),
+        Fragment::AssociatedType(it) => format!("associatedtype.{}", name(it)),
+        Fragment::Variant(it) => format!("variant.{}", name(it)),
+        Fragment::StructField(it) => format!("structfield.{}", name(it)),
+        Fragment::VariantField(variant, field) => {
+            format!("variant.{}.field.{}", name(variant), name(field))
+        }
+        Fragment::InherentImpls => "implementations".to_owned(),
+        Fragment::TraitImpls => "trait-implementations".to_owned(),
+    }
+}
@@ -1,18 +1,46 @@
-use std::ffi::OsStr;
+mod brackets;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    ffi::OsStr,
+    time::Instant,
+};
 
 use expect_test::{expect, Expect};
 use hir::{HasAttrs, Semantics};
 use ide_db::{
-    base_db::{FilePosition, FileRange},
+    base_db::{
+        fixture::ChangeFixture, salsa::debug::DebugQueryTable, Change, Env, FilePosition,
+        FileRange, FileSet, ProcMacro, ProcMacroExpander, ProcMacroExpansionError, ProcMacroKind,
+        SourceDatabaseExt, SourceRoot, VfsPath,
+    },
     defs::Definition,
-    RootDatabase,
+    doc_links::{
+        broken_link_clone_cb, extract_definitions_from_docs, resolve_doc_path_for_def,
+        CrateDocBaseUrlQuery, DEFAULT_DOC_LINK_RESOLUTION_BUDGET, MARKDOWN_OPTIONS,
+    },
+    documentation::Documentation,
+    imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
+    RootDatabase, SnippetCap,
 };
 use itertools::Itertools;
-use syntax::{ast, match_ast, AstNode, SyntaxNode};
+use pulldown_cmark::{CowStr, Event, Parser, Tag};
+use syntax::{ast, match_ast, AstNode, SyntaxNode, TextRange};
+use test_utils::{bench, skip_slow_tests, AssertLinear};
+use triomphe::Arc;
+use tt::token_id::Subtree;
+use url::Url;
 
 use crate::{
-    doc_links::{extract_definitions_from_docs, resolve_doc_path_for_def, rewrite_links},
-    fixture, TryToNav,
+    doc_links::{
+        broken_doc_links, completion_docs, definition_at, demote_headings, doc_url, file_doc_links,
+        linkify_urls, process_links, remove_links, repository_url, resolve_doc_links,
+        rewrite_links, rewritten_link_events, BrokenDocLink, DocLinkOptions, DocLinksMode,
+        ItemDocLinks, ResolvedDocLink, PARALLEL_COMPLETION_DOCS_CUTOFF,
+    },
+    fixture, AnalysisHost, BrokenLinksMode, CompletionConfig, HoverConfig, HoverDocFormat,
+    PageLinksMode, TryToNav,
 };
 
 fn check_external_docs(
@@ -44,6 +72,27 @@ fn check_external_docs(
     }
 }
 
+fn check_web_doc_url(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let url = analysis.web_doc_url(position).unwrap();
+    expect.assert_debug_eq(&url);
+}
+
+fn check_doc_url(ra_fixture: &str, expect: &str) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let def = definition_at(&analysis.db, &position).expect("no definition at the cursor");
+    let url = doc_url(&analysis.db, &def).map(String::from);
+    assert_eq!(url.as_deref(), Some(expect), "{ra_fixture}");
+}
+
+fn check_repository_url(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, _) = def_under_cursor(sema, &position);
+    let url = repository_url(sema.db, cursor_def);
+    expect.assert_debug_eq(&url);
+}
+
 fn check_rewrite(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
     let sema = &Semantics::new(&*analysis.db);
@@ -52,6 +101,151 @@ fn check_rewrite(ra_fixture: &str, expect: Expect) {
     expect.assert_eq(&res)
 }
 
+fn check_process_links(ra_fixture: &str, options: DocLinkOptions, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let res = process_links(sema.db, docs.as_str(), cursor_def, &options, None);
+    expect.assert_eq(&res)
+}
+
+fn check_rewrite_to_source(ra_fixture: &str, options: DocLinkOptions, expect: Expect) {
+    // Fixture files have virtual paths, which have no `file://` URI, so move them below a root.
+    let root = if cfg!(windows) { "C:\\ws" } else { "/ws" };
+    let mut change_fixture = ChangeFixture::parse(ra_fixture);
+    for source_root in change_fixture.change.roots.iter_mut().flatten() {
+        let mut file_set = FileSet::default();
+        for file_id in source_root.iter() {
+            let path = source_root.path_for_file(&file_id).unwrap();
+            file_set.insert(file_id, VfsPath::new_real_path(format!("{root}{path}")));
+        }
+        *source_root = match source_root.is_library {
+            true => SourceRoot::new_library(file_set),
+            false => SourceRoot::new_local(file_set),
+        };
+    }
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture.file_position.unwrap();
+    let position = FilePosition { file_id, offset: range_or_offset.expect_offset() };
+
+    let sema = &Semantics::new(&host.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let res = process_links(sema.db, docs.as_str(), cursor_def, &options, None);
+    let root_url = Url::from_directory_path(root).unwrap();
+    expect.assert_eq(&res.replace(root_url.as_str(), "file:///$ROOT/"))
+}
+
+fn check_linkify(markdown: &str, expect: Expect) {
+    expect.assert_eq(&linkify_urls(markdown))
+}
+
+fn check_demote_headings(markdown: &str, expect: Expect) {
+    expect.assert_eq(&demote_headings(markdown))
+}
+
+fn check_resolve(ra_fixture: &str, expect: Expect) {
+    check_resolve_with_budget(ra_fixture, DEFAULT_DOC_LINK_RESOLUTION_BUDGET, expect)
+}
+
+fn check_resolve_with_budget(ra_fixture: &str, budget: usize, expect: Expect) {
+    let (host, position) = host_with_budget(ra_fixture, budget);
+    check_resolve_in(&host, position, expect)
+}
+
+fn check_resolve_with_doc_aliases(ra_fixture: &str, expect: Expect) {
+    let (mut host, position) = host_with_budget(ra_fixture, DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    host.update_doc_link_alias_resolution(true);
+    check_resolve_in(&host, position, expect)
+}
+
+fn check_resolve_in(host: &AnalysisHost, position: FilePosition, expect: Expect) {
+    let sema = &Semantics::new(&host.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let res =
+        resolve_doc_links(sema.db, cursor_def, docs.as_str(), &DocLinkOptions::default(), None)
+            .into_iter()
+            .map(|link| {
+                let ResolvedDocLink {
+                    range,
+                    url,
+                    target_kind: _,
+                    definition,
+                    text,
+                    ambiguous,
+                    impl_ambiguous,
+                    candidates,
+                    error,
+                    inactive_cfg,
+                    doc_alias,
+                    reference_definition,
+                    source_link,
+                    removed: _,
+                } = link;
+                let definition =
+                    definition.and_then(|def| def.name(sema.db)).map(|it| it.to_smol_str());
+                let ambiguous = if ambiguous { " ambiguous" } else { "" };
+                let impl_ambiguous = if impl_ambiguous { " impl ambiguous" } else { "" };
+                let candidates = match candidates.is_empty() {
+                    true => String::new(),
+                    false => {
+                        let urls = candidates.iter().map(|it| it.url.as_deref().unwrap_or("?"));
+                        format!(" candidates: {}", urls.format(", "))
+                    }
+                };
+                let doc_alias = if doc_alias { " doc alias" } else { "" };
+                let reference_definition =
+                    if reference_definition { " reference definition" } else { "" };
+                let source_link = if source_link { " source link" } else { "" };
+                let error = error.map(|it| format!(" {it:?}")).unwrap_or_default();
+                let inactive =
+                    inactive_cfg.map(|it| format!(" inactive: {it:?}")).unwrap_or_default();
+                format!(
+                "{range:?} {text:?} {url:?} {definition:?}{ambiguous}{impl_ambiguous}{candidates}{doc_alias}{reference_definition}{source_link}{error}{inactive}\n"
+            )
+            })
+            .collect::<String>();
+    expect.assert_eq(&res)
+}
+
+fn host_with_budget(ra_fixture: &str, budget: usize) -> (AnalysisHost, FilePosition) {
+    let change_fixture = ChangeFixture::parse(ra_fixture);
+    let mut host = AnalysisHost::default();
+    host.update_doc_link_resolution_budget(budget);
+    host.db.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture.file_position.unwrap();
+    (host, FilePosition { file_id, offset: range_or_offset.expect_offset() })
+}
+
+fn check_broken_doc_links(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, _) = def_under_cursor(sema, &position);
+    let res = broken_doc_links(sema.db, cursor_def)
+        .into_iter()
+        .map(|BrokenDocLink { range, target, reason }| {
+            format!("{:?} {target:?} {reason:?}\n", range.map(|it| it.range))
+        })
+        .collect::<String>();
+    expect.assert_eq(&res)
+}
+
+fn check_file_doc_links(ra_fixture: &str, expect: Expect) {
+    let (analysis, file_id) = fixture::file(ra_fixture);
+    let res = file_doc_links(&analysis.db, file_id)
+        .into_iter()
+        .map(|ItemDocLinks { definition: _, path, range, links }| {
+            let links = links.into_iter().map(|(range, link)| {
+                let ambiguous = if link.ambiguous { " ambiguous" } else { "" };
+                let error = link.error.map(|it| format!(" {it:?}")).unwrap_or_default();
+                format!("    {range:?} {:?} {:?}{ambiguous}{error}\n", link.target_kind, link.url)
+            });
+            format!("{path} {range:?}\n{}", links.collect::<String>())
+        })
+        .collect::<String>();
+    expect.assert_eq(&res)
+}
+
 fn check_doc_links(ra_fixture: &str) {
     let key_fn = |&(FileRange { file_id, range }, _): &_| (file_id, range.start());
 
@@ -150,6 +344,145 @@ pub struct Foo;
     );
 }
 
+#[test]
+fn doc_url_of_each_definition_kind() {
+    let table = [
+        ("pub mod modu$0le {}", "foo/module/index.html"),
+        ("pub fn func$0tion() {}", "foo/fn.function.html"),
+        ("pub struct Str$0uct;", "foo/struct.Struct.html"),
+        ("pub struct S { pub fi$0eld: u8 }", "foo/struct.S.html#structfield.field"),
+        ("pub union Uni$0on { f: u8 }", "foo/union.Union.html"),
+        ("pub enum En$0um {}", "foo/enum.Enum.html"),
+        ("pub enum E { Vari$0ant }", "foo/enum.E.html#variant.Variant"),
+        ("pub trait Tra$0it {}", "foo/trait.Trait.html"),
+        ("pub trait T { fn meth$0od(); }", "foo/trait.T.html#tymethod.method"),
+        (
+            "pub struct S; impl S { pub const CON$0ST: u8 = 0; }",
+            "foo/struct.S.html#associatedconstant.CONST",
+        ),
+        ("pub type Ali$0as = ();", "foo/type.Alias.html"),
+        ("pub const CON$0ST: u8 = 0;", "foo/constant.CONST.html"),
+        ("pub static STA$0TIC: u8 = 0;", "foo/static.STATIC.html"),
+        ("#[macro_export] macro_rules! ma$0c { () => {} }", "foo/macro.mac.html"),
+    ];
+    for (item, path) in table {
+        check_doc_url(
+            &format!("//- /lib.rs crate:foo@CratesIo:0.1.2,https://example.com/foo\n{item}"),
+            &format!("https://docs.rs/foo/0.1.2/{path}"),
+        );
+    }
+    check_doc_url(
+        "//- /lib.rs crate:foo\npub fn f(_: u$08) {}",
+        "https://doc.rust-lang.org/nightly/core/primitive.u8.html",
+    );
+}
+
+#[test]
+fn web_doc_url() {
+    check_web_doc_url(
+        r#"
+//- /main.rs crate:main deps:foo
+fn f(s: foo::S) {
+    s.meth$0od();
+}
+//- /lib.rs crate:foo@CratesIo:0.1.2,https://example.com/foo
+pub struct S;
+impl S {
+    pub fn method(&self) {}
+}
+"#,
+        expect![[r#"
+            Ok(
+                "https://docs.rs/foo/0.1.2/foo/struct.S.html#method.method",
+            )
+        "#]],
+    );
+    check_web_doc_url(
+        r#"
+//- /main.rs crate:main
+fn f() {
+    let local = 0;
+    loc$0al;
+}
+"#,
+        expect![[r#"
+            Err(
+                "The item at the cursor has no documentation page",
+            )
+        "#]],
+    );
+    check_web_doc_url(
+        r#"
+//- /main.rs crate:main
+fn f() {
+    $0
+}
+"#,
+        expect![[r#"
+            Err(
+                "No item found at the cursor",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn repository_url_of_library_item() {
+    check_repository_url(
+        r#"
+//- /foo/src/lib.rs crate:foo@CratesIo:0.1.2,https://github.com/org/foo.git new_source_root:library
+mod s;
+pub use s::S;
+//- /foo/src/s.rs
+/// Docs
+pub struct S$0 {
+    field: u32,
+}
+"#,
+        expect![[r#"
+            Some(
+                "https://github.com/org/foo/blob/v0.1.2/src/s.rs#L1-L4",
+            )
+        "#]],
+    );
+    check_repository_url(
+        r#"
+//- /foo/src/lib.rs crate:foo@CratesIo:0.1.2,https://gitlab.com/group/sub/foo/-/tree/main new_source_root:library
+/// Docs
+pub struct S$0;
+"#,
+        expect![[r#"
+            Some(
+                "https://gitlab.com/group/sub/foo/-/blob/v0.1.2/src/lib.rs#L1-2",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn no_repository_url_for_local_or_unknown_hosts() {
+    check_repository_url(
+        r#"
+//- /foo/src/lib.rs crate:foo@CratesIo:0.1.2,https://github.com/org/foo
+/// Docs
+pub struct S$0;
+"#,
+        expect![[r#"
+            None
+        "#]],
+    );
+    check_repository_url(
+        r#"
+//- /foo/src/lib.rs crate:foo@CratesIo:0.1.2,https://example.com/foo new_source_root:library
+/// Docs
+pub struct S$0;
+"#,
+        expect![[r#"
+            None
+        "#]],
+    );
+}
+
 #[test]
 fn external_docs_doc_url_std_crate() {
     check_external_docs(
@@ -236,6 +569,137 @@ pub fn fo$0o() {}
     );
 }
 
+#[test]
+fn external_docs_fragments() {
+    // the anchors rustdoc generates on the pages of items for their parts
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo {
+    r#type$0: (),
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#structfield.type"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo(pub u8, pub u8);
+fn f(foo: Foo) { foo.1$0; }
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#structfield.1"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub enum Foo {
+    Bar$0,
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/enum.Foo.html#variant.Bar"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub enum Foo {
+    Bar { r#type$0: () },
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/enum.Foo.html#variant.Bar.field.type"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub trait Foo {
+    fn required$0();
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/trait.Foo.html#tymethod.required"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub trait Foo {
+    type Type$0;
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/trait.Foo.html#associatedtype.Type"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo;
+impl Foo {
+    pub fn r#try$0() {}
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#method.try"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo;
+impl Foo {
+    pub fn café$0() {}
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#method.caf%C3%A9"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo;
+impl Foo {
+    pub fn new() -> Self$0 { Foo }
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#implementations"##]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+pub struct Foo;
+pub trait Make {
+    fn make() -> Self;
+}
+impl Make for Foo {
+    fn make() -> Self$0 { Foo }
+}
+"#,
+        None,
+        Some(expect![[r##"https://docs.rs/foo/*/foo/struct.Foo.html#trait-implementations"##]]),
+        None,
+        None,
+    );
+}
+
 #[test]
 fn external_docs_doc_url_impl_assoc() {
     check_external_docs(
@@ -266,6 +730,46 @@ impl Foo {
     );
 }
 
+#[test]
+fn external_docs_doc_url_deref_supplied_slice_method() {
+    check_external_docs(
+        r#"
+//- /main.rs crate:main deps:alloc
+fn f(v: alloc::vec::Vec<u8>) {
+    v.to_v$0ec();
+}
+//- /libcore.rs crate:core
+#![rustc_coherence_is_core]
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        #[lang = "deref_target"]
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
+}
+//- /liballoc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> core::ops::Deref for Vec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] { loop {} }
+    }
+}
+pub mod slice {
+    impl<T> [T] {
+        #[rustc_allow_incoherent_impl]
+        pub fn to_vec(&self) -> crate::vec::Vec<T> { loop {} }
+    }
+}
+"#,
+        None,
+        Some(expect!["https://doc.rust-lang.org/stable/alloc/primitive.slice.html#method.to_vec"]),
+        None,
+        None,
+    );
+}
+
 #[test]
 fn external_docs_doc_url_impl_trait_assoc() {
     check_external_docs(
@@ -372,6 +876,54 @@ trait Trait$0 {}
     )
 }
 
+#[test]
+fn external_docs_in_macro_call() {
+    check_external_docs(
+        r#"
+//- /main.rs crate:foo
+macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+pub struct Foo;
+impl Foo {
+    pub fn method(&self) {}
+}
+id! {
+    fn f(foo: Foo) {
+        foo.meth$0od();
+    }
+}
+"#,
+        None,
+        Some(expect![[r#"https://docs.rs/foo/*/foo/struct.Foo.html#method.method"#]]),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn external_docs_derive_and_attr_macros() {
+    check_external_docs(
+        r#"
+//- proc_macros: derive_identity
+use proc_macros::DeriveIdentity$0;
+"#,
+        None,
+        Some(expect![[r#"https://docs.rs/proc_macros/*/proc_macros/derive.DeriveIdentity.html"#]]),
+        None,
+        None,
+    );
+    check_external_docs(
+        r#"
+//- proc_macros: identity
+#[proc_macros::identity$0]
+fn foo() {}
+"#,
+        None,
+        Some(expect![[r#"https://docs.rs/proc_macros/*/proc_macros/attr.identity.html"#]]),
+        None,
+        None,
+    );
+}
+
 #[test]
 fn external_docs_module() {
     check_external_docs(
@@ -413,14 +965,35 @@ fn foo() {
 }
 
 #[test]
-fn doc_links_items_simple() {
-    check_doc_links(
+fn external_docs_reexport_of_private_module() {
+    check_external_docs(
         r#"
-//- /main.rs crate:main deps:krate
-/// [`krate`]
-//! [`Trait`]
-//! [`function`]
-//! [`CONST`]
+//- /main.rs crate:foo
+mod imp {
+    pub struct Item;
+}
+pub use imp::Item;
+
+fn foo() {
+    let bar: It$0em;
+}
+        "#,
+        None,
+        Some(expect![[r#"https://docs.rs/foo/*/foo/struct.Item.html"#]]),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn doc_links_items_simple() {
+    check_doc_links(
+        r#"
+//- /main.rs crate:main deps:krate
+/// [`krate`]
+//! [`Trait`]
+//! [`function`]
+//! [`CONST`]
 //! [`STATIC`]
 //! [`Struct`]
 //! [`Enum`]
@@ -459,14 +1032,15 @@ mod module {}
 fn doc_links_inherent_impl_items() {
     check_doc_links(
         r#"
-// /// [`Struct::CONST`]
-// /// [`Struct::function`]
-/// FIXME #9694
+/// [`Struct::CONST`]
+/// [`Struct::function`]
 struct Struct$0;
 
 impl Struct {
     const CONST: () = ();
+       // ^^^^^ Struct::CONST
     fn function() {}
+    // ^^^^^^^^ Struct::function
 }
 "#,
     )
@@ -481,21 +1055,82 @@ trait Trait {
     const CONST: usize;
     fn function();
 }
-// /// [`Struct::Type`]
-// /// [`Struct::CONST`]
-// /// [`Struct::function`]
-/// FIXME #9694
+/// [`Struct::Type`]
+/// [`Struct::CONST`]
+/// [`Struct::function`]
 struct Struct$0;
 
 impl Trait for Struct {
     type Type = ();
+      // ^^^^ Struct::Type
     const CONST: () = ();
+       // ^^^^^ Struct::CONST
     fn function() {}
+    // ^^^^^^^^ Struct::function
+}
+"#,
+    )
+}
+
+#[test]
+fn doc_links_self() {
+    check_doc_links(
+        r#"
+struct Struct;
+    // ^^^^^^ Self
+impl Struct {
+    /// [`Self`], [`Self::CONST`] and [`fn@Self::function`]
+    fn function$0() {}
+    // ^^^^^^^^ Self::function
+    const CONST: () = ();
+       // ^^^^^ Self::CONST
 }
 "#,
     )
 }
 
+#[test]
+fn doc_links_respect_namespace_of_assoc_items() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [`fn@Struct::new`] and [`type@Struct::new`]
+pub struct Struct$0;
+
+impl Struct {
+    pub fn new() {}
+}
+"#,
+        expect![[r#"
+            0..18 "Struct::new" Some("https://docs.rs/foo/*/foo/struct.Struct.html#method.new") Some("new")
+            23..43 "type@Struct::new" None None WrongNamespace { expected: Types }
+        "#]],
+    );
+}
+
+#[test]
+fn doc_links_respect_function_kind_of_long_disambiguators() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [`method@Shard::connect`], [`function@parse`], [`method@parse`] and
+/// [`function@Shard::connect`]
+pub struct Shard$0;
+
+impl Shard {
+    pub fn connect() {}
+}
+pub fn parse() {}
+"#,
+        expect![[r#"
+            0..25 "Shard::connect" Some("https://docs.rs/foo/*/foo/struct.Shard.html#method.connect") Some("connect")
+            27..45 "parse" Some("https://docs.rs/foo/*/foo/fn.parse.html") Some("parse")
+            47..63 "method@parse" None None WrongFunctionKind { expected: Associated }
+            68..95 "function@Shard::connect" None None WrongFunctionKind { expected: Free }
+        "#]],
+    );
+}
+
 #[test]
 fn doc_links_trait_items() {
     check_doc_links(
@@ -534,6 +1169,123 @@ pub struct B$0ar
     );
 }
 
+#[test]
+fn process_links_by_options() {
+    let fixture = r#"
+//- /main.rs crate:foo
+/// [Foo](struct.Foo.html) and [`Foo`].
+pub struct $0Foo;
+"#;
+    let (analysis, position) = fixture::position(fixture);
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let default = process_links(sema.db, docs.as_str(), cursor_def, &Default::default(), None);
+    assert_eq!(default, rewrite_links(sema.db, docs.as_str(), cursor_def));
+
+    check_process_links(
+        fixture,
+        DocLinkOptions::default(),
+        expect![[
+            r#"[Foo](https://docs.rs/foo/*/foo/struct.Foo.html) and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)."#
+        ]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_mode(DocLinksMode::Strip),
+        expect!["Foo and `Foo`."],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_mode(DocLinksMode::Raw),
+        expect!["[Foo](struct.Foo.html) and [`Foo`]."],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_page_links(PageLinksMode::Ignore),
+        expect!["[Foo](struct.Foo.html) and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)."],
+    );
+}
+
+#[test]
+fn rewrite_broken_links_by_mode() {
+    let fixture = r#"
+//- /main.rs crate:foo
+/// [`Foo`], [`Missing`], [gone](Gone), [`gone`](struct.Gone.html), [`both`], [docs](https://example.com)
+/// and [missing] with
+///
+/// [missing]: Missing
+pub struct $0Foo;
+pub mod both {}
+pub fn both() {}
+"#;
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_broken_links(BrokenLinksMode::Keep),
+        expect![[r#"
+        [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), `Missing`, [gone](Gone), [`gone`](https://docs.rs/foo/*/foo/struct.Gone.html), [`both`](https://docs.rs/foo/*/foo/both/index.html), [docs](https://example.com)
+        and [missing](Missing) with"#]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_broken_links(BrokenLinksMode::Strip),
+        expect![[r#"
+        [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), `Missing`, gone, [`gone`](https://docs.rs/foo/*/foo/struct.Gone.html), [`both`](https://docs.rs/foo/*/foo/both/index.html), [docs](https://example.com)
+        and missing with"#]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_broken_links(BrokenLinksMode::StripWithAmbiguous),
+        expect![[r#"
+        [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), `Missing`, gone, [`gone`](https://docs.rs/foo/*/foo/struct.Gone.html), `both`, [docs](https://example.com)
+        and missing with"#]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default()
+            .with_mode(DocLinksMode::Raw)
+            .with_broken_links(BrokenLinksMode::StripWithAmbiguous),
+        expect![[r#"
+        [`Foo`], [`Missing`], [gone](Gone), [`gone`](struct.Gone.html), [`both`], [docs](https://example.com)
+        and [missing] with
+
+        [missing]: Missing"#]],
+    );
+}
+
+#[test]
+fn rewrite_page_links_by_mode() {
+    let fixture = r#"
+//- /main.rs crate:foo
+/// [Foo](struct.Foo.html), [new](#method.new), [gone](struct.Gone.html), [docs](https://example.com)
+/// and [`Foo`].
+pub struct $0Foo;
+impl Foo {
+    pub fn new() -> Foo { Foo }
+}
+"#;
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_page_links(PageLinksMode::Rewrite),
+        expect![[r#"
+        [Foo](https://docs.rs/foo/*/foo/struct.Foo.html), [new](https://docs.rs/foo/*/foo/struct.Foo.html#method.new), [gone](https://docs.rs/foo/*/foo/struct.Gone.html), [docs](https://example.com)
+        and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)."#]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_page_links(PageLinksMode::Ignore),
+        expect![[r#"
+        [Foo](struct.Foo.html), [new](#method.new), [gone](struct.Gone.html), [docs](https://example.com)
+        and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)."#]],
+    );
+    check_process_links(
+        fixture,
+        DocLinkOptions::default().with_page_links(PageLinksMode::Strip),
+        expect![[r#"
+        Foo, new, gone, [docs](https://example.com)
+        and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)."#]],
+    );
+}
+
 #[test]
 fn rewrite_on_field() {
     check_rewrite(
@@ -600,6 +1352,1977 @@ pub struct $0Foo;
 /// [`foo`]: Foo
 pub struct $0Foo;
 "#,
-        expect![["[`foo`]"]],
+        expect![[r#"[`foo`](https://docs.rs/foo/*/foo/struct.Foo.html)"#]],
+    );
+}
+
+#[test]
+fn rewrite_links_to_reexported_items() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [`Client`], [`Error`], [`gateway::Shard`], [`Event`], [`Plain`], [`session`], [`Session`],
+/// [`prelude::Config`] and [`Legacy`].
+pub fn $0links() {}
+
+mod client {
+    pub struct Client;
+}
+pub use client::Client;
+
+mod error {
+    pub enum Error {}
+}
+pub use error::*;
+
+pub mod gateway {
+    mod shard {
+        pub struct Shard;
+    }
+    pub use self::shard::Shard;
+
+    pub mod event {
+        pub struct Event;
+    }
+}
+#[doc(inline)]
+pub use gateway::event::Event;
+
+#[doc(hidden)]
+pub mod internal {
+    pub struct Plain;
+}
+pub use internal::Plain;
+
+mod sessions {
+    pub mod session {
+        pub struct Session;
+    }
+}
+pub use sessions::session::{self, Session};
+
+mod config {
+    pub struct Config;
+}
+pub mod prelude {
+    pub use crate::config::Config;
+}
+
+pub mod v1 {
+    mod imp {
+        pub struct Legacy;
+    }
+    #[doc(no_inline)]
+    pub use self::imp::Legacy;
+}
+pub use v1::Legacy;
+"#,
+        expect![[r#"
+            [`Client`](https://docs.rs/foo/*/foo/struct.Client.html), [`Error`](https://docs.rs/foo/*/foo/enum.Error.html), [`gateway::Shard`](https://docs.rs/foo/*/foo/gateway/struct.Shard.html), [`Event`](https://docs.rs/foo/*/foo/gateway/event/struct.Event.html), [`Plain`](https://docs.rs/foo/*/foo/struct.Plain.html), [`session`](https://docs.rs/foo/*/foo/session/index.html), [`Session`](https://docs.rs/foo/*/foo/session/struct.Session.html),
+            [`prelude::Config`](https://docs.rs/foo/*/foo/prelude/struct.Config.html) and [`Legacy`](https://docs.rs/foo/*/foo/struct.Legacy.html)."#]],
+    );
+}
+
+#[test]
+fn rewrite_core_and_alloc_links_to_std() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo deps:core,alloc,std
+/// [Vec], [`core::fmt::Debug`] and [`Option`].
+pub fn $0links() {}
+//- /std.rs crate:std deps:core,alloc
+pub use alloc::vec;
+pub use core::{fmt, option};
+pub mod prelude {
+    pub mod rust_2021 {
+        pub use crate::{option::Option, vec::Vec};
+    }
+}
+//- /alloc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec;
+}
+//- /core.rs crate:core
+pub mod fmt {
+    pub trait Debug {}
+}
+pub mod option {
+    pub enum Option {}
+}
+pub mod prelude {
+    pub mod rust_2021 {
+        pub use crate::option::Option;
+    }
+}
+"#,
+        expect!["[Vec](https://doc.rust-lang.org/stable/std/vec/struct.Vec.html), [`core::fmt::Debug`](https://doc.rust-lang.org/stable/std/fmt/trait.Debug.html) and [`Option`](https://doc.rust-lang.org/stable/std/option/enum.Option.html)."],
+    );
+}
+
+#[test]
+fn rewrite_core_and_alloc_links_in_no_std_crates() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo deps:core,alloc,std
+#![no_std]
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// [Vec], [`core::fmt::Debug`], [`Option`] and [`alloc::vec`].
+pub fn $0links() {}
+//- /std.rs crate:std deps:core,alloc
+pub use alloc::vec;
+pub use core::{fmt, option};
+pub mod prelude {
+    pub mod rust_2021 {
+        pub use crate::{option::Option, vec::Vec};
+    }
+}
+//- /alloc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec;
+}
+//- /core.rs crate:core
+pub mod fmt {
+    pub trait Debug {}
+}
+pub mod option {
+    pub enum Option {}
+}
+pub mod prelude {
+    pub mod rust_2021 {
+        pub use crate::option::Option;
+    }
+}
+"#,
+        expect!["[Vec](https://doc.rust-lang.org/stable/alloc/vec/struct.Vec.html), [`core::fmt::Debug`](https://doc.rust-lang.org/stable/core/fmt/trait.Debug.html), [`Option`](https://doc.rust-lang.org/stable/core/option/enum.Option.html) and [`alloc::vec`](https://doc.rust-lang.org/stable/alloc/vec/index.html)."],
+    );
+}
+
+#[test]
+fn rewrite_links_to_and_from_primitive_modules_in_core() {
+    check_rewrite(
+        r#"
+//- /core.rs crate:core
+mod primitive_docs {
+    /// The string slice type, see [`len`](#method.len), [`Chars`](str/struct.Chars.html),
+    /// [`Utf8Error`](crate::str::Utf8Error) and [`slice`](primitive.slice.html).
+    #[rustc_doc_primitive = "str"]
+    mod $0prim_str {}
+}
+pub mod str {
+    pub struct Chars;
+    pub struct Utf8Error;
+}
+"#,
+        expect![[r#"
+            The string slice type, see [`len`](https://doc.rust-lang.org/stable/core/primitive.str.html#method.len), [`Chars`](https://doc.rust-lang.org/stable/core/str/struct.Chars.html),
+            [`Utf8Error`](https://doc.rust-lang.org/stable/core/str/struct.Utf8Error.html) and [`slice`](https://doc.rust-lang.org/stable/core/primitive.slice.html)."#]],
+    );
+    check_rewrite(
+        r#"
+//- /core.rs crate:core
+/// See [`docs::prim_str`] and [str](crate::docs::prim_str).
+pub mod $0docs {
+    #[doc(primitive = "str")]
+    pub mod prim_str {}
+}
+"#,
+        expect!["See [`docs::prim_str`](https://doc.rust-lang.org/stable/core/primitive.str.html) and [str](https://doc.rust-lang.org/stable/core/primitive.str.html)."],
+    );
+}
+
+#[test]
+fn rewrite_links_in_trait_impl_items() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub trait Gateway {
+    type Error;
+    fn connect();
+    fn heartbeat();
+    fn reconnect() {}
+}
+pub struct Shard;
+impl Shard {
+    pub fn inherent() {}
+}
+/// Shadowed by the item of the impl.
+pub struct Error;
+pub struct Session;
+impl Gateway for Shard {
+    type Error = ();
+    fn connect() {}
+    /// Impl items [Error] and [connect], trait items [reconnect], items in scope [Session] and
+    /// [Self::inherent], but not [inherent].
+    fn heart$0beat() {}
+}
+"#,
+        expect!["Impl items [Error](https://docs.rs/foo/*/foo/struct.Shard.html#associatedtype.Error) and [connect](https://docs.rs/foo/*/foo/struct.Shard.html#method.connect), trait items [reconnect](https://docs.rs/foo/*/foo/trait.Gateway.html#method.reconnect), items in scope [Session](https://docs.rs/foo/*/foo/struct.Session.html) and\n[Self::inherent](https://docs.rs/foo/*/foo/struct.Shard.html#method.inherent), but not inherent."],
+    );
+}
+
+#[test]
+fn rewrite_links_written_as_types() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub trait Handler {}
+pub trait Error {}
+pub struct Box<T>(T);
+/// [dyn Handler], [impl Handler], [`&dyn Handler`], [`&mut dyn Handler`] and
+/// [`Box<dyn Error>`]
+pub struct $0Foo;
+"#,
+        expect!["[dyn Handler](https://docs.rs/foo/*/foo/trait.Handler.html), [impl Handler](https://docs.rs/foo/*/foo/trait.Handler.html), [`&dyn Handler`](https://docs.rs/foo/*/foo/trait.Handler.html), [`&mut dyn Handler`](https://docs.rs/foo/*/foo/trait.Handler.html) and\n[`Box<dyn Error>`](https://docs.rs/foo/*/foo/struct.Box.html)"],
+    );
+}
+
+#[test]
+fn rewrite_tuple_field_links() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub struct Pair(pub u8, pub u8);
+pub struct Named { pub x: u8 }
+pub enum E { V(u8), W }
+/// [Pair::0], [Pair::1], [Self::0], [E::V::0], but not [Pair::2], [Named::0] or [E::W::0]
+pub struct $0Foo(u8);
+"#,
+        expect!["[Pair::0](https://docs.rs/foo/*/foo/struct.Pair.html#structfield.0), [Pair::1](https://docs.rs/foo/*/foo/struct.Pair.html#structfield.1), [Self::0](https://docs.rs/foo/*/foo/struct.Foo.html#structfield.0), [E::V::0](https://docs.rs/foo/*/foo/enum.E.html#variant.V.field.0), but not Pair::2, Named::0 or E::W::0"],
+    );
+}
+
+#[test]
+fn rewrite_named_field_links() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub enum Event { Ready { session_id: String }, Closed(u8) }
+pub union Bits { pub raw: u32 }
+use Event::Ready;
+/// [Event::Ready::session_id], [`Ready::session_id`], [Bits::raw], [Self::id],
+/// but not [Self::Ready::session_id], [Self::Closed] or [Event::Closed::session_id]
+pub struct $0Foo { id: u8 }
+"#,
+        expect![[r#"
+            [Event::Ready::session_id](https://docs.rs/foo/*/foo/enum.Event.html#variant.Ready.field.session_id), [`Ready::session_id`](https://docs.rs/foo/*/foo/enum.Event.html#variant.Ready.field.session_id), [Bits::raw](https://docs.rs/foo/*/foo/union.Bits.html#structfield.raw), [Self::id](https://docs.rs/foo/*/foo/struct.Foo.html#structfield.id),
+            but not Self::Ready::session_id, Self::Closed or Event::Closed::session_id"#]],
+    );
+}
+
+#[test]
+fn rewrite_links_in_docs_of_variant_fields() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub enum Event {
+    Ready {
+        /// See [Event], [Self], [Self::Closed], [Self::Ready::peer] and [Config].
+        session$0_id: String,
+        peer: String,
+    },
+    Closed,
+}
+pub struct Config;
+"#,
+        expect!["See [Event](https://docs.rs/foo/*/foo/enum.Event.html), [Self](https://docs.rs/foo/*/foo/enum.Event.html), [Self::Closed](https://docs.rs/foo/*/foo/enum.Event.html#variant.Closed), [Self::Ready::peer](https://docs.rs/foo/*/foo/enum.Event.html#variant.Ready.field.peer) and [Config](https://docs.rs/foo/*/foo/struct.Config.html)."],
+    );
+}
+
+#[test]
+fn rewrite_links_to_generic_params() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub struct T;
+pub struct Shard;
+impl Shard {
+    /// Opens `N` connections of type [T], [`N`] of them, with [`T`](type@T) shadowing the struct.
+    pub fn op$0en<T, const N: usize>() {}
+}
+"#,
+        expect!["Opens `N` connections of type [T](https://docs.rs/foo/*/foo/struct.Shard.html#method.open), [`N`](https://docs.rs/foo/*/foo/struct.Shard.html#method.open) of them, with [`T`](https://docs.rs/foo/*/foo/struct.Shard.html#method.open) shadowing the struct."],
+    );
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub struct Shard<T>(T);
+impl<T> Shard<T> {
+    /// Connects [T] to [Self].
+    pub fn con$0nect() {}
+}
+"#,
+        expect!["Connects [T](https://docs.rs/foo/*/foo/struct.Shard.html#implementations) to [Self](https://docs.rs/foo/*/foo/struct.Shard.html)."],
+    );
+}
+
+#[test]
+fn rewrite_leaves_links_to_lifetimes_alone() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Borrows the session for ['a], [`'a`] and ['static].
+pub struct Sha$0rd<'a>(&'a ());
+"#,
+        expect!["Borrows the session for ['a], [`'a`] and ['static]."],
+    );
+}
+
+#[test]
+fn rewrite_multi_part_link_text() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [the `Foo` type](Foo) and [`struct@Foo`]
+pub struct $0Foo;
+"#,
+        expect![[
+            r#"[the `Foo` type](https://docs.rs/foo/*/foo/struct.Foo.html) and [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html)"#
+        ]],
+    );
+}
+
+#[test]
+fn rewrite_keeps_urls_and_unresolved_links() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [docs](https://example.com/docs), <https://example.com> and [Missing]
+pub struct $0Foo;
+"#,
+        expect!["[docs](https://example.com/docs), <https://example.com> and Missing"],
+    );
+}
+
+#[test]
+fn rewrite_unresolved_shortcut_links_as_text() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [Missing], [`Missing`][], [the docs](docs/missing.md) and [Foo]
+pub struct $0Foo;
+"#,
+        expect!["Missing, `Missing`, [the docs](docs/missing.md) and [Foo](https://docs.rs/foo/*/foo/struct.Foo.html)"],
+    );
+}
+
+#[test]
+fn rewrite_reference_defined_in_separate_attribute() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Uses [my Foo][foo].
+#[doc = ""]
+#[doc = " [foo]: Foo"]
+pub struct $0Foo;
+"#,
+        expect!["Uses [my Foo](https://docs.rs/foo/*/foo/struct.Foo.html)."],
+    );
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/** Uses [my Foo][foo].
+
+    More text. */
+#[doc = ""]
+#[doc = "[foo]: Foo"]
+pub struct $0Foo;
+"#,
+        expect![[r#"
+            Uses [my Foo](https://docs.rs/foo/*/foo/struct.Foo.html).
+
+            More text."#]],
+    );
+}
+
+#[test]
+fn rewrite_local_links_to_source() {
+    check_rewrite_to_source(
+        r#"
+//- /main.rs crate:foo deps:dep
+/// [`Local`], [the module](inner) and [`Dep`](dep::Dep)
+pub struct $0Foo;
+
+pub struct Local;
+pub mod inner {}
+//- /dep.rs crate:dep new_source_root:library
+pub struct Dep;
+"#,
+        DocLinkOptions::default().with_mode(DocLinksMode::Navigate),
+        expect!["[`Local`](file:///$ROOT/main.rs#L4), [the module](file:///$ROOT/main.rs#L5) and [`Dep`](https://docs.rs/dep/*/dep/struct.Dep.html)"],
+    );
+}
+
+#[test]
+fn rewrite_links_without_published_docs_to_source() {
+    check_rewrite_to_source(
+        r#"
+//- /main.rs crate:foo deps:documented,dep
+/// [`Local`], [`Documented`](documented::Documented) and [`Dep`](dep::Dep)
+pub struct $0Foo;
+
+pub struct Local;
+//- /documented.rs crate:documented
+#![doc(html_root_url = "https://example.com/")]
+pub struct Documented;
+//- /dep.rs crate:dep new_source_root:library
+pub struct Dep;
+"#,
+        DocLinkOptions::default().with_source_fallback(true),
+        expect!["[`Local`](file:///$ROOT/main.rs#L4), [`Documented`](https://example.com/documented/struct.Documented.html) and [`Dep`](https://docs.rs/dep/*/dep/struct.Dep.html)"],
+    );
+}
+
+#[test]
+fn rewrite_links_starting_with_own_crate_name() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo@CratesIo:0.1.0,https://example.com/foo
+/// [foo::Config], [`::foo::Config::new`], [foo] and [foo::Missing]
+pub struct Shard$0;
+pub struct Config;
+impl Config {
+    pub fn new() {}
+}
+"#,
+        expect![[r#"
+            0..13 "foo::Config" Some("https://docs.rs/foo/0.1.0/foo/struct.Config.html") Some("Config")
+            15..37 "::foo::Config::new" Some("https://docs.rs/foo/0.1.0/foo/struct.Config.html#method.new") Some("new")
+            39..44 "foo" Some("https://docs.rs/foo/0.1.0/foo/index.html") None
+            49..63 "foo::Missing" None None UnresolvedSegment { index: 1, segment: "Missing" }
+        "#]],
+    );
+    check_rewrite_to_source(
+        r#"
+//- /main.rs crate:foo
+/// [`foo::Config`]
+pub struct $0Shard;
+
+pub struct Config;
+"#,
+        DocLinkOptions::default().with_source_fallback(true),
+        expect!["[`foo::Config`](file:///$ROOT/main.rs#L4)"],
+    );
+}
+
+#[test]
+fn rewrite_links_without_published_docs_by_default() {
+    check_rewrite_to_source(
+        r#"
+//- /main.rs crate:foo
+/// [`Local`]
+pub struct $0Foo;
+
+pub struct Local;
+"#,
+        DocLinkOptions::default(),
+        expect!["[`Local`](https://docs.rs/foo/*/foo/struct.Local.html)"],
+    );
+}
+
+#[test]
+fn rewrite_relative_links_of_included_files() {
+    check_rewrite_to_source(
+        r#"
+//- /foo/src/lib.rs crate:foo
+//! Summary$0, see [the pages](struct.Foo.html).
+#![doc = include_str!("../README.md")]
+pub struct Foo;
+//- /foo/README.md
+See the [guide](docs/guide.md#setup), [`Foo`] and [the site](https://example.com).
+
+![logo](assets/logo.png)
+"#,
+        DocLinkOptions::default(),
+        expect![[r#"
+            Summary, see [the pages](https://docs.rs/foo/*/foo/struct.Foo.html).
+            See the [guide](file:///$ROOT/foo/docs/guide.md#setup), [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html) and [the site](https://example.com).
+
+            ![logo](file:///$ROOT/foo/assets/logo.png)"#]],
+    );
+    check_rewrite_to_source(
+        r#"
+//- /foo/src/lib.rs crate:foo@CratesIo:0.1.2,https://github.com/org/foo.git new_source_root:library
+//! Summary$0.
+#![doc = include_str!("../README.md")]
+//- /foo/README.md
+See the [guide](docs/guide.md) and [the changes](../foo/CHANGELOG.md).
+
+![logo](assets/logo.png)
+"#,
+        DocLinkOptions::default(),
+        expect![[r#"
+            Summary.
+            See the [guide](https://github.com/org/foo/blob/v0.1.2/docs/guide.md) and [the changes](https://github.com/org/foo/blob/v0.1.2/CHANGELOG.md).
+
+            ![logo](https://github.com/org/foo/raw/v0.1.2/assets/logo.png)"#]],
+    );
+}
+
+#[test]
+fn resolve_links() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [`fn@bar`], [the bar](bar), [Foo](struct.Foo.html), [docs](https://example.com) and [Missing]
+pub struct $0Foo;
+pub fn bar() {}
+"#,
+        expect![[r#"
+            0..10 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            12..26 "the bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            28..50 "Foo" Some("https://docs.rs/foo/*/foo/struct.Foo.html") None
+            52..79 "docs" Some("https://example.com") None
+            84..93 "Missing" None None UnresolvedSegment { index: 0, segment: "Missing" }
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_links_in_source_order_without_overlaps() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [bar] and [bar], [the bar][b], [b][] and [b], [outer [bar] text](Foo).
+///
+/// [b]: bar
+/// [B]: Foo
+/// [unused]: Foo
+pub struct $0Foo;
+pub fn bar() {}
+"#,
+        expect![[r#"
+            0..5 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            10..15 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            17..29 "the bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            31..34 "b" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            41..44 "b" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            53..58 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            72..80 "b" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar") reference definition
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_links_reports_why_they_have_no_url() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [some text], [page](gateway/Missing), [gateway::Missing], [`gateway::run::Missing`] and
+/// [struct@run]
+pub fn $0run() {}
+pub mod gateway {
+    pub fn run() {}
+}
+"#,
+        expect![[r#"
+            13..36 "page" None None UnparsablePath
+            38..56 "gateway::Missing" None None UnresolvedSegment { index: 1, segment: "Missing" }
+            58..83 "gateway::run::Missing" None None WrongNamespace { expected: Types }
+            88..100 "struct@run" None None WrongNamespace { expected: Types }
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_ambiguous_links() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [parse], [mod@parse] and [`parse()`]
+pub struct $0Foo;
+pub mod parse {}
+pub fn parse() {}
+"#,
+        expect![[r#"
+            0..7 "parse" Some("https://docs.rs/foo/*/foo/parse/index.html") Some("parse") ambiguous
+            9..20 "parse" Some("https://docs.rs/foo/*/foo/parse/index.html") Some("parse")
+            25..36 "parse" Some("https://docs.rs/foo/*/foo/fn.parse.html") Some("parse")
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_links_to_inactive_items() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [Extra], [struct@Extra], [gateway::thing], [extras] and [Missing]
+pub struct $0Foo;
+#[cfg(feature = "extras")]
+pub struct Extra;
+#[cfg(feature = "extras")]
+pub mod extras {}
+pub mod gateway {
+    #[cfg(feature = "extras")]
+    pub fn thing() {}
+}
+"#,
+        expect![[r#"
+            0..7 "Extra" Some("https://docs.rs/foo/*/foo/struct.Extra.html") None inactive: Atom(KeyValue { key: "feature", value: "extras" })
+            9..23 "Extra" Some("https://docs.rs/foo/*/foo/struct.Extra.html") None inactive: Atom(KeyValue { key: "feature", value: "extras" })
+            25..41 "gateway::thing" Some("https://docs.rs/foo/*/foo/gateway/fn.thing.html") None inactive: Atom(KeyValue { key: "feature", value: "extras" })
+            43..51 "extras" Some("https://docs.rs/foo/*/foo/extras/index.html") None inactive: Atom(KeyValue { key: "feature", value: "extras" })
+            56..65 "Missing" None None UnresolvedSegment { index: 0, segment: "Missing" }
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_links_by_doc_alias() {
+    let fixture = r#"
+//- /main.rs crate:foo
+/// [delete], [Bag::delete], [fn@erase], [struct@erase], [Bag::remove] and [Missing]
+pub struct $0Foo;
+pub struct Bag;
+impl Bag {
+    #[doc(alias = "delete")]
+    pub fn remove(&self) {}
+}
+#[doc(alias("delete", "erase"))]
+pub fn remove() {}
+"#;
+    check_resolve(
+        fixture,
+        expect![[r#"
+            0..8 "delete" None None UnresolvedSegment { index: 0, segment: "delete" }
+            10..23 "Bag::delete" None None UnresolvedSegment { index: 1, segment: "delete" }
+            25..35 "fn@erase" None None UnresolvedSegment { index: 0, segment: "erase" }
+            37..51 "struct@erase" None None UnresolvedSegment { index: 0, segment: "erase" }
+            53..66 "Bag::remove" Some("https://docs.rs/foo/*/foo/struct.Bag.html#method.remove") Some("remove")
+            71..80 "Missing" None None UnresolvedSegment { index: 0, segment: "Missing" }
+        "#]],
+    );
+    check_resolve_with_doc_aliases(
+        fixture,
+        expect![[r#"
+            0..8 "delete" Some("https://docs.rs/foo/*/foo/fn.remove.html") Some("remove") doc alias
+            10..23 "Bag::delete" Some("https://docs.rs/foo/*/foo/struct.Bag.html#method.remove") Some("remove") doc alias
+            25..35 "erase" Some("https://docs.rs/foo/*/foo/fn.remove.html") Some("remove") doc alias
+            37..51 "struct@erase" None None UnresolvedSegment { index: 0, segment: "erase" }
+            53..66 "Bag::remove" Some("https://docs.rs/foo/*/foo/struct.Bag.html#method.remove") Some("remove")
+            71..80 "Missing" None None UnresolvedSegment { index: 0, segment: "Missing" }
+        "#]],
+    );
+}
+
+#[test]
+fn broken_doc_links_by_doc_alias() {
+    check_broken_doc_links(
+        r#"
+/// [delete] and [Bag::delete]
+pub struct $0Foo;
+pub struct Bag;
+impl Bag {
+    #[doc(alias = "delete")]
+    pub fn remove(&self) {}
+}
+#[doc(alias = "delete")]
+pub fn remove() {}
+"#,
+        expect![[r#"
+            Some(5..11) "delete" DocAlias { def: Function(Function { id: FunctionId(0) }), ns: Values, name: "remove" }
+            Some(18..29) "Bag::delete" DocAlias { def: Function(Function { id: FunctionId(1) }), ns: Values, name: "remove" }
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_methods_defined_by_several_impls() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [Foo::run], [`<Foo as Run>::run`], [`<Foo as Stop>::run`], [Foo::halt],
+/// [`<Foo as Stop>::halt`] and [Run::run]
+pub struct $0Foo;
+impl Run for Foo {
+    fn run(&self) {}
+}
+impl Foo {
+    pub fn run(&self) {}
+}
+impl Stop for Foo {
+    fn run(&self) {}
+}
+pub trait Run { fn run(&self); }
+pub trait Stop { fn run(&self); fn halt(&self) {} }
+"#,
+        expect![[r#"
+            0..10 "Foo::run" Some("https://docs.rs/foo/*/foo/struct.Foo.html#method.run") Some("run") impl ambiguous
+            12..33 "<Foo as Run>::run" Some("https://docs.rs/foo/*/foo/struct.Foo.html#method.run-1") Some("run")
+            35..57 "<Foo as Stop>::run" Some("https://docs.rs/foo/*/foo/struct.Foo.html#method.run-2") Some("run")
+            59..70 "Foo::halt" None None UnresolvedSegment { index: 1, segment: "halt" }
+            72..95 "<Foo as Stop>::halt" Some("https://docs.rs/foo/*/foo/trait.Stop.html#method.halt") Some("halt")
+            100..110 "Run::run" Some("https://docs.rs/foo/*/foo/trait.Run.html#tymethod.run") Some("run")
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_names_brought_in_by_several_glob_imports() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo deps:log,net
+/// [Error], [Error::new], [Level] and [`Client`]
+pub struct $0Foo;
+use net::*;
+use log::*;
+use local::*;
+use net::Client;
+mod local {
+    pub struct Client;
+    pub struct Level;
+}
+//- /net.rs crate:net
+pub struct Error;
+impl Error { pub fn new() -> Self { Error } }
+pub struct Level;
+pub struct Client;
+//- /log.rs crate:log
+pub struct Error;
+impl Error { pub fn new() -> Self { Error } }
+"#,
+        expect![[r#"
+            0..7 "Error" Some("https://docs.rs/log/*/log/struct.Error.html") Some("Error") candidates: https://docs.rs/log/*/log/struct.Error.html, https://docs.rs/net/*/net/struct.Error.html
+            9..21 "Error::new" Some("https://docs.rs/log/*/log/struct.Error.html#method.new") Some("new") candidates: https://docs.rs/log/*/log/struct.Error.html#method.new, https://docs.rs/net/*/net/struct.Error.html#method.new
+            23..30 "Level" Some("https://docs.rs/foo/*/foo/local/struct.Level.html") Some("Level") candidates: https://docs.rs/foo/*/foo/local/struct.Level.html, https://docs.rs/net/*/net/struct.Level.html
+            35..45 "Client" Some("https://docs.rs/net/*/net/struct.Client.html") Some("Client")
+        "#]],
+    );
+}
+
+#[test]
+fn resolve_links_over_budget() {
+    check_resolve_with_budget(
+        r#"
+//- /main.rs crate:foo
+/// [`bar`], [Foo](struct.Foo.html), [docs](https://example.com), [`bar`] and [`baz`]
+pub struct $0Foo;
+pub fn bar() {}
+pub fn baz() {}
+"#,
+        2,
+        expect![[r#"
+            0..7 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            9..31 "Foo" Some("https://docs.rs/foo/*/foo/struct.Foo.html") None
+            33..60 "docs" Some("https://example.com") None
+            62..69 "bar" Some("https://docs.rs/foo/*/foo/fn.bar.html") Some("bar")
+            74..81 "baz" None None BudgetExceeded
+        "#]],
+    );
+}
+
+#[test]
+fn strict_rewrite_keeps_links_over_budget() {
+    let (host, position) = host_with_budget(
+        r#"
+//- /main.rs crate:foo
+/// [`bar`], [`missing`](Missing) and [`baz`](baz)
+pub struct $0Foo;
+pub fn bar() {}
+pub fn baz() {}
+"#,
+        2,
+    );
+    let sema = &Semantics::new(&host.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let options = DocLinkOptions::default().with_broken_links(BrokenLinksMode::Strip);
+    let res = process_links(sema.db, docs.as_str(), cursor_def, &options, None);
+    expect![[r#"
+        [`bar`](https://docs.rs/foo/*/foo/fn.bar.html), `missing` and [`baz`](baz)
+
+        *Some links were not resolved, this documentation has too many of them.*"#]]
+    .assert_eq(&res);
+}
+
+#[test]
+fn rewrite_links_over_budget() {
+    let (host, position) = host_with_budget(
+        r#"
+//- /main.rs crate:foo
+/// [`bar`] and [`baz`]
+pub struct $0Foo;
+pub fn bar() {}
+pub fn baz() {}
+"#,
+        1,
+    );
+    let sema = &Semantics::new(&host.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    let res = rewrite_links(sema.db, docs.as_str(), cursor_def);
+    expect![[r#"
+        [`bar`](https://docs.rs/foo/*/foo/fn.bar.html) and `baz`
+
+        *Some links were not resolved, this documentation has too many of them.*"#]]
+    .assert_eq(&res);
+}
+
+#[test]
+fn resolve_links_computes_crate_doc_roots_once() {
+    let (host, def) = rewrite_bench_fixture(DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    let docs =
+        "[`gateway::Shard`], [`foo::Connect`], [old](gateway/struct.Shard.html) and [`u8`]\n";
+    let links = host.analysis().resolve_doc_links(def, &docs.repeat(250)).unwrap();
+    assert_eq!(links.iter().filter(|it| it.url.is_some()).count(), 1000);
+
+    // `main` and `foo`, builtin types are documented in `core` without looking it up
+    let base_urls = CrateDocBaseUrlQuery.in_db(host.raw_database()).entries::<Vec<_>>();
+    assert_eq!(base_urls.len(), 2);
+}
+
+#[test]
+fn resolve_links_resolves_repeated_targets_once() {
+    let fixture = format!(
+        "//- /main.rs crate:foo\n/// {}\npub trait $0Foo {{\n    fn run(&self);\n}}\n",
+        "[`Self::run`] ".repeat(50)
+    );
+    let (host, position) = host_with_budget(&fixture, DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    let sema = &Semantics::new(&host.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+
+    cov_mark::check_count!(doc_link_target_resolved, 1);
+    let links =
+        resolve_doc_links(sema.db, cursor_def, docs.as_str(), &DocLinkOptions::default(), None);
+    assert_eq!(links.len(), 50);
+    let url = links[0].url.as_ref().unwrap();
+    assert_eq!(&**url, "https://docs.rs/foo/*/foo/trait.Foo.html#tymethod.run");
+    assert!(links.iter().all(|it| it.url.as_ref().map_or(false, |it| Arc::ptr_eq(it, url))));
+}
+
+#[test]
+fn broken_links() {
+    check_broken_doc_links(
+        r#"
+//- /main.rs crate:foo
+/// [Missing], [gateway::Missing], [parse], [struct@run], [`Shard::id`] and [valid](gateway).
+///
+/// Skips [some text], [1], [docs](https://example.com) and [the page](gateway/index.html).
+pub fn $0run() {}
+pub mod gateway {}
+pub mod parse {}
+pub fn parse() {}
+pub struct Shard;
+"#,
+        expect![[r#"
+            Some(5..12) "Missing" NotInScope
+            Some(16..32) "gateway::Missing" NoSuchItem { resolved: "gateway", segment: "Missing" }
+            Some(36..41) "parse" Ambiguous([Types, Values])
+            Some(52..55) "run" WrongNamespace { expected: Types }
+            Some(60..69) "Shard::id" NoSuchItem { resolved: "Shard", segment: "id" }
+        "#]],
+    );
+}
+
+#[test]
+fn file_doc_links_of_all_documented_items() {
+    check_file_doc_links(
+        r#"
+//- /main.rs crate:foo
+//! Starts with [`Gateway::connect`].
+/// Connects through a [`Gateway`], see [run], [`Missing`] and the
+/// [guide](https://example.com/guide) or the [docs](index.html).
+pub struct Shard {
+    /// The [`Gateway`] of the shard.
+    pub gateway: Gateway,
+}
+pub struct Gateway;
+impl Gateway {
+    #[doc = "Opens a [`Shard`]."]
+    pub fn connect() {}
+}
+pub fn run() {}
+pub mod run {}
+"#,
+        expect![[r#"
+            foo 0..37
+                Some(16..36) Intra Some("https://docs.rs/foo/*/foo/struct.Gateway.html#method.connect")
+            foo::Shard 38..170
+                Some(61..72) Intra Some("https://docs.rs/foo/*/foo/struct.Gateway.html")
+                Some(78..83) Intra Some("https://docs.rs/foo/*/foo/run/index.html") ambiguous
+                Some(85..96) Intra None UnresolvedSegment { index: 0, segment: "Missing" }
+                Some(109..143) Url Some("https://example.com/guide")
+                Some(151..169) Page Some("https://docs.rs/foo/*/foo/index.html")
+            foo::Shard::gateway 194..227
+                Some(202..213) Intra Some("https://docs.rs/foo/*/foo/struct.Gateway.html")
+            foo::Gateway::connect 295..324
+                Some(312..321) Intra Some("https://docs.rs/foo/*/foo/struct.Shard.html")
+        "#]],
+    );
+}
+
+#[test]
+fn broken_links_with_wrong_function_kind() {
+    check_broken_doc_links(
+        r#"
+//- /main.rs crate:foo
+/// [method@parse], [function@Shard::connect] and [method@Shard::ID].
+pub fn $0run() {}
+pub fn parse() {}
+pub struct Shard;
+impl Shard {
+    pub const ID: u32 = 0;
+    pub fn connect() {}
+}
+"#,
+        expect![[r#"
+            Some(12..17) "parse" WrongFunctionKind { expected: Associated }
+            Some(30..44) "Shard::connect" WrongFunctionKind { expected: Free }
+            Some(58..67) "Shard::ID" WrongFunctionKind { expected: Associated }
+        "#]],
+    );
+}
+
+#[test]
+fn rewrite_links_in_table_cells() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// | Kind | Meaning |
+/// |------|---------|
+/// | [`ErrorKind::NotFound`] | returned when [`Missing`] or [`ErrorKind::Denied`] |
+pub struct Shard$0;
+pub enum ErrorKind { NotFound, Denied }
+"#,
+        expect![[r#"
+            |Kind|Meaning|
+            |----|-------|
+            |[`ErrorKind::NotFound`](https://docs.rs/foo/*/foo/enum.ErrorKind.html#variant.NotFound)|returned when `Missing` or [`ErrorKind::Denied`](https://docs.rs/foo/*/foo/enum.ErrorKind.html#variant.Denied)|"#]],
+    );
+}
+
+#[test]
+fn rewrite_keeps_heading_style() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Setext [Foo]
+/// ===
+///
+/// Subtitle
+/// ---------
+///
+/// ## ATX
+///
+/// - Listed
+///   ===
+pub struct $0Foo;
+"#,
+        expect![[r#"
+            Setext [Foo](https://docs.rs/foo/*/foo/struct.Foo.html)
+            ===
+
+            Subtitle
+            ---------
+
+            ## ATX
+
+            - # Listed"#]],
+    );
+}
+
+#[test]
+fn rewrite_keeps_code_span_delimiters() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [`Foo`], [``Foo``], [```Foo```], [`` `Foo` ``], [```a``b```](Foo) and ```a``b```.
+///
+/// [`Missing`], [``Missing``], [``` `Missing` ```] and [`` a`b ``](Missing).
+pub struct $0Foo;
+"#,
+        expect![[r#"
+            [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), [``Foo``](https://docs.rs/foo/*/foo/struct.Foo.html), [```Foo```](https://docs.rs/foo/*/foo/struct.Foo.html), [`` `Foo` ``](https://docs.rs/foo/*/foo/struct.Foo.html), [```a``b```](https://docs.rs/foo/*/foo/struct.Foo.html) and ```a``b```.
+
+            `Missing`, ``Missing``, ``` `Missing` ``` and [`` a`b ``](Missing)."#]],
+    );
+}
+
+#[test]
+fn resolve_code_span_link_texts() {
+    check_resolve(
+        r#"
+//- /main.rs crate:foo
+/// [`Foo`], [``Foo``], [```Foo```], [`` `Foo` ``] and [``` `Missing` ```]
+pub struct $0Foo;
+"#,
+        expect![[r#"
+            0..7 "Foo" Some("https://docs.rs/foo/*/foo/struct.Foo.html") Some("Foo")
+            9..18 "Foo" Some("https://docs.rs/foo/*/foo/struct.Foo.html") Some("Foo")
+            20..31 "Foo" Some("https://docs.rs/foo/*/foo/struct.Foo.html") Some("Foo")
+            33..46 "`Foo`" Some("https://docs.rs/foo/*/foo/struct.Foo.html") Some("Foo")
+            51..70 "`Missing`" None None UnresolvedSegment { index: 0, segment: "Missing" }
+        "#]],
+    );
+}
+
+#[test]
+fn rewrite_keeps_list_numbers() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Reconnecting:
+///
+/// 10. Close the [`Shard`].
+/// 11. Wait:
+///     - for the backoff
+/// 12.  Open it again
+///
+/// 3) Then resume
+pub struct $0Shard;
+"#,
+        expect![[r#"
+            Reconnecting:
+
+            10. Close the [`Shard`](https://docs.rs/foo/*/foo/struct.Shard.html).
+            11. Wait:
+                - for the backoff
+            12.  Open it again
+
+            3) Then resume"#]],
+    );
+}
+
+#[test]
+fn rewrite_keeps_nested_list_indentation() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// - A [`Shard`]
+///   * has a session
+///     + with a sequence number
+///   * and a heartbeat
+/// - The gateway
+///     - four spaces deep
+pub struct $0Shard;
+"#,
+        expect![[r#"
+            - A [`Shard`](https://docs.rs/foo/*/foo/struct.Shard.html)
+              * has a session
+                + with a sequence number
+              * and a heartbeat
+            - The gateway
+                - four spaces deep"#]],
+    );
+}
+
+#[test]
+fn linkify_and_remove_links_keep_setext_headings() {
+    check_linkify(
+        "See https://example.com\n===\n\nText",
+        expect![[r#"
+            See <https://example.com>
+            ===
+
+            Text"#]],
+    );
+    expect![[r#"
+        A link
+        ------"#]]
+    .assert_eq(&remove_links("A [link](Foo)\n------"));
+}
+
+#[test]
+fn demote_top_level_headings() {
+    check_demote_headings(
+        "# Examples\n\n## Panics\n\n### Errors\n\nTitle\n=====\n\nSubtitle\n---",
+        expect![[r#"
+            ## Examples
+
+            ### Panics
+
+            ### Errors
+
+            Title
+            -----
+
+            ### Subtitle"#]],
+    );
+}
+
+#[test]
+fn linkify_urls_at_end_of_sentence() {
+    check_linkify(
+        "The spec is at https://example.com/spec. See also http://example.com/faq?q=1#a!",
+        expect![
+            "The spec is at <https://example.com/spec>. See also <http://example.com/faq?q=1#a>!"
+        ],
+    );
+}
+
+#[test]
+fn linkify_urls_in_parentheses() {
+    check_linkify(
+        "The spec (https://example.com/spec), not https://en.wikipedia.org/wiki/Rust_(language).",
+        expect!["The spec (<https://example.com/spec>), not <https://en.wikipedia.org/wiki/Rust_(language)>."],
+    );
+}
+
+#[test]
+fn linkify_urls_skips_code_and_links() {
+    check_linkify(
+        r#"[https://example.com/a](https://example.com/b), <https://example.com/c>, `https://example.com/d`
+
+```
+https://example.com/e
+```
+
+nothttps://example.com/f and https:// alone"#,
+        expect![[r#"
+            [https://example.com/a](https://example.com/b), <https://example.com/c>, `https://example.com/d`
+
+            ```
+            https://example.com/e
+            ```
+
+            nothttps://example.com/f and https:// alone"#]],
+    );
+}
+
+/// Counts the allocations made on each thread, for asserting that a piece of code doesn't
+/// allocate.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|it| it.set(it.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|it| it.set(it.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations `f` makes on the current thread.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn rewrite_links_keeps_url_links_borrowed() {
+    let markdown = (0..500)
+        .map(|i| format!("See [the `site`](https://example.com/{i}), <https://example.com/{i}>.\n"))
+        .collect::<String>();
+    let (analysis, position) = fixture::position(
+        r#"
+/// Docs
+pub fn run$0() {}
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, _) = def_under_cursor(sema, &position);
+    let options = DocLinkOptions::default();
+    let links = resolve_doc_links(sema.db, cursor_def, &markdown, &options, None);
+    let mut cb = broken_link_clone_cb;
+    let doc = Parser::new_with_broken_link_callback(&markdown, MARKDOWN_OPTIONS, Some(&mut cb));
+    let events = rewritten_link_events(sema.db, &markdown, doc, cursor_def, &options, None, &links);
+
+    let mut link_count = 0;
+    let allocations = count_allocations(|| {
+        let mut in_link = false;
+        for event in events {
+            match event {
+                Event::Start(Tag::Link(..)) => in_link = true,
+                Event::End(Tag::Link(_, target, _)) => {
+                    in_link = false;
+                    link_count += 1;
+                    assert!(matches!(target, CowStr::Borrowed(_)), "{target:?} was reallocated");
+                }
+                Event::Text(text) | Event::Code(text) if in_link => {
+                    assert!(matches!(text, CowStr::Borrowed(_)), "{text:?} was reallocated");
+                }
+                _ => (),
+            }
+        }
+    });
+    assert_eq!(link_count, 1000);
+    // parsing the inline markdown grows a few buffers, none are allocated per link
+    assert!(allocations < 50, "rewriting {link_count} links made {allocations} allocations");
+}
+
+#[test]
+fn benchmark_rewrite_links() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    // a 100 KB document with 500 links, alternating between URLs and intra-doc links
+    let paragraph = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(3);
+    let docs = (0..500)
+        .map(|i| match i % 2 {
+            0 => format!("{paragraph}See [the site](https://example.com/{i}).\n\n"),
+            _ => format!("{paragraph}See [`Foo::bar`] and {paragraph}\n\n"),
+        })
+        .collect::<String>();
+    assert!(docs.len() > 100_000);
+    let (analysis, position) = fixture::position(
+        r#"
+pub struct Foo;
+impl Foo {
+    pub fn bar() {}
+}
+/// Docs
+pub fn run$0() {}
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, _) = def_under_cursor(sema, &position);
+
+    let rewritten = {
+        let _pt = bench("rewrite links");
+        rewrite_links(sema.db, &docs, cursor_def)
+    };
+    assert_eq!(rewritten.matches("https://docs.rs/test/*/test/struct.Foo.html").count(), 250);
+
+    // the same document with only URLs, whose links are kept as they are
+    let docs = docs.replace("[`Foo::bar`]", "[`the docs`](https://docs.example.com)");
+    let rewritten = {
+        let _pt = bench("rewrite URL links");
+        rewrite_links(sema.db, &docs, cursor_def)
+    };
+    assert_eq!(rewritten.matches("](https://").count(), 500);
+}
+
+#[test]
+fn benchmark_rewrite_links_into_dependency() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let items = (0..200).map(|i| format!("pub struct Item{i};\n")).collect::<String>();
+    let links = (0..200).map(|i| format!("/// See [`Item{i}`].\n")).collect::<String>();
+    let (analysis, position) = fixture::position(&format!(
+        r#"
+//- /main.rs crate:main deps:foo
+pub use foo::gateway::shard::*;
+{links}pub fn run$0() {{}}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://github.com/x/foo
+pub mod gateway {{
+    pub mod shard {{
+        {items}
+    }}
+}}
+"#
+    ));
+    let sema = &Semantics::new(&*analysis.db);
+    let (cursor_def, docs) = def_under_cursor(sema, &position);
+    // compute what the rewrite depends on first, only the rewrite itself is measured
+    rewrite_links(sema.db, docs.as_str(), cursor_def);
+
+    let rewritten = {
+        let _pt = bench("rewrite links into dependency");
+        (0..10).map(|_| rewrite_links(sema.db, docs.as_str(), cursor_def)).last().unwrap()
+    };
+    assert_eq!(rewritten.matches("https://docs.rs/foo/0.1.0/foo/gateway/shard/").count(), 200);
+}
+
+#[test]
+fn benchmark_rewrite_links_with_cached_resolutions() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    // 500 links, alternating between URLs and intra-doc links to 250 items
+    let items = (0..250).map(|i| format!("pub struct Item{i};\n")).collect::<String>();
+    let paragraph = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(3);
+    let links = (0..250)
+        .map(|i| {
+            format!("/// {paragraph}See [`Item{i}`] and [the site](https://example.com/{i}).\n")
+        })
+        .collect::<String>();
+    let (mut host, position) = host_with_budget(
+        &format!(
+            r#"
+//- /main.rs crate:main
+{items}{links}pub fn run$0() {{}}
+fn helper() {{}}
+"#
+        ),
+        DEFAULT_DOC_LINK_RESOLUTION_BUDGET,
+    );
+    let rewrite = |host: &AnalysisHost| {
+        let sema = &Semantics::new(host.raw_database());
+        let (cursor_def, docs) = def_under_cursor(sema, &position);
+        rewrite_links(sema.db, docs.as_str(), cursor_def)
+    };
+
+    let rewritten = {
+        let _pt = bench("rewrite links, resolving them");
+        rewrite(&host)
+    };
+    assert_eq!(rewritten.matches("https://docs.rs/main/*/main/struct.Item").count(), 250);
+    let cached = {
+        let _pt = bench("rewrite links with cached resolutions x10");
+        (0..10).map(|_| rewrite(&host)).last().unwrap()
+    };
+    assert_eq!(cached, rewritten);
+
+    // edits of function bodies keep the resolutions
+    let text = host.raw_database().file_text(position.file_id).replace("{}", "{ let _ = 92; }");
+    let mut change = Change::new();
+    change.change_file(position.file_id, Some(Arc::from(text)));
+    host.apply_change(change);
+    // analyzing the edited file isn't part of the rewrite
+    def_under_cursor(&Semantics::new(host.raw_database()), &position);
+    cov_mark::check_count!(doc_link_resolutions_computed, 0);
+    let edited = {
+        let _pt = bench("rewrite links with cached resolutions after an edit");
+        rewrite(&host)
+    };
+    assert_eq!(edited, rewritten);
+}
+
+/// A crate with a few items to link to, and the function whose docs are rewritten by the
+/// benchmarks.
+fn rewrite_bench_fixture(budget: usize) -> (AnalysisHost, Definition) {
+    let (host, position) = host_with_budget(
+        r#"
+//- /main.rs crate:main deps:foo
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn start(&self) {}
+    }
+}
+/// Docs
+pub fn run$0() {}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://github.com/x/foo
+pub trait Connect {
+    fn connect(&self);
+}
+"#,
+        budget,
+    );
+    let sema = &Semantics::new(host.raw_database());
+    let (cursor_def, _) = def_under_cursor(sema, &position);
+    (host, cursor_def)
+}
+
+/// Documentation with `n` shortcut references that don't resolve.
+fn broken_references(n: usize) -> String {
+    (0..n).map(|i| format!("Not an item: [missing{i}], [`also missing {i}`].\n")).collect()
+}
+
+#[test]
+fn benchmark_rewrite_links_small_docs() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let (host, def) = rewrite_bench_fixture(DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    let docs =
+        "Starts a [`gateway::Shard`] with [`foo::Connect`], see [the guide](https://example.com).";
+    let rewritten = {
+        let _pt = bench("rewrite links of small docs x1000");
+        (0..1000).map(|_| rewrite_links(host.raw_database(), docs, def)).last().unwrap()
+    };
+    assert_eq!(rewritten.matches("https://docs.rs/").count(), 2);
+}
+
+#[test]
+fn benchmark_rewrite_links_readme_sized_docs() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let (host, def) = rewrite_bench_fixture(DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    let section = r#"
+## Connecting
+
+Create a [`Shard`](gateway::Shard) and call [`start`][start], which uses [`foo::Connect`]. The
+[protocol](https://example.com/protocol) is described in the [old docs](gateway/struct.Shard.html)
+and [`not::an::Item`] is not linked.
+
+[start]: gateway::Shard::start
+"#;
+    // 10 sections of 5 links
+    let docs = section.repeat(10);
+    let rewritten = {
+        let _pt = bench("rewrite links of README sized docs x100");
+        (0..100).map(|_| rewrite_links(host.raw_database(), &docs, def)).last().unwrap()
+    };
+    assert_eq!(rewritten.matches("https://docs.rs/").count(), 40);
+}
+
+#[test]
+fn benchmark_rewrite_links_broken_references() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let (host, def) = rewrite_bench_fixture(DEFAULT_DOC_LINK_RESOLUTION_BUDGET);
+    let docs = broken_references(2500);
+    let rewritten = {
+        let _pt = bench("rewrite 5000 broken references");
+        rewrite_links(host.raw_database(), &docs, def)
+    };
+    // unresolved shortcut links are written as their plain text
+    assert_eq!(rewritten.matches("Not an item: missing").count(), 2500);
+    assert!(!rewritten.contains("]("));
+}
+
+#[test]
+fn benchmark_rewrite_page_links() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    // page links are never intra-doc links, so none of them should reach the resolver
+    let (host, def) = rewrite_bench_fixture(usize::MAX);
+    let docs = (0..1000)
+        .map(|i| format!("See [the method](gateway/struct.Shard.html#method.start{i}).\n"))
+        .collect::<String>();
+    let rewritten = {
+        let _pt = bench("rewrite 1000 page links");
+        rewrite_links(host.raw_database(), &docs, def)
+    };
+    assert_eq!(
+        rewritten.matches("https://docs.rs/main/*/main/gateway/struct.Shard.html").count(),
+        1000
+    );
+}
+
+#[test]
+fn rewrite_links_not_quadratic() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    // the budget would cut the work short
+    let (host, def) = rewrite_bench_fixture(usize::MAX);
+    let mut al = AssertLinear::default();
+    while al.next_round() {
+        for i in 6..=10 {
+            let n = 1 << i;
+            let docs = broken_references(n);
+
+            let time = Instant::now();
+            let rewritten = rewrite_links(host.raw_database(), &docs, def);
+            assert!(rewritten.len() > docs.len() / 2);
+
+            let elapsed = time.elapsed();
+            al.sample(n as f64, elapsed.as_millis() as f64);
+        }
+    }
+}
+
+#[test]
+fn rewrite_malformed_markdown() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Unclosed [Foo](target and [Foo
+/// stray ] and ][ brackets,
+/// nested [[Foo]](Foo), [[Foo](Foo)] and [`a [Foo] b`](Foo),
+/// and an empty link [](Foo)
+pub struct $0Foo;
+"#,
+        expect![[r#"
+            Unclosed [Foo](https://docs.rs/foo/*/foo/struct.Foo.html)(target and [Foo
+            stray ] and ][ brackets,
+            nested [[Foo](https://docs.rs/foo/*/foo/struct.Foo.html)](Foo), [[Foo](https://docs.rs/foo/*/foo/struct.Foo.html)] and [`a [Foo] b`](https://docs.rs/foo/*/foo/struct.Foo.html),
+            and an empty link [](Foo)"#]],
+    );
+}
+
+#[test]
+fn rewrite_links_fuzz() {
+    const REPEATS: usize = 512;
+    const ALPHABET: &[char] = &[
+        '[', ']', '(', ')', '`', '!', '<', '>', '\\', ':', '@', '#', '*', '\0', '\n', ' ', 'F', 'o',
+    ];
+
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:foo
+/// Foo
+pub struct $0Foo;
+pub mod o {}
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (def, _) = def_under_cursor(sema, &position);
+    let mut rng = oorandom::Rand32::new(123456789);
+    for _ in 0..REPEATS {
+        let len = rng.rand_range(0..64);
+        let docs: String =
+            (0..len).map(|_| ALPHABET[rng.rand_range(0..ALPHABET.len() as u32) as usize]).collect();
+        // the output is garbage too, just no panic
+        rewrite_links(sema.db, &docs, def);
+    }
+}
+
+#[test]
+fn rewrite_links_linear_in_brackets() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:foo
+/// Foo
+pub struct $0Foo;
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (def, _) = def_under_cursor(sema, &position);
+    for bracket_soup in [|n| "[".repeat(n), |n| "[a](".repeat(n), |n| "[a][".repeat(n)] {
+        let mut al = AssertLinear::default();
+        while al.next_round() {
+            for i in 10..=14 {
+                let n = 1 << i;
+                let docs: String = bracket_soup(n);
+
+                let time = Instant::now();
+                rewrite_links(sema.db, &docs, def);
+
+                let elapsed = time.elapsed();
+                al.sample(n as f64, elapsed.as_millis() as f64);
+            }
+        }
+    }
+}
+
+const COMPLETION_CONFIG: CompletionConfig = CompletionConfig {
+    enable_postfix_completions: false,
+    enable_imports_on_the_fly: false,
+    enable_self_on_the_fly: false,
+    enable_private_editable: false,
+    callable: None,
+    snippet_cap: None,
+    prefer_no_std: false,
+    insert_use: InsertUseConfig {
+        granularity: ImportGranularity::Crate,
+        prefix_kind: PrefixKind::Plain,
+        enforce_granularity: true,
+        group: true,
+        skip_glob_imports: true,
+    },
+    snippets: Vec::new(),
+    limit: None,
+};
+
+#[test]
+fn completion_list_docs_match_serial_processing() {
+    let mut ra_fixture = "//- /main.rs crate:main\npub struct Target;\n".to_owned();
+    for i in 0..100 {
+        let next = (i + 1) % 100;
+        ra_fixture +=
+            &format!("/// Makes [`Target`] {i}, see [`item{next}`].\npub fn item{i}() {{}}\n");
+    }
+    ra_fixture += "fn main() { item$0 }\n";
+    let (analysis, position) = fixture::position(&ra_fixture);
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    assert!(items.len() > PARALLEL_COMPLETION_DOCS_CUTOFF);
+
+    let options = DocLinkOptions::default().with_full_docs_link(true);
+    let serial = items.iter().map(|it| completion_docs(&analysis.db, it, &options)).collect_vec();
+    let target_url = "https://docs.rs/main/*/main/struct.Target.html";
+    assert_eq!(serial.iter().flatten().filter(|it| it.contains(target_url)).count(), 100);
+    for _ in 0..3 {
+        let parallel = analysis.completion_list_docs(&items, &options).unwrap();
+        assert_eq!(parallel, serial);
+    }
+}
+
+#[test]
+fn completion_docs_of_deref_supplied_methods() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:main deps:alloc
+fn f(v: alloc::vec::Vec<u8>) {
+    v.$0
+}
+//- /libcore.rs crate:core
+#![rustc_coherence_is_core]
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        #[lang = "deref_target"]
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
+}
+impl<T> [T] {
+    /// Returns the number of elements, see [`slice::is_empty`] and [`Self::is_empty`].
+    pub fn len(&self) -> usize { 0 }
+    pub fn is_empty(&self) -> bool { true }
+}
+//- /liballoc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> core::ops::Deref for Vec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] { loop {} }
+    }
+}
+"#,
+    );
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    let len = items.iter().find(|it| it.label == "len").expect("no completion for `len`");
+    let docs = completion_docs(&analysis.db, len, &DocLinkOptions::default()).unwrap();
+    expect!["Returns the number of elements, see [`slice::is_empty`](https://doc.rust-lang.org/stable/core/primitive.slice.html#method.is_empty) and [`Self::is_empty`](https://doc.rust-lang.org/stable/core/primitive.slice.html#method.is_empty)."]
+        .assert_eq(&docs);
+}
+
+#[test]
+fn resolve_completion_docs_of_items_sharing_a_label() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:main
+pub struct Buf {
+    /// The length, see [`Buf::len`].
+    pub len: usize,
+}
+impl Buf {
+    /// Counts the bytes, see [`Buf`].
+    pub fn len(&self) -> usize { self.len }
+}
+fn f(buf: Buf) {
+    buf.$0
+}
+"#,
+    );
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    let items = items.iter().filter(|it| it.label == "len").collect_vec();
+    assert_eq!(items.len(), 2);
+    let options = DocLinkOptions::default();
+    let docs = items
+        .iter()
+        .map(|it| {
+            let key = it.doc_owner_key().unwrap();
+            analysis.resolve_completion_docs(&COMPLETION_CONFIG, position, "len", key, &options)
+        })
+        .map(|it| it.unwrap().unwrap())
+        .collect_vec();
+    expect![[r#"
+        [
+            "The length, see [`Buf::len`](https://docs.rs/main/*/main/struct.Buf.html#method.len).",
+            "Counts the bytes, see [`Buf`](https://docs.rs/main/*/main/struct.Buf.html).",
+        ]
+    "#]]
+    .assert_debug_eq(&docs);
+}
+
+#[test]
+fn completion_docs_of_derive_macros() {
+    #[derive(Debug)]
+    struct Identity;
+    impl ProcMacroExpander for Identity {
+        fn expand(
+            &self,
+            subtree: &Subtree,
+            _: Option<&Subtree>,
+            _: &Env,
+        ) -> Result<Subtree, ProcMacroExpansionError> {
+            Ok(subtree.clone())
+        }
+    }
+
+    // fixture proc macros live in the `proc_macros` crate, standing in for `serde_derive`
+    let serialize = r#"
+/// Derives [`Serialize`] for a type, writing it to a [Serializer].
+#[proc_macro_derive(Serialize)]
+pub fn derive_serialize(item: TokenStream) -> TokenStream {
+    item
+}
+pub trait Serializer {}
+"#;
+    let change_fixture = ChangeFixture::parse_with_proc_macros(
+        r#"
+//- proc_macros: serialize
+//- minicore: derive
+//- /main.rs crate:main
+use proc_macros::Serialize;
+#[derive($0)]
+struct Foo;
+"#,
+        vec![(
+            serialize.to_owned(),
+            ProcMacro {
+                name: "Serialize".into(),
+                kind: ProcMacroKind::CustomDerive,
+                expander: std::sync::Arc::new(Identity),
+            },
+        )],
+    );
+    let mut host = AnalysisHost::default();
+    host.db.enable_proc_attr_macros();
+    host.db.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture.file_position.unwrap();
+    let position = FilePosition { file_id, offset: range_or_offset.expect_offset() };
+    let analysis = host.analysis();
+
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    let item =
+        items.iter().find(|it| it.label == "Serialize").expect("no completion for `Serialize`");
+    let docs = completion_docs(&analysis.db, item, &DocLinkOptions::default()).unwrap();
+    expect!["Derives [`Serialize`](https://docs.rs/proc_macros/*/proc_macros/derive.Serialize.html) for a type, writing it to a [Serializer](https://docs.rs/proc_macros/*/proc_macros/trait.Serializer.html)."]
+        .assert_eq(&docs);
+}
+
+#[test]
+fn completion_docs_full_docs_link() {
+    let long_docs = "/// Each shard handles a subset of the guilds.\n".repeat(30);
+    let (analysis, position) = fixture::position(&format!(
+        "//- /main.rs crate:main deps:dep\nfn main() {{ dep::$0 }}\n\
+         //- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep\n\
+         {long_docs}pub struct Shard;\n\
+         /// A short one.\npub struct Session;\n"
+    ));
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    let options = DocLinkOptions::default().with_full_docs_link(true);
+    let docs = |label| {
+        let item = items.iter().find(|it| it.label == label).expect("no completion");
+        completion_docs(&analysis.db, item, &options).unwrap()
+    };
+    expect![[r#"— [view full documentation](https://docs.rs/dep/0.1.0/dep/struct.Shard.html)"#]]
+        .assert_eq(docs("Shard").lines().last().unwrap());
+    expect!["A short one."].assert_eq(&docs("Session"));
+    let without_link = items.iter().find(|it| it.label == "Shard").unwrap();
+    let without_link = completion_docs(&analysis.db, without_link, &DocLinkOptions::default());
+    assert!(!without_link.unwrap().contains("view full documentation"));
+}
+
+#[test]
+fn completion_docs_of_postfix_snippets() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    0.$0
+}
+//- /std.rs crate:std
+pub mod boxed {
+    pub struct Box<T>(T);
+    impl<T> Box<T> {
+        pub fn new(x: T) -> Self { Box(x) }
+    }
+}
+#[macro_export]
+macro_rules! dbg { ($e:expr) => { $e } }
+"#,
+    );
+    let config = CompletionConfig {
+        enable_postfix_completions: true,
+        snippet_cap: SnippetCap::new(true),
+        ..COMPLETION_CONFIG
+    };
+    let items = analysis.completions(&config, position, None).unwrap().unwrap();
+    let options = DocLinkOptions::default().with_full_docs_link(true);
+    let docs = |label| {
+        let item = items.iter().find(|it| it.label == label).expect("no postfix completion");
+        completion_docs(&analysis.db, item, &options).unwrap()
+    };
+    expect!["Prints the expression and its value to stderr, see [`std::dbg`](https://doc.rust-lang.org/stable/std/macro.dbg.html)."]
+        .assert_eq(&docs("dbg"));
+    expect!["Moves the expression to the heap, see [`Box::new`](https://doc.rust-lang.org/stable/std/boxed/struct.Box.html#method.new)."]
+        .assert_eq(&docs("box"));
+}
+
+#[test]
+fn docs_are_the_same_in_hover_completion_and_signature_help() {
+    let ra_fixture = r#"
+//- /main.rs crate:main
+pub mod shards {
+    /// Opens a [`Shard`], see [the gateway](crate::gateway).
+    ///
+    /// 3. connects ``the `ws` socket``
+    /// 4. sends [`Shard::identify`]
+    ///
+    #[doc = include_str!("open.md")]
+    pub fn open(id: u32) -> Shard { Shard }
+    pub struct Shard;
+    impl Shard {
+        pub fn identify(&self) {}
+    }
+}
+pub mod gateway {}
+fn main() {
+    shards::$0
+}
+//- /open.md
+Closes the [`Shard`] first.
+"#;
+    let (analysis, position) = fixture::position(ra_fixture);
+    let items = analysis.completions(&COMPLETION_CONFIG, position, None).unwrap().unwrap();
+    let open =
+        items.iter().find(|it| it.label.starts_with("open")).expect("no completion for `open`");
+    let completion = completion_docs(&analysis.db, open, &DocLinkOptions::default()).unwrap();
+
+    let (analysis, position) = fixture::position(&ra_fixture.replace("$0", "op$0en(0)"));
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        memory_layout: false,
+        documentation: true,
+        keywords: false,
+        format: HoverDocFormat::Markdown,
+        max_doc_length: None,
+        inactive_cfg_docs: false,
+        name_link: false,
+        linkify_urls: false,
+        demote_headings: false,
+        original_markup: false,
+    };
+    let range = FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+    let hover = analysis.hover(&config, range).unwrap().unwrap();
+    let hover = analysis.render_hover(&config, &hover.info.markup).unwrap();
+    let (_, hover) = hover.as_str().split_once("\n---\n\n").unwrap();
+
+    let (analysis, position) = fixture::position(&ra_fixture.replace("$0", "open($0)"));
+    let signature_help = analysis.signature_help(position, true).unwrap().unwrap().doc.unwrap();
+
+    assert_eq!(hover, completion);
+    assert_eq!(signature_help, completion);
+    expect![[r#"
+        Opens a [`Shard`](https://docs.rs/main/*/main/shards/struct.Shard.html), see [the gateway](https://docs.rs/main/*/main/gateway/index.html).
+
+        3. connects ``the `ws` socket``
+        4. sends [`Shard::identify`](https://docs.rs/main/*/main/shards/struct.Shard.html#method.identify)
+
+        Closes the [`Shard`](https://docs.rs/main/*/main/shards/struct.Shard.html) first."#]]
+    .assert_eq(&completion);
+}
+
+#[test]
+fn rewritten_docs_are_kept_per_options() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:main
+/// Opens a [`Shard`].
+pub fn open$0() {}
+pub struct Shard;
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (def, _) = def_under_cursor(sema, &position);
+    let docs = Documentation::for_definition(&analysis.db, &def).unwrap();
+    let rewrite = DocLinkOptions::default();
+    let strip = DocLinkOptions::default().with_mode(DocLinksMode::Strip);
+
+    let rewritten = docs.rewritten(&analysis.db, &rewrite);
+    assert!(Arc::ptr_eq(&rewritten, &docs.clone().rewritten(&analysis.db, &rewrite)));
+    expect!["Opens a `Shard`."].assert_eq(&docs.rewritten(&analysis.db, &strip));
+    expect!["Opens a [`Shard`](https://docs.rs/main/*/main/struct.Shard.html)."]
+        .assert_eq(&rewritten);
+}
+
+#[test]
+fn inherited_docs_expand_includes() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:main
+pub trait Open {
+    #[doc = include_str!("open.md")]
+    fn open();
+}
+pub struct Shard;
+impl Open for Shard {
+    fn open$0() {}
+}
+//- /open.md
+Opens a [`Shard`].
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let token = sema.parse(position.file_id).syntax().token_at_offset(position.offset);
+    let node = token.left_biased().unwrap().parent().unwrap();
+    let (_, def) = node.ancestors().find_map(|it| node_to_def(sema, &it)).flatten().unwrap();
+    let docs = Documentation::for_definition(&analysis.db, &def).unwrap();
+    assert!(docs.is_inherited());
+    expect!["Opens a [`Shard`](https://docs.rs/main/*/main/struct.Shard.html)."]
+        .assert_eq(&docs.rewritten(&analysis.db, &DocLinkOptions::default()));
+}
+
+#[test]
+fn rewrite_implied_links_with_disambiguators() {
+    // the first lines are from std
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// [`trait@Eq`]. Formally speaking, when `Rhs == Self`, this trait corresponds
+/// The error type returned when a conversion from [`prim@u32`] to [`prim@char`] fails.
+/// [`u8`](prim@u8) value.
+/// [`struct Foo`], [struct `Foo`], [fn connect], [`fn connect`][], [prim@char],
+/// [fn pointers](fn@connect) and [a struct Foo][struct@Foo]
+pub struct $0Foo;
+pub trait Eq {}
+pub fn connect() {}
+"#,
+        expect![[r#"
+            [`Eq`](https://docs.rs/foo/*/foo/trait.Eq.html). Formally speaking, when `Rhs == Self`, this trait corresponds
+            The error type returned when a conversion from [`u32`](https://doc.rust-lang.org/nightly/core/primitive.u32.html) to [`char`](https://doc.rust-lang.org/nightly/core/primitive.char.html) fails.
+            [`u8`](https://doc.rust-lang.org/nightly/core/primitive.u8.html) value.
+            [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), [`Foo`](https://docs.rs/foo/*/foo/struct.Foo.html), [connect](https://docs.rs/foo/*/foo/fn.connect.html), [`connect`](https://docs.rs/foo/*/foo/fn.connect.html), [char](https://doc.rust-lang.org/nightly/core/primitive.char.html),
+            [fn pointers](https://docs.rs/foo/*/foo/fn.connect.html) and [a struct Foo](https://docs.rs/foo/*/foo/struct.Foo.html)"#]],
+    );
+}
+
+#[test]
+fn rewrite_reference_links() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+/// Full: [the shard type][shard], [`Shard`][struct] and [the runner][run].
+/// Collapsed: [Shard][] and [`fn run`][].
+/// Shortcut: [shard] and [pointers].
+/// Unresolved: [gone][missing].
+/// Inline: [the shard type](crate::gateway::Shard), [`Shard`](struct@gateway::Shard), [the runner](run()).
+///
+/// [shard]: crate::gateway::Shard
+/// [struct]: struct@gateway::Shard
+/// [run]: run()
+/// [Shard]: gateway::Shard
+/// [`fn run`]: fn@run
+/// [pointers]: fn@run
+/// [missing]: crate::gateway::Missing
+/// [unused]: crate::gateway::Shard
+pub fn $0run() {}
+pub mod gateway {
+    pub struct Shard;
+}
+"#,
+        expect![[r#"
+            Full: [the shard type](https://docs.rs/foo/*/foo/gateway/struct.Shard.html), [`Shard`](https://docs.rs/foo/*/foo/gateway/struct.Shard.html) and [the runner](https://docs.rs/foo/*/foo/fn.run.html).
+            Collapsed: [Shard](https://docs.rs/foo/*/foo/gateway/struct.Shard.html) and [`fn run`](https://docs.rs/foo/*/foo/fn.run.html).
+            Shortcut: [shard](https://docs.rs/foo/*/foo/gateway/struct.Shard.html) and [pointers](https://docs.rs/foo/*/foo/fn.run.html).
+            Unresolved: [gone](crate::gateway::Missing).
+            Inline: [the shard type](https://docs.rs/foo/*/foo/gateway/struct.Shard.html), [`Shard`](https://docs.rs/foo/*/foo/gateway/struct.Shard.html), [the runner](https://docs.rs/foo/*/foo/fn.run.html)."#]],
+    );
+}
+
+#[test]
+fn rewrite_links_to_macro_rules_defined_later() {
+    check_rewrite(
+        r#"
+//- /main.rs crate:foo
+pub mod inner {
+    /// Uses [helper!], [outer!] and [`root!`].
+    pub fn fo$0o() {}
+    macro_rules! helper { () => {} }
+}
+macro_rules! outer { () => {} }
+#[macro_use]
+mod late {
+    macro_rules! root { () => {} }
+}
+"#,
+        expect!["Uses [helper](https://docs.rs/foo/*/foo/inner/macro.helper.html), [outer](https://docs.rs/foo/*/foo/macro.outer.html) and [`root`](https://docs.rs/foo/*/foo/late/macro.root.html)."],
     );
 }
@@ -0,0 +1,55 @@
+//! Brackets in prose that can't be intra-doc links, they must come out of every pass as they
+//! went in.
+use hir::Semantics;
+
+use crate::{
+    doc_links::{linkify_urls, remove_links, rewrite_links, tests::def_under_cursor},
+    fixture,
+};
+
+const NO_LINKS: &[&str] = &[
+    "Indexes with [0..n], [..] and [1..=n].",
+    "An array [T; N] of [u8; 4].",
+    "Bounded by ['a] and ['static].",
+    "See [0], [1] and [42].",
+    "The [first second] range.",
+    "Empty [] brackets.",
+    "Math like [a, b) or [a - b].",
+    "Slices [&x[..]] and [x + 1].",
+    "Code `[0..n]` and [`0..n`].",
+    "Escaped \\[Foo\\] and \\[0\\].",
+    "Attributes like #\\[doc = string\\] and #\\[inline\\].",
+    "Stray ] and ][ brackets.",
+    "- [ ] todo\n- [x] done",
+    "|[T; N]|[0..n]|\n|------|------|\n|[0]|['a]|",
+];
+
+#[test]
+fn rewrite_keeps_brackets_that_are_no_links() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- /main.rs crate:foo
+/// Foo
+pub struct $0Foo;
+"#,
+    );
+    let sema = &Semantics::new(&*analysis.db);
+    let (def, _) = def_under_cursor(sema, &position);
+    for &docs in NO_LINKS {
+        assert_eq!(rewrite_links(sema.db, docs, def), docs);
+    }
+}
+
+#[test]
+fn remove_links_keeps_brackets_that_are_no_links() {
+    for &docs in NO_LINKS {
+        assert_eq!(remove_links(docs), docs);
+    }
+}
+
+#[test]
+fn linkify_urls_keeps_brackets_that_are_no_links() {
+    for &docs in NO_LINKS {
+        assert_eq!(linkify_urls(docs), docs);
+    }
+}
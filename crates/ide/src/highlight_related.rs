@@ -449,6 +449,7 @@ mod tests {
                             ReferenceCategory::Read => "read",
                             ReferenceCategory::Write => "write",
                             ReferenceCategory::Import => "import",
+                            ReferenceCategory::DocLink => "doc link",
                         }
                         .to_string()
                     }),
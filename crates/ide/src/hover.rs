@@ -15,10 +15,14 @@ use ide_db::{
     FxIndexSet, RootDatabase,
 };
 use itertools::Itertools;
-use syntax::{ast, AstNode, SyntaxKind::*, SyntaxNode, T};
+use syntax::{ast, AstNode, SyntaxKind::*, SyntaxNode, TextSize, T};
 
 use crate::{
-    doc_links::token_as_doc_comment,
+    doc_links::{
+        doc_url, inlining_crate, repository_url, token_as_doc_comment, DocLinkOptions,
+        ResolvedDocLink,
+    },
+    hover::render::DefinitionMarkup,
     markdown_remove::remove_markdown,
     markup::Markup,
     runnables::{runnable_fn, runnable_mod},
@@ -26,11 +30,24 @@ use crate::{
 };
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HoverConfig {
-    pub links_in_hover: bool,
+    pub links: DocLinkOptions,
     pub memory_layout: bool,
     pub documentation: bool,
     pub keywords: bool,
+    /// The number of characters after which documentation is cut off at a paragraph boundary.
+    pub max_doc_length: Option<usize>,
+    /// Whether to include the docs of `cfg_attr`s whose condition doesn't hold.
+    pub inactive_cfg_docs: bool,
+    /// Whether to prepend the hovered item's name as a link to its documentation page.
+    pub name_link: bool,
+    /// Whether to turn bare URLs in the documentation into links.
+    pub linkify_urls: bool,
+    /// Whether to demote the level 1 and 2 headings of Markdown documentation by one level, so
+    /// the `# Examples` of crate docs don't dwarf the popup.
+    pub demote_headings: bool,
     pub format: HoverDocFormat,
+    /// Whether to also return the markup as it was before processing its links.
+    pub original_markup: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -45,6 +62,10 @@ pub enum HoverAction {
     Implementation(FilePosition),
     Reference(FilePosition),
     GoToType(Vec<HoverGotoTypeData>),
+    /// The web URL of the hovered definition's documentation.
+    OpenDocs(String),
+    /// The web URL of the hovered definition's source in its repository.
+    OpenSource(String),
 }
 
 impl HoverAction {
@@ -75,8 +96,98 @@ pub struct HoverGotoTypeData {
 /// Contains the results when hovering over an item
 #[derive(Debug, Default)]
 pub struct HoverResult {
-    pub markup: Markup,
+    pub markup: HoverMarkup,
     pub actions: Vec<HoverAction>,
+    /// The markup before its links were processed, if requested by
+    /// [`HoverConfig::original_markup`].
+    pub original: Option<OriginalMarkup>,
+}
+
+/// The markup of a hover, with the docs of the hovered definitions kept apart until
+/// [`Analysis::render_hover`] processes their links for the client's format.
+///
+/// [`Analysis::render_hover`]: crate::Analysis::render_hover
+#[derive(Debug, Default)]
+pub struct HoverMarkup {
+    sections: Vec<HoverSection>,
+}
+
+#[derive(Debug)]
+enum HoverSection {
+    Markup(Markup),
+    Definition(DefinitionMarkup),
+}
+
+impl From<Markup> for HoverMarkup {
+    fn from(markup: Markup) -> Self {
+        HoverMarkup { sections: vec![HoverSection::Markup(markup)] }
+    }
+}
+
+impl From<String> for HoverMarkup {
+    fn from(text: String) -> Self {
+        Markup::from(text).into()
+    }
+}
+
+impl From<DefinitionMarkup> for HoverMarkup {
+    fn from(markup: DefinitionMarkup) -> Self {
+        HoverMarkup { sections: vec![HoverSection::Definition(markup)] }
+    }
+}
+
+impl HoverMarkup {
+    /// Appends the sections of `other`, separated by a horizontal rule when rendered.
+    fn extend(&mut self, other: HoverMarkup) {
+        self.sections.extend(other.sections);
+    }
+}
+
+/// Renders `markup`, processing the links of its docs for `config.format`. Plain text hovers
+/// lose the links' targets anyway, so their links are stripped without resolving them.
+pub(crate) fn render_hover(
+    db: &RootDatabase,
+    markup: &HoverMarkup,
+    config: &HoverConfig,
+) -> Markup {
+    let text = markup
+        .sections
+        .iter()
+        .map(|section| match section {
+            HoverSection::Markup(markup) => markup.as_str().to_owned(),
+            HoverSection::Definition(markup) => {
+                let rendered = markup.render(db, config);
+                match markup.linked_name.and_then(|def| name_link(db, def, config)) {
+                    Some(link) => format!("{link}\n\n{}", rendered.trim_start()),
+                    None => rendered,
+                }
+            }
+        })
+        .join("\n---\n");
+    match config.format {
+        HoverDocFormat::Markdown => text.into(),
+        HoverDocFormat::PlainText => remove_markdown(&text).into(),
+    }
+}
+
+/// Hover markup with its links left untouched.
+#[derive(Debug, Default)]
+pub struct OriginalMarkup {
+    pub markup: Markup,
+    /// The links in `markup`, with what they resolve to.
+    pub links: Vec<ResolvedDocLink>,
+}
+
+impl OriginalMarkup {
+    /// Appends `other` the way hovers for multiple definitions are joined.
+    fn merge(&mut self, other: OriginalMarkup) {
+        let offset = TextSize::of(self.markup.as_str()) + TextSize::of("\n---\n");
+        self.markup = Markup::from(format!("{}\n---\n{}", self.markup, other.markup));
+        self.links.extend(other.links.into_iter().map(|mut link| {
+            link.range += offset;
+            link
+        }));
+    }
 }
 
 // Feature: Hover
@@ -92,16 +203,11 @@ pub(crate) fn hover(
 ) -> Option<RangeInfo<HoverResult>> {
     let sema = &hir::Semantics::new(db);
     let file = sema.parse(file_id).syntax().clone();
-    let mut res = if range.is_empty() {
+    if range.is_empty() {
         hover_simple(sema, FilePosition { file_id, offset: range.start() }, file, config)
     } else {
         hover_ranged(sema, frange, file, config)
-    }?;
-
-    if let HoverDocFormat::PlainText = config.format {
-        res.info.markup = remove_markdown(res.info.markup.as_str()).into();
     }
-    Some(res)
 }
 
 fn hover_simple(
@@ -175,10 +281,16 @@ fn hover_simple(
                 })
                 .flatten()
                 .unique_by(|&(def, _)| def)
-                .filter_map(|(def, node)| hover_for_definition(sema, file_id, def, &node, config))
-                .reduce(|mut acc: HoverResult, HoverResult { markup, actions }| {
+                .filter_map(|(def, node)| {
+                    let reexport_docs = render::reexport_docs(sema, &node, config);
+                    definition_hover(sema, file_id, def, &node, config, reexport_docs)
+                })
+                .reduce(|mut acc: HoverResult, HoverResult { markup, actions, original }| {
                     acc.actions.extend(actions);
-                    acc.markup = Markup::from(format!("{}\n---\n{markup}", acc.markup));
+                    acc.markup.extend(markup);
+                    if let (Some(acc), Some(original)) = (&mut acc.original, original) {
+                        acc.merge(original);
+                    }
                     acc
                 })
         })
@@ -274,25 +386,59 @@ pub(crate) fn hover_for_definition(
     definition: Definition,
     node: &SyntaxNode,
     config: &HoverConfig,
+) -> Option<HoverResult> {
+    definition_hover(sema, file_id, definition, node, config, None)
+}
+
+/// Renders the hover for `definition`, with the docs of the re-export it is hovered in shown
+/// first.
+fn definition_hover(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    definition: Definition,
+    node: &SyntaxNode,
+    config: &HoverConfig,
+    reexport_docs: Option<(String, hir::Module)>,
 ) -> Option<HoverResult> {
     let famous_defs = match &definition {
         Definition::BuiltinType(_) => Some(FamousDefs(sema, sema.scope(node)?.krate())),
         _ => None,
     };
-    render::definition(sema.db, definition, famous_defs.as_ref(), config).map(|markup| {
-        HoverResult {
-            markup: render::process_markup(sema.db, definition, &markup, config),
+    let documented_in = inlining_crate(sema, node, definition);
+    render::definition(sema.db, definition, famous_defs.as_ref(), config, reexport_docs).map(
+        |mut markup| HoverResult {
+            original: {
+                markup.linked_name = Some(definition);
+                markup.documented_in = documented_in;
+                config.original_markup.then(|| markup.original(sema.db, config))
+            },
+            markup: markup.into(),
             actions: [
                 show_implementations_action(sema.db, definition),
                 show_fn_references_action(sema.db, definition),
                 runnable_action(sema, definition, file_id),
                 goto_type_action_for_def(sema.db, definition),
+                open_docs_action(sema.db, definition),
+                repository_url(sema.db, definition).map(HoverAction::OpenSource),
             ]
             .into_iter()
             .flatten()
             .collect(),
-        }
-    })
+        },
+    )
+}
+
+/// The name of `def` as a markdown link to its documentation page, if enabled and known.
+fn name_link(db: &RootDatabase, def: Definition, config: &HoverConfig) -> Option<String> {
+    if !config.name_link || config.format != HoverDocFormat::Markdown {
+        return None;
+    }
+    let url = doc_url(db, &def)?;
+    Some(format!("[`{}`]({url})", def.name(db)?))
+}
+
+fn open_docs_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
+    doc_url(db, &def).map(|url| HoverAction::OpenDocs(url.into()))
 }
 
 fn show_implementations_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
@@ -402,6 +548,8 @@ fn dedupe_or_merge_hover_actions(actions: Vec<HoverAction>) -> Vec<HoverAction>
     let mut seen_implementation = false;
     let mut seen_reference = false;
     let mut seen_runnable = false;
+    let mut seen_docs = false;
+    let mut seen_source = false;
     for action in actions {
         match action {
             HoverAction::GoToType(targets) => {
@@ -425,6 +573,18 @@ fn dedupe_or_merge_hover_actions(actions: Vec<HoverAction>) -> Vec<HoverAction>
                     deduped_actions.push(action);
                 }
             }
+            HoverAction::OpenDocs(..) => {
+                if !seen_docs {
+                    seen_docs = true;
+                    deduped_actions.push(action);
+                }
+            }
+            HoverAction::OpenSource(..) => {
+                if !seen_source {
+                    seen_source = true;
+                    deduped_actions.push(action);
+                }
+            }
         };
     }
 
@@ -3,12 +3,14 @@ use std::fmt::Display;
 
 use either::Either;
 use hir::{
-    Adt, AsAssocItem, AttributeTemplate, CaptureKind, HasAttrs, HasSource, HirDisplay, Semantics,
+    Adt, AsAssocItem, AttributeTemplate, CaptureKind, Crate, HasSource, HirDisplay, Semantics,
     TypeInfo,
 };
 use ide_db::{
     base_db::SourceDatabase,
     defs::Definition,
+    doc_links::primitive_of_module,
+    documentation::Documentation,
     famous_defs::FamousDefs,
     generated::lints::{CLIPPY_LINTS, DEFAULT_LINTS, FEATURES},
     syntax_helpers::insert_whitespace_into_node,
@@ -21,13 +23,15 @@ use syntax::{
     ast::{self, RecordPat},
     match_ast, AstNode, Direction,
     SyntaxKind::{LET_EXPR, LET_STMT},
-    SyntaxToken, T,
+    SyntaxNode, SyntaxToken, T,
 };
 
 use crate::{
-    doc_links::{remove_links, rewrite_links},
-    hover::walk_and_push_ty,
-    HoverAction, HoverConfig, HoverResult, Markup,
+    doc_links::{
+        append_full_docs_link, demote_headings, linkify_urls, process_links, resolve_doc_links,
+    },
+    hover::{walk_and_push_ty, OriginalMarkup},
+    DocLinksMode, HoverAction, HoverConfig, HoverDocFormat, HoverMarkup, HoverResult, Markup,
 };
 
 pub(super) fn type_info_of(
@@ -288,15 +292,19 @@ pub(super) fn keyword(
 
     let KeywordHint { description, keyword_mod, actions } = keyword_hints(sema, token, parent);
 
-    let doc_owner = find_std_module(&famous_defs, &keyword_mod)?;
-    let docs = doc_owner.attrs(sema.db).docs()?;
-    let markup = process_markup(
-        sema.db,
-        Definition::Module(doc_owner),
-        &markup(Some(docs.into()), description, None)?,
-        config,
-    );
-    Some(HoverResult { markup, actions })
+    let docs = find_std_module(&famous_defs, &keyword_mod)
+        .and_then(|doc_owner| Documentation::for_definition(sema.db, &doc_owner.into()));
+    let markup = match docs {
+        Some(docs) => {
+            let def = docs.owner();
+            let docs = hover_docs(sema.db, def, docs.as_str().to_owned(), config, false);
+            let markup = DefinitionMarkup::new(label_markup(description, None), Some(docs), def);
+            HoverMarkup::from(markup)
+        }
+        // without the sources of std there is nothing to document the keyword with
+        None => markup(None, description, None)?.into(),
+    };
+    Some(HoverResult { markup, actions, original: None })
 }
 
 /// Returns missing types in a record pattern.
@@ -333,7 +341,7 @@ pub(super) fn struct_rest_pat(
         // get rid of trailing comma
         s.truncate(s.len() - 2);
 
-        Markup::fenced_block(&s)
+        Markup::fenced_block(&s).into()
     };
     res.actions.push(HoverAction::goto_type_from_targets(sema.db, targets));
     res
@@ -375,21 +383,99 @@ pub(super) fn try_for_lint(attr: &ast::Attr, token: &SyntaxToken) -> Option<Hove
     let lint =
         lints.binary_search_by_key(&needle, |lint| lint.label).ok().map(|idx| &lints[idx])?;
     Some(HoverResult {
-        markup: Markup::from(format!("```\n{}\n```\n___\n\n{}", lint.label, lint.description)),
+        markup: format!("```\n{}\n```\n___\n\n{}", lint.label, lint.description).into(),
         ..Default::default()
     })
 }
 
-pub(super) fn process_markup(
+/// The hover markup of a definition, whose links are only processed once it is rendered for a
+/// format.
+#[derive(Debug)]
+pub(crate) struct DefinitionMarkup {
+    /// The definition whose name is shown as a link to its documentation page, if enabled.
+    pub(super) linked_name: Option<Definition>,
+    /// The code blocks naming the definition, or the whole markup if it has no docs of its own.
+    label: String,
+    docs: Option<String>,
+    /// The definition owning `docs`. That is `def` itself, unless its docs are inherited from
+    /// the trait item it implements.
+    docs_owner: Definition,
+    /// The crate inlining the docs if they are hovered through its re-export.
+    pub(super) documented_in: Option<Crate>,
+    /// The docs of the `use` item the definition is hovered in, whose links are resolved in the
+    /// scope of the re-exporting module.
+    reexport_docs: Option<(String, hir::Module)>,
+}
+
+impl DefinitionMarkup {
+    fn new(label: String, docs: Option<String>, docs_owner: Definition) -> Self {
+        DefinitionMarkup {
+            linked_name: None,
+            label,
+            docs,
+            docs_owner,
+            documented_in: None,
+            reexport_docs: None,
+        }
+    }
+
+    /// Renders the markup with its links processed for `config.format`.
+    pub(super) fn render(&self, db: &RootDatabase, config: &HoverConfig) -> String {
+        let markup = self.assemble(db, config);
+        process_docs(db, &markup, self.docs_owner, config, self.documented_in)
+    }
+
+    /// The markup with the links of the definition's own docs left untouched.
+    pub(super) fn original(&self, db: &RootDatabase, config: &HoverConfig) -> OriginalMarkup {
+        let markup = self.assemble(db, config);
+        let links =
+            resolve_doc_links(db, self.docs_owner, &markup, &config.links, self.documented_in);
+        OriginalMarkup { markup: markup.into(), links }
+    }
+
+    /// Joins the label and the docs, with the links of the re-export docs processed.
+    ///
+    /// These links are URLs afterwards, so processing them again with the docs owner leaves
+    /// them as they are.
+    fn assemble(&self, db: &RootDatabase, config: &HoverConfig) -> String {
+        let reexport_docs = self.reexport_docs.as_ref().map(|(docs, module)| {
+            process_docs(db, docs, Definition::Module(*module), config, None)
+        });
+        let docs = match (reexport_docs, self.docs.as_deref()) {
+            (Some(reexport_docs), Some(docs)) => Some(format!("{reexport_docs}\n\n---\n\n{docs}")),
+            (reexport_docs, docs) => reexport_docs.or(docs.map(ToOwned::to_owned)),
+        };
+        match docs {
+            Some(docs) => format!("{}\n___\n\n{docs}", self.label),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// Processes the links in `docs`, if enabled turns its bare URLs into links, and demotes its top
+/// level headings to fit the popup.
+fn process_docs(
     db: &RootDatabase,
+    docs: &str,
     def: Definition,
-    markup: &Markup,
     config: &HoverConfig,
-) -> Markup {
-    let markup = markup.as_str();
-    let markup =
-        if config.links_in_hover { rewrite_links(db, markup, def) } else { remove_links(markup) };
-    Markup::from(markup)
+    documented_in: Option<Crate>,
+) -> String {
+    let _p = profile::span("hover_process_docs");
+    // plain text hovers lose the links' targets anyway, so they are not worth resolving
+    let options = match config.format {
+        HoverDocFormat::Markdown => config.links,
+        HoverDocFormat::PlainText => config.links.with_mode(DocLinksMode::Strip),
+    };
+    let docs = process_links(db, docs, def, &options, documented_in);
+    if options.mode == DocLinksMode::Raw {
+        return docs;
+    }
+    let docs = if config.linkify_urls { linkify_urls(&docs) } else { docs };
+    match config.format {
+        HoverDocFormat::Markdown if config.demote_headings => demote_headings(&docs),
+        _ => docs,
+    }
 }
 
 fn definition_owner_name(db: &RootDatabase, def: &Definition) -> Option<String> {
@@ -417,16 +503,19 @@ pub(super) fn path(db: &RootDatabase, module: hir::Module, item_name: Option<Str
     crate_name.into_iter().chain(module_path).chain(item_name).join("::")
 }
 
+/// Renders the hover of `def`, with the docs of the re-export it is hovered in shown first.
 pub(super) fn definition(
     db: &RootDatabase,
     def: Definition,
     famous_defs: Option<&FamousDefs<'_, '_>>,
     config: &HoverConfig,
-) -> Option<Markup> {
+    reexport_docs: Option<(String, hir::Module)>,
+) -> Option<DefinitionMarkup> {
+    let whole = |markup: Markup| DefinitionMarkup::new(markup.into(), None, def);
     let mod_path = definition_mod_path(db, &def);
-    let (label, docs) = match def {
-        Definition::Macro(it) => label_and_docs(db, it),
-        Definition::Field(it) => label_and_layout_info_and_docs(db, it, config, |&it| {
+    let label = match def {
+        Definition::Macro(it) => label(db, it),
+        Definition::Field(it) => label_and_layout_info(db, it, config, |&it| {
             let var_def = it.parent_def(db);
             let id = it.index();
             let layout = it.layout(db).ok()?;
@@ -444,13 +533,13 @@ pub(super) fn definition(
                 offset.as_deref().unwrap_or_default()
             ))
         }),
-        Definition::Module(it) => label_and_docs(db, it),
-        Definition::Function(it) => label_and_docs(db, it),
-        Definition::Adt(it) => label_and_layout_info_and_docs(db, it, config, |&it| {
+        Definition::Module(it) => label(db, it),
+        Definition::Function(it) => label(db, it),
+        Definition::Adt(it) => label_and_layout_info(db, it, config, |&it| {
             let layout = it.layout(db).ok()?;
             Some(format!("size = {}, align = {}", layout.size.bytes(), layout.align.abi.bytes()))
         }),
-        Definition::Variant(it) => label_value_and_docs(db, it, |&it| {
+        Definition::Variant(it) => label_and_value(db, it, |&it| {
             if !it.parent_enum(db).is_data_carrying(db) {
                 match it.eval(db) {
                     Ok(x) => Some(if x >= 10 { format!("{x} ({x:#X})") } else { format!("{x}") }),
@@ -460,7 +549,7 @@ pub(super) fn definition(
                 None
             }
         }),
-        Definition::Const(it) => label_value_and_docs(db, it, |it| {
+        Definition::Const(it) => label_and_value(db, it, |it| {
             let body = it.render_eval(db);
             match body {
                 Ok(x) => Some(x),
@@ -474,7 +563,7 @@ pub(super) fn definition(
                 }
             }
         }),
-        Definition::Static(it) => label_value_and_docs(db, it, |it| {
+        Definition::Static(it) => label_and_value(db, it, |it| {
             let source = it.source(db)?;
             let mut body = source.value.body()?.syntax().clone();
             if source.file_id.is_macro() {
@@ -482,42 +571,185 @@ pub(super) fn definition(
             }
             Some(body.to_string())
         }),
-        Definition::Trait(it) => label_and_docs(db, it),
-        Definition::TraitAlias(it) => label_and_docs(db, it),
-        Definition::TypeAlias(it) => label_and_layout_info_and_docs(db, it, config, |&it| {
+        Definition::Trait(it) => label(db, it),
+        Definition::TraitAlias(it) => label(db, it),
+        Definition::TypeAlias(it) => label_and_layout_info(db, it, config, |&it| {
             let layout = it.ty(db).layout(db).ok()?;
             Some(format!("size = {}, align = {}", layout.size.bytes(), layout.align.abi.bytes()))
         }),
         Definition::BuiltinType(it) => {
             return famous_defs
-                .and_then(|fd| builtin(fd, it))
-                .or_else(|| Some(Markup::fenced_block(&it.name())))
-        }
-        Definition::Local(it) => return local(db, it),
-        Definition::SelfType(impl_def) => {
-            impl_def.self_ty(db).as_adt().map(|adt| label_and_docs(db, adt))?
+                .and_then(|fd| builtin(fd, it, config))
+                .or_else(|| Some(whole(Markup::fenced_block(it.name()))))
         }
-        Definition::GenericParam(it) => label_and_docs(db, it),
-        Definition::Label(it) => return Some(Markup::fenced_block(&it.name(db))),
+        Definition::Local(it) => return local(db, it).map(whole),
+        Definition::SelfType(impl_def) => label(db, impl_def.self_ty(db).as_adt()?),
+        Definition::GenericParam(it) => label(db, it),
+        Definition::Label(it) => return Some(whole(Markup::fenced_block(it.name(db)))),
         // FIXME: We should be able to show more info about these
-        Definition::BuiltinAttr(it) => return render_builtin_attr(db, it),
-        Definition::ToolModule(it) => return Some(Markup::fenced_block(&it.name(db))),
-        Definition::DeriveHelper(it) => (format!("derive_helper {}", it.name(db)), None),
+        Definition::BuiltinAttr(it) => return render_builtin_attr(db, it).map(whole),
+        Definition::ToolModule(it) => return Some(whole(Markup::fenced_block(it.name(db)))),
+        Definition::DeriveHelper(it) => format!("derive_helper {}", it.name(db)),
     };
 
-    let docs = docs
-        .filter(|_| config.documentation)
-        .or_else(|| {
-            // docs are missing, for assoc items of trait impls try to fall back to the docs of the
-            // original item of the trait
-            let assoc = def.as_assoc_item(db)?;
-            let trait_ = assoc.containing_trait_impl(db)?;
-            let name = Some(assoc.name(db)?);
-            let item = trait_.items(db).into_iter().find(|it| it.name(db) == name)?;
-            item.docs(db)
-        })
-        .map(Into::into);
-    markup(docs, label, mod_path)
+    let docs = match config.inactive_cfg_docs {
+        true => Documentation::with_inactive_cfg_attrs(db, &def),
+        false => Documentation::for_definition(db, &def),
+    };
+    let (docs, docs_owner) = match docs {
+        Some(docs) if config.documentation => {
+            let owner = docs.owner();
+            let docs = if docs.is_inherited() {
+                let full_len = docs.as_str().len();
+                let text = truncate_docs(docs.as_str().to_owned(), config, false);
+                let truncated = text.len() != full_len;
+                let text = format!("{text}\n\n*(docs from trait)*");
+                with_full_docs_link(db, owner, config, truncated, text)
+            } else {
+                let is_crate_root = matches!(owner, Definition::Module(it) if it.is_crate_root(db));
+                hover_docs(db, owner, docs.as_str().to_owned(), config, is_crate_root)
+            };
+            (Some(docs), owner)
+        }
+        _ => (None, def),
+    };
+    let mut markup = DefinitionMarkup::new(label_markup(label, mod_path), docs, docs_owner);
+    markup.reexport_docs = reexport_docs;
+    Some(markup)
+}
+
+/// Returns the docs of the `use` item re-exporting the item named by `node`, together with the
+/// re-exporting module whose scope their links are resolved in.
+pub(super) fn reexport_docs(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+    config: &HoverConfig,
+) -> Option<(String, hir::Module)> {
+    if !config.documentation {
+        return None;
+    }
+    let use_tree = node.ancestors().find_map(ast::UseTree::cast)?;
+    if use_tree.use_tree_list().is_some() || use_tree.star_token().is_some() {
+        return None;
+    }
+    // only the final segment or the rename names the re-exported item
+    let name_range = match use_tree.rename() {
+        Some(rename) if rename.syntax().text_range().contains_range(node.text_range()) => {
+            rename.syntax().text_range()
+        }
+        _ => use_tree.path()?.segment()?.syntax().text_range(),
+    };
+    if !name_range.contains_range(node.text_range()) {
+        return None;
+    }
+    let use_ = use_tree.syntax().ancestors().find_map(ast::Use::cast)?;
+    let docs = sema.use_attrs(&use_)?.docs()?;
+    let module = sema.scope(use_.syntax())?.module();
+    Some((docs.as_str().to_owned(), module))
+}
+
+/// Truncates `docs` of `def` like [`truncate_docs`] and, if enabled, ends them with a link to
+/// the full documentation.
+fn hover_docs(
+    db: &RootDatabase,
+    def: Definition,
+    docs: String,
+    config: &HoverConfig,
+    at_first_heading: bool,
+) -> String {
+    let full_len = docs.len();
+    let docs = truncate_docs(docs, config, at_first_heading);
+    let truncated = docs.len() != full_len;
+    with_full_docs_link(db, def, config, truncated, docs)
+}
+
+/// Ends `docs` of `def` with a link to the full documentation if that is enabled and they were
+/// `truncated` or are long.
+fn with_full_docs_link(
+    db: &RootDatabase,
+    def: Definition,
+    config: &HoverConfig,
+    truncated: bool,
+    mut docs: String,
+) -> String {
+    if config.links.full_docs_link && config.format == HoverDocFormat::Markdown {
+        append_full_docs_link(db, &mut docs, def, truncated);
+    }
+    docs
+}
+
+/// Cuts `docs` off after the last paragraph that fits into `config.max_doc_length`.
+///
+/// With `at_first_heading`, used for crate docs which tend to be a whole README, they are cut
+/// before their first heading following some text if that comes earlier.
+fn truncate_docs(docs: String, config: &HoverConfig, at_first_heading: bool) -> String {
+    let Some(max_len) = config.max_doc_length else { return docs };
+    if docs.chars().count() <= max_len {
+        return docs;
+    }
+
+    let mut cut = None;
+    let mut seen_text = false;
+    // the length is in chars, the offsets of the lines in bytes
+    let (mut counted, mut chars) = (0, 0);
+    for (offset, line) in lines_outside_code_blocks(&docs) {
+        chars += docs[counted..offset].chars().count();
+        counted = offset;
+        if at_first_heading && seen_text && line.trim_start().starts_with('#') {
+            cut = Some(offset);
+            break;
+        }
+        if line.trim().is_empty() {
+            // always keep the first paragraph
+            if cut.is_some() && chars > max_len {
+                break;
+            }
+            cut = Some(offset);
+        }
+        seen_text |= !line.trim().is_empty();
+    }
+    match cut {
+        Some(cut) => cut_docs(docs, cut),
+        None => docs,
+    }
+}
+
+/// The lines of `docs` outside of fenced code blocks, with their offsets.
+fn lines_outside_code_blocks(docs: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_code_block = false;
+    let mut offset = 0;
+    docs.split_inclusive('\n').filter_map(move |line| {
+        let line_offset = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            return None;
+        }
+        (!in_code_block).then_some((line_offset, line))
+    })
+}
+
+/// Cuts `docs` off at `cut`, keeping the link reference definitions of the removed part so the
+/// remaining links stay intact.
+fn cut_docs(docs: String, cut: usize) -> String {
+    if docs[cut..].trim().is_empty() {
+        return docs;
+    }
+    let mut res = docs[..cut].trim_end().to_owned();
+    let definitions = docs[cut..].lines().filter(|line| is_link_reference_definition(line));
+    for (idx, definition) in definitions.enumerate() {
+        res.push_str(if idx == 0 { "\n\n" } else { "\n" });
+        res.push_str(definition);
+    }
+    res.push_str("\n\n*(documentation truncated)*");
+    res
+}
+
+/// Whether `line` is a markdown link reference definition like `[label]: target`.
+fn is_link_reference_definition(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('[') && line.find("]:").map_or(false, |idx| idx > 1)
 }
 
 fn type_info(
@@ -549,7 +781,7 @@ fn type_info(
         )
         .into()
     } else {
-        Markup::fenced_block(&original.display(sema.db))
+        Markup::fenced_block(&original.display(sema.db)).into()
     };
     res.actions.push(HoverAction::goto_type_from_targets(sema.db, targets));
     Some(res)
@@ -576,51 +808,38 @@ fn render_builtin_attr(db: &RootDatabase, attr: hir::BuiltinAttr) -> Option<Mark
     markup(Some(docs.replace('*', "\\*")), desc, None)
 }
 
-fn label_and_docs<D>(db: &RootDatabase, def: D) -> (String, Option<hir::Documentation>)
-where
-    D: HasAttrs + HirDisplay,
-{
-    let label = def.display(db).to_string();
-    let docs = def.attrs(db).docs();
-    (label, docs)
+fn label<D: HirDisplay>(db: &RootDatabase, def: D) -> String {
+    def.display(db).to_string()
 }
 
-fn label_and_layout_info_and_docs<D, E, V>(
+fn label_and_layout_info<D, E, V>(
     db: &RootDatabase,
     def: D,
     config: &HoverConfig,
     value_extractor: E,
-) -> (String, Option<hir::Documentation>)
+) -> String
 where
-    D: HasAttrs + HirDisplay,
+    D: HirDisplay,
     E: Fn(&D) -> Option<V>,
     V: Display,
 {
-    let label = match value_extractor(&def) {
+    match value_extractor(&def) {
         Some(value) if config.memory_layout => format!("{} // {value}", def.display(db)),
         _ => def.display(db).to_string(),
-    };
-    let docs = def.attrs(db).docs();
-    (label, docs)
+    }
 }
 
-fn label_value_and_docs<D, E, V>(
-    db: &RootDatabase,
-    def: D,
-    value_extractor: E,
-) -> (String, Option<hir::Documentation>)
+fn label_and_value<D, E, V>(db: &RootDatabase, def: D, value_extractor: E) -> String
 where
-    D: HasAttrs + HirDisplay,
+    D: HirDisplay,
     E: Fn(&D) -> Option<V>,
     V: Display,
 {
-    let label = if let Some(value) = value_extractor(&def) {
+    if let Some(value) = value_extractor(&def) {
         format!("{} = {value}", def.display(db))
     } else {
         def.display(db).to_string()
-    };
-    let docs = def.attrs(db).docs();
-    (label, docs)
+    }
 }
 
 fn definition_mod_path(db: &RootDatabase, def: &Definition) -> Option<String> {
@@ -631,6 +850,15 @@ fn definition_mod_path(db: &RootDatabase, def: &Definition) -> Option<String> {
 }
 
 fn markup(docs: Option<String>, desc: String, mod_path: Option<String>) -> Option<Markup> {
+    let mut buf = label_markup(desc, mod_path);
+    if let Some(doc) = docs {
+        format_to!(buf, "\n___\n\n{}", doc);
+    }
+    Some(buf.into())
+}
+
+/// The code blocks of a hover naming the definition, ahead of its docs.
+fn label_markup(desc: String, mod_path: Option<String>) -> String {
     let mut buf = String::new();
 
     if let Some(mod_path) = mod_path {
@@ -639,19 +867,30 @@ fn markup(docs: Option<String>, desc: String, mod_path: Option<String>) -> Optio
         }
     }
     format_to!(buf, "```rust\n{}\n```", desc);
-
-    if let Some(doc) = docs {
-        format_to!(buf, "\n___\n\n{}", doc);
-    }
-    Some(buf.into())
+    buf
 }
 
-fn builtin(famous_defs: &FamousDefs<'_, '_>, builtin: hir::BuiltinType) -> Option<Markup> {
+/// Renders the docs of `builtin`, owned by the definition their links are relative to.
+fn builtin(
+    famous_defs: &FamousDefs<'_, '_>,
+    builtin: hir::BuiltinType,
+    config: &HoverConfig,
+) -> Option<DefinitionMarkup> {
+    let db = famous_defs.0.db;
     // std exposes prim_{} modules with docstrings on the root to document the builtins
     let primitive_mod = format!("prim_{}", builtin.name());
     let doc_owner = find_std_module(famous_defs, &primitive_mod)?;
-    let docs = doc_owner.attrs(famous_defs.0.db).docs()?;
-    markup(Some(docs.into()), builtin.name().to_string(), None)
+    let docs = Documentation::for_definition(db, &doc_owner.into())?;
+    // links in the docs of `#[doc(primitive)]` modules are resolved in the module's scope and
+    // relative to the primitive's page
+    let docs_owner = match primitive_of_module(db, doc_owner) {
+        Some(_) => Definition::Module(doc_owner),
+        None => Definition::BuiltinType(builtin),
+    };
+    let docs =
+        hover_docs(db, Definition::BuiltinType(builtin), docs.as_str().to_owned(), config, false);
+    let label = label_markup(builtin.name().to_string(), None);
+    Some(DefinitionMarkup::new(label, Some(docs), docs_owner))
 }
 
 fn find_std_module(famous_defs: &FamousDefs<'_, '_>, name: &str) -> Option<hir::Module> {
@@ -1,22 +1,48 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use expect_test::{expect, Expect};
-use ide_db::base_db::{FileLoader, FileRange};
-use syntax::TextRange;
+use ide_db::{
+    base_db::{
+        fixture::ChangeFixture, salsa::Database, Change, Env, FileLoader, FileRange, ProcMacro,
+        ProcMacroExpander, ProcMacroExpansionError, ProcMacroKind,
+    },
+    defs::Definition,
+    doc_links::DocLinksDatabase,
+};
+use syntax::{AstNode, SourceFile, TextRange};
+use triomphe::Arc;
+use tt::token_id::Subtree;
 
-use crate::{fixture, HoverConfig, HoverDocFormat};
+use crate::{
+    doc_links::CANCELLATION_CHECK_HOOK, fixture, AnalysisHost, BrokenLinksMode, DocLinkOptions,
+    DocLinksMode, HoverConfig, HoverDocFormat, PageLinksMode,
+};
 
 const HOVER_BASE_CONFIG: HoverConfig = HoverConfig {
-    links_in_hover: false,
+    links: DocLinkOptions {
+        mode: DocLinksMode::Strip,
+        page_links: PageLinksMode::Rewrite,
+        full_docs_link: false,
+        source_fallback: false,
+        broken_links: BrokenLinksMode::Keep,
+    },
     memory_layout: true,
     documentation: true,
     format: HoverDocFormat::Markdown,
     keywords: true,
+    max_doc_length: None,
+    inactive_cfg_docs: false,
+    name_link: false,
+    linkify_urls: false,
+    demote_headings: false,
+    original_markup: false,
 };
 
 fn check_hover_no_result(ra_fixture: &str) {
     let (analysis, position) = fixture::position(ra_fixture);
     let hover = analysis
         .hover(
-            &HoverConfig { links_in_hover: true, ..HOVER_BASE_CONFIG },
+            &HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG },
             FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
         )
         .unwrap();
@@ -26,73 +52,237 @@ fn check_hover_no_result(ra_fixture: &str) {
 #[track_caller]
 fn check(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
     let hover = analysis
         .hover(
-            &HoverConfig { links_in_hover: true, ..HOVER_BASE_CONFIG },
+            &config,
             FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
         )
         .unwrap()
         .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
 
     let content = analysis.db.file_text(position.file_id);
     let hovered_element = &content[hover.range];
 
-    let actual = format!("*{hovered_element}*\n{}\n", hover.info.markup);
+    let actual = format!("*{hovered_element}*\n{}\n", markup);
     expect.assert_eq(&actual)
 }
 
 fn check_hover_no_links(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
+    let config = HOVER_BASE_CONFIG;
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    let content = analysis.db.file_text(position.file_id);
+    let hovered_element = &content[hover.range];
+
+    let actual = format!("*{hovered_element}*\n{}\n", markup);
+    expect.assert_eq(&actual)
+}
+
+fn check_hover_raw_links(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default().with_mode(DocLinksMode::Raw),
+        ..HOVER_BASE_CONFIG
+    };
     let hover = analysis
         .hover(
-            &HOVER_BASE_CONFIG,
+            &config,
             FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
         )
         .unwrap()
         .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
 
     let content = analysis.db.file_text(position.file_id);
     let hovered_element = &content[hover.range];
 
-    let actual = format!("*{hovered_element}*\n{}\n", hover.info.markup);
+    let actual = format!("*{hovered_element}*\n{}\n", markup);
+    expect.assert_eq(&actual)
+}
+
+fn check_hover_max_doc_length(ra_fixture: &str, max_doc_length: usize, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        max_doc_length: Some(max_doc_length),
+        ..HOVER_BASE_CONFIG
+    };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    expect.assert_eq(markup.as_str())
+}
+
+fn hover_with_full_docs_link(ra_fixture: &str, max_doc_length: Option<usize>) -> String {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default().with_full_docs_link(true),
+        max_doc_length,
+        ..HOVER_BASE_CONFIG
+    };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    markup.as_str().to_owned()
+}
+
+fn check_hover_name_link(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config =
+        HoverConfig { links: DocLinkOptions::default(), name_link: true, ..HOVER_BASE_CONFIG };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    expect.assert_eq(markup.as_str())
+}
+
+fn check_hover_inactive_cfg_docs(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        inactive_cfg_docs: true,
+        ..HOVER_BASE_CONFIG
+    };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    expect.assert_eq(markup.as_str())
+}
+
+fn check_hover_linkify_urls(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config =
+        HoverConfig { links: DocLinkOptions::default(), linkify_urls: true, ..HOVER_BASE_CONFIG };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    expect.assert_eq(markup.as_str())
+}
+
+fn check_hover_demote_headings(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        demote_headings: true,
+        ..HOVER_BASE_CONFIG
+    };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    expect.assert_eq(markup.as_str())
+}
+
+fn check_hover_original_markup(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        original_markup: true,
+        ..HOVER_BASE_CONFIG
+    };
+    let hover = analysis
+        .hover(
+            &config,
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
+
+    let original = hover.info.original.unwrap();
+    let links = original
+        .links
+        .iter()
+        .map(|link| format!("{} -> {:?}\n", &original.markup.as_str()[link.range], link.url))
+        .collect::<String>();
+    let actual = format!("{}\n---\n{}\n---\n{links}", markup, original.markup);
     expect.assert_eq(&actual)
 }
 
 fn check_hover_no_memory_layout(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig { memory_layout: false, ..HOVER_BASE_CONFIG };
     let hover = analysis
         .hover(
-            &HoverConfig { memory_layout: false, ..HOVER_BASE_CONFIG },
+            &config,
             FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
         )
         .unwrap()
         .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
 
     let content = analysis.db.file_text(position.file_id);
     let hovered_element = &content[hover.range];
 
-    let actual = format!("*{hovered_element}*\n{}\n", hover.info.markup);
+    let actual = format!("*{hovered_element}*\n{}\n", markup);
     expect.assert_eq(&actual)
 }
 
 fn check_hover_no_markdown(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
+    let config = HoverConfig {
+        links: DocLinkOptions::default(),
+        format: HoverDocFormat::PlainText,
+        ..HOVER_BASE_CONFIG
+    };
     let hover = analysis
         .hover(
-            &HoverConfig {
-                links_in_hover: true,
-                format: HoverDocFormat::PlainText,
-                ..HOVER_BASE_CONFIG
-            },
+            &config,
             FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
         )
         .unwrap()
         .unwrap();
+    let markup = analysis.render_hover(&config, &hover.info.markup).unwrap();
 
     let content = analysis.db.file_text(position.file_id);
     let hovered_element = &content[hover.range];
 
-    let actual = format!("*{hovered_element}*\n{}\n", hover.info.markup);
+    let actual = format!("*{hovered_element}*\n{}\n", markup);
     expect.assert_eq(&actual)
 }
 
@@ -100,7 +290,7 @@ fn check_actions(ra_fixture: &str, expect: Expect) {
     let (analysis, file_id, position) = fixture::range_or_position(ra_fixture);
     let hover = analysis
         .hover(
-            &HoverConfig { links_in_hover: true, ..HOVER_BASE_CONFIG },
+            &HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG },
             FileRange { file_id, range: position.range_or_empty() },
         )
         .unwrap()
@@ -111,7 +301,8 @@ fn check_actions(ra_fixture: &str, expect: Expect) {
 fn check_hover_range(ra_fixture: &str, expect: Expect) {
     let (analysis, range) = fixture::range(ra_fixture);
     let hover = analysis.hover(&HOVER_BASE_CONFIG, range).unwrap().unwrap();
-    expect.assert_eq(hover.info.markup.as_str())
+    let markup = analysis.render_hover(&HOVER_BASE_CONFIG, &hover.info.markup).unwrap();
+    expect.assert_eq(markup.as_str())
 }
 
 fn check_hover_range_no_results(ra_fixture: &str) {
@@ -215,6 +406,72 @@ fn main() {
     );
 }
 
+#[test]
+fn hover_remove_markdown_without_resolving_links() {
+    cov_mark::check_count!(doc_link_target_resolved, 0);
+    check_hover_no_markdown(
+        r#"
+pub struct Bar;
+pub fn baz() {}
+/// Makes a [`Bar`] with [fn@baz], see [the guide](https://example.com/guide).
+pub fn foo() {}
+
+fn main() {
+    foo$0();
+}
+"#,
+        expect![[r#"
+            *foo*
+            test
+
+            pub fn foo()
+
+
+            Makes a Bar with baz, see the guide.
+        "#]],
+    );
+}
+
+#[test]
+fn hover_processes_links_when_rendered() {
+    let (analysis, position) = fixture::position(
+        r#"
+pub struct Bar;
+/// Makes a [`Bar`].
+pub fn foo$0() {}
+"#,
+    );
+    let range = FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+    let markdown = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = {
+        cov_mark::check_count!(doc_link_target_resolved, 0);
+        analysis.hover(&markdown, range).unwrap().unwrap()
+    };
+    expect![[r#"
+
+        ```rust
+        test
+        ```
+
+        ```rust
+        pub fn foo()
+        ```
+
+        ---
+
+        Makes a [`Bar`](https://docs.rs/test/*/test/struct.Bar.html)."#]]
+    .assert_eq(analysis.render_hover(&markdown, &hover.info.markup).unwrap().as_str());
+    let plain_text = HoverConfig { format: HoverDocFormat::PlainText, ..markdown };
+    expect![[r#"
+        test
+
+        pub fn foo()
+
+
+        Makes a Bar."#]]
+    .assert_eq(analysis.render_hover(&plain_text, &hover.info.markup).unwrap().as_str());
+}
+
 #[test]
 fn hover_closure() {
     check(
@@ -1283,6 +1540,9 @@ fn foo(Foo { b$0ar }: &Foo) {}
         "#,
         expect![[r#"
             [
+                OpenDocs(
+                    "https://docs.rs/test/*/test/struct.Foo.html#structfield.bar",
+                ),
                 GoToType(
                     [
                         HoverGotoTypeData {
@@ -1461,6 +1721,9 @@ fn test_hover_trait_show_qualifiers() {
                             offset: 13,
                         },
                     ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/trait.foo.html",
+                    ),
                 ]
             "#]],
     );
@@ -1521,788 +1784,813 @@ extern crate std as ab$0c;
 }
 
 #[test]
-fn test_hover_mod_with_same_name_as_function() {
-    check(
+fn test_hover_dependency_crate_docs() {
+    check_hover_max_doc_length(
         r#"
-use self::m$0y::Bar;
-mod my { pub struct Bar; }
-
-fn my() {}
+//- /main.rs crate:main deps:serde
+use serde$0::Deserialize;
+//- /serde/lib.rs crate:serde@CratesIo:1.0.0,https://github.com/serde-rs/serde
+//! A framework for [`Deserialize`]ing data, see [the guide].
+//!
+//! ```rust
+//! # A comment, not a heading
+//! ```
+//!
+//! # Design
+//!
+//! Long [`Deserialize`] explanations.
+//!
+//! [the guide]: guide/index.html
+pub trait Deserialize {}
 "#,
+        150,
         expect![[r#"
-                *my*
 
-                ```rust
-                test
-                ```
+            ```rust
+            extern crate serde
+            ```
 
-                ```rust
-                mod my
-                ```
-            "#]],
+            ---
+
+            A framework for [`Deserialize`](https://docs.rs/serde/1.0.0/serde/trait.Deserialize.html)ing data, see [the guide](https://docs.rs/serde/1.0.0/serde/guide/index.html).
+
+            ```rust
+            # A comment, not a heading
+            ```
+
+            *(documentation truncated)*"#]],
     );
 }
 
 #[test]
-fn test_hover_struct_doc_comment() {
+fn test_hover_dependency_crate_docs_links() {
+    // the links in the root docs of a dependency resolve in its scope, wherever it is named
     check(
         r#"
-/// This is an example
-/// multiline doc
-///
-/// # Example
-///
-/// ```
-/// let five = 5;
-///
-/// assert_eq!(6, my_crate::add_one(5));
-/// ```
-struct Bar;
-
-fn foo() { let bar = Ba$0r; }
+//- /main.rs crate:main deps:dep
+extern crate dep as d$0;
+//- /dep/lib.rs crate:dep@CratesIo:0.2.0,https://github.com/example/dep
+//! A [`Client`] for the gateway, see [the examples](#examples) and [the guide](guide/index.html).
+//!
+//! # Examples
+pub struct Client;
 "#,
         expect![[r#"
-            *Bar*
-
-            ```rust
-            test
-            ```
+            *d*
 
             ```rust
-            struct Bar // size = 0, align = 1
+            extern crate dep
             ```
 
             ---
 
-            This is an example
-            multiline doc
-
-            # Example
+            A [`Client`](https://docs.rs/dep/0.2.0/dep/struct.Client.html) for the gateway, see [the examples](https://docs.rs/dep/0.2.0/dep/index.html#examples) and [the guide](https://docs.rs/dep/0.2.0/dep/guide/index.html).
 
-            ```
-            let five = 5;
-
-            assert_eq!(6, my_crate::add_one(5));
-            ```
+            # Examples
         "#]],
     );
-}
-
-#[test]
-fn test_hover_struct_doc_attr() {
     check(
         r#"
-#[doc = "bar docs"]
-struct Bar;
-
-fn foo() { let bar = Ba$0r; }
+//- /main.rs crate:main deps:dep
+fn f() {
+    let _ = dep$0::Client;
+}
+//- /dep/lib.rs crate:dep@CratesIo:0.2.0,https://github.com/example/dep
+//! A [`Client`] for the gateway, see [the examples](#examples) and [the guide](guide/index.html).
+//!
+//! # Examples
+pub struct Client;
 "#,
         expect![[r#"
-            *Bar*
-
-            ```rust
-            test
-            ```
+            *dep*
 
             ```rust
-            struct Bar // size = 0, align = 1
+            extern crate dep
             ```
 
             ---
 
-            bar docs
+            A [`Client`](https://docs.rs/dep/0.2.0/dep/struct.Client.html) for the gateway, see [the examples](https://docs.rs/dep/0.2.0/dep/index.html#examples) and [the guide](https://docs.rs/dep/0.2.0/dep/guide/index.html).
+
+            # Examples
         "#]],
     );
-}
-
-#[test]
-fn test_hover_struct_doc_attr_multiple_and_mixed() {
     check(
         r#"
-/// bar docs 0
-#[doc = "bar docs 1"]
-#[doc = "bar docs 2"]
-struct Bar;
-
-fn foo() { let bar = Ba$0r; }
+//- /main.rs crate:main deps:dep,mid
+use mid::dep$0::Client;
+//- /mid/lib.rs crate:mid deps:dep
+pub use dep;
+//- /dep/lib.rs crate:dep@CratesIo:0.2.0,https://github.com/example/dep
+//! A [`Client`] for the gateway, see [the examples](#examples) and [the guide](guide/index.html).
+//!
+//! # Examples
+pub struct Client;
 "#,
         expect![[r#"
-            *Bar*
-
-            ```rust
-            test
-            ```
+            *dep*
 
             ```rust
-            struct Bar // size = 0, align = 1
+            extern crate dep
             ```
 
             ---
 
-            bar docs 0
-            bar docs 1
-            bar docs 2
+            A [`Client`](https://docs.rs/dep/0.2.0/dep/struct.Client.html) for the gateway, see [the examples](https://docs.rs/dep/0.2.0/dep/index.html#examples) and [the guide](https://docs.rs/dep/0.2.0/dep/guide/index.html).
+
+            # Examples
         "#]],
     );
 }
 
 #[test]
-fn test_hover_external_url() {
+fn test_hover_reexport_docs() {
     check(
         r#"
-pub struct Foo;
-/// [external](https://www.google.com)
-pub struct B$0ar
+//- /main.rs crate:main deps:dep
+/// Re-exported for the [`connect`] helper.
+pub use dep::Sha$0rd;
+pub fn connect() {}
+//- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep
+/// A shard connection, see [`Gateway`] and [the guide](../dep/guide/index.html).
+pub struct Shard;
+pub struct Gateway;
 "#,
         expect![[r#"
-            *Bar*
+            *Shard*
 
             ```rust
-            test
+            dep
             ```
 
             ```rust
-            pub struct Bar // size = 0, align = 1
+            pub struct Shard // size = 0, align = 1
             ```
 
             ---
 
-            [external](https://www.google.com)
+            Re-exported for the [`connect`](https://docs.rs/main/*/main/fn.connect.html) helper.
+
+            ---
+
+            A shard connection, see [`Gateway`](https://docs.rs/dep/0.1.0/dep/struct.Gateway.html) and [the guide](https://docs.rs/dep/0.1.0/dep/guide/index.html).
         "#]],
     );
-}
-
-// Check that we don't rewrite links which we can't identify
-#[test]
-fn test_hover_unknown_target() {
     check(
         r#"
-pub struct Foo;
-/// [baz](Baz)
-pub struct B$0ar
+//- /main.rs crate:main deps:dep
+/// Re-exported.
+pub use dep$0::Shard;
+//- /dep/lib.rs crate:dep
+pub struct Shard;
 "#,
         expect![[r#"
-            *Bar*
+            *dep*
 
             ```rust
-            test
+            extern crate dep
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_reexport_docs_resolve_links_in_the_reexporting_module() {
+    check(
+        r#"
+//- /main.rs crate:main
+pub mod net {
+    /// Re-exported as [Shard] for the [gateway](crate::gateway), see [`Session`] and its
+    /// [page](struct.Session.html).
+    pub use crate::internal::Sh$0ard;
+    pub struct Session;
+}
+pub mod gateway {}
+mod internal {
+    /// The shard, see [`Session`].
+    pub struct Shard;
+    pub struct Session;
+}
+"#,
+        expect![[r#"
+            *Shard*
+
+            ```rust
+            main::internal
             ```
 
             ```rust
-            pub struct Bar // size = 0, align = 1
+            pub struct Shard // size = 0, align = 1
             ```
 
             ---
 
-            [baz](Baz)
+            Re-exported as [Shard](https://docs.rs/main/*/main/net/struct.Shard.html) for the [gateway](https://docs.rs/main/*/main/gateway/index.html), see [`Session`](https://docs.rs/main/*/main/net/struct.Session.html) and its
+            [page](https://docs.rs/main/*/main/net/struct.Session.html).
+
+            ---
+
+            The shard, see [`Session`](https://docs.rs/main/*/main/internal/struct.Session.html).
         "#]],
     );
 }
 
 #[test]
-fn test_hover_no_links() {
-    check_hover_no_links(
+fn test_hover_mod_with_same_name_as_function() {
+    check(
         r#"
-/// Test cases:
-/// case 1.  bare URL: https://www.example.com/
-/// case 2.  inline URL with title: [example](https://www.example.com/)
-/// case 3.  code reference: [`Result`]
-/// case 4.  code reference but miss footnote: [`String`]
-/// case 5.  autolink: <http://www.example.com/>
-/// case 6.  email address: <test@example.com>
-/// case 7.  reference: [example][example]
-/// case 8.  collapsed link: [example][]
-/// case 9.  shortcut link: [example]
-/// case 10. inline without URL: [example]()
-/// case 11. reference: [foo][foo]
-/// case 12. reference: [foo][bar]
-/// case 13. collapsed link: [foo][]
-/// case 14. shortcut link: [foo]
-/// case 15. inline without URL: [foo]()
-/// case 16. just escaped text: \[foo]
-/// case 17. inline link: [Foo](foo::Foo)
-///
-/// [`Result`]: ../../std/result/enum.Result.html
-/// [^example]: https://www.example.com/
-pub fn fo$0o() {}
+use self::m$0y::Bar;
+mod my { pub struct Bar; }
+
+fn my() {}
 "#,
         expect![[r#"
-                *foo*
+                *my*
 
                 ```rust
                 test
                 ```
 
                 ```rust
-                pub fn foo()
+                mod my
                 ```
-
-                ---
-
-                Test cases:
-                case 1.  bare URL: https://www.example.com/
-                case 2.  inline URL with title: [example](https://www.example.com/)
-                case 3.  code reference: `Result`
-                case 4.  code reference but miss footnote: `String`
-                case 5.  autolink: http://www.example.com/
-                case 6.  email address: test@example.com
-                case 7.  reference: example
-                case 8.  collapsed link: example
-                case 9.  shortcut link: example
-                case 10. inline without URL: example
-                case 11. reference: foo
-                case 12. reference: foo
-                case 13. collapsed link: foo
-                case 14. shortcut link: foo
-                case 15. inline without URL: foo
-                case 16. just escaped text: \[foo\]
-                case 17. inline link: Foo
-
-                [^example]: https://www.example.com/
             "#]],
     );
 }
 
 #[test]
-fn test_hover_no_memory_layout() {
-    check_hover_no_memory_layout(
-        r#"struct Foo { fiel$0d_a: u8, field_b: i32, field_c: i16 }"#,
+fn test_hover_struct_doc_comment() {
+    check(
+        r#"
+/// This is an example
+/// multiline doc
+///
+/// # Example
+///
+/// ```
+/// let five = 5;
+///
+/// assert_eq!(6, my_crate::add_one(5));
+/// ```
+struct Bar;
+
+fn foo() { let bar = Ba$0r; }
+"#,
         expect![[r#"
-            *field_a*
+            *Bar*
 
             ```rust
-            test::Foo
+            test
             ```
 
             ```rust
-            field_a: u8
+            struct Bar // size = 0, align = 1
+            ```
+
+            ---
+
+            This is an example
+            multiline doc
+
+            # Example
+
+            ```
+            let five = 5;
+
+            assert_eq!(6, my_crate::add_one(5));
             ```
         "#]],
     );
+}
 
-    check_hover_no_memory_layout(
+#[test]
+fn hover_demotes_top_level_headings() {
+    check_hover_demote_headings(
         r#"
-//- minicore: copy
-fn main() {
-    let x = 2;
-    let y = $0|z| x + z;
-}
+/// Bar
+/// ===
+///
+/// ## Panics
+///
+/// ### Errors
+struct Bar;
+
+fn foo() { let bar = Ba$0r; }
 "#,
         expect![[r#"
-            *|*
+
             ```rust
-            {closure#0}
-            impl Fn(i32) -> i32
+            test
             ```
 
-            ## Captures
-            * `x` by immutable borrow
-        "#]],
+            ```rust
+            struct Bar // size = 0, align = 1
+            ```
+
+            ---
+
+            Bar
+            ---
+
+            ### Panics
+
+            ### Errors"#]],
     );
 }
 
 #[test]
-fn test_hover_macro_generated_struct_fn_doc_comment() {
-    cov_mark::check!(hover_macro_generated_struct_fn_doc_comment);
-
+fn test_hover_struct_doc_attr() {
     check(
         r#"
-macro_rules! bar {
-    () => {
-        struct Bar;
-        impl Bar {
-            /// Do the foo
-            fn foo(&self) {}
-        }
-    }
-}
-
-bar!();
+#[doc = "bar docs"]
+struct Bar;
 
-fn foo() { let bar = Bar; bar.fo$0o(); }
+fn foo() { let bar = Ba$0r; }
 "#,
         expect![[r#"
-                *foo*
+            *Bar*
 
-                ```rust
-                test::Bar
-                ```
+            ```rust
+            test
+            ```
 
-                ```rust
-                fn foo(&self)
-                ```
+            ```rust
+            struct Bar // size = 0, align = 1
+            ```
 
-                ---
+            ---
 
-                Do the foo
-            "#]],
+            bar docs
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_macro_generated_struct_fn_doc_attr() {
-    cov_mark::check!(hover_macro_generated_struct_fn_doc_attr);
-
+fn test_hover_struct_doc_attr_multiple_and_mixed() {
     check(
         r#"
-macro_rules! bar {
-    () => {
-        struct Bar;
-        impl Bar {
-            #[doc = "Do the foo"]
-            fn foo(&self) {}
-        }
-    }
-}
-
-bar!();
+/// bar docs 0
+#[doc = "bar docs 1"]
+#[doc = "bar docs 2"]
+struct Bar;
 
-fn foo() { let bar = Bar; bar.fo$0o(); }
+fn foo() { let bar = Ba$0r; }
 "#,
         expect![[r#"
-                *foo*
+            *Bar*
 
-                ```rust
-                test::Bar
-                ```
+            ```rust
+            test
+            ```
 
-                ```rust
-                fn foo(&self)
-                ```
+            ```rust
+            struct Bar // size = 0, align = 1
+            ```
 
-                ---
+            ---
 
-                Do the foo
-            "#]],
+            bar docs 0
+            bar docs 1
+            bar docs 2
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_variadic_function() {
+fn test_hover_external_url() {
     check(
         r#"
-extern "C" {
-    pub fn foo(bar: i32, ...) -> i32;
-}
-
-fn main() { let foo_test = unsafe { fo$0o(1, 2, 3); } }
+pub struct Foo;
+/// [external](https://www.google.com)
+pub struct B$0ar
 "#,
         expect![[r#"
-            *foo*
+            *Bar*
 
             ```rust
             test
             ```
 
             ```rust
-            pub unsafe fn foo(bar: i32, ...) -> i32
+            pub struct Bar // size = 0, align = 1
             ```
+
+            ---
+
+            [external](https://www.google.com)
         "#]],
     );
 }
 
+// Check that we don't rewrite links which we can't identify
 #[test]
-fn test_hover_trait_has_impl_action() {
-    check_actions(
-        r#"trait foo$0() {}"#,
-        expect![[r#"
-                [
-                    Implementation(
-                        FilePosition {
-                            file_id: FileId(
-                                0,
-                            ),
-                            offset: 6,
-                        },
-                    ),
-                ]
-            "#]],
+fn test_hover_unknown_target() {
+    check(
+        r#"
+pub struct Foo;
+/// [baz](Baz)
+pub struct B$0ar
+"#,
+        expect![[r#"
+            *Bar*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub struct Bar // size = 0, align = 1
+            ```
+
+            ---
+
+            [baz](Baz)
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_struct_has_impl_action() {
-    check_actions(
-        r"struct foo$0() {}",
+fn test_hover_raw_links() {
+    check_hover_raw_links(
+        r#"
+/// See [`Bar`], [the docs](https://www.example.com/) and [Foo](struct.Foo.html).
+pub fn fo$0o() {}
+pub struct Bar;
+"#,
         expect![[r#"
-                [
-                    Implementation(
-                        FilePosition {
-                            file_id: FileId(
-                                0,
-                            ),
-                            offset: 7,
-                        },
-                    ),
-                ]
-            "#]],
+            *foo*
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub fn foo()
+            ```
+            ___
+
+            See [`Bar`], [the docs](https://www.example.com/) and [Foo](struct.Foo.html).
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_union_has_impl_action() {
-    check_actions(
-        r#"union foo$0() {}"#,
+fn test_hover_original_markup() {
+    check_hover_original_markup(
+        r#"
+/// See [`Bar`] and [the docs](https://www.example.com/).
+pub fn fo$0o() {}
+pub struct Bar;
+"#,
         expect![[r#"
-                [
-                    Implementation(
-                        FilePosition {
-                            file_id: FileId(
-                                0,
-                            ),
-                            offset: 6,
-                        },
-                    ),
-                ]
-            "#]],
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub fn foo()
+            ```
+
+            ---
+
+            See [`Bar`](https://docs.rs/test/*/test/struct.Bar.html) and [the docs](https://www.example.com/).
+            ---
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub fn foo()
+            ```
+            ___
+
+            See [`Bar`] and [the docs](https://www.example.com/).
+            ---
+            [`Bar`] -> Some("https://docs.rs/test/*/test/struct.Bar.html")
+            [the docs](https://www.example.com/) -> Some("https://www.example.com/")
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_enum_has_impl_action() {
-    check_actions(
-        r"enum foo$0() { A, B }",
-        expect![[r#"
-                [
-                    Implementation(
-                        FilePosition {
-                            file_id: FileId(
-                                0,
-                            ),
-                            offset: 5,
-                        },
-                    ),
-                ]
-            "#]],
-    );
+fn test_hover_original_markup_of_multiple_definitions() {
+    check_hover_original_markup(
+        r#"
+macro_rules! m {
+    ($name:ident) => {
+        /// Outer [`Bar`]
+        fn $name() {}
+
+        mod module {
+            /// Inner [`super::Baz`]
+            fn $name() {}
+        }
+    };
 }
+pub struct Bar;
+pub struct Baz;
 
-#[test]
-fn test_hover_self_has_impl_action() {
-    check_actions(
-        r#"struct foo where Self$0:;"#,
+m!(ab$0c);
+"#,
         expect![[r#"
-                [
-                    Implementation(
-                        FilePosition {
-                            file_id: FileId(
-                                0,
-                            ),
-                            offset: 7,
-                        },
-                    ),
-                ]
-            "#]],
+
+            ```rust
+            test::module
+            ```
+
+            ```rust
+            fn abc()
+            ```
+
+            ---
+
+            Inner [`super::Baz`](https://docs.rs/test/*/test/struct.Baz.html)
+            ---
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn abc()
+            ```
+
+            ---
+
+            Outer [`Bar`](https://docs.rs/test/*/test/struct.Bar.html)
+            ---
+            ```rust
+            test::module
+            ```
+
+            ```rust
+            fn abc()
+            ```
+            ___
+
+            Inner [`super::Baz`]
+            ---
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn abc()
+            ```
+            ___
+
+            Outer [`Bar`]
+            ---
+            [`super::Baz`] -> Some("https://docs.rs/test/*/test/struct.Baz.html")
+            [`Bar`] -> Some("https://docs.rs/test/*/test/struct.Bar.html")
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_test_has_action() {
-    check_actions(
+fn test_hover_no_links_strips_disambiguators_of_implied_links() {
+    check_hover_no_links(
         r#"
-#[test]
-fn foo_$0test() {}
+/// [`struct Foo`], [struct `Foo`], [fn@run] and [fn pointers](fn@run)
+pub fn $0run() {}
+pub struct Foo;
 "#,
         expect![[r#"
-            [
-                Reference(
-                    FilePosition {
-                        file_id: FileId(
-                            0,
-                        ),
-                        offset: 11,
-                    },
-                ),
-                Runnable(
-                    Runnable {
-                        use_name_in_title: false,
-                        nav: NavigationTarget {
-                            file_id: FileId(
-                                0,
-                            ),
-                            full_range: 0..24,
-                            focus_range: 11..19,
-                            name: "foo_test",
-                            kind: Function,
-                        },
-                        kind: Test {
-                            test_id: Path(
-                                "foo_test",
-                            ),
-                            attr: TestAttr {
-                                ignore: false,
-                            },
-                        },
-                        cfg: None,
-                    },
-                ),
-            ]
+            *run*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub fn run()
+            ```
+
+            ---
+
+            `Foo`, `Foo`, run and fn pointers
         "#]],
     );
 }
 
 #[test]
-fn test_hover_test_mod_has_action() {
-    check_actions(
+fn test_hover_no_links() {
+    check_hover_no_links(
         r#"
-mod tests$0 {
-    #[test]
-    fn foo_test() {}
-}
+/// Test cases:
+/// case 1.  bare URL: https://www.example.com/
+/// case 2.  inline URL with title: [example](https://www.example.com/)
+/// case 3.  code reference: [`Result`]
+/// case 4.  code reference but miss footnote: [`String`]
+/// case 5.  autolink: <http://www.example.com/>
+/// case 6.  email address: <test@example.com>
+/// case 7.  reference: [example][example]
+/// case 8.  collapsed link: [example][]
+/// case 9.  shortcut link: [example]
+/// case 10. inline without URL: [example]()
+/// case 11. reference: [foo][foo]
+/// case 12. reference: [foo][bar]
+/// case 13. collapsed link: [foo][]
+/// case 14. shortcut link: [foo]
+/// case 15. inline without URL: [foo]()
+/// case 16. just escaped text: \[foo]
+/// case 17. inline link: [Foo](foo::Foo)
+///
+/// [`Result`]: ../../std/result/enum.Result.html
+/// [^example]: https://www.example.com/
+pub fn fo$0o() {}
 "#,
         expect![[r#"
-                [
-                    Runnable(
-                        Runnable {
-                            use_name_in_title: false,
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    0,
-                                ),
-                                full_range: 0..46,
-                                focus_range: 4..9,
-                                name: "tests",
-                                kind: Module,
-                                description: "mod tests",
-                            },
-                            kind: TestMod {
-                                path: "tests",
-                            },
-                            cfg: None,
-                        },
-                    ),
-                ]
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                pub fn foo()
+                ```
+
+                ---
+
+                Test cases:
+                case 1.  bare URL: https://www.example.com/
+                case 2.  inline URL with title: [example](https://www.example.com/)
+                case 3.  code reference: `Result`
+                case 4.  code reference but miss footnote: `String`
+                case 5.  autolink: http://www.example.com/
+                case 6.  email address: test@example.com
+                case 7.  reference: example
+                case 8.  collapsed link: example
+                case 9.  shortcut link: example
+                case 10. inline without URL: example
+                case 11. reference: foo
+                case 12. reference: foo
+                case 13. collapsed link: foo
+                case 14. shortcut link: foo
+                case 15. inline without URL: foo
+                case 16. just escaped text: \[foo\]
+                case 17. inline link: Foo
+
+                [^example]: https://www.example.com/
             "#]],
     );
 }
 
 #[test]
-fn test_hover_struct_has_goto_type_action() {
-    check_actions(
-        r#"
-struct S{ f1: u32 }
+fn test_hover_no_memory_layout() {
+    check_hover_no_memory_layout(
+        r#"struct Foo { fiel$0d_a: u8, field_b: i32, field_c: i16 }"#,
+        expect![[r#"
+            *field_a*
 
-fn main() { let s$0t = S{ f1:0 }; }
+            ```rust
+            test::Foo
+            ```
+
+            ```rust
+            field_a: u8
+            ```
+        "#]],
+    );
+
+    check_hover_no_memory_layout(
+        r#"
+//- minicore: copy
+fn main() {
+    let x = 2;
+    let y = $0|z| x + z;
+}
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::S",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..19,
-                                    focus_range: 7..8,
-                                    name: "S",
-                                    kind: Struct,
-                                    description: "struct S",
-                                },
-                            },
-                        ],
-                    ),
-                ]
-            "#]],
+            *|*
+            ```rust
+            {closure#0}
+            impl Fn(i32) -> i32
+            ```
+
+            ## Captures
+            * `x` by immutable borrow
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_generic_struct_has_goto_type_actions() {
-    check_actions(
+fn test_hover_macro_generated_struct_fn_doc_comment() {
+    cov_mark::check!(hover_macro_generated_struct_fn_doc_comment);
+
+    check(
         r#"
-struct Arg(u32);
-struct S<T>{ f1: T }
+macro_rules! bar {
+    () => {
+        struct Bar;
+        impl Bar {
+            /// Do the foo
+            fn foo(&self) {}
+        }
+    }
+}
 
-fn main() { let s$0t = S{ f1:Arg(0) }; }
+bar!();
+
+fn foo() { let bar = Bar; bar.fo$0o(); }
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::S",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 17..37,
-                                    focus_range: 24..25,
-                                    name: "S",
-                                    kind: Struct,
-                                    description: "struct S<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::Arg",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..16,
-                                    focus_range: 7..10,
-                                    name: "Arg",
-                                    kind: Struct,
-                                    description: "struct Arg",
-                                },
-                            },
-                        ],
-                    ),
-                ]
+                *foo*
+
+                ```rust
+                test::Bar
+                ```
+
+                ```rust
+                fn foo(&self)
+                ```
+
+                ---
+
+                Do the foo
             "#]],
     );
 }
 
 #[test]
-fn test_hover_generic_excludes_sized_go_to_action() {
-    check_actions(
+fn test_hover_macro_generated_struct_fn_doc_attr() {
+    cov_mark::check!(hover_macro_generated_struct_fn_doc_attr);
+
+    check(
         r#"
-//- minicore: sized
-struct S<T$0>(T);
-    "#,
-        expect![[r#"
-            []
-        "#]],
-    );
+macro_rules! bar {
+    () => {
+        struct Bar;
+        impl Bar {
+            #[doc = "Do the foo"]
+            fn foo(&self) {}
+        }
+    }
 }
 
-#[test]
-fn test_hover_generic_struct_has_flattened_goto_type_actions() {
-    check_actions(
-        r#"
-struct Arg(u32);
-struct S<T>{ f1: T }
+bar!();
 
-fn main() { let s$0t = S{ f1: S{ f1: Arg(0) } }; }
+fn foo() { let bar = Bar; bar.fo$0o(); }
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::S",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 17..37,
-                                    focus_range: 24..25,
-                                    name: "S",
-                                    kind: Struct,
-                                    description: "struct S<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::Arg",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..16,
-                                    focus_range: 7..10,
-                                    name: "Arg",
-                                    kind: Struct,
-                                    description: "struct Arg",
-                                },
-                            },
-                        ],
-                    ),
-                ]
+                *foo*
+
+                ```rust
+                test::Bar
+                ```
+
+                ```rust
+                fn foo(&self)
+                ```
+
+                ---
+
+                Do the foo
             "#]],
     );
 }
 
 #[test]
-fn test_hover_tuple_has_goto_type_actions() {
-    check_actions(
+fn test_hover_variadic_function() {
+    check(
         r#"
-struct A(u32);
-struct B(u32);
-mod M {
-    pub struct C(u32);
+extern "C" {
+    pub fn foo(bar: i32, ...) -> i32;
 }
 
-fn main() { let s$0t = (A(1), B(2), M::C(3) ); }
+fn main() { let foo_test = unsafe { fo$0o(1, 2, 3); } }
 "#,
         expect![[r#"
-            [
-                GoToType(
-                    [
-                        HoverGotoTypeData {
-                            mod_path: "test::A",
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    0,
-                                ),
-                                full_range: 0..14,
-                                focus_range: 7..8,
-                                name: "A",
-                                kind: Struct,
-                                description: "struct A",
-                            },
-                        },
-                        HoverGotoTypeData {
-                            mod_path: "test::B",
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    0,
-                                ),
-                                full_range: 15..29,
-                                focus_range: 22..23,
-                                name: "B",
-                                kind: Struct,
-                                description: "struct B",
-                            },
-                        },
-                        HoverGotoTypeData {
-                            mod_path: "test::M::C",
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    0,
-                                ),
-                                full_range: 42..60,
-                                focus_range: 53..54,
-                                name: "C",
-                                kind: Struct,
-                                container_name: "M",
-                                description: "pub struct C",
-                            },
-                        },
-                    ],
-                ),
-            ]
+            *foo*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub unsafe fn foo(bar: i32, ...) -> i32
+            ```
         "#]],
     );
 }
 
 #[test]
-fn test_hover_return_impl_trait_has_goto_type_action() {
+fn test_hover_trait_has_impl_action() {
     check_actions(
-        r#"
-trait Foo {}
-fn foo() -> impl Foo {}
-
-fn main() { let s$0t = foo(); }
-"#,
+        r#"trait foo$0() {}"#,
         expect![[r#"
                 [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..12,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo",
-                                },
-                            },
-                        ],
+                    Implementation(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 6,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/trait.foo.html",
                     ),
                 ]
             "#]],
@@ -2310,46 +2598,43 @@ fn main() { let s$0t = foo(); }
 }
 
 #[test]
-fn test_hover_generic_return_impl_trait_has_goto_type_action() {
+fn test_hover_struct_has_impl_action() {
     check_actions(
-        r#"
-trait Foo<T> {}
-struct S;
-fn foo() -> impl Foo<S> {}
+        r"struct foo$0() {}",
+        expect![[r#"
+                [
+                    Implementation(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 7,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/struct.foo.html",
+                    ),
+                ]
+            "#]],
+    );
+}
 
-fn main() { let s$0t = foo(); }
-"#,
+#[test]
+fn test_hover_union_has_impl_action() {
+    check_actions(
+        r#"union foo$0() {}"#,
         expect![[r#"
                 [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..15,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::S",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 16..25,
-                                    focus_range: 23..24,
-                                    name: "S",
-                                    kind: Struct,
-                                    description: "struct S",
-                                },
-                            },
-                        ],
+                    Implementation(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 6,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/union.foo.html",
                     ),
                 ]
             "#]],
@@ -2357,46 +2642,43 @@ fn main() { let s$0t = foo(); }
 }
 
 #[test]
-fn test_hover_return_impl_traits_has_goto_type_action() {
+fn test_hover_enum_has_impl_action() {
     check_actions(
-        r#"
-trait Foo {}
-trait Bar {}
-fn foo() -> impl Foo + Bar {}
+        r"enum foo$0() { A, B }",
+        expect![[r#"
+                [
+                    Implementation(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 5,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/enum.foo.html",
+                    ),
+                ]
+            "#]],
+    );
+}
 
-fn main() { let s$0t = foo(); }
-"#,
+#[test]
+fn test_hover_self_has_impl_action() {
+    check_actions(
+        r#"struct foo where Self$0:;"#,
         expect![[r#"
                 [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..12,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::Bar",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 13..25,
-                                    focus_range: 19..22,
-                                    name: "Bar",
-                                    kind: Trait,
-                                    description: "trait Bar",
-                                },
-                            },
-                        ],
+                    Implementation(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 7,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/struct.foo.html",
                     ),
                 ]
             "#]],
@@ -2404,75 +2686,85 @@ fn main() { let s$0t = foo(); }
 }
 
 #[test]
-fn test_hover_generic_return_impl_traits_has_goto_type_action() {
+fn test_hover_test_has_action() {
     check_actions(
         r#"
-trait Foo<T> {}
-trait Bar<T> {}
-struct S1 {}
-struct S2 {}
-
-fn foo() -> impl Foo<S1> + Bar<S2> {}
-
-fn main() { let s$0t = foo(); }
+#[test]
+fn foo_$0test() {}
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..15,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::Bar",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 16..31,
-                                    focus_range: 22..25,
-                                    name: "Bar",
-                                    kind: Trait,
-                                    description: "trait Bar<T>",
-                                },
+            [
+                Reference(
+                    FilePosition {
+                        file_id: FileId(
+                            0,
+                        ),
+                        offset: 11,
+                    },
+                ),
+                Runnable(
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..24,
+                            focus_range: 11..19,
+                            name: "foo_test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "foo_test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
                             },
-                            HoverGotoTypeData {
-                                mod_path: "test::S1",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 32..44,
-                                    focus_range: 39..41,
-                                    name: "S1",
-                                    kind: Struct,
-                                    description: "struct S1",
-                                },
+                        },
+                        cfg: None,
+                    },
+                ),
+                OpenDocs(
+                    "https://docs.rs/test/*/test/fn.foo_test.html",
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_test_mod_has_action() {
+    check_actions(
+        r#"
+mod tests$0 {
+    #[test]
+    fn foo_test() {}
+}
+"#,
+        expect![[r#"
+                [
+                    Runnable(
+                        Runnable {
+                            use_name_in_title: false,
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 0..46,
+                                focus_range: 4..9,
+                                name: "tests",
+                                kind: Module,
+                                description: "mod tests",
                             },
-                            HoverGotoTypeData {
-                                mod_path: "test::S2",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 45..57,
-                                    focus_range: 52..54,
-                                    name: "S2",
-                                    kind: Struct,
-                                    description: "struct S2",
-                                },
+                            kind: TestMod {
+                                path: "tests",
                             },
-                        ],
+                            cfg: None,
+                        },
+                    ),
+                    OpenDocs(
+                        "https://docs.rs/test/*/test/tests/index.html",
                     ),
                 ]
             "#]],
@@ -2480,27 +2772,55 @@ fn main() { let s$0t = foo(); }
 }
 
 #[test]
-fn test_hover_arg_impl_trait_has_goto_type_action() {
+fn test_hover_method_has_open_docs_action() {
     check_actions(
         r#"
-trait Foo {}
-fn foo(ar$0g: &impl Foo) {}
+pub struct Foo;
+impl Foo {
+    pub fn bar$0(&self) {}
+}
+"#,
+        expect![[r#"
+            [
+                Reference(
+                    FilePosition {
+                        file_id: FileId(
+                            0,
+                        ),
+                        offset: 38,
+                    },
+                ),
+                OpenDocs(
+                    "https://docs.rs/test/*/test/struct.Foo.html#method.bar",
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_struct_has_goto_type_action() {
+    check_actions(
+        r#"
+struct S{ f1: u32 }
+
+fn main() { let s$0t = S{ f1:0 }; }
 "#,
         expect![[r#"
                 [
                     GoToType(
                         [
                             HoverGotoTypeData {
-                                mod_path: "test::Foo",
+                                mod_path: "test::S",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..12,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo",
+                                    full_range: 0..19,
+                                    focus_range: 7..8,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S",
                                 },
                             },
                         ],
@@ -2511,56 +2831,42 @@ fn foo(ar$0g: &impl Foo) {}
 }
 
 #[test]
-fn test_hover_arg_impl_traits_has_goto_type_action() {
+fn test_hover_generic_struct_has_goto_type_actions() {
     check_actions(
         r#"
-trait Foo {}
-trait Bar<T> {}
-struct S{}
+struct Arg(u32);
+struct S<T>{ f1: T }
 
-fn foo(ar$0g: &impl Foo + Bar<S>) {}
+fn main() { let s$0t = S{ f1:Arg(0) }; }
 "#,
         expect![[r#"
                 [
                     GoToType(
                         [
                             HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..12,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::Bar",
+                                mod_path: "test::S",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 13..28,
-                                    focus_range: 19..22,
-                                    name: "Bar",
-                                    kind: Trait,
-                                    description: "trait Bar<T>",
+                                    full_range: 17..37,
+                                    focus_range: 24..25,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S<T>",
                                 },
                             },
                             HoverGotoTypeData {
-                                mod_path: "test::S",
+                                mod_path: "test::Arg",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 29..39,
-                                    focus_range: 36..37,
-                                    name: "S",
+                                    full_range: 0..16,
+                                    focus_range: 7..10,
+                                    name: "Arg",
                                     kind: Struct,
-                                    description: "struct S",
+                                    description: "struct Arg",
                                 },
                             },
                         ],
@@ -2571,96 +2877,55 @@ fn foo(ar$0g: &impl Foo + Bar<S>) {}
 }
 
 #[test]
-fn test_hover_async_block_impl_trait_has_goto_type_action() {
+fn test_hover_generic_excludes_sized_go_to_action() {
     check_actions(
         r#"
-//- /main.rs crate:main deps:core
-// we don't use minicore here so that this test doesn't randomly fail
-// when someone edits minicore
-struct S;
-fn foo() {
-    let fo$0o = async { S };
-}
-//- /core.rs crate:core
-pub mod future {
-    #[lang = "future_trait"]
-    pub trait Future {}
-}
-"#,
+//- minicore: sized
+struct S<T$0>(T);
+    "#,
         expect![[r#"
-            [
-                GoToType(
-                    [
-                        HoverGotoTypeData {
-                            mod_path: "core::future::Future",
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    1,
-                                ),
-                                full_range: 21..69,
-                                focus_range: 60..66,
-                                name: "Future",
-                                kind: Trait,
-                                container_name: "future",
-                                description: "pub trait Future",
-                            },
-                        },
-                        HoverGotoTypeData {
-                            mod_path: "main::S",
-                            nav: NavigationTarget {
-                                file_id: FileId(
-                                    0,
-                                ),
-                                full_range: 0..110,
-                                focus_range: 108..109,
-                                name: "S",
-                                kind: Struct,
-                                description: "struct S",
-                            },
-                        },
-                    ],
-                ),
-            ]
+            []
         "#]],
     );
 }
 
 #[test]
-fn test_hover_arg_generic_impl_trait_has_goto_type_action() {
+fn test_hover_generic_struct_has_flattened_goto_type_actions() {
     check_actions(
         r#"
-trait Foo<T> {}
-struct S {}
-fn foo(ar$0g: &impl Foo<S>) {}
+struct Arg(u32);
+struct S<T>{ f1: T }
+
+fn main() { let s$0t = S{ f1: S{ f1: Arg(0) } }; }
 "#,
         expect![[r#"
                 [
                     GoToType(
                         [
                             HoverGotoTypeData {
-                                mod_path: "test::Foo",
+                                mod_path: "test::S",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..15,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo<T>",
+                                    full_range: 17..37,
+                                    focus_range: 24..25,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S<T>",
                                 },
                             },
                             HoverGotoTypeData {
-                                mod_path: "test::S",
+                                mod_path: "test::Arg",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 16..27,
-                                    focus_range: 23..24,
-                                    name: "S",
+                                    full_range: 0..16,
+                                    focus_range: 7..10,
+                                    name: "Arg",
                                     kind: Struct,
-                                    description: "struct S",
+                                    description: "struct Arg",
                                 },
                             },
                         ],
@@ -2671,61 +2936,76 @@ fn foo(ar$0g: &impl Foo<S>) {}
 }
 
 #[test]
-fn test_hover_dyn_return_has_goto_type_action() {
+fn test_hover_tuple_has_goto_type_actions() {
     check_actions(
         r#"
-trait Foo {}
-struct S;
-impl Foo for S {}
-
-struct B<T>{}
-fn foo() -> B<dyn Foo> {}
+struct A(u32);
+struct B(u32);
+mod M {
+    pub struct C(u32);
+}
 
-fn main() { let s$0t = foo(); }
+fn main() { let s$0t = (A(1), B(2), M::C(3) ); }
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::B",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 42..55,
-                                    focus_range: 49..50,
-                                    name: "B",
-                                    kind: Struct,
-                                    description: "struct B<T>",
-                                },
+            [
+                GoToType(
+                    [
+                        HoverGotoTypeData {
+                            mod_path: "test::A",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 0..14,
+                                focus_range: 7..8,
+                                name: "A",
+                                kind: Struct,
+                                description: "struct A",
                             },
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..12,
-                                    focus_range: 6..9,
-                                    name: "Foo",
-                                    kind: Trait,
-                                    description: "trait Foo",
-                                },
+                        },
+                        HoverGotoTypeData {
+                            mod_path: "test::B",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 15..29,
+                                focus_range: 22..23,
+                                name: "B",
+                                kind: Struct,
+                                description: "struct B",
                             },
-                        ],
-                    ),
-                ]
-            "#]],
+                        },
+                        HoverGotoTypeData {
+                            mod_path: "test::M::C",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 42..60,
+                                focus_range: 53..54,
+                                name: "C",
+                                kind: Struct,
+                                container_name: "M",
+                                description: "pub struct C",
+                            },
+                        },
+                    ],
+                ),
+            ]
+        "#]],
     );
 }
 
 #[test]
-fn test_hover_dyn_arg_has_goto_type_action() {
+fn test_hover_return_impl_trait_has_goto_type_action() {
     check_actions(
         r#"
 trait Foo {}
-fn foo(ar$0g: &dyn Foo) {}
+fn foo() -> impl Foo {}
+
+fn main() { let s$0t = foo(); }
 "#,
         expect![[r#"
                 [
@@ -2752,12 +3032,14 @@ fn foo(ar$0g: &dyn Foo) {}
 }
 
 #[test]
-fn test_hover_generic_dyn_arg_has_goto_type_action() {
+fn test_hover_generic_return_impl_trait_has_goto_type_action() {
     check_actions(
         r#"
 trait Foo<T> {}
-struct S {}
-fn foo(ar$0g: &dyn Foo<S>) {}
+struct S;
+fn foo() -> impl Foo<S> {}
+
+fn main() { let s$0t = foo(); }
 "#,
         expect![[r#"
                 [
@@ -2782,7 +3064,7 @@ fn foo(ar$0g: &dyn Foo<S>) {}
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 16..27,
+                                    full_range: 16..25,
                                     focus_range: 23..24,
                                     name: "S",
                                     kind: Struct,
@@ -2797,70 +3079,43 @@ fn foo(ar$0g: &dyn Foo<S>) {}
 }
 
 #[test]
-fn test_hover_goto_type_action_links_order() {
+fn test_hover_return_impl_traits_has_goto_type_action() {
     check_actions(
         r#"
-trait ImplTrait<T> {}
-trait DynTrait<T> {}
-struct B<T> {}
-struct S {}
+trait Foo {}
+trait Bar {}
+fn foo() -> impl Foo + Bar {}
 
-fn foo(a$0rg: &impl ImplTrait<B<dyn DynTrait<B<S>>>>) {}
+fn main() { let s$0t = foo(); }
 "#,
         expect![[r#"
                 [
                     GoToType(
                         [
                             HoverGotoTypeData {
-                                mod_path: "test::ImplTrait",
+                                mod_path: "test::Foo",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..21,
-                                    focus_range: 6..15,
-                                    name: "ImplTrait",
+                                    full_range: 0..12,
+                                    focus_range: 6..9,
+                                    name: "Foo",
                                     kind: Trait,
-                                    description: "trait ImplTrait<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::B",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 43..57,
-                                    focus_range: 50..51,
-                                    name: "B",
-                                    kind: Struct,
-                                    description: "struct B<T>",
+                                    description: "trait Foo",
                                 },
                             },
                             HoverGotoTypeData {
-                                mod_path: "test::DynTrait",
+                                mod_path: "test::Bar",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 22..42,
-                                    focus_range: 28..36,
-                                    name: "DynTrait",
+                                    full_range: 13..25,
+                                    focus_range: 19..22,
+                                    name: "Bar",
                                     kind: Trait,
-                                    description: "trait DynTrait<T>",
-                                },
-                            },
-                            HoverGotoTypeData {
-                                mod_path: "test::S",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 58..69,
-                                    focus_range: 65..66,
-                                    name: "S",
-                                    kind: Struct,
-                                    description: "struct S",
+                                    description: "trait Bar",
                                 },
                             },
                         ],
@@ -2871,22 +3126,17 @@ fn foo(a$0rg: &impl ImplTrait<B<dyn DynTrait<B<S>>>>) {}
 }
 
 #[test]
-fn test_hover_associated_type_has_goto_type_action() {
+fn test_hover_generic_return_impl_traits_has_goto_type_action() {
     check_actions(
         r#"
-trait Foo {
-    type Item;
-    fn get(self) -> Self::Item {}
-}
-
-struct Bar{}
-struct S{}
-
-impl Foo for S { type Item = Bar; }
+trait Foo<T> {}
+trait Bar<T> {}
+struct S1 {}
+struct S2 {}
 
-fn test() -> impl Foo { S {} }
+fn foo() -> impl Foo<S1> + Bar<S2> {}
 
-fn main() { let s$0t = test().get(); }
+fn main() { let s$0t = foo(); }
 "#,
         expect![[r#"
                 [
@@ -2898,44 +3148,50 @@ fn main() { let s$0t = test().get(); }
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..62,
+                                    full_range: 0..15,
                                     focus_range: 6..9,
                                     name: "Foo",
                                     kind: Trait,
-                                    description: "trait Foo",
+                                    description: "trait Foo<T>",
                                 },
                             },
-                        ],
-                    ),
-                ]
-            "#]],
-    );
-}
-
-#[test]
-fn test_hover_const_param_has_goto_type_action() {
-    check_actions(
-        r#"
-struct Bar;
-struct Foo<const BAR: Bar>;
-
-impl<const BAR: Bar> Foo<BAR$0> {}
-"#,
-        expect![[r#"
-                [
-                    GoToType(
-                        [
                             HoverGotoTypeData {
                                 mod_path: "test::Bar",
                                 nav: NavigationTarget {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..11,
-                                    focus_range: 7..10,
+                                    full_range: 16..31,
+                                    focus_range: 22..25,
                                     name: "Bar",
+                                    kind: Trait,
+                                    description: "trait Bar<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S1",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 32..44,
+                                    focus_range: 39..41,
+                                    name: "S1",
                                     kind: Struct,
-                                    description: "struct Bar",
+                                    description: "struct S1",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S2",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 45..57,
+                                    focus_range: 52..54,
+                                    name: "S2",
+                                    kind: Struct,
+                                    description: "struct S2",
                                 },
                             },
                         ],
@@ -2946,12 +3202,11 @@ impl<const BAR: Bar> Foo<BAR$0> {}
 }
 
 #[test]
-fn test_hover_type_param_has_goto_type_action() {
+fn test_hover_arg_impl_trait_has_goto_type_action() {
     check_actions(
         r#"
 trait Foo {}
-
-fn foo<T: Foo>(t: T$0){}
+fn foo(ar$0g: &impl Foo) {}
 "#,
         expect![[r#"
                 [
@@ -2978,13 +3233,127 @@ fn foo<T: Foo>(t: T$0){}
 }
 
 #[test]
-fn test_hover_self_has_go_to_type() {
+fn test_hover_arg_impl_traits_has_goto_type_action() {
     check_actions(
         r#"
-struct Foo;
-impl Foo {
-    fn foo(&self$0) {}
+trait Foo {}
+trait Bar<T> {}
+struct S{}
+
+fn foo(ar$0g: &impl Foo + Bar<S>) {}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..12,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::Bar",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 13..28,
+                                    focus_range: 19..22,
+                                    name: "Bar",
+                                    kind: Trait,
+                                    description: "trait Bar<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 29..39,
+                                    focus_range: 36..37,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_async_block_impl_trait_has_goto_type_action() {
+    check_actions(
+        r#"
+//- /main.rs crate:main deps:core
+// we don't use minicore here so that this test doesn't randomly fail
+// when someone edits minicore
+struct S;
+fn foo() {
+    let fo$0o = async { S };
+}
+//- /core.rs crate:core
+pub mod future {
+    #[lang = "future_trait"]
+    pub trait Future {}
+}
+"#,
+        expect![[r#"
+            [
+                GoToType(
+                    [
+                        HoverGotoTypeData {
+                            mod_path: "core::future::Future",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    1,
+                                ),
+                                full_range: 21..69,
+                                focus_range: 60..66,
+                                name: "Future",
+                                kind: Trait,
+                                container_name: "future",
+                                description: "pub trait Future",
+                            },
+                        },
+                        HoverGotoTypeData {
+                            mod_path: "main::S",
+                            nav: NavigationTarget {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                full_range: 0..110,
+                                focus_range: 108..109,
+                                name: "S",
+                                kind: Struct,
+                                description: "struct S",
+                            },
+                        },
+                    ],
+                ),
+            ]
+        "#]],
+    );
 }
+
+#[test]
+fn test_hover_arg_generic_impl_trait_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo<T> {}
+struct S {}
+fn foo(ar$0g: &impl Foo<S>) {}
 "#,
         expect![[r#"
                 [
@@ -2996,11 +3365,105 @@ impl Foo {
                                     file_id: FileId(
                                         0,
                                     ),
-                                    full_range: 0..11,
-                                    focus_range: 7..10,
+                                    full_range: 0..15,
+                                    focus_range: 6..9,
                                     name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 16..27,
+                                    focus_range: 23..24,
+                                    name: "S",
                                     kind: Struct,
-                                    description: "struct Foo",
+                                    description: "struct S",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_dyn_return_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo {}
+struct S;
+impl Foo for S {}
+
+struct B<T>{}
+fn foo() -> B<dyn Foo> {}
+
+fn main() { let s$0t = foo(); }
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::B",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 42..55,
+                                    focus_range: 49..50,
+                                    name: "B",
+                                    kind: Struct,
+                                    description: "struct B<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..12,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_dyn_arg_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo {}
+fn foo(ar$0g: &dyn Foo) {}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..12,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo",
                                 },
                             },
                         ],
@@ -3011,649 +3474,1362 @@ impl Foo {
 }
 
 #[test]
-fn hover_displays_normalized_crate_names() {
+fn test_hover_generic_dyn_arg_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo<T> {}
+struct S {}
+fn foo(ar$0g: &dyn Foo<S>) {}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..15,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 16..27,
+                                    focus_range: 23..24,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_goto_type_action_links_order() {
+    check_actions(
+        r#"
+trait ImplTrait<T> {}
+trait DynTrait<T> {}
+struct B<T> {}
+struct S {}
+
+fn foo(a$0rg: &impl ImplTrait<B<dyn DynTrait<B<S>>>>) {}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::ImplTrait",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..21,
+                                    focus_range: 6..15,
+                                    name: "ImplTrait",
+                                    kind: Trait,
+                                    description: "trait ImplTrait<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::B",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 43..57,
+                                    focus_range: 50..51,
+                                    name: "B",
+                                    kind: Struct,
+                                    description: "struct B<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::DynTrait",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 22..42,
+                                    focus_range: 28..36,
+                                    name: "DynTrait",
+                                    kind: Trait,
+                                    description: "trait DynTrait<T>",
+                                },
+                            },
+                            HoverGotoTypeData {
+                                mod_path: "test::S",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 58..69,
+                                    focus_range: 65..66,
+                                    name: "S",
+                                    kind: Struct,
+                                    description: "struct S",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_associated_type_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo {
+    type Item;
+    fn get(self) -> Self::Item {}
+}
+
+struct Bar{}
+struct S{}
+
+impl Foo for S { type Item = Bar; }
+
+fn test() -> impl Foo { S {} }
+
+fn main() { let s$0t = test().get(); }
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..62,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_const_param_has_goto_type_action() {
+    check_actions(
+        r#"
+struct Bar;
+struct Foo<const BAR: Bar>;
+
+impl<const BAR: Bar> Foo<BAR$0> {}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Bar",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..11,
+                                    focus_range: 7..10,
+                                    name: "Bar",
+                                    kind: Struct,
+                                    description: "struct Bar",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_type_param_has_goto_type_action() {
+    check_actions(
+        r#"
+trait Foo {}
+
+fn foo<T: Foo>(t: T$0){}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..12,
+                                    focus_range: 6..9,
+                                    name: "Foo",
+                                    kind: Trait,
+                                    description: "trait Foo",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn test_hover_self_has_go_to_type() {
+    check_actions(
+        r#"
+struct Foo;
+impl Foo {
+    fn foo(&self$0) {}
+}
+"#,
+        expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..11,
+                                    focus_range: 7..10,
+                                    name: "Foo",
+                                    kind: Struct,
+                                    description: "struct Foo",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+    );
+}
+
+#[test]
+fn hover_displays_normalized_crate_names() {
+    check(
+        r#"
+//- /lib.rs crate:name-with-dashes
+pub mod wrapper {
+    pub struct Thing { x: u32 }
+
+    impl Thing {
+        pub fn new() -> Thing { Thing { x: 0 } }
+    }
+}
+
+//- /main.rs crate:main deps:name-with-dashes
+fn main() { let foo_test = name_with_dashes::wrapper::Thing::new$0(); }
+"#,
+        expect![[r#"
+            *new*
+
+            ```rust
+            name_with_dashes::wrapper::Thing
+            ```
+
+            ```rust
+            pub fn new() -> Thing
+            ```
+            "#]],
+    )
+}
+
+#[test]
+fn hover_field_pat_shorthand_ref_match_ergonomics() {
+    check(
+        r#"
+struct S {
+    f: i32,
+}
+
+fn main() {
+    let s = S { f: 0 };
+    let S { f$0 } = &s;
+}
+"#,
+        expect![[r#"
+            *f*
+
+            ```rust
+            f: &i32
+            ```
+            ---
+
+            ```rust
+            test::S
+            ```
+
+            ```rust
+            f: i32 // size = 4, align = 4, offset = 0
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_order() {
+    check(
+        r#"
+struct Foo;
+struct S$0T<const C: usize = 1, T = Foo>(T);
+"#,
+        expect![[r#"
+            *ST*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            struct ST<const C: usize, T = Foo>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_positive_i8_literal() {
+    check(
+        r#"
+struct Const<const N: i8>;
+
+fn main() {
+    let v$0alue = Const::<1>;
+}
+"#,
+        expect![[r#"
+            *value*
+
+            ```rust
+            let value: Const<1>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_zero_i8_literal() {
+    check(
+        r#"
+struct Const<const N: i8>;
+
+fn main() {
+    let v$0alue = Const::<0>;
+}
+"#,
+        expect![[r#"
+            *value*
+
+            ```rust
+            let value: Const<0>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_negative_i8_literal() {
+    check(
+        r#"
+struct Const<const N: i8>;
+
+fn main() {
+    let v$0alue = Const::<-1>;
+}
+"#,
+        expect![[r#"
+            *value*
+
+            ```rust
+            let value: Const<-1>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_bool_literal() {
+    check(
+        r#"
+struct Const<const F: bool>;
+
+fn main() {
+    let v$0alue = Const::<true>;
+}
+"#,
+        expect![[r#"
+            *value*
+
+            ```rust
+            let value: Const<true>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn const_generic_char_literal() {
+    check(
+        r#"
+struct Const<const C: char>;
+
+fn main() {
+    let v$0alue = Const::<'🦀'>;
+}
+"#,
+        expect![[r#"
+            *value*
+
+            ```rust
+            let value: Const<'🦀'>
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_self_param_shows_type() {
+    check(
+        r#"
+struct Foo {}
+impl Foo {
+    fn bar(&sel$0f) {}
+}
+"#,
+        expect![[r#"
+                *self*
+
+                ```rust
+                self: &Foo
+                ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_self_param_shows_type_for_arbitrary_self_type() {
+    check(
+        r#"
+struct Arc<T>(T);
+struct Foo {}
+impl Foo {
+    fn bar(sel$0f: Arc<Foo>) {}
+}
+"#,
+        expect![[r#"
+                *self*
+
+                ```rust
+                self: Arc<Foo>
+                ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_doc_outer_inner() {
+    check(
+        r#"
+/// Be quick;
+mod Foo$0 {
+    //! time is mana
+
+    /// This comment belongs to the function
+    fn foo() {}
+}
+"#,
+        expect![[r#"
+                *Foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                mod Foo
+                ```
+
+                ---
+
+                Be quick;
+                time is mana
+            "#]],
+    );
+}
+
+#[test]
+fn hover_doc_outer_inner_attribute() {
+    check(
+        r#"
+#[doc = "Be quick;"]
+mod Foo$0 {
+    #![doc = "time is mana"]
+
+    #[doc = "This comment belongs to the function"]
+    fn foo() {}
+}
+"#,
+        expect![[r#"
+                *Foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                mod Foo
+                ```
+
+                ---
+
+                Be quick;
+                time is mana
+            "#]],
+    );
+}
+
+#[test]
+fn hover_doc_block_style_indent_end() {
+    check(
+        r#"
+/**
+    foo
+    ```rust
+    let x = 3;
+    ```
+*/
+fn foo$0() {}
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                fn foo()
+                ```
+
+                ---
+
+                foo
+
+                ```rust
+                let x = 3;
+                ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_comments_dont_highlight_parent() {
+    cov_mark::check!(no_highlight_on_comment_hover);
+    check_hover_no_result(
+        r#"
+fn no_hover() {
+    // no$0hover
+}
+"#,
+    );
+}
+
+#[test]
+fn hover_label() {
+    check(
+        r#"
+fn foo() {
+    'label$0: loop {}
+}
+"#,
+        expect![[r#"
+            *'label*
+
+            ```rust
+            'label
+            ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_lifetime() {
+    check(
+        r#"fn foo<'lifetime>(_: &'lifetime$0 ()) {}"#,
+        expect![[r#"
+            *'lifetime*
+
+            ```rust
+            'lifetime
+            ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_type_param() {
+    check(
+        r#"
+//- minicore: sized
+struct Foo<T>(T);
+trait TraitA {}
+trait TraitB {}
+impl<T: TraitA + TraitB> Foo<T$0> where T: Sized {}
+"#,
+        expect![[r#"
+                *T*
+
+                ```rust
+                T: TraitA + TraitB
+                ```
+            "#]],
+    );
+    check(
+        r#"
+//- minicore: sized
+struct Foo<T>(T);
+impl<T> Foo<T$0> {}
+"#,
+        expect![[r#"
+                *T*
+
+                ```rust
+                T
+                ```
+                "#]],
+    );
+    // lifetimes bounds arent being tracked yet
+    check(
+        r#"
+//- minicore: sized
+struct Foo<T>(T);
+impl<T: 'static> Foo<T$0> {}
+"#,
+        expect![[r#"
+                *T*
+
+                ```rust
+                T
+                ```
+                "#]],
+    );
+}
+
+#[test]
+fn hover_type_param_sized_bounds() {
+    // implicit `: Sized` bound
+    check(
+        r#"
+//- minicore: sized
+trait Trait {}
+struct Foo<T>(T);
+impl<T: Trait> Foo<T$0> {}
+"#,
+        expect![[r#"
+                *T*
+
+                ```rust
+                T: Trait
+                ```
+            "#]],
+    );
+    check(
+        r#"
+//- minicore: sized
+trait Trait {}
+struct Foo<T>(T);
+impl<T: Trait + ?Sized> Foo<T$0> {}
+"#,
+        expect![[r#"
+                *T*
+
+                ```rust
+                T: Trait + ?Sized
+                ```
+            "#]],
+    );
+}
+
+mod type_param_sized_bounds {
+    use super::*;
+
+    #[test]
+    fn single_implicit() {
+        check(
+            r#"
+//- minicore: sized
+fn foo<T$0>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn single_explicit() {
+        check(
+            r#"
+//- minicore: sized
+fn foo<T$0: Sized>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn single_relaxed() {
+        check(
+            r#"
+//- minicore: sized
+fn foo<T$0: ?Sized>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T: ?Sized
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn multiple_implicit() {
+        check(
+            r#"
+//- minicore: sized
+trait Trait {}
+fn foo<T$0: Trait>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T: Trait
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn multiple_explicit() {
+        check(
+            r#"
+//- minicore: sized
+trait Trait {}
+fn foo<T$0: Trait + Sized>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T: Trait
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn multiple_relaxed() {
+        check(
+            r#"
+//- minicore: sized
+trait Trait {}
+fn foo<T$0: Trait + ?Sized>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T: Trait + ?Sized
+                    ```
+                "#]],
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        check(
+            r#"
+//- minicore: sized
+fn foo<T$0: ?Sized + Sized + Sized>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T
+                    ```
+                "#]],
+        );
+        check(
+            r#"
+//- minicore: sized
+trait Trait {}
+fn foo<T$0: Sized + ?Sized + Sized + Trait>() {}
+"#,
+            expect![[r#"
+                    *T*
+
+                    ```rust
+                    T: Trait
+                    ```
+                "#]],
+        );
+    }
+}
+
+#[test]
+fn hover_const_generic_type_alias() {
+    check(
+        r#"
+struct Foo<const LEN: usize>;
+type Fo$0o2 = Foo<2>;
+"#,
+        expect![[r#"
+                *Foo2*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                type Foo2 = Foo<2> // size = 0, align = 1
+                ```
+            "#]],
+    );
+}
+
+#[test]
+fn hover_const_param() {
     check(
         r#"
-//- /lib.rs crate:name-with-dashes
-pub mod wrapper {
-    pub struct Thing { x: u32 }
-
-    impl Thing {
-        pub fn new() -> Thing { Thing { x: 0 } }
-    }
-}
-
-//- /main.rs crate:main deps:name-with-dashes
-fn main() { let foo_test = name_with_dashes::wrapper::Thing::new$0(); }
+struct Foo<const LEN: usize>;
+impl<const LEN: usize> Foo<LEN$0> {}
 "#,
         expect![[r#"
-            *new*
-
-            ```rust
-            name_with_dashes::wrapper::Thing
-            ```
+                *LEN*
 
-            ```rust
-            pub fn new() -> Thing
-            ```
+                ```rust
+                const LEN: usize
+                ```
             "#]],
-    )
+    );
 }
 
 #[test]
-fn hover_field_pat_shorthand_ref_match_ergonomics() {
+fn hover_const_eval_discriminant() {
+    // Don't show hex for <10
     check(
         r#"
-struct S {
-    f: i32,
-}
-
-fn main() {
-    let s = S { f: 0 };
-    let S { f$0 } = &s;
+#[repr(u8)]
+enum E {
+    /// This is a doc
+    A$0 = 1 << 3,
 }
 "#,
         expect![[r#"
-            *f*
+            *A*
 
             ```rust
-            f: &i32
+            test::E
             ```
-            ---
 
             ```rust
-            test::S
+            A = 8
             ```
 
-            ```rust
-            f: i32 // size = 4, align = 4, offset = 0
-            ```
+            ---
+
+            This is a doc
         "#]],
     );
-}
-
-#[test]
-fn const_generic_order() {
+    // Show hex for >10
     check(
         r#"
-struct Foo;
-struct S$0T<const C: usize = 1, T = Foo>(T);
+#[repr(u8)]
+enum E {
+    /// This is a doc
+    A$0 = (1 << 3) + (1 << 2),
+}
 "#,
         expect![[r#"
-            *ST*
+            *A*
 
             ```rust
-            test
+            test::E
             ```
 
             ```rust
-            struct ST<const C: usize, T = Foo>
+            A = 12 (0xC)
             ```
-        "#]],
-    );
-}
-
-#[test]
-fn const_generic_positive_i8_literal() {
-    check(
-        r#"
-struct Const<const N: i8>;
 
-fn main() {
-    let v$0alue = Const::<1>;
-}
-"#,
-        expect![[r#"
-            *value*
+            ---
 
-            ```rust
-            let value: Const<1>
-            ```
+            This is a doc
         "#]],
     );
-}
-
-#[test]
-fn const_generic_zero_i8_literal() {
+    // enums in const eval
     check(
         r#"
-struct Const<const N: i8>;
-
-fn main() {
-    let v$0alue = Const::<0>;
+#[repr(u8)]
+enum E {
+    A = 1,
+    /// This is a doc
+    B$0 = E::A as u8 + 1,
 }
 "#,
         expect![[r#"
-            *value*
+            *B*
 
             ```rust
-            let value: Const<0>
+            test::E
             ```
-        "#]],
-    );
-}
-
-#[test]
-fn const_generic_negative_i8_literal() {
-    check(
-        r#"
-struct Const<const N: i8>;
-
-fn main() {
-    let v$0alue = Const::<-1>;
-}
-"#,
-        expect![[r#"
-            *value*
 
             ```rust
-            let value: Const<-1>
+            B = 2
             ```
+
+            ---
+
+            This is a doc
         "#]],
     );
-}
-
-#[test]
-fn const_generic_bool_literal() {
+    // unspecified variant should increment by one
     check(
         r#"
-struct Const<const F: bool>;
-
-fn main() {
-    let v$0alue = Const::<true>;
+#[repr(u8)]
+enum E {
+    A = 4,
+    /// This is a doc
+    B$0,
 }
 "#,
         expect![[r#"
-            *value*
+            *B*
 
             ```rust
-            let value: Const<true>
+            test::E
             ```
-        "#]],
-    );
-}
-
-#[test]
-fn const_generic_char_literal() {
-    check(
-        r#"
-struct Const<const C: char>;
-
-fn main() {
-    let v$0alue = Const::<'🦀'>;
-}
-"#,
-        expect![[r#"
-            *value*
 
             ```rust
-            let value: Const<'🦀'>
+            B = 5
             ```
+
+            ---
+
+            This is a doc
         "#]],
     );
 }
 
 #[test]
-fn hover_self_param_shows_type() {
+fn hover_const_eval() {
     check(
         r#"
-struct Foo {}
-impl Foo {
-    fn bar(&sel$0f) {}
+trait T {
+    const B: bool = false;
 }
-"#,
-        expect![[r#"
-                *self*
-
-                ```rust
-                self: &Foo
-                ```
-            "#]],
-    );
+impl T for <()> {
+    /// true
+    const B: bool = true;
 }
-
-#[test]
-fn hover_self_param_shows_type_for_arbitrary_self_type() {
-    check(
-        r#"
-struct Arc<T>(T);
-struct Foo {}
-impl Foo {
-    fn bar(sel$0f: Arc<Foo>) {}
+fn main() {
+    <()>::B$0;
 }
 "#,
         expect![[r#"
-                *self*
+        *B*
 
-                ```rust
-                self: Arc<Foo>
-                ```
-            "#]],
+        ```rust
+        test
+        ```
+
+        ```rust
+        const B: bool = true
+        ```
+
+        ---
+
+        true
+    "#]],
     );
-}
 
-#[test]
-fn hover_doc_outer_inner() {
     check(
         r#"
-/// Be quick;
-mod Foo$0 {
-    //! time is mana
+struct A {
+    i: i32
+};
 
-    /// This comment belongs to the function
-    fn foo() {}
+trait T {
+    const AA: A = A {
+        i: 1
+    };
 }
-"#,
-        expect![[r#"
-                *Foo*
-
-                ```rust
-                test
-                ```
-
-                ```rust
-                mod Foo
-                ```
+impl T for i32 {
+    const AA: A = A {
+        i: 2 + 3
+    }
+}
+fn main() {
+    <i32>::AA$0;
+}
+"#,
+        expect![[r#"
+        *AA*
 
-                ---
+        ```rust
+        test
+        ```
 
-                Be quick;
-                time is mana
-            "#]],
+        ```rust
+        const AA: A = A { i: 5 }
+        ```
+    "#]],
     );
-}
 
-#[test]
-fn hover_doc_outer_inner_attribute() {
     check(
         r#"
-#[doc = "Be quick;"]
-mod Foo$0 {
-    #![doc = "time is mana"]
-
-    #[doc = "This comment belongs to the function"]
-    fn foo() {}
+trait T {
+    /// false
+    const B: bool = false;
+}
+impl T for () {
+    /// true
+    const B: bool = true;
+}
+fn main() {
+    T::B$0;
 }
 "#,
         expect![[r#"
-                *Foo*
+            *B*
 
-                ```rust
-                test
-                ```
+            ```rust
+            test
+            ```
 
-                ```rust
-                mod Foo
-                ```
+            ```rust
+            const B: bool = false
+            ```
 
-                ---
+            ---
 
-                Be quick;
-                time is mana
-            "#]],
+            false
+        "#]],
     );
-}
 
-#[test]
-fn hover_doc_block_style_indent_end() {
     check(
         r#"
-/**
-    foo
-    ```rust
-    let x = 3;
-    ```
-*/
-fn foo$0() {}
+trait T {
+    /// false
+    const B: bool = false;
+}
+impl T for () {
+}
+fn main() {
+    <()>::B$0;
+}
 "#,
         expect![[r#"
-                *foo*
-
-                ```rust
-                test
-                ```
+            *B*
 
-                ```rust
-                fn foo()
-                ```
+            ```rust
+            test
+            ```
 
-                ---
+            ```rust
+            const B: bool = false
+            ```
 
-                foo
+            ---
 
-                ```rust
-                let x = 3;
-                ```
-            "#]],
+            false
+        "#]],
     );
-}
 
-#[test]
-fn hover_comments_dont_highlight_parent() {
-    cov_mark::check!(no_highlight_on_comment_hover);
-    check_hover_no_result(
+    check(
         r#"
-fn no_hover() {
-    // no$0hover
+trait T {
+    /// false
+    const B: bool = false;
 }
-"#,
-    );
+impl T for () {
+    /// true
+    const B: bool = true;
 }
-
-#[test]
-fn hover_label() {
-    check(
-        r#"
-fn foo() {
-    'label$0: loop {}
+impl T for i32 {}
+fn main() {
+    <i32>::B$0;
 }
 "#,
         expect![[r#"
-            *'label*
+            *B*
 
             ```rust
-            'label
+            test
             ```
-            "#]],
-    );
-}
-
-#[test]
-fn hover_lifetime() {
-    check(
-        r#"fn foo<'lifetime>(_: &'lifetime$0 ()) {}"#,
-        expect![[r#"
-            *'lifetime*
 
             ```rust
-            'lifetime
+            const B: bool = false
             ```
-            "#]],
+
+            ---
+
+            false
+        "#]],
     );
-}
 
-#[test]
-fn hover_type_param() {
+    // show hex for <10
     check(
         r#"
-//- minicore: sized
-struct Foo<T>(T);
-trait TraitA {}
-trait TraitB {}
-impl<T: TraitA + TraitB> Foo<T$0> where T: Sized {}
+/// This is a doc
+const FOO$0: usize = 1 << 3;
 "#,
         expect![[r#"
-                *T*
+            *FOO*
 
-                ```rust
-                T: TraitA + TraitB
-                ```
-            "#]],
+            ```rust
+            test
+            ```
+
+            ```rust
+            const FOO: usize = 8
+            ```
+
+            ---
+
+            This is a doc
+        "#]],
     );
     check(
         r#"
-//- minicore: sized
-struct Foo<T>(T);
-impl<T> Foo<T$0> {}
+/// This is a doc
+const FOO$0: usize = (1 << 3) + (1 << 2);
 "#,
         expect![[r#"
-                *T*
+            *FOO*
 
-                ```rust
-                T
-                ```
-                "#]],
+            ```rust
+            test
+            ```
+
+            ```rust
+            const FOO: usize = 12 (0xC)
+            ```
+
+            ---
+
+            This is a doc
+        "#]],
     );
-    // lifetimes bounds arent being tracked yet
+    // show original body when const eval fails
     check(
         r#"
-//- minicore: sized
-struct Foo<T>(T);
-impl<T: 'static> Foo<T$0> {}
+/// This is a doc
+const FOO$0: usize = 2 - 3;
 "#,
         expect![[r#"
-                *T*
+            *FOO*
 
-                ```rust
-                T
-                ```
-                "#]],
-    );
-}
+            ```rust
+            test
+            ```
 
-#[test]
-fn hover_type_param_sized_bounds() {
-    // implicit `: Sized` bound
+            ```rust
+            const FOO: usize = 2 - 3
+            ```
+
+            ---
+
+            This is a doc
+        "#]],
+    );
+    // don't show hex for negatives
     check(
         r#"
-//- minicore: sized
-trait Trait {}
-struct Foo<T>(T);
-impl<T: Trait> Foo<T$0> {}
+/// This is a doc
+const FOO$0: i32 = 2 - 3;
 "#,
         expect![[r#"
-                *T*
+            *FOO*
 
-                ```rust
-                T: Trait
-                ```
-            "#]],
+            ```rust
+            test
+            ```
+
+            ```rust
+            const FOO: i32 = -1 (0xFFFFFFFF)
+            ```
+
+            ---
+
+            This is a doc
+        "#]],
     );
     check(
         r#"
-//- minicore: sized
-trait Trait {}
-struct Foo<T>(T);
-impl<T: Trait + ?Sized> Foo<T$0> {}
+/// This is a doc
+const FOO$0: &str = "bar";
 "#,
         expect![[r#"
-                *T*
-
-                ```rust
-                T: Trait + ?Sized
-                ```
-            "#]],
-    );
-}
+            *FOO*
 
-mod type_param_sized_bounds {
-    use super::*;
+            ```rust
+            test
+            ```
 
-    #[test]
-    fn single_implicit() {
-        check(
-            r#"
-//- minicore: sized
-fn foo<T$0>() {}
-"#,
-            expect![[r#"
-                    *T*
+            ```rust
+            const FOO: &str = "bar"
+            ```
 
-                    ```rust
-                    T
-                    ```
-                "#]],
-        );
-    }
+            ---
 
-    #[test]
-    fn single_explicit() {
-        check(
-            r#"
-//- minicore: sized
-fn foo<T$0: Sized>() {}
+            This is a doc
+        "#]],
+    );
+    // show char literal
+    check(
+        r#"
+/// This is a doc
+const FOO$0: char = 'a';
 "#,
-            expect![[r#"
-                    *T*
+        expect![[r#"
+            *FOO*
 
-                    ```rust
-                    T
-                    ```
-                "#]],
-        );
-    }
+            ```rust
+            test
+            ```
 
-    #[test]
-    fn single_relaxed() {
-        check(
-            r#"
-//- minicore: sized
-fn foo<T$0: ?Sized>() {}
-"#,
-            expect![[r#"
-                    *T*
+            ```rust
+            const FOO: char = 'a'
+            ```
 
-                    ```rust
-                    T: ?Sized
-                    ```
-                "#]],
-        );
-    }
+            ---
 
-    #[test]
-    fn multiple_implicit() {
-        check(
-            r#"
-//- minicore: sized
-trait Trait {}
-fn foo<T$0: Trait>() {}
+            This is a doc
+        "#]],
+    );
+    // show escaped char literal
+    check(
+        r#"
+/// This is a doc
+const FOO$0: char = '\x61';
 "#,
-            expect![[r#"
-                    *T*
+        expect![[r#"
+            *FOO*
 
-                    ```rust
-                    T: Trait
-                    ```
-                "#]],
-        );
-    }
+            ```rust
+            test
+            ```
 
-    #[test]
-    fn multiple_explicit() {
-        check(
-            r#"
-//- minicore: sized
-trait Trait {}
-fn foo<T$0: Trait + Sized>() {}
-"#,
-            expect![[r#"
-                    *T*
+            ```rust
+            const FOO: char = 'a'
+            ```
 
-                    ```rust
-                    T: Trait
-                    ```
-                "#]],
-        );
-    }
+            ---
 
-    #[test]
-    fn multiple_relaxed() {
-        check(
-            r#"
-//- minicore: sized
-trait Trait {}
-fn foo<T$0: Trait + ?Sized>() {}
+            This is a doc
+        "#]],
+    );
+    // show byte literal
+    check(
+        r#"
+/// This is a doc
+const FOO$0: u8 = b'a';
 "#,
-            expect![[r#"
-                    *T*
-
-                    ```rust
-                    T: Trait + ?Sized
-                    ```
-                "#]],
-        );
-    }
+        expect![[r#"
+            *FOO*
 
-    #[test]
-    fn mixed() {
-        check(
-            r#"
-//- minicore: sized
-fn foo<T$0: ?Sized + Sized + Sized>() {}
-"#,
-            expect![[r#"
-                    *T*
+            ```rust
+            test
+            ```
 
-                    ```rust
-                    T
-                    ```
-                "#]],
-        );
-        check(
-            r#"
-//- minicore: sized
-trait Trait {}
-fn foo<T$0: Sized + ?Sized + Sized + Trait>() {}
-"#,
-            expect![[r#"
-                    *T*
+            ```rust
+            const FOO: u8 = 97 (0x61)
+            ```
 
-                    ```rust
-                    T: Trait
-                    ```
-                "#]],
-        );
-    }
-}
+            ---
 
-#[test]
-fn hover_const_generic_type_alias() {
+            This is a doc
+        "#]],
+    );
+    // show escaped byte literal
     check(
         r#"
-struct Foo<const LEN: usize>;
-type Fo$0o2 = Foo<2>;
+/// This is a doc
+const FOO$0: u8 = b'\x61';
 "#,
         expect![[r#"
-                *Foo2*
+            *FOO*
 
-                ```rust
-                test
-                ```
+            ```rust
+            test
+            ```
 
-                ```rust
-                type Foo2 = Foo<2> // size = 0, align = 1
-                ```
-            "#]],
-    );
-}
+            ```rust
+            const FOO: u8 = 97 (0x61)
+            ```
 
-#[test]
-fn hover_const_param() {
-    check(
-        r#"
-struct Foo<const LEN: usize>;
-impl<const LEN: usize> Foo<LEN$0> {}
-"#,
-        expect![[r#"
-                *LEN*
+            ---
 
-                ```rust
-                const LEN: usize
-                ```
-            "#]],
+            This is a doc
+        "#]],
     );
-}
-
-#[test]
-fn hover_const_eval_discriminant() {
-    // Don't show hex for <10
+    // show float literal
     check(
         r#"
-#[repr(u8)]
-enum E {
     /// This is a doc
-    A$0 = 1 << 3,
-}
-"#,
+    const FOO$0: f64 = 1.0234;
+    "#,
         expect![[r#"
-            *A*
+            *FOO*
 
             ```rust
-            test::E
+            test
             ```
 
             ```rust
-            A = 8
+            const FOO: f64 = 1.0234
             ```
 
             ---
@@ -3661,24 +4837,21 @@ enum E {
             This is a doc
         "#]],
     );
-    // Show hex for >10
+    //show float typecasted from int
     check(
         r#"
-#[repr(u8)]
-enum E {
-    /// This is a doc
-    A$0 = (1 << 3) + (1 << 2),
-}
+/// This is a doc
+const FOO$0: f32 = 1f32;
 "#,
         expect![[r#"
-            *A*
+            *FOO*
 
             ```rust
-            test::E
+            test
             ```
 
             ```rust
-            A = 12 (0xC)
+            const FOO: f32 = 1.0
             ```
 
             ---
@@ -3686,25 +4859,21 @@ enum E {
             This is a doc
         "#]],
     );
-    // enums in const eval
+    // Don't show `<ref-not-supported>` in const hover
     check(
         r#"
-#[repr(u8)]
-enum E {
-    A = 1,
-    /// This is a doc
-    B$0 = E::A as u8 + 1,
-}
+/// This is a doc
+const FOO$0: &i32 = &2;
 "#,
         expect![[r#"
-            *B*
+            *FOO*
 
             ```rust
-            test::E
+            test
             ```
 
             ```rust
-            B = 2
+            const FOO: &i32 = &2
             ```
 
             ---
@@ -3712,25 +4881,21 @@ enum E {
             This is a doc
         "#]],
     );
-    // unspecified variant should increment by one
+    //show f64 typecasted from float
     check(
         r#"
-#[repr(u8)]
-enum E {
-    A = 4,
-    /// This is a doc
-    B$0,
-}
+/// This is a doc
+const FOO$0: f64 = 1.0f64;
 "#,
         expect![[r#"
-            *B*
+            *FOO*
 
             ```rust
-            test::E
+            test
             ```
 
             ```rust
-            B = 5
+            const FOO: f64 = 1.0
             ```
 
             ---
@@ -3741,793 +4906,852 @@ enum E {
 }
 
 #[test]
-fn hover_const_eval() {
+fn hover_const_eval_in_generic_trait() {
+    // Doesn't compile, but we shouldn't crash.
     check(
         r#"
-trait T {
-    const B: bool = false;
-}
-impl T for <()> {
-    /// true
-    const B: bool = true;
+trait Trait<T> {
+    const FOO: bool = false;
 }
-fn main() {
-    <()>::B$0;
+struct S<T>(T);
+impl<T> Trait<T> for S<T> {
+    const FOO: bool = true;
 }
-"#,
-        expect![[r#"
-        *B*
-
-        ```rust
-        test
-        ```
-
-        ```rust
-        const B: bool = true
-        ```
-
-        ---
-
-        true
-    "#]],
-    );
-
-    check(
-        r#"
-struct A {
-    i: i32
-};
 
-trait T {
-    const AA: A = A {
-        i: 1
-    };
-}
-impl T for i32 {
-    const AA: A = A {
-        i: 2 + 3
-    }
-}
-fn main() {
-    <i32>::AA$0;
+fn test() {
+    S::FOO$0;
 }
 "#,
         expect![[r#"
-        *AA*
+            *FOO*
 
-        ```rust
-        test
-        ```
+            ```rust
+            test
+            ```
 
-        ```rust
-        const AA: A = A { i: 5 }
-        ```
-    "#]],
+            ```rust
+            const FOO: bool = true
+            ```
+        "#]],
     );
+}
 
+#[test]
+fn hover_const_pat() {
     check(
         r#"
-trait T {
-    /// false
-    const B: bool = false;
-}
-impl T for () {
-    /// true
-    const B: bool = true;
-}
-fn main() {
-    T::B$0;
+/// This is a doc
+const FOO: usize = 3;
+fn foo() {
+    match 5 {
+        FOO$0 => (),
+        _ => ()
+    }
 }
 "#,
         expect![[r#"
-            *B*
+            *FOO*
 
             ```rust
             test
             ```
 
             ```rust
-            const B: bool = false
+            const FOO: usize = 3
             ```
 
             ---
 
-            false
+            This is a doc
         "#]],
     );
-
     check(
         r#"
-trait T {
-    /// false
-    const B: bool = false;
-}
-impl T for () {
+enum E {
+    /// This is a doc
+    A = 3,
 }
-fn main() {
-    <()>::B$0;
+fn foo(e: E) {
+    match e {
+        E::A$0 => (),
+        _ => ()
+    }
 }
 "#,
         expect![[r#"
-            *B*
+            *A*
 
             ```rust
-            test
+            test::E
             ```
 
             ```rust
-            const B: bool = false
+            A = 3
             ```
 
             ---
 
-            false
+            This is a doc
         "#]],
     );
+}
 
+#[test]
+fn array_repeat_exp() {
     check(
         r#"
-trait T {
-    /// false
-    const B: bool = false;
-}
-impl T for () {
-    /// true
-    const B: bool = true;
-}
-impl T for i32 {}
 fn main() {
-    <i32>::B$0;
+    let til$0e4 = [0_u32; (4 * 8 * 8) / 32];
 }
-"#,
+        "#,
         expect![[r#"
-            *B*
-
-            ```rust
-            test
-            ```
+            *tile4*
 
             ```rust
-            const B: bool = false
+            let tile4: [u32; 8]
             ```
-
-            ---
-
-            false
-        "#]],
+            "#]],
     );
+}
 
-    // show hex for <10
+#[test]
+fn hover_mod_def() {
     check(
         r#"
-/// This is a doc
-const FOO$0: usize = 1 << 3;
+//- /main.rs
+mod foo$0;
+//- /foo.rs
+//! For the horde!
 "#,
         expect![[r#"
-            *FOO*
+                *foo*
 
-            ```rust
-            test
-            ```
+                ```rust
+                test
+                ```
 
-            ```rust
-            const FOO: usize = 8
-            ```
+                ```rust
+                mod foo
+                ```
 
-            ---
+                ---
 
-            This is a doc
-        "#]],
+                For the horde!
+            "#]],
     );
+}
+
+#[test]
+fn hover_self_in_use() {
     check(
         r#"
-/// This is a doc
-const FOO$0: usize = (1 << 3) + (1 << 2);
+//! This should not appear
+mod foo {
+    /// But this should appear
+    pub mod bar {}
+}
+use foo::bar::{self$0};
 "#,
         expect![[r#"
-            *FOO*
+                *self*
 
-            ```rust
-            test
-            ```
+                ```rust
+                test::foo
+                ```
 
-            ```rust
-            const FOO: usize = 12 (0xC)
-            ```
+                ```rust
+                mod bar
+                ```
 
-            ---
+                ---
+
+                But this should appear
+            "#]],
+    )
+}
 
-            This is a doc
-        "#]],
-    );
-    // show original body when const eval fails
+#[test]
+fn hover_keyword() {
     check(
         r#"
-/// This is a doc
-const FOO$0: usize = 2 - 3;
+//- /main.rs crate:main deps:std
+fn f() { retur$0n; }
+//- /libstd.rs crate:std
+/// Docs for return_keyword
+mod return_keyword {}
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
+                *return*
 
-            ```rust
-            const FOO: usize = 2 - 3
-            ```
+                ```rust
+                return
+                ```
 
-            ---
+                ---
 
-            This is a doc
-        "#]],
+                Docs for return_keyword
+            "#]],
     );
-    // don't show hex for negatives
+}
+
+#[test]
+fn hover_keyword_doc() {
     check(
         r#"
-/// This is a doc
-const FOO$0: i32 = 2 - 3;
+//- /main.rs crate:main deps:std
+fn foo() {
+    let bar = mov$0e || {};
+}
+//- /libstd.rs crate:std
+#[doc(keyword = "move")]
+/// [closure]
+/// [closures][closure]
+/// [threads]
+/// <https://doc.rust-lang.org/nightly/book/ch13-01-closures.html>
+///
+/// [closure]: ../book/ch13-01-closures.html
+/// [threads]: ../book/ch16-01-threads.html#using-move-closures-with-threads
+mod move_keyword {}
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
+            *move*
 
             ```rust
-            const FOO: i32 = -1 (0xFFFFFFFF)
+            move
             ```
 
             ---
 
-            This is a doc
+            [closure](https://doc.rust-lang.org/stable/book/ch13-01-closures.html)
+            [closures](https://doc.rust-lang.org/stable/book/ch13-01-closures.html)
+            [threads](https://doc.rust-lang.org/stable/book/ch16-01-threads.html#using-move-closures-with-threads)
+            <https://doc.rust-lang.org/nightly/book/ch13-01-closures.html>
         "#]],
     );
-    check(
+}
+
+#[test]
+fn hover_name_link() {
+    check_hover_name_link(
         r#"
-/// This is a doc
-const FOO$0: &str = "bar";
+//- /main.rs crate:main deps:dep
+fn main() { dep::gateway::Shard$0::start(); }
+//- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn start() {}
+    }
+}
 "#,
         expect![[r#"
-            *FOO*
+            [`Shard`](https://docs.rs/dep/0.1.0/dep/gateway/struct.Shard.html)
 
             ```rust
-            test
+            dep::gateway
             ```
 
             ```rust
-            const FOO: &str = "bar"
-            ```
-
-            ---
-
-            This is a doc
-        "#]],
+            pub struct Shard // size = 0, align = 1
+            ```"#]],
     );
-    // show char literal
-    check(
+    check_hover_name_link(
         r#"
-/// This is a doc
-const FOO$0: char = 'a';
+//- /main.rs crate:main deps:dep
+fn main() { dep::gateway::Shard::sta$0rt(); }
+//- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep
+pub mod gateway {
+    pub struct Shard;
+    impl Shard {
+        pub fn start() {}
+    }
+}
 "#,
         expect![[r#"
-            *FOO*
+            [`start`](https://docs.rs/dep/0.1.0/dep/gateway/struct.Shard.html#method.start)
 
             ```rust
-            test
+            dep::gateway::Shard
             ```
 
             ```rust
-            const FOO: char = 'a'
-            ```
-
-            ---
-
-            This is a doc
-        "#]],
+            pub fn start()
+            ```"#]],
     );
-    // show escaped char literal
-    check(
+    check_hover_name_link(
         r#"
-/// This is a doc
-const FOO$0: char = '\x61';
+fn main() { let x$0 = 0; }
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
 
             ```rust
-            const FOO: char = 'a'
-            ```
-
-            ---
-
-            This is a doc
-        "#]],
+            let x: i32
+            ```"#]],
     );
-    // show byte literal
+}
+
+#[test]
+fn hover_keyword_without_std() {
     check(
         r#"
-/// This is a doc
-const FOO$0: u8 = b'a';
+fn f() { retur$0n; }
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
-
+            *return*
             ```rust
-            const FOO: u8 = 97 (0x61)
+            return
             ```
-
-            ---
-
-            This is a doc
         "#]],
     );
-    // show escaped byte literal
-    check(
+}
+
+#[test]
+fn hover_keyword_doc_truncated() {
+    check_hover_max_doc_length(
         r#"
-/// This is a doc
-const FOO$0: u8 = b'\x61';
+//- /main.rs crate:main deps:std
+fn foo() {
+    let bar = mov$0e || {};
+}
+//- /libstd.rs crate:std
+#[doc(keyword = "move")]
+/// Capture a [closure]'s environment by value.
+///
+/// `move` converts any variables captured by reference or mutable reference to variables
+/// captured by value, see [threads].
+///
+/// ```rust
+/// let data = vec![1, 2, 3];
+///
+/// let closure = move || println!("captured {data:?} by value");
+/// ```
+///
+/// [closure]: ../book/ch13-01-closures.html
+/// [threads]: ../book/ch16-01-threads.html#using-move-closures-with-threads
+mod move_keyword {}
 "#,
+        200,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
 
             ```rust
-            const FOO: u8 = 97 (0x61)
+            move
             ```
 
             ---
 
-            This is a doc
-        "#]],
+            Capture a [closure](https://doc.rust-lang.org/stable/book/ch13-01-closures.html)'s environment by value.
+
+            `move` converts any variables captured by reference or mutable reference to variables
+            captured by value, see [threads](https://doc.rust-lang.org/stable/book/ch16-01-threads.html#using-move-closures-with-threads).
+
+            *(documentation truncated)*"#]],
     );
-    // show float literal
-    check(
+}
+
+#[test]
+fn hover_doc_length_counts_chars() {
+    check_hover_max_doc_length(
         r#"
-    /// This is a doc
-    const FOO$0: f64 = 1.0234;
-    "#,
+/// Grüße, schöne Grüße.
+///
+/// Äöü äöü äöü.
+pub fn fo$0o() {}
+"#,
+        40,
         expect![[r#"
-            *FOO*
 
             ```rust
             test
             ```
 
             ```rust
-            const FOO: f64 = 1.0234
+            pub fn foo()
             ```
 
             ---
 
-            This is a doc
-        "#]],
+            Grüße, schöne Grüße.
+
+            Äöü äöü äöü."#]],
     );
-    //show float typecasted from int
-    check(
+    check_hover_max_doc_length(
         r#"
-/// This is a doc
-const FOO$0: f32 = 1f32;
+/// Grüße, schöne Grüße.
+///
+/// Äöü äöü äöü.
+pub fn fo$0o() {}
 "#,
+        30,
         expect![[r#"
-            *FOO*
 
             ```rust
             test
             ```
 
             ```rust
-            const FOO: f32 = 1.0
+            pub fn foo()
             ```
 
             ---
 
-            This is a doc
-        "#]],
+            Grüße, schöne Grüße.
+
+            *(documentation truncated)*"#]],
     );
-    // Don't show `<ref-not-supported>` in const hover
+}
+
+#[test]
+fn hover_keyword_as_primitive() {
     check(
         r#"
-/// This is a doc
-const FOO$0: &i32 = &2;
+//- /main.rs crate:main deps:std
+type F = f$0n(i32) -> i32;
+//- /libstd.rs crate:std
+/// Docs for prim_fn
+mod prim_fn {}
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
+                *fn*
 
-            ```rust
-            const FOO: &i32 = &2
-            ```
+                ```rust
+                fn
+                ```
 
-            ---
+                ---
 
-            This is a doc
-        "#]],
+                Docs for prim_fn
+            "#]],
     );
-    //show f64 typecasted from float
+}
+
+#[test]
+fn hover_builtin() {
     check(
         r#"
-/// This is a doc
-const FOO$0: f64 = 1.0f64;
+//- /main.rs crate:main deps:std
+const _: &str$0 = ""; }
+
+//- /libstd.rs crate:std
+/// Docs for prim_str
+/// [`foo`](../std/keyword.foo.html)
+mod prim_str {}
 "#,
         expect![[r#"
-            *FOO*
-
-            ```rust
-            test
-            ```
+                *str*
 
-            ```rust
-            const FOO: f64 = 1.0
-            ```
+                ```rust
+                str
+                ```
 
-            ---
+                ---
 
-            This is a doc
-        "#]],
+                Docs for prim_str
+                [`foo`](https://doc.rust-lang.org/nightly/std/keyword.foo.html)
+            "#]],
     );
 }
 
 #[test]
-fn hover_const_eval_in_generic_trait() {
-    // Doesn't compile, but we shouldn't crash.
+fn hover_builtin_doc_links() {
     check(
         r#"
-trait Trait<T> {
-    const FOO: bool = false;
+//- /main.rs crate:main deps:std
+const _: &str$0 = "";
+//- /libcore.rs crate:core
+#![rustc_coherence_is_core]
+pub mod str {
+    pub trait FromStr {}
 }
-struct S<T>(T);
-impl<T> Trait<T> for S<T> {
-    const FOO: bool = true;
+impl str {
+    pub fn len(&self) -> usize { 0 }
 }
+//- /libstd.rs crate:std deps:core
+pub use core::str;
 
-fn test() {
-    S::FOO$0;
-}
+#[doc(primitive = "str")]
+/// String slices, [`len`](str::len) gives their length and [`FromStr`](crate::str::FromStr)
+/// parses them.
+mod prim_str {}
 "#,
         expect![[r#"
-            *FOO*
+            *str*
 
             ```rust
-            test
+            str
             ```
 
-            ```rust
-            const FOO: bool = true
-            ```
+            ---
+
+            String slices, [`len`](https://doc.rust-lang.org/stable/core/primitive.str.html#method.len) gives their length and [`FromStr`](https://doc.rust-lang.org/stable/core/str/trait.FromStr.html)
+            parses them.
         "#]],
     );
 }
 
 #[test]
-fn hover_const_pat() {
+fn hover_deref_supplied_method_doc_links() {
     check(
         r#"
-/// This is a doc
-const FOO: usize = 3;
-fn foo() {
-    match 5 {
-        FOO$0 => (),
-        _ => ()
+//- /main.rs crate:main deps:alloc
+fn f(v: alloc::vec::Vec<u8>) {
+    v.it$0er();
+}
+//- /libcore.rs crate:core
+#![rustc_coherence_is_core]
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        #[lang = "deref_target"]
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
+}
+pub mod slice {
+    pub struct Iter<'a, T>(&'a T);
+}
+impl<T> [T] {
+    /// Returns an [`Iter`](slice::Iter) over the slice, see also [`len`](slice::len) and
+    /// [`Self::len`].
+    pub fn iter(&self) -> slice::Iter<'_, T> { loop {} }
+    pub fn len(&self) -> usize { 0 }
+}
+//- /liballoc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> core::ops::Deref for Vec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] { loop {} }
     }
 }
 "#,
         expect![[r#"
-            *FOO*
+            *iter*
 
             ```rust
-            test
+            core
             ```
 
             ```rust
-            const FOO: usize = 3
+            pub fn iter(&self) -> slice::Iter<'_, T>
             ```
 
             ---
 
-            This is a doc
+            Returns an [`Iter`](https://doc.rust-lang.org/stable/core/slice/struct.Iter.html) over the slice, see also [`len`](https://doc.rust-lang.org/stable/core/primitive.slice.html#method.len) and
+            [`Self::len`](https://doc.rust-lang.org/stable/core/primitive.slice.html#method.len).
         "#]],
     );
+}
+
+#[test]
+fn hover_deref_supplied_method_doc_links_of_other_crate() {
     check(
         r#"
-enum E {
-    /// This is a doc
-    A = 3,
+//- /main.rs crate:main deps:alloc
+fn f(v: alloc::vec::Vec<u8>) {
+    v.to_v$0ec();
+}
+//- /libcore.rs crate:core
+#![rustc_coherence_is_core]
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        #[lang = "deref_target"]
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
 }
-fn foo(e: E) {
-    match e {
-        E::A$0 => (),
-        _ => ()
+impl<T> [T] {
+    pub fn len(&self) -> usize { 0 }
+}
+//- /liballoc.rs crate:alloc deps:core
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> core::ops::Deref for Vec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] { loop {} }
+    }
+}
+pub mod slice {
+    impl<T> [T] {
+        /// Copies the slice into a [`Vec`](crate::vec::Vec) of [`Self::len`] elements.
+        #[rustc_allow_incoherent_impl]
+        pub fn to_vec(&self) -> crate::vec::Vec<T> { loop {} }
     }
 }
 "#,
         expect![[r#"
-            *A*
+            *to_vec*
 
             ```rust
-            test::E
+            alloc::slice
             ```
 
             ```rust
-            A = 3
+            pub fn to_vec(&self) -> crate::vec::Vec<T>
             ```
 
             ---
 
-            This is a doc
+            Copies the slice into a [`Vec`](https://doc.rust-lang.org/stable/alloc/vec/struct.Vec.html) of [`Self::len`](https://doc.rust-lang.org/stable/core/primitive.slice.html#method.len) elements.
         "#]],
     );
 }
 
 #[test]
-fn array_repeat_exp() {
+fn hover_macro_expanded_function() {
     check(
         r#"
-fn main() {
-    let til$0e4 = [0_u32; (4 * 8 * 8) / 32];
+struct S<'a, T>(&'a T);
+trait Clone {}
+macro_rules! foo {
+    () => {
+        fn bar<'t, T: Clone + 't>(s: &mut S<'t, T>, t: u32) -> *mut u32 where
+            't: 't + 't,
+            for<'a> T: Clone + 'a
+        { 0 as _ }
+    };
 }
-        "#,
-        expect![[r#"
-            *tile4*
 
-            ```rust
-            let tile4: [u32; 8]
-            ```
-            "#]],
-    );
-}
+foo!();
 
-#[test]
-fn hover_mod_def() {
-    check(
-        r#"
-//- /main.rs
-mod foo$0;
-//- /foo.rs
-//! For the horde!
+fn main() {
+    bar$0;
+}
 "#,
         expect![[r#"
-                *foo*
+                *bar*
 
                 ```rust
                 test
                 ```
 
                 ```rust
-                mod foo
+                fn bar<'t, T>(s: &mut S<'t, T>, t: u32) -> *mut u32
+                where
+                    T: Clone + 't,
+                    't: 't + 't,
+                    for<'a> T: Clone + 'a,
                 ```
-
-                ---
-
-                For the horde!
             "#]],
-    );
+    )
 }
 
 #[test]
-fn hover_self_in_use() {
+fn hover_intra_doc_links() {
     check(
         r#"
-//! This should not appear
-mod foo {
-    /// But this should appear
-    pub mod bar {}
+pub mod theitem {
+    /// This is the item. Cool!
+    pub struct TheItem;
+}
+
+/// Gives you a [`TheItem$0`].
+///
+/// [`TheItem`]: theitem::TheItem
+pub fn gimme() -> theitem::TheItem {
+    theitem::TheItem
 }
-use foo::bar::{self$0};
 "#,
         expect![[r#"
-                *self*
+            *[`TheItem`]*
 
-                ```rust
-                test::foo
-                ```
+            ```rust
+            test::theitem
+            ```
 
-                ```rust
-                mod bar
-                ```
+            ```rust
+            pub struct TheItem // size = 0, align = 1
+            ```
 
-                ---
+            ---
 
-                But this should appear
-            "#]],
-    )
+            This is the item. Cool!
+        "#]],
+    );
 }
 
 #[test]
-fn hover_keyword() {
+fn hover_intra_doc_link_into_dependency() {
     check(
         r#"
-//- /main.rs crate:main deps:std
-fn f() { retur$0n; }
-//- /libstd.rs crate:std
-/// Docs for return_keyword
-mod return_keyword {}
+//- /main.rs crate:main deps:dep
+/// Opens a [`dep::Shard$0`].
+pub fn connect() {}
+//- /dep.rs crate:dep
+/// A shard of a [`Gateway`].
+pub struct Shard;
+pub struct Gateway;
 "#,
         expect![[r#"
-                *return*
+            *[`dep::Shard`]*
 
-                ```rust
-                return
-                ```
+            ```rust
+            dep
+            ```
 
-                ---
+            ```rust
+            pub struct Shard // size = 0, align = 1
+            ```
 
-                Docs for return_keyword
-            "#]],
+            ---
+
+            A shard of a [`Gateway`](https://docs.rs/dep/*/dep/struct.Gateway.html).
+        "#]],
     );
 }
 
 #[test]
-fn hover_keyword_doc() {
+fn hover_intra_doc_links_between_test_items_of_dependency() {
     check(
         r#"
-//- /main.rs crate:main deps:std
-fn foo() {
-    let bar = mov$0e || {};
+//- /main.rs crate:main deps:dep
+fn f(_: dep::tests::Test$0Shard) {}
+//- /dep.rs crate:dep cfg:test
+#[cfg(test)]
+pub mod tests {
+    /// A shard connected to a [`TestGateway`].
+    pub struct TestShard;
+    pub struct TestGateway;
 }
-//- /libstd.rs crate:std
-#[doc(keyword = "move")]
-/// [closure]
-/// [closures][closure]
-/// [threads]
-/// <https://doc.rust-lang.org/nightly/book/ch13-01-closures.html>
-///
-/// [closure]: ../book/ch13-01-closures.html
-/// [threads]: ../book/ch16-01-threads.html#using-move-closures-with-threads
-mod move_keyword {}
 "#,
         expect![[r#"
-            *move*
+            *TestShard*
 
             ```rust
-            move
+            dep::tests
+            ```
+
+            ```rust
+            pub struct TestShard // size = 0, align = 1
             ```
 
             ---
 
-            [closure](https://doc.rust-lang.org/stable/book/ch13-01-closures.html)
-            [closures](https://doc.rust-lang.org/stable/book/ch13-01-closures.html)
-            [threads](https://doc.rust-lang.org/stable/book/ch16-01-threads.html#using-move-closures-with-threads)
-            <https://doc.rust-lang.org/nightly/book/ch13-01-closures.html>
+            A shard connected to a [`TestGateway`](https://docs.rs/dep/*/dep/tests/struct.TestGateway.html).
         "#]],
     );
 }
 
 #[test]
-fn hover_keyword_as_primitive() {
+fn hover_doc_include_str() {
     check(
         r#"
-//- /main.rs crate:main deps:std
-type F = f$0n(i32) -> i32;
-//- /libstd.rs crate:std
-/// Docs for prim_fn
-mod prim_fn {}
+//- /main.rs crate:main deps:dep
+use dep$0;
+//- /dep/src/lib.rs crate:dep
+//! Crate summary.
+#![doc = include_str!("../README.md")]
+pub struct Gateway;
+//- /dep/README.md
+# Dep
+
+Connects through a [`Gateway`], see the [guide](docs/guide.md).
+
+![logo](assets/logo.png)
 "#,
         expect![[r#"
-                *fn*
+            *dep*
 
-                ```rust
-                fn
-                ```
+            ```rust
+            extern crate dep
+            ```
 
-                ---
+            ---
 
-                Docs for prim_fn
-            "#]],
+            Crate summary.
+
+            # Dep
+
+            Connects through a [`Gateway`](https://docs.rs/dep/*/dep/struct.Gateway.html), see the [guide](docs/guide.md).
+
+            ![logo](assets/logo.png)
+        "#]],
     );
 }
 
 #[test]
-fn hover_builtin() {
+fn hover_doc_include_str_does_not_expand_included_includes() {
     check(
         r#"
-//- /main.rs crate:main deps:std
-const _: &str$0 = ""; }
-
-//- /libstd.rs crate:std
-/// Docs for prim_str
-/// [`foo`](../std/keyword.foo.html)
-mod prim_str {}
+//- /main.rs crate:main deps:dep
+use dep$0;
+//- /dep/src/lib.rs crate:dep
+#![doc = include_str!("../README.md")]
+#![doc = include_str!("../a.md")]
+//- /dep/README.md
+Includes itself with `#![doc = include_str!("README.md")]`.
+//- /dep/a.md
+A, including [b](b.md) with `#![doc = include_str!("b.md")]`.
+//- /dep/b.md
+B, including [a](a.md) with `#![doc = include_str!("a.md")]`.
 "#,
         expect![[r#"
-                *str*
+            *dep*
 
-                ```rust
-                str
-                ```
+            ```rust
+            extern crate dep
+            ```
 
-                ---
+            ---
 
-                Docs for prim_str
-                [`foo`](https://doc.rust-lang.org/nightly/std/keyword.foo.html)
-            "#]],
+            Includes itself with `#![doc = include_str!("README.md")]`.
+            A, including [b](b.md) with `#![doc = include_str!("b.md")]`.
+        "#]],
     );
 }
 
-#[test]
-fn hover_macro_expanded_function() {
-    check(
-        r#"
-struct S<'a, T>(&'a T);
-trait Clone {}
-macro_rules! foo {
-    () => {
-        fn bar<'t, T: Clone + 't>(s: &mut S<'t, T>, t: u32) -> *mut u32 where
-            't: 't + 't,
-            for<'a> T: Clone + 'a
-        { 0 as _ }
-    };
-}
-
-foo!();
-
-fn main() {
-    bar$0;
-}
-"#,
+#[test]
+fn hover_doc_include_str_of_huge_file() {
+    let readme = "Lorem ipsum dolor sit amet.\n".repeat(50_000);
+    check(
+        &format!(
+            r#"
+//- /main.rs crate:main deps:dep
+use dep$0;
+//- /dep/src/lib.rs crate:dep
+//! Crate summary.
+#![doc = include_str!("../README.md")]
+//- /dep/README.md
+{readme}"#
+        ),
         expect![[r#"
-                *bar*
+            *dep*
 
-                ```rust
-                test
-                ```
+            ```rust
+            extern crate dep
+            ```
 
-                ```rust
-                fn bar<'t, T>(s: &mut S<'t, T>, t: u32) -> *mut u32
-                where
-                    T: Clone + 't,
-                    't: 't + 't,
-                    for<'a> T: Clone + 'a,
-                ```
-            "#]],
-    )
+            ---
+
+            Crate summary.
+            *The included file `../README.md` is too large to show.*
+        "#]],
+    );
 }
 
 #[test]
-fn hover_intra_doc_links() {
+fn hover_doc_include_str_missing_file() {
     check(
         r#"
-pub mod theitem {
-    /// This is the item. Cool!
-    pub struct TheItem;
-}
-
-/// Gives you a [`TheItem$0`].
-///
-/// [`TheItem`]: theitem::TheItem
-pub fn gimme() -> theitem::TheItem {
-    theitem::TheItem
-}
+/// Summary.
+#[doc = include_str!("missing.md")]
+pub struct Foo$0;
 "#,
         expect![[r#"
-            *[`TheItem`]*
+            *Foo*
 
             ```rust
-            test::theitem
+            test
             ```
 
             ```rust
-            pub struct TheItem // size = 0, align = 1
+            pub struct Foo // size = 0, align = 1
             ```
 
             ---
 
-            This is the item. Cool!
+            Summary.
         "#]],
     );
 }
@@ -4613,1382 +5837,2295 @@ trait A {
                 ```
 
                 ```rust
-                type Assoc
+                type Assoc
+                ```
+            "#]],
+    );
+    check(
+        r#"
+trait A where
+    Self::Assoc$0: ,
+{
+    type Assoc;
+}"#,
+        expect![[r#"
+                *Assoc*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                type Assoc
+                ```
+            "#]],
+    );
+}
+
+#[test]
+fn string_shadowed_with_inner_items() {
+    check(
+        r#"
+//- /main.rs crate:main deps:alloc
+
+/// Custom `String` type.
+struct String;
+
+fn f() {
+    let _: String$0;
+
+    fn inner() {}
+}
+
+//- /alloc.rs crate:alloc
+#[prelude_import]
+pub use string::*;
+
+mod string {
+    /// This is `alloc::String`.
+    pub struct String;
+}
+"#,
+        expect![[r#"
+            *String*
+
+            ```rust
+            main
+            ```
+
+            ```rust
+            struct String // size = 0, align = 1
+            ```
+
+            ---
+
+            Custom `String` type.
+        "#]],
+    )
+}
+
+#[test]
+fn function_doesnt_shadow_crate_in_use_tree() {
+    check(
+        r#"
+//- /main.rs crate:main deps:foo
+use foo$0::{foo};
+
+//- /foo.rs crate:foo
+pub fn foo() {}
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                extern crate foo
+                ```
+            "#]],
+    )
+}
+
+#[test]
+fn hover_feature() {
+    check(
+        r#"#![feature(box_syntax$0)]"#,
+        expect![[r##"
+                *box_syntax*
+                ```
+                box_syntax
+                ```
+                ___
+
+                # `box_syntax`
+
+                The tracking issue for this feature is: [#49733]
+
+                [#49733]: https://github.com/rust-lang/rust/issues/49733
+
+                See also [`box_patterns`](box-patterns.md)
+
+                ------------------------
+
+                Currently the only stable way to create a `Box` is via the `Box::new` method.
+                Also it is not possible in stable Rust to destructure a `Box` in a match
+                pattern. The unstable `box` keyword can be used to create a `Box`. An example
+                usage would be:
+
+                ```rust
+                #![feature(box_syntax)]
+
+                fn main() {
+                    let b = box 5;
+                }
+                ```
+
+            "##]],
+    )
+}
+
+#[test]
+fn hover_lint() {
+    check(
+        r#"#![allow(arithmetic_overflow$0)]"#,
+        expect![[r#"
+                *arithmetic_overflow*
+                ```
+                arithmetic_overflow
+                ```
+                ___
+
+                arithmetic operation overflows
+            "#]],
+    )
+}
+
+#[test]
+fn hover_clippy_lint() {
+    check(
+        r#"#![allow(clippy::almost_swapped$0)]"#,
+        expect![[r#"
+                *almost_swapped*
+                ```
+                clippy::almost_swapped
+                ```
+                ___
+
+                Checks for `foo = bar; bar = foo` sequences.
+            "#]],
+    )
+}
+
+#[test]
+fn hover_attr_path_qualifier() {
+    check(
+        r#"
+//- /foo.rs crate:foo
+
+//- /lib.rs crate:main.rs deps:foo
+#[fo$0o::bar()]
+struct Foo;
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                extern crate foo
+                ```
+            "#]],
+    )
+}
+
+#[test]
+fn hover_rename() {
+    check(
+        r#"
+use self as foo$0;
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                extern crate test
+                ```
+            "#]],
+    );
+    check(
+        r#"
+mod bar {}
+use bar::{self as foo$0};
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                mod bar
+                ```
+            "#]],
+    );
+    check(
+        r#"
+mod bar {
+    use super as foo$0;
+}
+"#,
+        expect![[r#"
+                *foo*
+
+                ```rust
+                extern crate test
                 ```
             "#]],
     );
     check(
         r#"
-trait A where
-    Self::Assoc$0: ,
-{
-    type Assoc;
-}"#,
+use crate as foo$0;
+"#,
         expect![[r#"
-                *Assoc*
-
-                ```rust
-                test
-                ```
+                *foo*
 
                 ```rust
-                type Assoc
+                extern crate test
                 ```
             "#]],
     );
 }
 
 #[test]
-fn string_shadowed_with_inner_items() {
+fn hover_attribute_in_macro() {
     check(
         r#"
-//- /main.rs crate:main deps:alloc
-
-/// Custom `String` type.
-struct String;
+//- minicore:derive
+macro_rules! identity {
+    ($struct:item) => {
+        $struct
+    };
+}
+#[rustc_builtin_macro]
+pub macro Copy {}
+identity!{
+    #[derive(Copy$0)]
+    struct Foo;
+}
+"#,
+        expect![[r#"
+            *Copy*
 
-fn f() {
-    let _: String$0;
+            ```rust
+            test
+            ```
 
-    fn inner() {}
+            ```rust
+            macro Copy
+            ```
+        "#]],
+    );
 }
 
-//- /alloc.rs crate:alloc
-#[prelude_import]
-pub use string::*;
+#[test]
+fn hover_derive_input() {
+    check(
+        r#"
+//- minicore:derive
+#[rustc_builtin_macro]
+pub macro Copy {}
+#[derive(Copy$0)]
+struct Foo;
+"#,
+        expect![[r#"
+            *Copy*
 
-mod string {
-    /// This is `alloc::String`.
-    pub struct String;
+            ```rust
+            test
+            ```
+
+            ```rust
+            macro Copy
+            ```
+        "#]],
+    );
+    check(
+        r#"
+//- minicore:derive
+mod foo {
+    #[rustc_builtin_macro]
+    pub macro Copy {}
 }
+#[derive(foo::Copy$0)]
+struct Foo;
 "#,
         expect![[r#"
-            *String*
+            *Copy*
 
             ```rust
-            main
+            test::foo
             ```
 
             ```rust
-            struct String // size = 0, align = 1
+            macro Copy
             ```
+        "#]],
+    );
+}
 
-            ---
+#[test]
+fn hover_range_math() {
+    check_hover_range(
+        r#"
+fn f() { let expr = $01 + 2 * 3$0 }
+"#,
+        expect![[r#"
+            ```rust
+            i32
+            ```"#]],
+    );
 
-            Custom `String` type.
-        "#]],
-    )
+    check_hover_range(
+        r#"
+fn f() { let expr = 1 $0+ 2 * $03 }
+"#,
+        expect![[r#"
+            ```rust
+            i32
+            ```"#]],
+    );
+
+    check_hover_range(
+        r#"
+fn f() { let expr = 1 + $02 * 3$0 }
+"#,
+        expect![[r#"
+            ```rust
+            i32
+            ```"#]],
+    );
 }
 
 #[test]
-fn function_doesnt_shadow_crate_in_use_tree() {
-    check(
+fn hover_range_arrays() {
+    check_hover_range(
         r#"
-//- /main.rs crate:main deps:foo
-use foo$0::{foo};
+fn f() { let expr = $0[1, 2, 3, 4]$0 }
+"#,
+        expect![[r#"
+            ```rust
+            [i32; 4]
+            ```"#]],
+    );
 
-//- /foo.rs crate:foo
-pub fn foo() {}
+    check_hover_range(
+        r#"
+fn f() { let expr = [1, 2, $03, 4]$0 }
 "#,
         expect![[r#"
-                *foo*
+            ```rust
+            [i32; 4]
+            ```"#]],
+    );
 
-                ```rust
-                extern crate foo
-                ```
-            "#]],
-    )
+    check_hover_range(
+        r#"
+fn f() { let expr = [1, 2, $03$0, 4] }
+"#,
+        expect![[r#"
+            ```rust
+            i32
+            ```"#]],
+    );
 }
 
 #[test]
-fn hover_feature() {
-    check(
-        r#"#![feature(box_syntax$0)]"#,
-        expect![[r##"
-                *box_syntax*
-                ```
-                box_syntax
-                ```
-                ___
+fn hover_range_functions() {
+    check_hover_range(
+        r#"
+fn f<T>(a: &[T]) { }
+fn b() { $0f$0(&[1, 2, 3, 4, 5]); }
+"#,
+        expect![[r#"
+            ```rust
+            fn f<i32>(&[i32])
+            ```"#]],
+    );
 
-                # `box_syntax`
+    check_hover_range(
+        r#"
+fn f<T>(a: &[T]) { }
+fn b() { f($0&[1, 2, 3, 4, 5]$0); }
+"#,
+        expect![[r#"
+            ```rust
+            &[i32; 5]
+            ```"#]],
+    );
+}
 
-                The tracking issue for this feature is: [#49733]
+#[test]
+fn hover_range_shows_nothing_when_invalid() {
+    check_hover_range_no_results(
+        r#"
+fn f<T>(a: &[T]) { }
+fn b()$0 { f(&[1, 2, 3, 4, 5]); }$0
+"#,
+    );
 
-                [#49733]: https://github.com/rust-lang/rust/issues/49733
+    check_hover_range_no_results(
+        r#"
+fn f<T>$0(a: &[T]) { }
+fn b() { f(&[1, 2, 3,$0 4, 5]); }
+"#,
+    );
 
-                See also [`box_patterns`](box-patterns.md)
+    check_hover_range_no_results(
+        r#"
+fn $0f() { let expr = [1, 2, 3, 4]$0 }
+"#,
+    );
+}
 
-                ------------------------
+#[test]
+fn hover_range_shows_unit_for_statements() {
+    check_hover_range(
+        r#"
+fn f<T>(a: &[T]) { }
+fn b() { $0f(&[1, 2, 3, 4, 5]); }$0
+"#,
+        expect![[r#"
+            ```rust
+            ()
+            ```"#]],
+    );
 
-                Currently the only stable way to create a `Box` is via the `Box::new` method.
-                Also it is not possible in stable Rust to destructure a `Box` in a match
-                pattern. The unstable `box` keyword can be used to create a `Box`. An example
-                usage would be:
+    check_hover_range(
+        r#"
+fn f() { let expr$0 = $0[1, 2, 3, 4] }
+"#,
+        expect![[r#"
+            ```rust
+            ()
+            ```"#]],
+    );
+}
 
+#[test]
+fn hover_range_for_pat() {
+    check_hover_range(
+        r#"
+fn foo() {
+    let $0x$0 = 0;
+}
+"#,
+        expect![[r#"
                 ```rust
-                #![feature(box_syntax)]
-
-                fn main() {
-                    let b = box 5;
-                }
-                ```
+                i32
+                ```"#]],
+    );
 
-            "##]],
-    )
+    check_hover_range(
+        r#"
+fn foo() {
+    let $0x$0 = "";
+}
+"#,
+        expect![[r#"
+                ```rust
+                &str
+                ```"#]],
+    );
 }
 
 #[test]
-fn hover_lint() {
-    check(
-        r#"#![allow(arithmetic_overflow$0)]"#,
+fn hover_range_shows_coercions_if_applicable_expr() {
+    check_hover_range(
+        r#"
+fn foo() {
+    let x: &u32 = $0&&&&&0$0;
+}
+"#,
         expect![[r#"
-                *arithmetic_overflow*
+                ```text
+                Type:       &&&&&u32
+                Coerced to:     &u32
                 ```
-                arithmetic_overflow
+            "#]],
+    );
+    check_hover_range(
+        r#"
+fn foo() {
+    let x: *const u32 = $0&0$0;
+}
+"#,
+        expect![[r#"
+                ```text
+                Type:             &u32
+                Coerced to: *const u32
                 ```
-                ___
-
-                arithmetic operation overflows
             "#]],
-    )
+    );
 }
 
 #[test]
-fn hover_clippy_lint() {
-    check(
-        r#"#![allow(clippy::almost_swapped$0)]"#,
+fn hover_range_shows_type_actions() {
+    check_actions(
+        r#"
+struct Foo;
+fn foo() {
+    let x: &Foo = $0&&&&&Foo$0;
+}
+"#,
         expect![[r#"
-                *almost_swapped*
-                ```
-                clippy::almost_swapped
-                ```
-                ___
-
-                Checks for `foo = bar; bar = foo` sequences.
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "test::Foo",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 0..11,
+                                    focus_range: 7..10,
+                                    name: "Foo",
+                                    kind: Struct,
+                                    description: "struct Foo",
+                                },
+                            },
+                        ],
+                    ),
+                ]
             "#]],
-    )
+    );
 }
 
 #[test]
-fn hover_attr_path_qualifier() {
-    check(
+fn hover_try_expr_res() {
+    check_hover_range(
         r#"
-//- /foo.rs crate:foo
+//- minicore: try, from, result
+struct FooError;
 
-//- /lib.rs crate:main.rs deps:foo
-#[fo$0o::bar()]
-struct Foo;
+fn foo() -> Result<(), FooError> {
+    Ok($0Result::<(), FooError>::Ok(())?$0)
+}
 "#,
         expect![[r#"
-                *foo*
-
                 ```rust
-                extern crate foo
+                ()
+                ```"#]],
+    );
+    check_hover_range(
+        r#"
+//- minicore: try, from, result
+struct FooError;
+struct BarError;
+
+fn foo() -> Result<(), FooError> {
+    Ok($0Result::<(), BarError>::Ok(())?$0)
+}
+"#,
+        expect![[r#"
+                ```text
+                Try Error Type: BarError
+                Propagated as:  FooError
                 ```
             "#]],
-    )
+    );
 }
 
 #[test]
-fn hover_rename() {
-    check(
+fn hover_try_expr() {
+    check_hover_range(
         r#"
-use self as foo$0;
+//- minicore: try
+struct NotResult<T, U>(T, U);
+struct Short;
+struct Looooong;
+
+fn foo() -> NotResult<(), Looooong> {
+    $0NotResult((), Short)?$0;
+}
 "#,
         expect![[r#"
-                *foo*
-
-                ```rust
-                extern crate test
+                ```text
+                Try Target Type:    NotResult<(), Short>
+                Propagated as:   NotResult<(), Looooong>
                 ```
             "#]],
     );
-    check(
+    check_hover_range(
         r#"
-mod bar {}
-use bar::{self as foo$0};
+//- minicore: try
+struct NotResult<T, U>(T, U);
+struct Short;
+struct Looooong;
+
+fn foo() -> NotResult<(), Short> {
+    $0NotResult((), Looooong)?$0;
+}
 "#,
         expect![[r#"
-                *foo*
-
-                ```rust
-                test
-                ```
-
-                ```rust
-                mod bar
+                ```text
+                Try Target Type: NotResult<(), Looooong>
+                Propagated as:      NotResult<(), Short>
                 ```
             "#]],
     );
-    check(
+}
+
+#[test]
+fn hover_try_expr_option() {
+    cov_mark::check!(hover_try_expr_opt_opt);
+    check_hover_range(
         r#"
-mod bar {
-    use super as foo$0;
+//- minicore: option, try
+
+fn foo() -> Option<()> {
+    $0Some(0)?$0;
+    None
 }
 "#,
         expect![[r#"
-                *foo*
-
                 ```rust
-                extern crate test
+                i32
+                ```"#]],
+    );
+}
+
+#[test]
+fn hover_deref_expr() {
+    check_hover_range(
+        r#"
+//- minicore: deref
+use core::ops::Deref;
+
+struct DerefExample<T> {
+    value: T
+}
+
+impl<T> Deref for DerefExample<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+fn foo() {
+    let x = DerefExample { value: 0 };
+    let y: i32 = $0*x$0;
+}
+"#,
+        expect![[r#"
+                ```text
+                Dereferenced from: DerefExample<i32>
+                To type:                         i32
                 ```
             "#]],
     );
-    check(
+}
+
+#[test]
+fn hover_deref_expr_with_coercion() {
+    check_hover_range(
         r#"
-use crate as foo$0;
+//- minicore: deref
+use core::ops::Deref;
+
+struct DerefExample<T> {
+    value: T
+}
+
+impl<T> Deref for DerefExample<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+fn foo() {
+    let x = DerefExample { value: &&&&&0 };
+    let y: &i32 = $0*x$0;
+}
 "#,
         expect![[r#"
-                *foo*
-
-                ```rust
-                extern crate test
+                ```text
+                Dereferenced from: DerefExample<&&&&&i32>
+                To type:                         &&&&&i32
+                Coerced to:                          &i32
                 ```
             "#]],
     );
 }
 
 #[test]
-fn hover_attribute_in_macro() {
+fn hover_intra_in_macro() {
     check(
         r#"
-//- minicore:derive
-macro_rules! identity {
-    ($struct:item) => {
-        $struct
-    };
-}
-#[rustc_builtin_macro]
-pub macro Copy {}
-identity!{
-    #[derive(Copy$0)]
-    struct Foo;
+macro_rules! foo_macro {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        pub struct $name;
+    }
 }
+
+foo_macro!(
+    /// Doc comment for [`Foo$0`]
+    Foo
+);
 "#,
         expect![[r#"
-            *Copy*
+            *[`Foo`]*
 
             ```rust
             test
             ```
 
             ```rust
-            macro Copy
+            pub struct Foo // size = 0, align = 1
             ```
+
+            ---
+
+            Doc comment for [`Foo`](https://docs.rs/test/*/test/struct.Foo.html)
         "#]],
     );
 }
 
 #[test]
-fn hover_derive_input() {
+fn hover_macro_generated_method_in_macro_call() {
     check(
         r#"
-//- minicore:derive
-#[rustc_builtin_macro]
-pub macro Copy {}
-#[derive(Copy$0)]
-struct Foo;
+macro_rules! gen {
+    () => {
+        impl super::Foo {
+            /// Forwards to [`Self::other`], see [`helper`].
+            pub fn method(&self) {}
+            pub fn other(&self) {}
+        }
+        pub fn helper() {}
+    };
+}
+macro_rules! id {
+    ($e:expr) => { $e };
+}
+pub struct Foo;
+mod imp {
+    gen!();
+}
+fn f(foo: Foo) {
+    id!(foo.meth$0od());
+}
 "#,
         expect![[r#"
-            *Copy*
+            *method*
 
             ```rust
-            test
+            test::imp::Foo
             ```
 
             ```rust
-            macro Copy
+            pub fn method(&self)
             ```
+
+            ---
+
+            Forwards to [`Self::other`](https://docs.rs/test/*/test/struct.Foo.html#method.other), see [`helper`](https://docs.rs/test/*/test/imp/fn.helper.html).
         "#]],
     );
+}
+
+#[test]
+fn hover_derive_generated_method() {
+    /// Derives an impl of `Foo` with a documented method.
+    #[derive(Debug)]
+    struct Methods;
+    impl ProcMacroExpander for Methods {
+        fn expand(
+            &self,
+            _: &Subtree,
+            _: Option<&Subtree>,
+            _: &Env,
+        ) -> Result<Subtree, ProcMacroExpansionError> {
+            let source = SourceFile::parse(
+                r#"impl Foo {
+                    #[doc = "Forwards to [`Self::other`], see [`helper`]."]
+                    pub fn method(&self) {}
+                    pub fn other(&self) {}
+                }"#,
+            );
+            Ok(mbe::syntax_node_to_token_tree(source.tree().syntax()).0)
+        }
+    }
+
+    let methods = r#"
+#[proc_macro_derive(Methods)]
+pub fn derive_methods(item: TokenStream) -> TokenStream {
+    item
+}
+"#;
+    let change_fixture = ChangeFixture::parse_with_proc_macros(
+        r#"
+//- proc_macros: methods
+//- minicore: derive
+//- /main.rs crate:main
+mod imp {
+    use proc_macros::Methods;
+    #[derive(Methods)]
+    pub struct Foo;
+    pub fn helper() {}
+}
+fn f(foo: imp::Foo) {
+    foo.meth$0od();
+}
+"#,
+        vec![(
+            methods.to_owned(),
+            ProcMacro {
+                name: "Methods".into(),
+                kind: ProcMacroKind::CustomDerive,
+                expander: std::sync::Arc::new(Methods),
+            },
+        )],
+    );
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture.file_position.unwrap();
+    let range = FileRange { file_id, range: TextRange::empty(range_or_offset.expect_offset()) };
+    let analysis = host.analysis();
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = analysis.hover(&config, range).unwrap().unwrap();
+    expect![[r#"
+
+        ```rust
+        main::imp::Foo
+        ```
+
+        ```rust
+        pub fn method(&self)
+        ```
+
+        ---
+
+        Forwards to [`Self::other`](https://docs.rs/main/*/main/imp/struct.Foo.html#method.other), see [`helper`](https://docs.rs/main/*/main/imp/fn.helper.html)."#]]
+        .assert_eq(analysis.render_hover(&config, &hover.info.markup).unwrap().as_str());
+}
+
+#[test]
+fn hover_intra_in_attr() {
     check(
         r#"
-//- minicore:derive
-mod foo {
-    #[rustc_builtin_macro]
-    pub macro Copy {}
-}
-#[derive(foo::Copy$0)]
-struct Foo;
+#[doc = "Doc comment for [`Foo$0`]"]
+pub struct Foo(i32);
 "#,
         expect![[r#"
-            *Copy*
+            *[`Foo`]*
 
             ```rust
-            test::foo
+            test
             ```
 
             ```rust
-            macro Copy
+            pub struct Foo // size = 4, align = 4
             ```
+
+            ---
+
+            Doc comment for [`Foo`](https://docs.rs/test/*/test/struct.Foo.html)
         "#]],
     );
 }
 
 #[test]
-fn hover_range_math() {
-    check_hover_range(
+fn hover_inert_attr() {
+    check(
         r#"
-fn f() { let expr = $01 + 2 * 3$0 }
+#[doc$0 = ""]
+pub struct Foo;
 "#,
-        expect![[r#"
+        expect![[r##"
+            *doc*
+
             ```rust
-            i32
-            ```"#]],
-    );
+            #[doc]
+            ```
 
-    check_hover_range(
+            ---
+
+            Valid forms are:
+
+             - \#\[doc(hidden|inline|...)\]
+             - \#\[doc = string\]
+        "##]],
+    );
+    check(
         r#"
-fn f() { let expr = 1 $0+ 2 * $03 }
+#[allow$0()]
+pub struct Foo;
 "#,
-        expect![[r#"
+        expect![[r##"
+            *allow*
+
             ```rust
-            i32
-            ```"#]],
+            #[allow]
+            ```
+
+            ---
+
+            Valid forms are:
+
+             - \#\[allow(lint1, lint2, ..., /\*opt\*/ reason = "...")\]
+        "##]],
     );
+}
 
-    check_hover_range(
+#[test]
+fn hover_dollar_crate() {
+    // $crate should be resolved to the right crate name.
+
+    check(
         r#"
-fn f() { let expr = 1 + $02 * 3$0 }
+//- /main.rs crate:main deps:dep
+dep::m!(KONST$0);
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! m {
+    ( $name:ident ) => { const $name: $crate::Type = $crate::Type; };
+}
+
+pub struct Type;
 "#,
         expect![[r#"
+            *KONST*
+
             ```rust
-            i32
-            ```"#]],
+            main
+            ```
+
+            ```rust
+            const KONST: dep::Type = Type
+            ```
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_arrays() {
-    check_hover_range(
+fn hover_record_variant() {
+    check(
         r#"
-fn f() { let expr = $0[1, 2, 3, 4]$0 }
+enum Enum {
+    RecordV$0 { field: u32 }
+}
 "#,
         expect![[r#"
+            *RecordV*
+
             ```rust
-            [i32; 4]
-            ```"#]],
-    );
+            test::Enum
+            ```
 
-    check_hover_range(
-        r#"
-fn f() { let expr = [1, 2, $03, 4]$0 }
-"#,
-        expect![[r#"
             ```rust
-            [i32; 4]
-            ```"#]],
+            RecordV { field: u32 }
+            ```
+        "#]],
     );
+}
 
-    check_hover_range(
+#[test]
+fn hover_record_variant_field() {
+    check(
         r#"
-fn f() { let expr = [1, 2, $03$0, 4] }
+enum Enum {
+    RecordV { field$0: u32 }
+}
 "#,
         expect![[r#"
+            *field*
+
             ```rust
-            i32
-            ```"#]],
+            test::RecordV
+            ```
+
+            ```rust
+            field: u32 // size = 4, align = 4
+            ```
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_functions() {
-    check_hover_range(
+fn hover_record_variant_field_docs_with_links() {
+    check(
         r#"
-fn f<T>(a: &[T]) { }
-fn b() { $0f$0(&[1, 2, 3, 4, 5]); }
+pub enum Event {
+    Ready {
+        /// The session of [`Self::Ready`], see [Event::Ready::peer].
+        session_id: u32,
+        peer: u32,
+    },
+}
+fn f(event: Event) {
+    let Event::Ready { session_id$0, .. } = event;
+}
 "#,
         expect![[r#"
+            *session_id*
+
             ```rust
-            fn f<i32>(&[i32])
-            ```"#]],
-    );
+            session_id: u32
+            ```
+            ---
 
-    check_hover_range(
-        r#"
-fn f<T>(a: &[T]) { }
-fn b() { f($0&[1, 2, 3, 4, 5]$0); }
-"#,
-        expect![[r#"
             ```rust
-            &[i32; 5]
-            ```"#]],
+            test::Ready
+            ```
+
+            ```rust
+            pub session_id: u32 // size = 4, align = 4
+            ```
+
+            ---
+
+            The session of [`Self::Ready`](https://docs.rs/test/*/test/enum.Event.html#variant.Ready), see [Event::Ready::peer](https://docs.rs/test/*/test/enum.Event.html#variant.Ready.field.peer).
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_shows_nothing_when_invalid() {
-    check_hover_range_no_results(
+fn hover_trait_impl_assoc_item_def_doc_forwarding() {
+    check(
         r#"
-fn f<T>(a: &[T]) { }
-fn b()$0 { f(&[1, 2, 3, 4, 5]); }$0
+trait T {
+    /// Trait docs
+    fn func() {}
+}
+impl T for () {
+    fn func$0() {}
+}
 "#,
-    );
+        expect![[r#"
+            *func*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn func()
+            ```
 
-    check_hover_range_no_results(
-        r#"
-fn f<T>$0(a: &[T]) { }
-fn b() { f(&[1, 2, 3,$0 4, 5]); }
-"#,
-    );
+            ---
 
-    check_hover_range_no_results(
-        r#"
-fn $0f() { let expr = [1, 2, 3, 4]$0 }
-"#,
+            Trait docs
+
+            *(docs from trait)*
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_shows_unit_for_statements() {
-    check_hover_range(
+fn hover_trait_impl_assoc_item_inherited_doc_links() {
+    check(
         r#"
-fn f<T>(a: &[T]) { }
-fn b() { $0f(&[1, 2, 3, 4, 5]); }$0
+//- /main.rs crate:main deps:dep
+struct S;
+impl dep::traits::T for S {
+    fn func$0() {}
+}
+//- /dep.rs crate:dep
+pub mod traits {
+    pub trait T {
+        /// Calls [`Self::other`] through [`super::helper`].
+        fn func();
+        fn other();
+    }
+}
+pub fn helper() {}
 "#,
         expect![[r#"
+            *func*
+
             ```rust
-            ()
-            ```"#]],
-    );
+            main::S
+            ```
 
-    check_hover_range(
-        r#"
-fn f() { let expr$0 = $0[1, 2, 3, 4] }
-"#,
-        expect![[r#"
             ```rust
-            ()
-            ```"#]],
+            fn func()
+            ```
+
+            ---
+
+            Calls [`Self::other`](https://docs.rs/dep/*/dep/traits/trait.T.html#tymethod.other) through [`super::helper`](https://docs.rs/dep/*/dep/fn.helper.html).
+
+            *(docs from trait)*
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_for_pat() {
-    check_hover_range(
+fn hover_trait_impl_assoc_item_own_docs() {
+    check(
         r#"
-fn foo() {
-    let $0x$0 = 0;
+trait T {
+    /// Trait docs
+    fn func() {}
 }
-"#,
-        expect![[r#"
-                ```rust
-                i32
-                ```"#]],
-    );
-
-    check_hover_range(
-        r#"
-fn foo() {
-    let $0x$0 = "";
+impl T for () {
+    /// Impl docs
+    fn func$0() {}
 }
 "#,
         expect![[r#"
-                ```rust
-                &str
-                ```"#]],
+            *func*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn func()
+            ```
+
+            ---
+
+            Impl docs
+        "#]],
     );
 }
 
 #[test]
-fn hover_range_shows_coercions_if_applicable_expr() {
+fn hover_ranged_macro_call() {
     check_hover_range(
         r#"
-fn foo() {
-    let x: &u32 = $0&&&&&0$0;
+macro_rules! __rust_force_expr {
+    ($e:expr) => {
+        $e
+    };
 }
-"#,
-        expect![[r#"
-                ```text
-                Type:       &&&&&u32
-                Coerced to:     &u32
-                ```
-            "#]],
-    );
-    check_hover_range(
-        r#"
-fn foo() {
-    let x: *const u32 = $0&0$0;
+macro_rules! vec {
+    ($elem:expr) => {
+        __rust_force_expr!($elem)
+    };
 }
-"#,
-        expect![[r#"
-                ```text
-                Type:             &u32
-                Coerced to: *const u32
-                ```
-            "#]],
-    );
+
+struct Struct;
+impl Struct {
+    fn foo(self) {}
 }
 
-#[test]
-fn hover_range_shows_type_actions() {
-    check_actions(
-        r#"
-struct Foo;
-fn foo() {
-    let x: &Foo = $0&&&&&Foo$0;
+fn f() {
+    $0vec![Struct]$0;
 }
 "#,
         expect![[r#"
-                [
-                    GoToType(
-                        [
-                            HoverGotoTypeData {
-                                mod_path: "test::Foo",
-                                nav: NavigationTarget {
-                                    file_id: FileId(
-                                        0,
-                                    ),
-                                    full_range: 0..11,
-                                    focus_range: 7..10,
-                                    name: "Foo",
-                                    kind: Struct,
-                                    description: "struct Foo",
-                                },
-                            },
-                        ],
-                    ),
-                ]
-            "#]],
+            ```rust
+            Struct
+            ```"#]],
     );
 }
 
 #[test]
-fn hover_try_expr_res() {
-    check_hover_range(
+fn hover_deref() {
+    check(
         r#"
-//- minicore: try, from, result
-struct FooError;
+//- minicore: deref
 
-fn foo() -> Result<(), FooError> {
-    Ok($0Result::<(), FooError>::Ok(())?$0)
+struct Struct(usize);
+
+impl core::ops::Deref for Struct {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
-"#,
-        expect![[r#"
-                ```rust
-                ()
-                ```"#]],
-    );
-    check_hover_range(
-        r#"
-//- minicore: try, from, result
-struct FooError;
-struct BarError;
 
-fn foo() -> Result<(), FooError> {
-    Ok($0Result::<(), BarError>::Ok(())?$0)
+fn f() {
+    $0*Struct(0);
 }
 "#,
         expect![[r#"
-                ```text
-                Try Error Type: BarError
-                Propagated as:  FooError
-                ```
-            "#]],
+            ***
+
+            ```rust
+            test::Struct
+            ```
+
+            ```rust
+            fn deref(&self) -> &Self::Target
+            ```
+        "#]],
     );
 }
 
 #[test]
-fn hover_try_expr() {
-    check_hover_range(
+fn static_const_macro_expanded_body() {
+    check(
         r#"
-//- minicore: try
-struct NotResult<T, U>(T, U);
-struct Short;
-struct Looooong;
-
-fn foo() -> NotResult<(), Looooong> {
-    $0NotResult((), Short)?$0;
+macro_rules! m {
+    () => {
+        pub const V: i8 = {
+            let e = 123;
+            f(e) // Prevent const eval from evaluating this constant, we want to print the body's code.
+        };
+    };
 }
+m!();
+fn main() { $0V; }
 "#,
         expect![[r#"
-                ```text
-                Try Target Type:    NotResult<(), Short>
-                Propagated as:   NotResult<(), Looooong>
-                ```
-            "#]],
+            *V*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub const V: i8 = {
+              let e = 123;
+              f(e)
+            }
+            ```
+        "#]],
     );
-    check_hover_range(
+    check(
         r#"
-//- minicore: try
-struct NotResult<T, U>(T, U);
-struct Short;
-struct Looooong;
-
-fn foo() -> NotResult<(), Short> {
-    $0NotResult((), Looooong)?$0;
+macro_rules! m {
+    () => {
+        pub static V: i8 = {
+            let e = 123;
+        };
+    };
 }
+m!();
+fn main() { $0V; }
 "#,
         expect![[r#"
-                ```text
-                Try Target Type: NotResult<(), Looooong>
-                Propagated as:      NotResult<(), Short>
-                ```
-            "#]],
+            *V*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub static V: i8 = {
+              let e = 123;
+            }
+            ```
+        "#]],
     );
 }
 
 #[test]
-fn hover_try_expr_option() {
-    cov_mark::check!(hover_try_expr_opt_opt);
-    check_hover_range(
+fn hover_rest_pat() {
+    check(
         r#"
-//- minicore: option, try
+struct Struct {a: u32, b: u32, c: u8, d: u16};
 
-fn foo() -> Option<()> {
-    $0Some(0)?$0;
-    None
+fn main() {
+    let Struct {a, c, .$0.} = Struct {a: 1, b: 2, c: 3, d: 4};
 }
 "#,
         expect![[r#"
-                ```rust
-                i32
-                ```"#]],
+            *..*
+            ```rust
+            .., b: u32, d: u16
+            ```
+        "#]],
     );
-}
 
-#[test]
-fn hover_deref_expr() {
-    check_hover_range(
+    check(
         r#"
-//- minicore: deref
-use core::ops::Deref;
-
-struct DerefExample<T> {
-    value: T
-}
-
-impl<T> Deref for DerefExample<T> {
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
-        &self.value
-    }
-}
+struct Struct {a: u32, b: u32, c: u8, d: u16};
 
-fn foo() {
-    let x = DerefExample { value: 0 };
-    let y: i32 = $0*x$0;
+fn main() {
+    let Struct {a, b, c, d, .$0.} = Struct {a: 1, b: 2, c: 3, d: 4};
 }
 "#,
         expect![[r#"
-                ```text
-                Dereferenced from: DerefExample<i32>
-                To type:                         i32
-                ```
-            "#]],
+            *..*
+            ```rust
+            ..
+            ```
+        "#]],
     );
 }
 
 #[test]
-fn hover_deref_expr_with_coercion() {
-    check_hover_range(
+fn hover_underscore_pat() {
+    check(
         r#"
-//- minicore: deref
-use core::ops::Deref;
-
-struct DerefExample<T> {
-    value: T
-}
-
-impl<T> Deref for DerefExample<T> {
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
-        &self.value
-    }
-}
-
-fn foo() {
-    let x = DerefExample { value: &&&&&0 };
-    let y: &i32 = $0*x$0;
+fn main() {
+    let _$0 = 0;
 }
 "#,
         expect![[r#"
-                ```text
-                Dereferenced from: DerefExample<&&&&&i32>
-                To type:                         &&&&&i32
-                Coerced to:                          &i32
-                ```
-            "#]],
+            *_*
+            ```rust
+            i32
+            ```
+        "#]],
     );
-}
-
-#[test]
-fn hover_intra_in_macro() {
     check(
         r#"
-macro_rules! foo_macro {
-    ($(#[$attr:meta])* $name:ident) => {
-        $(#[$attr])*
-        pub struct $name;
-    }
+fn main() {
+    let (_$0,) = (0,);
 }
-
-foo_macro!(
-    /// Doc comment for [`Foo$0`]
-    Foo
-);
 "#,
         expect![[r#"
-            *[`Foo`]*
-
-            ```rust
-            test
-            ```
-
+            *_*
             ```rust
-            pub struct Foo // size = 0, align = 1
+            i32
             ```
-
-            ---
-
-            Doc comment for [`Foo`](https://docs.rs/test/*/test/struct.Foo.html)
         "#]],
     );
 }
 
 #[test]
-fn hover_intra_in_attr() {
+fn hover_underscore_expr() {
     check(
         r#"
-#[doc = "Doc comment for [`Foo$0`]"]
-pub struct Foo(i32);
+fn main() {
+    _$0 = 0;
+}
 "#,
         expect![[r#"
-            *[`Foo`]*
-
+            *_*
             ```rust
-            test
+            i32
             ```
-
+        "#]],
+    );
+    check(
+        r#"
+fn main() {
+    (_$0,) = (0,);
+}
+"#,
+        expect![[r#"
+            *_*
             ```rust
-            pub struct Foo // size = 4, align = 4
+            i32
             ```
-
-            ---
-
-            Doc comment for [`Foo`](https://docs.rs/test/*/test/struct.Foo.html)
         "#]],
     );
 }
 
 #[test]
-fn hover_inert_attr() {
-    check(
+fn hover_underscore_type() {
+    check_hover_no_result(
         r#"
-#[doc$0 = ""]
-pub struct Foo;
+fn main() {
+    let x: _$0 = 0;
+}
 "#,
-        expect![[r##"
-            *doc*
+    );
+    check_hover_no_result(
+        r#"
+fn main() {
+    let x: (_$0,) = (0,);
+}
+"#,
+    );
+}
 
+#[test]
+fn hover_call_parens() {
+    check(
+        r#"
+fn foo() -> i32 {}
+fn main() {
+    foo($0);
+}
+"#,
+        expect![[r#"
+            *)*
             ```rust
-            #[doc]
+            i32
             ```
-
-            ---
-
-            Valid forms are:
-
-            * \#\[doc(hidden|inline|...)\]
-            * \#\[doc = string\]
-        "##]],
+        "#]],
     );
     check(
         r#"
-#[allow$0()]
-pub struct Foo;
+struct S;
+impl S {
+    fn foo(self) -> i32 {}
+}
+fn main() {
+    S.foo($0);
+}
 "#,
-        expect![[r##"
-            *allow*
-
+        expect![[r#"
+            *)*
             ```rust
-            #[allow]
+            i32
             ```
-
-            ---
-
-            Valid forms are:
-
-            * \#\[allow(lint1, lint2, ..., /\*opt\*/ reason = "...")\]
-        "##]],
+        "#]],
     );
 }
 
 #[test]
-fn hover_dollar_crate() {
-    // $crate should be resolved to the right crate name.
-
+fn assoc_fn_in_block_local_impl() {
     check(
         r#"
-//- /main.rs crate:main deps:dep
-dep::m!(KONST$0);
-//- /dep.rs crate:dep
-#[macro_export]
-macro_rules! m {
-    ( $name:ident ) => { const $name: $crate::Type = $crate::Type; };
+struct S;
+mod m {
+    const _: () = {
+        impl crate::S {
+            pub(crate) fn foo() {}
+        }
+    };
+}
+fn test() {
+    S::foo$0();
 }
-
-pub struct Type;
 "#,
         expect![[r#"
-            *KONST*
+            *foo*
 
             ```rust
-            main
+            test::S
             ```
 
             ```rust
-            const KONST: dep::Type = Type
+            pub(crate) fn foo()
             ```
         "#]],
     );
-}
 
-#[test]
-fn hover_record_variant() {
     check(
         r#"
-enum Enum {
-    RecordV$0 { field: u32 }
+struct S;
+mod m {
+    const _: () = {
+        const _: () = {
+            impl crate::S {
+                pub(crate) fn foo() {}
+            }
+        };
+    };
+}
+fn test() {
+    S::foo$0();
 }
 "#,
         expect![[r#"
-            *RecordV*
+            *foo*
 
             ```rust
-            test::Enum
+            test::S
             ```
 
             ```rust
-            RecordV { field: u32 }
+            pub(crate) fn foo()
             ```
         "#]],
     );
-}
 
-#[test]
-fn hover_record_variant_field() {
     check(
         r#"
-enum Enum {
-    RecordV { field$0: u32 }
+struct S;
+mod m {
+    mod inner {
+        const _: () = {
+            impl crate::S {
+                pub(super) fn foo() {}
+            }
+        };
+    }
+
+    fn test() {
+        crate::S::foo$0();
+    }
 }
 "#,
         expect![[r#"
-            *field*
+            *foo*
 
             ```rust
-            test::RecordV
+            test::S
             ```
 
             ```rust
-            field: u32 // size = 4, align = 4
+            pub(super) fn foo()
             ```
         "#]],
     );
 }
 
 #[test]
-fn hover_trait_impl_assoc_item_def_doc_forwarding() {
+fn assoc_const_in_block_local_impl() {
     check(
         r#"
-trait T {
-    /// Trait docs
-    fn func() {}
+struct S;
+mod m {
+    const _: () = {
+        impl crate::S {
+            pub(crate) const A: () = ();
+        }
+    };
 }
-impl T for () {
-    fn func$0() {}
+fn test() {
+    S::A$0;
 }
 "#,
         expect![[r#"
-            *func*
+            *A*
 
             ```rust
             test
             ```
 
             ```rust
-            fn func()
+            pub(crate) const A: () = ()
             ```
-
-            ---
-
-            Trait docs
         "#]],
     );
-}
 
-#[test]
-fn hover_ranged_macro_call() {
-    check_hover_range(
+    check(
         r#"
-macro_rules! __rust_force_expr {
-    ($e:expr) => {
-        $e
-    };
-}
-macro_rules! vec {
-    ($elem:expr) => {
-        __rust_force_expr!($elem)
+struct S;
+mod m {
+    const _: () = {
+        const _: () = {
+            impl crate::S {
+                pub(crate) const A: () = ();
+            }
+        };
     };
 }
-
-struct Struct;
-impl Struct {
-    fn foo(self) {}
-}
-
-fn f() {
-    $0vec![Struct]$0;
+fn test() {
+    S::A$0;
 }
 "#,
         expect![[r#"
+            *A*
+
             ```rust
-            Struct
-            ```"#]],
+            test
+            ```
+
+            ```rust
+            pub(crate) const A: () = ()
+            ```
+        "#]],
     );
-}
 
-#[test]
-fn hover_deref() {
     check(
         r#"
-//- minicore: deref
-
-struct Struct(usize);
-
-impl core::ops::Deref for Struct {
-    type Target = usize;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
+struct S;
+mod m {
+    mod inner {
+        const _: () = {
+            impl crate::S {
+                pub(super) const A: () = ();
+            }
+        };
     }
-}
 
-fn f() {
-    $0*Struct(0);
+    fn test() {
+        crate::S::A$0;
+    }
 }
 "#,
         expect![[r#"
-            ***
+            *A*
 
             ```rust
-            test::Struct
+            test
             ```
 
             ```rust
-            fn deref(&self) -> &Self::Target
+            pub(super) const A: () = ()
             ```
         "#]],
     );
 }
 
 #[test]
-fn static_const_macro_expanded_body() {
+fn field_as_method_call_fallback() {
     check(
         r#"
-macro_rules! m {
-    () => {
-        pub const V: i8 = {
-            let e = 123;
-            f(e) // Prevent const eval from evaluating this constant, we want to print the body's code.
-        };
-    };
+struct S { f: u32 }
+fn test() {
+    S { f: 0 }.f$0();
 }
-m!();
-fn main() { $0V; }
 "#,
         expect![[r#"
-            *V*
+            *f*
 
             ```rust
-            test
+            test::S
             ```
 
             ```rust
-            pub const V: i8 = {
-              let e = 123;
-              f(e)
-            }
+            f: u32 // size = 4, align = 4, offset = 0
             ```
         "#]],
     );
-    check(
-        r#"
-macro_rules! m {
-    () => {
-        pub static V: i8 = {
-            let e = 123;
-        };
-    };
 }
-m!();
-fn main() { $0V; }
+
+#[test]
+fn hover_linkify_urls() {
+    check_hover_linkify_urls(
+        r#"
+/// Implements the protocol (https://example.com/spec), see https://example.com/faq.
+///
+/// ```
+/// // https://example.com/example
+/// ```
+pub struct Shard$0;
 "#,
         expect![[r#"
-            *V*
 
             ```rust
             test
             ```
 
             ```rust
-            pub static V: i8 = {
-              let e = 123;
-            }
+            pub struct Shard // size = 0, align = 1
             ```
-        "#]],
+
+            ---
+
+            Implements the protocol (<https://example.com/spec>), see <https://example.com/faq>.
+
+            ```
+            // https://example.com/example
+            ```"#]],
     );
 }
 
 #[test]
-fn hover_rest_pat() {
-    check(
-        r#"
-struct Struct {a: u32, b: u32, c: u8, d: u16};
+fn hover_full_docs_link() {
+    let fixture = r#"
+//- /main.rs crate:main deps:dep
+fn main() { dep::Shard$0; }
+//- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep
+/// A shard of the gateway connection.
+///
+/// Each shard handles a subset of the guilds, see the gateway documentation for how
+/// many are needed.
+pub struct Shard;
+"#;
+    expect![[r#"
 
-fn main() {
-    let Struct {a, c, .$0.} = Struct {a: 1, b: 2, c: 3, d: 4};
-}
-"#,
-        expect![[r#"
-            *..*
-            ```rust
-            .., b: u32, d: u16
-            ```
-        "#]],
+        ```rust
+        dep
+        ```
+
+        ```rust
+        pub struct Shard // size = 0, align = 1
+        ```
+
+        ---
+
+        A shard of the gateway connection.
+
+        *(documentation truncated)*
+
+        — [view full documentation](https://docs.rs/dep/0.1.0/dep/struct.Shard.html)"#]]
+    .assert_eq(&hover_with_full_docs_link(fixture, Some(50)));
+    // short docs shown in full don't get the link
+    assert!(!hover_with_full_docs_link(fixture, None).contains("view full documentation"));
+
+    let long_docs = "/// Each shard handles a subset of the guilds.\n".repeat(30);
+    let fixture = format!(
+        "//- /main.rs crate:main deps:dep\nfn main() {{ dep::Shard$0; }}\n\
+         //- /dep/lib.rs crate:dep@CratesIo:0.1.0,https://github.com/example/dep\n\
+         {long_docs}pub struct Shard;\n"
     );
+    let markup = hover_with_full_docs_link(&fixture, None);
+    expect![[r#"— [view full documentation](https://docs.rs/dep/0.1.0/dep/struct.Shard.html)"#]]
+        .assert_eq(markup.lines().last().unwrap());
+}
 
-    check(
+#[test]
+fn hover_reuses_doc_link_resolutions_after_unrelated_edits() {
+    let change_fixture = ChangeFixture::parse(
         r#"
-struct Struct {a: u32, b: u32, c: u8, d: u16};
-
-fn main() {
-    let Struct {a, b, c, d, .$0.} = Struct {a: 1, b: 2, c: 3, d: 4};
+//- /lib.rs
+mod other;
+/// Starts the [`Shard`] with [`Shard::new`].
+pub fn start() {}
+pub struct Shard;
+impl Shard {
+    pub fn new() -> Self { Shard }
 }
+//- /other.rs
+fn helper() {}
 "#,
-        expect![[r#"
-            *..*
-            ```rust
-            ..
-            ```
-        "#]],
     );
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let file_id = change_fixture.files[0];
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = |host: &AnalysisHost| {
+        let offset = host.raw_database().file_text(file_id).find("start(").unwrap();
+        let range = FileRange { file_id, range: TextRange::empty((offset as u32).into()) };
+        let analysis = host.analysis();
+        let hover = analysis.hover(&config, range).unwrap().unwrap();
+        analysis.render_hover(&config, &hover.info.markup).unwrap().to_string()
+    };
+    let start = hir::Crate::all(&host.db)[0]
+        .root_module(&host.db)
+        .declarations(&host.db)
+        .into_iter()
+        .find_map(|it| match it {
+            hir::ModuleDef::Function(it) => Some(Definition::Function(it)),
+            _ => None,
+        })
+        .unwrap();
+
+    let markup = hover(&host);
+    let resolutions = host.raw_database().doc_link_resolutions(start);
+    assert_eq!(resolutions.len(), 2);
+
+    let mut change = Change::new();
+    change.change_file(change_fixture.files[1], Some(Arc::from("fn helper() { let _ = 92; }")));
+    host.apply_change(change);
+    assert_eq!(hover(&host), markup);
+    assert!(Arc::ptr_eq(&resolutions, &host.raw_database().doc_link_resolutions(start)));
+
+    let text = host.raw_database().file_text(file_id).replace("[`Shard::new`]", "[`Shard`]");
+    let mut change = Change::new();
+    change.change_file(file_id, Some(Arc::from(text)));
+    host.apply_change(change);
+    assert_ne!(hover(&host), markup);
+    assert_eq!(host.raw_database().doc_link_resolutions(start).len(), 1);
 }
 
 #[test]
-fn hover_underscore_pat() {
-    check(
+fn hover_reuses_doc_link_resolutions_after_edits_in_other_crates() {
+    let change_fixture = ChangeFixture::parse(
         r#"
+//- /gateway.rs crate:gateway new_source_root:library
+/// Starts the [`Shard`] with [`Shard::new`], see the [`gateway`](crate) docs.
+pub fn start() {}
+pub struct Shard;
+impl Shard {
+    pub fn new() -> Self { Shard }
+}
+//- /main.rs crate:main deps:gateway new_source_root:local
+use gateway::start;
 fn main() {
-    let _$0 = 0;
+    start();
 }
 "#,
-        expect![[r#"
-            *_*
-            ```rust
-            i32
-            ```
-        "#]],
     );
-    check(
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let file_id = change_fixture.files[1];
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = |host: &AnalysisHost| {
+        let offset = host.raw_database().file_text(file_id).find("start()").unwrap();
+        let range = FileRange { file_id, range: TextRange::empty((offset as u32).into()) };
+        let analysis = host.analysis();
+        let hover = analysis.hover(&config, range).unwrap().unwrap();
+        analysis.render_hover(&config, &hover.info.markup).unwrap().to_string()
+    };
+    let markup = hover(&host);
+    assert!(markup.contains("(https://docs.rs/gateway/*/gateway/struct.Shard.html)"));
+
+    let mut change = Change::new();
+    let text =
+        host.raw_database().file_text(file_id).replace("start();", "start();\n    let _ = 92;");
+    change.change_file(file_id, Some(Arc::from(text)));
+    host.apply_change(change);
+    // the docs of `start` only depend on the library's files, which the edit doesn't touch
+    cov_mark::check_count!(doc_link_resolutions_computed, 0);
+    assert_eq!(hover(&host), markup);
+}
+
+#[test]
+fn hover_reuses_doc_link_resolutions_after_unrelated_edits_in_the_same_file() {
+    let change_fixture = ChangeFixture::parse(
         r#"
+//- /main.rs crate:main
+/// Starts the [`Shard`] with [`Shard::new`].
+pub fn start() {}
+pub struct Shard;
+impl Shard {
+    pub fn new() -> Self { Shard }
+}
 fn main() {
-    let (_$0,) = (0,);
+    start();
 }
 "#,
-        expect![[r#"
-            *_*
-            ```rust
-            i32
-            ```
-        "#]],
     );
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let file_id = change_fixture.files[0];
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = |host: &AnalysisHost| {
+        let offset = host.raw_database().file_text(file_id).find("start();").unwrap();
+        let range = FileRange { file_id, range: TextRange::empty((offset as u32).into()) };
+        let analysis = host.analysis();
+        let hover = analysis.hover(&config, range).unwrap().unwrap();
+        analysis.render_hover(&config, &hover.info.markup).unwrap().to_string()
+    };
+    let markup = hover(&host);
+    assert!(markup.contains("(https://docs.rs/main/*/main/struct.Shard.html)"));
+
+    let edit = |host: &mut AnalysisHost, from: &str, to: &str| {
+        let mut change = Change::new();
+        let text = host.raw_database().file_text(file_id).replace(from, to);
+        change.change_file(file_id, Some(Arc::from(text)));
+        host.apply_change(change);
+    };
+    // the resolutions depend on the docs of `start` and the imports of its module, not on all
+    // of its attributes or the rest of the file
+    cov_mark::check_count!(doc_link_resolutions_computed, 0);
+    edit(&mut host, "pub fn start", "#[inline]\npub fn start");
+    assert_eq!(hover(&host), markup);
+    edit(&mut host, "start();", "start();\n    let _ = 92;");
+    assert_eq!(hover(&host), markup);
+}
+
+#[test]
+fn hover_links_follow_dependency_version_changes() {
+    let fixture = r#"
+//- /gateway.rs crate:gateway@CratesIo:0.1.0,https://example.com/gateway new_source_root:library
+/// Starts the [`Shard`], see the [`gateway`](crate) docs.
+pub fn start() {}
+pub struct Shard;
+//- /main.rs crate:main deps:gateway new_source_root:local
+use gateway::start;
+fn main() {
+    start();
+}
+"#;
+    let change_fixture = ChangeFixture::parse(fixture);
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let file_id = change_fixture.files[1];
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+    let hover = |host: &AnalysisHost| {
+        let offset = host.raw_database().file_text(file_id).find("start()").unwrap();
+        let range = FileRange { file_id, range: TextRange::empty((offset as u32).into()) };
+        let analysis = host.analysis();
+        let hover = analysis.hover(&config, range).unwrap().unwrap();
+        analysis.render_hover(&config, &hover.info.markup).unwrap().to_string()
+    };
+    let markup = hover(&host);
+    assert!(markup.contains("(https://docs.rs/gateway/0.1.0/gateway/struct.Shard.html)"));
+    assert!(markup.contains("(https://docs.rs/gateway/0.1.0/gateway/index.html)"));
+
+    // switching branches reloads the workspace with the crate graph of the new lock file
+    let crate_graph = ChangeFixture::parse(&fixture.replace("@CratesIo:0.1.0", "@CratesIo:0.2.0"))
+        .change
+        .crate_graph
+        .unwrap();
+    let mut change = Change::new();
+    change.set_crate_graph(crate_graph);
+    host.apply_change(change);
+    let markup = hover(&host);
+    assert!(markup.contains("(https://docs.rs/gateway/0.2.0/gateway/struct.Shard.html)"));
+    assert!(markup.contains("(https://docs.rs/gateway/0.2.0/gateway/index.html)"));
+    assert!(!markup.contains("0.1.0"));
+}
+
+#[test]
+fn hover_is_cancelled_while_rewriting_large_docs() {
+    let docs = "/// Lorem ipsum dolor sit amet, consectetur adipiscing elit, see [`start`].\n"
+        .repeat(10_000);
+    let change_fixture = ChangeFixture::parse(&format!("{docs}pub fn start$0() {{}}"));
+    let mut host = AnalysisHost::default();
+    host.db.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture.file_position.unwrap();
+    let range = FileRange { file_id, range: TextRange::empty(range_or_offset.expect_offset()) };
+    let config = HoverConfig { links: DocLinkOptions::default(), ..HOVER_BASE_CONFIG };
+
+    static REWRITING: AtomicBool = AtomicBool::new(false);
+    let analysis = host.analysis();
+    let hover = std::thread::spawn(move || {
+        // holds the rewrite at its first check until the cancellation is requested, so the hover
+        // can neither finish before nor be cancelled outside of the rewrite
+        CANCELLATION_CHECK_HOOK.with(|it| {
+            it.set(Some(|db| {
+                REWRITING.store(true, Ordering::SeqCst);
+                let is_cancelled = || db.unwind_if_cancelled();
+                while std::panic::catch_unwind(std::panic::AssertUnwindSafe(is_cancelled)).is_ok() {
+                    std::thread::yield_now();
+                }
+            }))
+        });
+        // the links are rewritten once the hover is rendered
+        let hover = analysis.hover(&config, range).unwrap().unwrap();
+        analysis.render_hover(&config, &hover.info.markup).map(|_| ())
+    });
+    while !REWRITING.load(Ordering::SeqCst) {
+        std::thread::yield_now();
+    }
+    // blocks until the hover is cancelled and drops its snapshot of the database
+    host.request_cancellation();
+    assert!(hover.join().unwrap().is_err());
 }
 
 #[test]
-fn hover_underscore_expr() {
+fn hover_module_docs_resolve_links_where_they_are_written() {
     check(
         r#"
-fn main() {
-    _$0 = 0;
-}
+//- /main.rs crate:foo
+/// Outer docs link [Item] and [`self::Item`] in the parent.
+mod $0gateway;
+pub struct Item;
+//- /gateway.rs
+//! Inner docs link [Item] and [`super::Item`] from inside.
+pub struct Item;
 "#,
         expect![[r#"
-            *_*
+            *gateway*
+
             ```rust
-            i32
+            foo
+            ```
+
+            ```rust
+            mod gateway
             ```
+
+            ---
+
+            Outer docs link [Item](https://docs.rs/foo/*/foo/struct.Item.html) and [`self::Item`](https://docs.rs/foo/*/foo/struct.Item.html) in the parent.
+            Inner docs link [Item](https://docs.rs/foo/*/foo/gateway/struct.Item.html) and [`super::Item`](https://docs.rs/foo/*/foo/struct.Item.html) from inside.
         "#]],
     );
+}
+
+#[test]
+fn hover_skips_docs_of_inactive_cfg_attrs() {
     check(
         r#"
-fn main() {
-    (_$0,) = (0,);
-}
+//- /main.rs crate:foo
+/// Basic docs.
+#[cfg_attr(feature = "docs", doc = "Extended docs with [Link].")]
+/// More docs.
+pub struct Fo$0o;
+pub struct Link;
 "#,
         expect![[r#"
-            *_*
+            *Foo*
+
             ```rust
-            i32
+            foo
+            ```
+
+            ```rust
+            pub struct Foo // size = 0, align = 1
             ```
+
+            ---
+
+            Basic docs.
+            More docs.
         "#]],
     );
 }
 
 #[test]
-fn hover_underscore_type() {
-    check_hover_no_result(
+fn hover_inactive_cfg_attr_docs() {
+    check_hover_inactive_cfg_docs(
         r#"
-fn main() {
-    let x: _$0 = 0;
-}
+//- /main.rs crate:foo
+/// Basic docs.
+#[cfg_attr(feature = "docs", doc = "Extended docs with [Link].")]
+/// More docs.
+pub struct Fo$0o;
+pub struct Link;
 "#,
+        expect![[r#"
+
+            ```rust
+            foo
+            ```
+
+            ```rust
+            pub struct Foo // size = 0, align = 1
+            ```
+
+            ---
+
+            Basic docs.
+            Extended docs with [Link](https://docs.rs/foo/*/foo/struct.Link.html).
+            More docs."#]],
     );
-    check_hover_no_result(
+    cov_mark::check!(cfg_attr_active);
+    check_hover_inactive_cfg_docs(
         r#"
-fn main() {
-    let x: (_$0,) = (0,);
-}
+//- /main.rs crate:foo cfg:feature=docs
+/// Basic docs.
+#[cfg_attr(feature = "docs", doc = "Extended docs with [Link].")]
+/// More docs.
+pub struct Fo$0o;
+pub struct Link;
 "#,
+        expect![[r#"
+
+            ```rust
+            foo
+            ```
+
+            ```rust
+            pub struct Foo // size = 0, align = 1
+            ```
+
+            ---
+
+            Basic docs.
+            Extended docs with [Link](https://docs.rs/foo/*/foo/struct.Link.html).
+            More docs."#]],
     );
 }
 
 #[test]
-fn hover_call_parens() {
+fn hover_through_inlining_reexport_links_to_the_reexporting_crate() {
     check(
         r#"
-fn foo() -> i32 {}
-fn main() {
-    foo($0);
+//- /main.rs crate:main deps:facade
+fn f(_: facade::Sh$0ard) {}
+//- /facade.rs crate:facade deps:internal
+#[doc(inline)]
+pub use internal::{Shard, gateway::Gateway};
+//- /internal.rs crate:internal
+/// Connects to a [`Gateway`] with [`Gateway::connect`], unlike a [`Helper`].
+pub struct Shard;
+pub struct Helper;
+pub mod gateway {
+    pub struct Gateway;
+    impl Gateway { pub fn connect() {} }
 }
+pub use gateway::Gateway;
 "#,
         expect![[r#"
-            *)*
+            *Shard*
+
             ```rust
-            i32
+            internal
             ```
-        "#]],
-    );
-    check(
-        r#"
-struct S;
-impl S {
-    fn foo(self) -> i32 {}
-}
-fn main() {
-    S.foo($0);
-}
-"#,
-        expect![[r#"
-            *)*
+
             ```rust
-            i32
+            pub struct Shard // size = 0, align = 1
             ```
+
+            ---
+
+            Connects to a [`Gateway`](https://docs.rs/facade/*/facade/struct.Gateway.html) with [`Gateway::connect`](https://docs.rs/facade/*/facade/struct.Gateway.html#method.connect), unlike a [`Helper`](https://docs.rs/internal/*/internal/struct.Helper.html).
         "#]],
     );
 }
 
 #[test]
-fn assoc_fn_in_block_local_impl() {
+fn hover_macro_generated_module_items_resolve_links_in_the_module() {
     check(
         r#"
-struct S;
-mod m {
-    const _: () = {
-        impl crate::S {
-            pub(crate) fn foo() {}
+//- /main.rs crate:foo
+macro_rules! bindings {
+    ($name:ident) => {
+        pub mod $name {
+            /// Made by [`Two::new`], see [Two].
+            pub struct One;
+            /// Makes [`One`].
+            pub struct Two;
+            impl Two { pub fn new() -> One { One } }
         }
     };
 }
-fn test() {
-    S::foo$0();
-}
+bindings!(generated);
+pub struct One;
+pub struct Two;
+fn f(_: generated::One$0) {}
 "#,
         expect![[r#"
-            *foo*
+            *One*
 
             ```rust
-            test::S
+            foo::generated
             ```
 
             ```rust
-            pub(crate) fn foo()
+            pub struct One // size = 0, align = 1
             ```
+
+            ---
+
+            Made by [`Two::new`](https://docs.rs/foo/*/foo/generated/struct.Two.html#method.new), see [Two](https://docs.rs/foo/*/foo/generated/struct.Two.html).
         "#]],
     );
+}
 
+#[test]
+fn hover_const_and_static_docs_resolve_links() {
     check(
         r#"
-struct S;
-mod m {
-    const _: () = {
-        const _: () = {
-            impl crate::S {
-                pub(crate) fn foo() {}
-            }
-        };
-    };
-}
-fn test() {
-    S::foo$0();
+//- /main.rs crate:foo
+pub mod config {
+    pub struct Config;
+    /// The default [Config].
+    pub const DEFAULT$0: Config = Config;
 }
 "#,
         expect![[r#"
-            *foo*
+            *DEFAULT*
 
             ```rust
-            test::S
+            foo::config
             ```
 
             ```rust
-            pub(crate) fn foo()
+            pub const DEFAULT: Config = Config
             ```
+
+            ---
+
+            The default [Config](https://docs.rs/foo/*/foo/config/struct.Config.html).
         "#]],
     );
-
     check(
         r#"
-struct S;
-mod m {
-    mod inner {
-        const _: () = {
-            impl crate::S {
-                pub(super) fn foo() {}
-            }
-        };
-    }
-
-    fn test() {
-        crate::S::foo$0();
-    }
+//- /main.rs crate:foo
+pub mod config {
+    pub struct Config;
+    /// The global [Config].
+    pub static GLOBAL$0: Config = Config;
 }
 "#,
         expect![[r#"
-            *foo*
+            *GLOBAL*
 
             ```rust
-            test::S
+            foo::config
             ```
 
             ```rust
-            pub(super) fn foo()
+            pub static GLOBAL: Config = Config
             ```
+
+            ---
+
+            The global [Config](https://docs.rs/foo/*/foo/config/struct.Config.html).
         "#]],
     );
+    let fixture = r#"
+//- /main.rs crate:foo
+pub mod config {
+    pub struct Config;
+    impl Config {
+        /// The default [Config], also [Self].
+        pub const DEFAULT: Config = Config;
+    }
 }
+"#;
+    check(
+        &format!("{}fn f() {{}}", fixture.replace("DEFAULT:", "DEFAULT$0:")),
+        expect![[r#"
+            *DEFAULT*
 
-#[test]
-fn assoc_const_in_block_local_impl() {
+            ```rust
+            foo::config
+            ```
+
+            ```rust
+            pub const DEFAULT: Config = Config
+            ```
+
+            ---
+
+            The default [Config](https://docs.rs/foo/*/foo/config/struct.Config.html), also [Self](https://docs.rs/foo/*/foo/config/struct.Config.html).
+        "#]],
+    );
     check(
-        r#"
-struct S;
-mod m {
-    const _: () = {
-        impl crate::S {
-            pub(crate) const A: () = ();
-        }
-    };
-}
-fn test() {
-    S::A$0;
-}
-"#,
+        &format!("{fixture}fn f() {{ let _ = config::Config::DEFAULT$0; }}"),
         expect![[r#"
-            *A*
+            *DEFAULT*
 
             ```rust
-            test
+            foo::config
             ```
 
             ```rust
-            pub(crate) const A: () = ()
+            pub const DEFAULT: Config = Config
             ```
+
+            ---
+
+            The default [Config](https://docs.rs/foo/*/foo/config/struct.Config.html), also [Self](https://docs.rs/foo/*/foo/config/struct.Config.html).
         "#]],
     );
+}
 
-    check(
+#[test]
+fn hover_name_link_of_associated_const() {
+    check_hover_name_link(
         r#"
-struct S;
-mod m {
-    const _: () = {
-        const _: () = {
-            impl crate::S {
-                pub(crate) const A: () = ();
-            }
-        };
-    };
+//- /main.rs crate:foo
+pub struct Config;
+impl Config {
+    /// The default [Config].
+    pub const DEFAULT: Config = Config;
 }
-fn test() {
-    S::A$0;
+fn f() {
+    let _ = Config::DEFAULT$0;
 }
 "#,
         expect![[r#"
-            *A*
+            [`DEFAULT`](https://docs.rs/foo/*/foo/struct.Config.html#associatedconstant.DEFAULT)
 
             ```rust
-            test
+            foo
             ```
 
             ```rust
-            pub(crate) const A: () = ()
+            pub const DEFAULT: Config = Config
             ```
-        "#]],
+
+            ---
+
+            The default [Config](https://docs.rs/foo/*/foo/struct.Config.html)."#]],
     );
+}
 
-    check(
-        r#"
-struct S;
-mod m {
-    mod inner {
-        const _: () = {
-            impl crate::S {
-                pub(super) const A: () = ();
-            }
-        };
+#[test]
+fn hover_trait_in_impl_header_rewrites_links_in_the_trait_crate() {
+    let fixture = r#"
+//- /main.rs crate:main deps:core
+use core::fmt::{self, Debug};
+/// A [Formatter] of our own.
+struct Formatter;
+struct Shard;
+impl Debug for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter) {}
+}
+//- /core.rs crate:core
+pub mod fmt {
+    /// `?` formatting, see the [`fmt`](self) module and [Formatter].
+    pub trait Debug {
+        /// Formats the value with [`Formatter::debug_struct`].
+        fn fmt(&self, f: &mut Formatter);
     }
-
-    fn test() {
-        crate::S::A$0;
+    pub struct Formatter;
+    impl Formatter {
+        pub fn debug_struct(&self) {}
     }
 }
-"#,
+"#;
+    check(
+        &fixture.replace("impl Debug", "impl Debug$0"),
         expect![[r#"
-            *A*
+            *Debug*
 
             ```rust
-            test
+            core::fmt
             ```
 
             ```rust
-            pub(super) const A: () = ()
+            pub trait Debug
             ```
+
+            ---
+
+            `?` formatting, see the [`fmt`](https://doc.rust-lang.org/stable/core/fmt/index.html) module and [Formatter](https://doc.rust-lang.org/stable/core/fmt/struct.Formatter.html).
         "#]],
     );
-}
-
-#[test]
-fn field_as_method_call_fallback() {
     check(
-        r#"
-struct S { f: u32 }
-fn test() {
-    S { f: 0 }.f$0();
-}
-"#,
+        &fixture.replace("fn fmt(&self, f: &mut fmt", "fn fmt$0(&self, f: &mut fmt"),
         expect![[r#"
-            *f*
+            *fmt*
 
             ```rust
-            test::S
+            main::Shard
             ```
 
             ```rust
-            f: u32 // size = 4, align = 4, offset = 0
+            fn fmt(&self, f: &mut fmt::Formatter)
             ```
+
+            ---
+
+            Formats the value with [`Formatter::debug_struct`](https://doc.rust-lang.org/stable/core/fmt/struct.Formatter.html#method.debug_struct).
+
+            *(docs from trait)*
         "#]],
     );
 }
@@ -70,6 +70,7 @@ use ide_db::{
         salsa::{self, ParallelDatabase},
         CrateOrigin, Env, FileLoader, FileSet, SourceDatabase, VfsPath,
     },
+    doc_links::DocLinksDatabase,
     symbol_index, FxHashMap, FxIndexSet, LineIndexDatabase,
 };
 use syntax::SourceFile;
@@ -80,11 +81,19 @@ use crate::navigation_target::{ToNav, TryToNav};
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind, AnnotationLocation},
     call_hierarchy::CallItem,
+    doc_links::{
+        doc_url, full_name, rewrite_links, BrokenDocLink, BrokenLinksMode, DocLinkCandidate,
+        DocLinkOptions, DocLinksMode, ItemDocLinks, PageLinksMode, ResolutionError,
+        ResolvedDocLink,
+    },
     expand_macro::ExpandedMacro,
     file_structure::{StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
     highlight_related::{HighlightRelatedConfig, HighlightedRange},
-    hover::{HoverAction, HoverConfig, HoverDocFormat, HoverGotoTypeData, HoverResult},
+    hover::{
+        HoverAction, HoverConfig, HoverDocFormat, HoverGotoTypeData, HoverMarkup, HoverResult,
+        OriginalMarkup,
+    },
     inlay_hints::{
         AdjustmentHints, AdjustmentHintsMode, ClosureReturnTypeHints, DiscriminantHints, InlayHint,
         InlayHintLabel, InlayHintLabelPart, InlayHintPosition, InlayHintsConfig, InlayKind,
@@ -119,6 +128,7 @@ pub use ide_db::{
         Cancelled, Change, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange,
         SourceRoot, SourceRootId,
     },
+    doc_links::{coverage::DocCoverage, DocLinkTargetKind},
     label::Label,
     line_index::{LineCol, LineIndex},
     search::{ReferenceCategory, SearchScope},
@@ -165,6 +175,18 @@ impl AnalysisHost {
         self.db.update_lru_capacities(lru_capacities);
     }
 
+    /// Sets how many link targets are resolved at most for one piece of documentation, links past
+    /// that are left as they are.
+    pub fn update_doc_link_resolution_budget(&mut self, budget: usize) {
+        self.db.update_doc_link_resolution_budget(budget);
+    }
+
+    /// Sets whether intra-doc links naming nothing resolve to the item that has their last
+    /// segment as a `#[doc(alias)]`.
+    pub fn update_doc_link_alias_resolution(&mut self, enable: bool) {
+        self.db.update_doc_link_alias_resolution(enable);
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -442,13 +464,18 @@ impl Analysis {
         self.with_db(|db| goto_type_definition::goto_type_definition(db, position))
     }
 
-    /// Finds all usages of the reference at point.
+    /// Finds all usages of the reference at point, optionally including intra-doc links
+    /// resolving to it.
     pub fn find_all_refs(
         &self,
         position: FilePosition,
         search_scope: Option<SearchScope>,
+        include_doc_links: bool,
     ) -> Cancellable<Option<Vec<ReferenceSearchResult>>> {
-        self.with_db(|db| references::find_all_refs(&Semantics::new(db), position, search_scope))
+        self.with_db(|db| {
+            let sema = Semantics::new(db);
+            references::find_all_refs(&sema, position, search_scope, include_doc_links)
+        })
     }
 
     /// Returns a short text describing element at position.
@@ -460,6 +487,11 @@ impl Analysis {
         self.with_db(|db| hover::hover(db, range, config))
     }
 
+    /// Renders the markup of a hover, processing the links in its docs for `config.format`.
+    pub fn render_hover(&self, config: &HoverConfig, markup: &HoverMarkup) -> Cancellable<Markup> {
+        self.with_db(|db| hover::render_hover(db, markup, config))
+    }
+
     /// Returns moniker of symbol at position.
     pub fn moniker(
         &self,
@@ -483,9 +515,50 @@ impl Analysis {
         })
     }
 
-    /// Computes parameter information at the given position.
-    pub fn signature_help(&self, position: FilePosition) -> Cancellable<Option<SignatureHelp>> {
-        self.with_db(|db| signature_help::signature_help(db, position))
+    /// Returns the web URL of the documentation of the symbol under the cursor, or a message
+    /// explaining why there is none.
+    pub fn web_doc_url(&self, position: FilePosition) -> Cancellable<Result<String, String>> {
+        self.with_db(|db| doc_links::web_doc_url(db, &position))
+    }
+
+    /// Resolves the links in `markdown`, the documentation of `def`.
+    pub fn resolve_doc_links(
+        &self,
+        def: ide_db::defs::Definition,
+        markdown: &str,
+    ) -> Cancellable<Vec<ResolvedDocLink>> {
+        self.with_db(|db| {
+            doc_links::resolve_doc_links(db, def, markdown, &DocLinkOptions::default(), None)
+        })
+    }
+
+    /// Returns the intra-doc links in the documentation of `def` that don't name a single item.
+    pub fn broken_doc_links(
+        &self,
+        def: ide_db::defs::Definition,
+    ) -> Cancellable<Vec<BrokenDocLink>> {
+        self.with_db(|db| doc_links::broken_doc_links(db, def))
+    }
+
+    /// Resolves the links in the documentation of every documented item of the file.
+    pub fn file_doc_links(&self, file_id: FileId) -> Cancellable<Vec<ItemDocLinks>> {
+        self.with_db(|db| doc_links::file_doc_links(db, file_id))
+    }
+
+    /// Returns how much of the public API of the crate is documented and how many of its
+    /// intra-doc links resolve.
+    pub fn doc_coverage(&self, crate_id: CrateId) -> Cancellable<DocCoverage> {
+        self.with_db(|db| *db.doc_coverage(crate_id.into()))
+    }
+
+    /// Computes parameter information at the given position, optionally rewriting intra-doc
+    /// links in its documentation.
+    pub fn signature_help(
+        &self,
+        position: FilePosition,
+        rewrite_doc_links: bool,
+    ) -> Cancellable<Option<SignatureHelp>> {
+        self.with_db(|db| signature_help::signature_help(db, position, rewrite_doc_links))
     }
 
     /// Computes call hierarchy candidates for the given file position.
@@ -536,6 +609,25 @@ impl Analysis {
         self.with_db(|db| db.crate_graph()[crate_id].root_file_id)
     }
 
+    /// Returns the display name of the given crate.
+    pub fn crate_display_name(&self, crate_id: CrateId) -> Cancellable<Option<String>> {
+        self.with_db(|db| {
+            let display_name = db.crate_graph()[crate_id].display_name.clone();
+            display_name.map(|it| it.canonical_name().to_owned())
+        })
+    }
+
+    /// Returns the crates of the workspace, leaving out its dependencies.
+    pub fn workspace_crates(&self) -> Cancellable<Vec<CrateId>> {
+        self.with_db(|db| {
+            let crate_graph = db.crate_graph();
+            crate_graph
+                .iter()
+                .filter(|&it| matches!(crate_graph[it].origin, CrateOrigin::Local { .. }))
+                .collect()
+        })
+    }
+
     /// Returns the set of possible targets to run for the current file.
     pub fn runnables(&self, file_id: FileId) -> Cancellable<Vec<Runnable>> {
         self.with_db(|db| runnables::runnables(db, file_id))
@@ -610,6 +702,38 @@ impl Analysis {
             .unwrap_or_default())
     }
 
+    /// Resolves the documentation of the completion item with the given label and
+    /// [`CompletionItem::doc_owner_key`], with its links processed according to `options`.
+    ///
+    /// This recomputes the completions at the position, so it is meant to be called for a single
+    /// item the client asks about rather than for a whole completion list.
+    pub fn resolve_completion_docs(
+        &self,
+        config: &CompletionConfig,
+        position: FilePosition,
+        label: &str,
+        doc_owner_key: u64,
+        options: &DocLinkOptions,
+    ) -> Cancellable<Option<String>> {
+        self.with_db(|db| {
+            // labels aren't unique, a field and a method can both be `len`
+            let item = ide_completion::completions(db, config, position, None)?
+                .into_iter()
+                .find(|it| it.label == label && it.doc_owner_key() == Some(doc_owner_key))?;
+            doc_links::completion_docs(db, &item, options)
+        })
+    }
+
+    /// Returns the documentation of each of the completion `items`, with its links processed,
+    /// for clients that can't resolve it item by item.
+    pub fn completion_list_docs(
+        &self,
+        items: &[CompletionItem],
+        options: &DocLinkOptions,
+    ) -> Cancellable<Vec<Option<String>>> {
+        self.with_db(|db| doc_links::completion_list_docs(db, items, options))
+    }
+
     /// Computes the set of diagnostics for the given file.
     pub fn diagnostics(
         &self,
@@ -13,6 +13,7 @@ use hir::{PathResolution, Semantics};
 use ide_db::{
     base_db::FileId,
     defs::{Definition, NameClass, NameRefClass},
+    doc_links::doc_link_references,
     search::{ReferenceCategory, SearchScope, UsageSearchResult},
     RootDatabase,
 };
@@ -55,6 +56,7 @@ pub(crate) fn find_all_refs(
     sema: &Semantics<'_, RootDatabase>,
     position: FilePosition,
     search_scope: Option<SearchScope>,
+    include_doc_links: bool,
 ) -> Option<Vec<ReferenceSearchResult>> {
     let _p = profile::span("find_all_refs");
     let syntax = sema.parse(position.file_id).syntax().clone();
@@ -80,7 +82,7 @@ pub(crate) fn find_all_refs(
                 retain_adt_literal_usages(&mut usages, def, sema);
             }
 
-            let references = usages
+            let mut references: IntMap<_, Vec<_>> = usages
                 .into_iter()
                 .map(|(file_id, refs)| {
                     (
@@ -92,6 +94,14 @@ pub(crate) fn find_all_refs(
                     )
                 })
                 .collect();
+            if include_doc_links && !literal_search {
+                for (file_id, refs) in doc_link_references(sema, def, search_scope.as_ref()) {
+                    references.entry(file_id).or_default().extend(
+                        refs.into_iter()
+                            .map(|it| (it.name_range, Some(ReferenceCategory::DocLink))),
+                    );
+                }
+            }
 
             ReferenceSearchResult { declaration, references }
         }
@@ -749,6 +759,52 @@ pub struct Foo {
         );
     }
 
+    #[test]
+    fn test_find_all_refs_doc_links() {
+        check_with_doc_links(
+            r#"
+/// Opens a [Shard], see [`crate::Shard`] and [the shard](Shard).
+fn connect(shard: Shard$0) {}
+
+/// Closes a [shard][s].
+///
+/// [s]: crate::Shard
+fn close() {}
+
+/// [Shard] does not resolve in here.
+mod voice {}
+
+pub struct Shard;
+"#,
+            expect![[r#"
+                Shard Struct FileId(0) 213..230 224..229
+
+                FileId(0) 84..89
+                FileId(0) 13..18 DocLink
+                FileId(0) 34..39 DocLink
+                FileId(0) 58..63 DocLink
+                FileId(0) 140..145 DocLink
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_find_all_refs_doc_links_opt_in() {
+        check(
+            r#"
+/// Opens a [Shard].
+fn connect(shard: Shard$0) {}
+
+pub struct Shard;
+"#,
+            expect![[r#"
+                Shard Struct FileId(0) 50..67 61..66
+
+                FileId(0) 39..44
+            "#]],
+        );
+    }
+
     #[test]
     fn test_find_all_refs_decl_module_on_self() {
         check(
@@ -1095,8 +1151,21 @@ impl Foo {
     }
 
     fn check_with_scope(ra_fixture: &str, search_scope: Option<SearchScope>, expect: Expect) {
+        check_impl(ra_fixture, search_scope, false, expect)
+    }
+
+    fn check_with_doc_links(ra_fixture: &str, expect: Expect) {
+        check_impl(ra_fixture, None, true, expect)
+    }
+
+    fn check_impl(
+        ra_fixture: &str,
+        search_scope: Option<SearchScope>,
+        include_doc_links: bool,
+        expect: Expect,
+    ) {
         let (analysis, pos) = fixture::position(ra_fixture);
-        let refs = analysis.find_all_refs(pos, search_scope).unwrap().unwrap();
+        let refs = analysis.find_all_refs(pos, search_scope, include_doc_links).unwrap().unwrap();
 
         let mut actual = String::new();
         for refs in refs {
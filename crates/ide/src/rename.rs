@@ -2487,4 +2487,125 @@ fn main() {
 ",
         )
     }
+
+    #[test]
+    fn test_rename_updates_doc_links() {
+        check(
+            "Connection",
+            r#"
+/// Opens a [Shard], see [`Shard`][] and [`crate::gateway::Shard`].
+///
+/// The [shard](gateway::Shard) is closed on drop, unlike [struct@Shard].
+pub fn connect() {}
+
+pub use gateway::Shard;
+
+pub mod gateway {
+    pub struct Shard$0;
+}
+"#,
+            r#"
+/// Opens a [Connection], see [`Connection`][] and [`crate::gateway::Connection`].
+///
+/// The [shard](gateway::Connection) is closed on drop, unlike [struct@Connection].
+pub fn connect() {}
+
+pub use gateway::Connection;
+
+pub mod gateway {
+    pub struct Connection;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_updates_reference_doc_links() {
+        check(
+            "Connection",
+            r#"
+/// Opens a [shard][s], see [the shard].
+///
+/// [s]: crate::Shard
+/// [the shard]: <struct@Shard> "The shard"
+pub fn connect() {}
+
+pub struct Shard$0;
+"#,
+            r#"
+/// Opens a [shard][s], see [the shard].
+///
+/// [s]: crate::Connection
+/// [the shard]: <struct@Connection> "The shard"
+pub fn connect() {}
+
+pub struct Connection;
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_updates_disambiguated_doc_links() {
+        check(
+            "open",
+            r#"
+/// Calls [fn@f], [`fn@f`], [function@f] and [fn f](value@f).
+pub fn connect() {}
+
+pub fn f$0() {}
+"#,
+            r#"
+/// Calls [fn@open], [`fn@open`], [function@open] and [fn f](value@open).
+pub fn connect() {}
+
+pub fn open() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_keeps_unrelated_doc_links() {
+        check(
+            "Connection",
+            r#"
+/// [Shard] and [voice::Shard]
+pub fn connect() {}
+
+pub struct Shard$0;
+
+pub mod voice {
+    pub struct Shard;
+}
+"#,
+            r#"
+/// [Connection] and [voice::Shard]
+pub fn connect() {}
+
+pub struct Connection;
+
+pub mod voice {
+    pub struct Shard;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_mod_updates_doc_links() {
+        check(
+            "voice",
+            r#"
+/// See [gateway] and [`crate::gateway`].
+pub fn connect() {}
+
+pub mod gateway$0 {}
+"#,
+            r#"
+/// See [voice] and [`crate::voice`].
+pub fn connect() {}
+
+pub mod voice {}
+"#,
+        );
+    }
 }
@@ -4,22 +4,25 @@
 use std::collections::BTreeSet;
 
 use either::Either;
-use hir::{
-    AssocItem, GenericParam, HasAttrs, HirDisplay, ModuleDef, PathResolution, Semantics, Trait,
-};
+use hir::{AssocItem, GenericParam, HirDisplay, ModuleDef, PathResolution, Semantics, Trait};
 use ide_db::{
     active_parameter::{callable_for_node, generic_def_for_node},
     base_db::FilePosition,
+    defs::Definition,
+    doc_links::MARKDOWN_OPTIONS,
+    documentation::Documentation,
     FxIndexMap,
 };
+use itertools::Itertools;
+use pulldown_cmark::{Event, Parser, Tag};
 use stdx::format_to;
 use syntax::{
     algo,
-    ast::{self, HasArgList},
+    ast::{self, HasArgList, HasName},
     match_ast, AstNode, Direction, SyntaxElementChildren, SyntaxToken, TextRange, TextSize,
 };
 
-use crate::RootDatabase;
+use crate::{DocLinkOptions, RootDatabase};
 
 /// Contains information about an item signature as seen from a use site.
 ///
@@ -31,6 +34,11 @@ pub struct SignatureHelp {
     pub signature: String,
     pub active_parameter: Option<usize>,
     parameters: Vec<TextRange>,
+    docs: Option<Documentation>,
+    /// The docs of the parameters, taken from the `# Arguments` section of the documentation.
+    parameter_docs: Vec<Option<String>>,
+    /// The names of the parameters of a function, whose docs are looked up.
+    parameter_names: Vec<Option<String>>,
 }
 
 impl SignatureHelp {
@@ -42,6 +50,16 @@ impl SignatureHelp {
         &self.parameters
     }
 
+    /// The documentation of the parameter at `idx`, as markdown.
+    pub fn parameter_doc(&self, idx: usize) -> Option<&str> {
+        self.parameter_docs.get(idx)?.as_deref()
+    }
+
+    fn set_docs(&mut self, db: &RootDatabase, def: impl Into<Definition>) {
+        self.docs = Documentation::for_definition(db, &def.into());
+        self.doc = self.docs.as_ref().map(|it| it.as_str().to_owned());
+    }
+
     fn push_call_param(&mut self, param: &str) {
         self.push_param("(", param);
     }
@@ -66,7 +84,88 @@ impl SignatureHelp {
 }
 
 /// Computes parameter information for the given position.
-pub(crate) fn signature_help(db: &RootDatabase, position: FilePosition) -> Option<SignatureHelp> {
+///
+/// When `rewrite_doc_links` is set, intra-doc links in the documentation are rewritten to point
+/// at the rendered docs, which is only useful for clients that display it as markdown. The docs
+/// of the parameters are taken from the documentation afterwards, so their links are rewritten
+/// too.
+pub(crate) fn signature_help(
+    db: &RootDatabase,
+    position: FilePosition,
+    rewrite_doc_links: bool,
+) -> Option<SignatureHelp> {
+    let mut res = signature_help_at(db, position)?;
+    if rewrite_doc_links {
+        if let Some(docs) = &res.docs {
+            res.doc = Some(docs.rewritten(db, &DocLinkOptions::default()).to_string());
+        }
+    }
+    if let Some(doc) = &res.doc {
+        if res.parameter_names.iter().any(Option::is_some) {
+            res.parameter_docs = parameter_docs(doc, &res.parameter_names);
+        }
+    }
+    Some(res)
+}
+
+/// Extracts the docs of the parameters `names` from the `# Arguments` (or `# Parameters`) section
+/// of `doc`, whose list items start with the parameter's name in backticks:
+///
+/// ```text
+/// # Arguments
+///
+/// * `name` - The description.
+/// ```
+fn parameter_docs(doc: &str, names: &[Option<String>]) -> Vec<Option<String>> {
+    let mut res = vec![None; names.len()];
+    // the section ends at the next heading, even one of a subsection
+    let mut in_section = false;
+    let mut heading: Option<String> = None;
+    let mut list_depth = 0;
+    for (event, range) in Parser::new_ext(doc, MARKDOWN_OPTIONS).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(..)) => heading = Some(String::new()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = &mut heading {
+                    heading.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                let text = heading.take().unwrap_or_default();
+                let text = text.trim();
+                in_section = text.eq_ignore_ascii_case("arguments")
+                    || text.eq_ignore_ascii_case("parameters");
+            }
+            Event::Start(Tag::List(_)) => list_depth += 1,
+            Event::End(Tag::List(_)) => list_depth -= 1,
+            Event::Start(Tag::Item) if in_section && list_depth == 1 => {
+                let Some((name, doc)) = parameter_item(&doc[range]) else { continue };
+                let idx = names.iter().position(|it| it.as_deref() == Some(name));
+                if let Some(slot) = idx.map(|idx| &mut res[idx]) {
+                    slot.get_or_insert(doc);
+                }
+            }
+            _ => (),
+        }
+    }
+    res
+}
+
+/// Splits the list item `* `name` - The description.` into the name and the description.
+fn parameter_item(item: &str) -> Option<(&str, String)> {
+    let item = item.trim_start();
+    let item = match item.strip_prefix(['*', '-', '+']) {
+        Some(it) => it,
+        None => item.trim_start_matches(|c: char| c.is_ascii_digit()).strip_prefix(['.', ')'])?,
+    };
+    let (name, rest) = item.trim_start().strip_prefix('`')?.split_once('`')?;
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix(['-', ':', '–', '—']).unwrap_or(rest);
+    let doc = rest.lines().map(str::trim).filter(|it| !it.is_empty()).join("\n");
+    (!doc.is_empty()).then_some((name, doc))
+}
+
+fn signature_help_at(db: &RootDatabase, position: FilePosition) -> Option<SignatureHelp> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id);
     let file = file.syntax();
@@ -154,14 +253,21 @@ fn signature_help_for_call(
 
     let (callable, active_parameter) = callable_for_node(sema, &calling_node, &token)?;
 
-    let mut res =
-        SignatureHelp { doc: None, signature: String::new(), parameters: vec![], active_parameter };
+    let mut res = SignatureHelp {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter,
+        docs: None,
+        parameter_docs: vec![],
+        parameter_names: vec![],
+    };
 
     let db = sema.db;
     let mut fn_params = None;
     match callable.kind() {
         hir::CallableKind::Function(func) => {
-            res.doc = func.docs(db).map(|it| it.into());
+            res.set_docs(db, func);
             format_to!(res.signature, "fn {}", func.name(db));
             fn_params = Some(match callable.receiver_param(db) {
                 Some(_self) => func.params_without_self(db),
@@ -169,11 +275,11 @@ fn signature_help_for_call(
             });
         }
         hir::CallableKind::TupleStruct(strukt) => {
-            res.doc = strukt.docs(db).map(|it| it.into());
+            res.set_docs(db, strukt);
             format_to!(res.signature, "struct {}", strukt.name(db));
         }
         hir::CallableKind::TupleEnumVariant(variant) => {
-            res.doc = variant.docs(db).map(|it| it.into());
+            res.set_docs(db, variant);
             format_to!(
                 res.signature,
                 "enum {}::{}",
@@ -192,6 +298,12 @@ fn signature_help_for_call(
         let mut buf = String::new();
         for (idx, (pat, ty)) in callable.params(db).into_iter().enumerate() {
             buf.clear();
+            res.parameter_names.push(match &pat {
+                Some(Either::Right(ast::Pat::IdentPat(pat))) => {
+                    pat.name().map(|it| it.text().to_string())
+                }
+                _ => None,
+            });
             if let Some(pat) = pat {
                 match pat {
                     Either::Left(_self) => format_to!(buf, "self: "),
@@ -242,43 +354,46 @@ fn signature_help_for_generics(
         signature: String::new(),
         parameters: vec![],
         active_parameter: None,
+        docs: None,
+        parameter_docs: vec![],
+        parameter_names: vec![],
     };
 
     let db = sema.db;
     match generics_def {
         hir::GenericDef::Function(it) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "fn {}", it.name(db));
         }
         hir::GenericDef::Adt(hir::Adt::Enum(it)) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "enum {}", it.name(db));
         }
         hir::GenericDef::Adt(hir::Adt::Struct(it)) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "struct {}", it.name(db));
         }
         hir::GenericDef::Adt(hir::Adt::Union(it)) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "union {}", it.name(db));
         }
         hir::GenericDef::Trait(it) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "trait {}", it.name(db));
         }
         hir::GenericDef::TraitAlias(it) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "trait {}", it.name(db));
         }
         hir::GenericDef::TypeAlias(it) => {
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             format_to!(res.signature, "type {}", it.name(db));
         }
         hir::GenericDef::Variant(it) => {
             // In paths, generics of an enum can be specified *after* one of its variants.
             // eg. `None::<u8>`
             // We'll use the signature of the enum, but include the docs of the variant.
-            res.doc = it.docs(db).map(|it| it.into());
+            res.set_docs(db, it);
             let enum_ = it.parent_enum(db);
             format_to!(res.signature, "enum {}", enum_.name(db));
             generics_def = enum_.into();
@@ -404,6 +519,9 @@ fn signature_help_for_tuple_struct_pat(
         signature: String::new(),
         parameters: vec![],
         active_parameter: None,
+        docs: None,
+        parameter_docs: vec![],
+        parameter_names: vec![],
     };
 
     let db = sema.db;
@@ -411,7 +529,7 @@ fn signature_help_for_tuple_struct_pat(
     let fields: Vec<_> = if let PathResolution::Def(ModuleDef::Variant(variant)) = path_res {
         let en = variant.parent_enum(db);
 
-        res.doc = en.docs(db).map(|it| it.into());
+        res.set_docs(db, en);
         format_to!(res.signature, "enum {}::{} (", en.name(db), variant.name(db));
         variant.fields(db)
     } else {
@@ -423,7 +541,7 @@ fn signature_help_for_tuple_struct_pat(
 
         match adt {
             hir::Adt::Struct(it) => {
-                res.doc = it.docs(db).map(|it| it.into());
+                res.set_docs(db, it);
                 format_to!(res.signature, "struct {} (", it.name(db));
                 it.fields(db)
             }
@@ -475,6 +593,9 @@ fn signature_help_for_record_(
         signature: String::new(),
         parameters: vec![],
         active_parameter: Some(active_parameter),
+        docs: None,
+        parameter_docs: vec![],
+        parameter_names: vec![],
     };
 
     let fields;
@@ -485,7 +606,7 @@ fn signature_help_for_record_(
         fields = variant.fields(db);
         let en = variant.parent_enum(db);
 
-        res.doc = en.docs(db).map(|it| it.into());
+        res.set_docs(db, en);
         format_to!(res.signature, "enum {}::{} {{ ", en.name(db), variant.name(db));
     } else {
         let adt = match path_res {
@@ -497,12 +618,12 @@ fn signature_help_for_record_(
         match adt {
             hir::Adt::Struct(it) => {
                 fields = it.fields(db);
-                res.doc = it.docs(db).map(|it| it.into());
+                res.set_docs(db, it);
                 format_to!(res.signature, "struct {} {{ ", it.name(db));
             }
             hir::Adt::Union(it) => {
                 fields = it.fields(db);
-                res.doc = it.docs(db).map(|it| it.into());
+                res.set_docs(db, it);
                 format_to!(res.signature, "union {} {{ ", it.name(db));
             }
             _ => return None,
@@ -555,6 +676,11 @@ mod tests {
 
     #[track_caller]
     fn check(ra_fixture: &str, expect: Expect) {
+        check_with(ra_fixture, false, expect)
+    }
+
+    #[track_caller]
+    fn check_with(ra_fixture: &str, rewrite_doc_links: bool, expect: Expect) {
         let fixture = format!(
             r#"
 //- minicore: sized, fn
@@ -562,7 +688,7 @@ mod tests {
             "#
         );
         let (db, position) = position(&fixture);
-        let sig_help = crate::signature_help::signature_help(&db, position);
+        let sig_help = crate::signature_help::signature_help(&db, position, rewrite_doc_links);
         let actual = match sig_help {
             Some(sig_help) => {
                 let mut rendered = String::new();
@@ -588,6 +714,11 @@ mod tests {
                 if !sig_help.parameter_ranges().is_empty() {
                     format_to!(rendered, "\n");
                 }
+                for (i, label) in sig_help.parameter_labels().enumerate() {
+                    if let Some(doc) = sig_help.parameter_doc(i) {
+                        format_to!(rendered, "{label}: {doc}\n");
+                    }
+                }
                 rendered
             }
             None => String::new(),
@@ -794,6 +925,119 @@ fn bar() {
         );
     }
 
+    #[test]
+    fn test_fn_signature_with_doc_links() {
+        check_with(
+            r#"
+//- /main.rs crate:foo
+/// Builds a [`Bar`], see [the type](Bar).
+pub fn foo(j: u32) {}
+pub struct Bar;
+
+fn main() {
+    foo($0);
+}
+"#,
+            true,
+            expect![[r#"
+                Builds a [`Bar`](https://docs.rs/foo/*/foo/struct.Bar.html), see [the type](https://docs.rs/foo/*/foo/struct.Bar.html).
+                ------
+                fn foo(j: u32)
+                       ^^^^^^
+            "#]],
+        );
+        check_with(
+            r#"
+//- /main.rs crate:foo
+pub struct Bar;
+impl Bar {
+    /// Returns a new [`Bar`].
+    pub fn new(j: u32) -> Bar { Bar }
+}
+
+fn main() {
+    Bar::new($0);
+}
+"#,
+            true,
+            expect![[r#"
+                Returns a new [`Bar`](https://docs.rs/foo/*/foo/struct.Bar.html).
+                ------
+                fn new(j: u32) -> Bar
+                       ^^^^^^
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_fn_signature_with_parameter_docs() {
+        check_with(
+            r#"
+//- /main.rs crate:foo
+/// Connects to a [`Gateway`].
+///
+/// # Arguments
+///
+/// * `gateway` - The [`Gateway`] to connect to.
+/// * `retries`: How often to retry,
+///   at most ten times.
+/// * `missing` - Not a parameter.
+///
+/// ## Notes
+///
+/// * `timeout` - Not in the arguments section.
+pub fn connect(gateway: Gateway, mut retries: u32, timeout: u32) {}
+pub struct Gateway;
+
+fn main() {
+    connect(Gateway, $0);
+}
+"#,
+            true,
+            expect![[r#"
+                Connects to a [`Gateway`](https://docs.rs/foo/*/foo/struct.Gateway.html).
+
+                # Arguments
+
+                * `gateway` - The [`Gateway`](https://docs.rs/foo/*/foo/struct.Gateway.html) to connect to.
+                * `retries`: How often to retry,
+                  at most ten times.
+                * `missing` - Not a parameter.
+
+                ## Notes
+
+                * `timeout` - Not in the arguments section.
+                ------
+                fn connect(gateway: Gateway, mut retries: u32, timeout: u32)
+                           ----------------  ^^^^^^^^^^^^^^^^  ------------
+                gateway: Gateway: The [`Gateway`](https://docs.rs/foo/*/foo/struct.Gateway.html) to connect to.
+                mut retries: u32: How often to retry,
+                at most ten times.
+            "#]],
+        );
+        check(
+            r#"
+/// # Parameters
+///
+/// 1. `x` - The number.
+fn add_one(x: i32) -> i32 { x + 1 }
+
+fn main() {
+    add_one($0);
+}
+"#,
+            expect![[r#"
+                # Parameters
+
+                1. `x` - The number.
+                ------
+                fn add_one(x: i32) -> i32
+                           ^^^^^^
+                x: i32: The number.
+            "#]],
+        );
+    }
+
     #[test]
     fn test_fn_signature_with_docs() {
         check(
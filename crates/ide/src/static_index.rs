@@ -12,11 +12,12 @@ use ide_db::{
 use syntax::{AstNode, SyntaxKind::*, SyntaxToken, TextRange, T};
 
 use crate::{
-    hover::hover_for_definition,
+    doc_links::{doc_url, has_web_docs, DocLinkOptions},
+    hover::{hover_for_definition, render_hover},
     inlay_hints::AdjustmentHintsMode,
     moniker::{def_to_moniker, MonikerResult},
     parent_module::crates_for,
-    Analysis, Fold, HoverConfig, HoverResult, InlayHint, InlayHintsConfig, TryToNav,
+    Analysis, Fold, HoverConfig, InlayHint, InlayHintsConfig, Markup, TryToNav,
 };
 
 /// A static representation of fully analyzed source code.
@@ -29,6 +30,8 @@ pub struct StaticIndex<'a> {
     analysis: &'a Analysis,
     db: &'a RootDatabase,
     def_map: HashMap<Definition, TokenId>,
+    /// Whether the items of a crate have documentation on the web, see [`has_web_docs`].
+    web_docs: HashMap<Crate, bool>,
 }
 
 #[derive(Debug)]
@@ -39,10 +42,13 @@ pub struct ReferenceData {
 
 #[derive(Debug)]
 pub struct TokenStaticData {
-    pub hover: Option<HoverResult>,
+    /// The rendered markup of the token's hover.
+    pub hover: Option<Markup>,
     pub definition: Option<FileRange>,
     pub references: Vec<ReferenceData>,
     pub moniker: Option<MonikerResult>,
+    /// The URL of the documentation page of the token's definition.
+    pub docs_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -137,11 +143,17 @@ impl StaticIndex<'_> {
             syntax::NodeOrToken::Token(x) => Some(x),
         });
         let hover_config = HoverConfig {
-            links_in_hover: true,
+            links: DocLinkOptions::default(),
             memory_layout: true,
             documentation: true,
             keywords: true,
+            max_doc_length: None,
+            inactive_cfg_docs: false,
+            name_link: false,
+            linkify_urls: true,
+            demote_headings: false,
             format: crate::HoverDocFormat::Markdown,
+            original_markup: false,
         };
         let tokens = tokens.filter(|token| {
             matches!(
@@ -160,13 +172,16 @@ impl StaticIndex<'_> {
             let id = if let Some(x) = self.def_map.get(&def) {
                 *x
             } else {
+                let docs_url = self.docs_url(def);
                 let x = self.tokens.insert(TokenStaticData {
-                    hover: hover_for_definition(&sema, file_id, def, &node, &hover_config),
+                    hover: hover_for_definition(&sema, file_id, def, &node, &hover_config)
+                        .map(|hover| render_hover(self.db, &hover.markup, &hover_config)),
                     definition: def
                         .try_to_nav(self.db)
                         .map(|x| FileRange { file_id: x.file_id, range: x.focus_or_full_range() }),
                     references: vec![],
                     moniker: current_crate.and_then(|cc| def_to_moniker(self.db, def, cc)),
+                    docs_url,
                 });
                 self.def_map.insert(def, x);
                 x
@@ -184,6 +199,17 @@ impl StaticIndex<'_> {
         self.files.push(result);
     }
 
+    fn docs_url(&mut self, def: Definition) -> Option<String> {
+        let db = self.db;
+        // builtin types don't have a crate but are documented in `core`
+        if let Some(krate) = def.krate(db) {
+            if !*self.web_docs.entry(krate).or_insert_with(|| has_web_docs(db, krate)) {
+                return None;
+            }
+        }
+        doc_url(db, &def).map(String::from)
+    }
+
     pub fn compute(analysis: &Analysis) -> StaticIndex<'_> {
         let db = &*analysis.db;
         let work = all_modules(db).into_iter().filter(|module| {
@@ -198,6 +224,7 @@ impl StaticIndex<'_> {
             analysis,
             db,
             def_map: Default::default(),
+            web_docs: Default::default(),
         };
         let mut visited_files = FxHashSet::default();
         for module in work {
@@ -327,4 +354,29 @@ struct Hello(i32);
 "#,
         );
     }
+
+    #[test]
+    fn docs_urls() {
+        let (analysis, _) = fixture::annotations_without_marker(
+            r#"
+//- /main.rs crate:main deps:foo
+use foo::Shard;
+fn run(shard: Shard) {}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://github.com/x/foo
+pub struct Shard;
+"#,
+        );
+        let s = StaticIndex::compute(&analysis);
+        let mut urls = s.tokens.iter().filter_map(|(_, t)| t.docs_url).collect::<Vec<_>>();
+        urls.sort();
+        urls.dedup();
+        assert_eq!(
+            urls,
+            [
+                "https://docs.rs/foo/0.1.0/foo/index.html",
+                "https://docs.rs/foo/0.1.0/foo/struct.Shard.html",
+                "https://docs.rs/main/*/main/fn.run.html",
+            ]
+        );
+    }
 }
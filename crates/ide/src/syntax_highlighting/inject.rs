@@ -5,8 +5,8 @@ use std::mem;
 use either::Either;
 use hir::{InFile, Semantics};
 use ide_db::{
-    active_parameter::ActiveParameter, base_db::FileId, defs::Definition, rust_doc::is_rust_fence,
-    SymbolKind,
+    active_parameter::ActiveParameter, base_db::FileId, defs::Definition,
+    doc_links::extract_links_from_docs, rust_doc::is_rust_fence, SymbolKind,
 };
 use syntax::{
     ast::{self, AstNode, IsString, QuoteOffsets},
@@ -14,7 +14,7 @@ use syntax::{
 };
 
 use crate::{
-    doc_links::{doc_attributes, extract_definitions_from_docs, resolve_doc_path_for_def},
+    doc_links::doc_attributes,
     syntax_highlighting::{highlights::Highlights, injector::Injector, HighlightConfig},
     Analysis, HlMod, HlRange, HlTag, RootDatabase,
 };
@@ -119,14 +119,14 @@ pub(super) fn doc_comment(
 
     // Extract intra-doc links and emit highlights for them.
     if let Some((docs, doc_mapping)) = attributes.docs_with_rangemap(sema.db) {
-        extract_definitions_from_docs(&docs)
+        extract_links_from_docs(&docs)
             .into_iter()
-            .filter_map(|(range, link, ns)| {
-                doc_mapping.map(range).filter(|mapping| mapping.file_id == src_file_id).and_then(
-                    |InFile { value: mapped_range, .. }| {
-                        Some(mapped_range).zip(resolve_doc_path_for_def(sema.db, def, &link, ns))
-                    },
-                )
+            .filter_map(|link| {
+                let InFile { value: range, .. } =
+                    doc_mapping.map(link.range).filter(|mapping| mapping.file_id == src_file_id)?;
+                // unresolved links stay highlighted as plain documentation
+                let def = link.resolve(sema.db, def)?;
+                Some((range, def))
             })
             .for_each(|(range, def)| {
                 hl.add(HlRange {
@@ -159,16 +159,23 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
 }
 
 fn completions_resolve_provider(client_caps: &ClientCapabilities) -> Option<bool> {
-    if completion_item_edit_resolve(client_caps) {
-        Some(true)
-    } else {
+    let edit_resolve = completion_item_edit_resolve(client_caps);
+    if !edit_resolve {
         tracing::info!("No `additionalTextEdits` completion resolve capability was found in the client capabilities, autoimport completion is disabled");
-        None
     }
+    (edit_resolve || completion_item_documentation_resolve(client_caps)).then_some(true)
 }
 
-/// Parses client capabilities and returns all completion resolve capabilities rust-analyzer supports.
 pub(crate) fn completion_item_edit_resolve(caps: &ClientCapabilities) -> bool {
+    completion_item_resolve(caps, "additionalTextEdits")
+}
+
+pub(crate) fn completion_item_documentation_resolve(caps: &ClientCapabilities) -> bool {
+    completion_item_resolve(caps, "documentation")
+}
+
+/// Checks whether the client can lazily resolve the given completion item property.
+fn completion_item_resolve(caps: &ClientCapabilities, property: &str) -> bool {
     (|| {
         Some(
             caps.text_document
@@ -181,7 +188,7 @@ pub(crate) fn completion_item_edit_resolve(caps: &ClientCapabilities) -> bool {
                 .as_ref()?
                 .properties
                 .iter()
-                .any(|cap_string| cap_string.as_str() == "additionalTextEdits"),
+                .any(|cap_string| cap_string.as_str() == property),
         )
     })() == Some(true)
 }
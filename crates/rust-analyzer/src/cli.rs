@@ -7,6 +7,7 @@ mod symbols;
 mod highlight;
 mod analysis_stats;
 mod diagnostics;
+mod doc_links;
 mod ssr;
 mod lsif;
 mod scip;
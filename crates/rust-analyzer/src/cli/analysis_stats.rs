@@ -3,12 +3,12 @@
 
 use std::{
     env,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 use hir::{
     db::{DefDatabase, ExpandDatabase, HirDatabase},
-    AssocItem, Crate, Function, HasSource, HirDisplay, ModuleDef,
+    AssocItem, Crate, Function, HasSource, HirDisplay, Module, ModuleDef,
 };
 use hir_def::{
     body::{BodySourceMap, SyntheticSyntax},
@@ -16,7 +16,7 @@ use hir_def::{
     FunctionId,
 };
 use hir_ty::{Interner, Substitution, TyExt, TypeFlags};
-use ide::{Analysis, AnalysisHost, LineCol, RootDatabase};
+use ide::{full_name, Analysis, AnalysisHost, LineCol, ResolutionError, RootDatabase};
 use ide_db::base_db::{
     salsa::{self, debug::DebugQueryTable, ParallelDatabase},
     SourceDatabase, SourceDatabaseExt,
@@ -26,12 +26,13 @@ use oorandom::Rand32;
 use profile::{Bytes, StopWatch};
 use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace, RustLibSource};
 use rayon::prelude::*;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::format_to;
 use syntax::{AstNode, SyntaxNode};
 use vfs::{AbsPathBuf, Vfs, VfsPath};
 
 use crate::cli::{
+    doc_links::doc_owners,
     flags::{self, OutputFormat},
     load_cargo::{load_workspace, LoadCargoConfig, ProcMacroServerChoice},
     print_memory_usage,
@@ -165,6 +166,10 @@ impl flags::AnalysisStats {
         self.run_data_layout(db, &adts, verbosity);
         self.run_const_eval(db, &consts, verbosity);
 
+        if self.doc_links {
+            self.run_doc_links(&host, db, &visited_modules, verbosity);
+        }
+
         let total_span = analysis_sw.elapsed();
         eprintln!("{:<20} {total_span}", "Total:");
         report_metric("total time", total_span.time.as_millis() as u64, "ms");
@@ -258,6 +263,96 @@ impl flags::AnalysisStats {
         report_metric("failed const evals", fail, "#");
     }
 
+    fn run_doc_links(
+        &self,
+        host: &AnalysisHost,
+        db: &RootDatabase,
+        modules: &FxHashSet<Module>,
+        verbosity: Verbosity,
+    ) {
+        let mut sw = self.stop_watch();
+        let documented = doc_owners(db, modules.iter().copied())
+            .into_iter()
+            .filter_map(|def| Some((def, def.attrs(db)?.docs()?)))
+            .collect::<Vec<_>>();
+
+        #[derive(Default)]
+        struct Counts {
+            all: u64,
+            resolved: u64,
+            unresolved: u64,
+            skipped: u64,
+            ambiguous: u64,
+            urls: u64,
+        }
+        let mut total = Counts::default();
+        let mut per_crate = FxHashMap::<Option<Crate>, Counts>::default();
+        let mut slowest = Vec::new();
+        let analysis = host.analysis();
+        for (def, docs) in &documented {
+            let start = Instant::now();
+            let Ok(links) = analysis.resolve_doc_links(*def, docs.as_str()) else { continue };
+            slowest.push((start.elapsed(), *def));
+
+            let counts = per_crate.entry(def.krate(db)).or_default();
+            for link in links {
+                let skipped = link.error == Some(ResolutionError::BudgetExceeded);
+                for counts in [&mut total, &mut *counts] {
+                    counts.all += 1;
+                    match (&link.definition, &link.url) {
+                        (Some(_), _) => counts.resolved += 1,
+                        (None, Some(_)) => counts.urls += 1,
+                        (None, None) if skipped => counts.skipped += 1,
+                        (None, None) => counts.unresolved += 1,
+                    }
+                    counts.ambiguous += link.ambiguous as u64;
+                }
+                match &link.error {
+                    Some(error) if verbosity.is_spammy() && !skipped => {
+                        let item = full_name(db, *def);
+                        println!("Unresolved doc link `{}` in {item}: {error}", link.text);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let print_counts = |name: &str, counts: &Counts| {
+            eprintln!(
+                "  {name}: links: {}, resolved: {} ({}%), unresolved: {} ({}%), over budget: {}, ambiguous: {}, urls: {}",
+                counts.all,
+                counts.resolved,
+                percentage(counts.resolved, counts.all),
+                counts.unresolved,
+                percentage(counts.unresolved, counts.all),
+                counts.skipped,
+                counts.ambiguous,
+                counts.urls,
+            )
+        };
+        eprintln!("{:<20} {}", "Doc links:", sw.elapsed());
+        eprintln!("  documented items: {}", documented.len());
+        print_counts("total", &total);
+        let mut per_crate = per_crate
+            .into_iter()
+            .map(|(krate, counts)| {
+                let name = krate.and_then(|it| it.display_name(db));
+                (name.map_or_else(|| "<unnamed>".to_owned(), |it| it.to_string()), counts)
+            })
+            .collect::<Vec<_>>();
+        per_crate.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (name, counts) in &per_crate {
+            print_counts(name, counts);
+        }
+        slowest.sort_by(|(a, _), (b, _)| b.cmp(a));
+        eprintln!("  slowest documents:");
+        for (time, def) in slowest.iter().take(5) {
+            eprintln!("    {}: {time:?}", full_name(db, *def));
+        }
+        report_metric("unresolved doc links", total.unresolved, "#");
+        report_metric("ambiguous doc links", total.ambiguous, "#");
+    }
+
     fn run_mir_lowering(&self, db: &RootDatabase, funcs: &[Function], verbosity: Verbosity) {
         let mut sw = self.stop_watch();
         let all = funcs.len() as u64;
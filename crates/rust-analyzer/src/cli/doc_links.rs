@@ -0,0 +1,248 @@
+//! Check the intra-doc links in the documentation of a project and list the broken ones. Exits
+//! with a non-zero status code if any are found.
+
+use std::iter;
+
+use cfg::DnfExpr;
+use hir::{Adt, AsAssocItem, AssocItemContainer, Module, ModuleDef, Namespace};
+use ide::{full_name, LineCol, RootDatabase};
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::Definition,
+    doc_links::{intra_doc_links::FunctionKind, DocLinkResolutionFailure},
+    LineIndexDatabase,
+};
+use itertools::Itertools;
+use project_model::{CargoConfig, CargoFeatures, RustLibSource};
+use serde_json::json;
+
+use crate::cli::{
+    flags::{self, DocLinksFormat},
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+};
+
+impl flags::DocLinks {
+    pub fn run(self) -> anyhow::Result<()> {
+        let features =
+            if self.all_features { CargoFeatures::All } else { CargoFeatures::default() };
+        let cargo_config =
+            CargoConfig { sysroot: Some(RustLibSource::Discover), features, ..Default::default() };
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let modules = hir::Crate::all(db).into_iter().flat_map(|krate| {
+            let root = krate.root_module(db);
+            let file_id = root.definition_source(db).file_id.original_file(db);
+            let is_library = db.source_root(db.file_source_root(file_id)).is_library;
+            if is_library {
+                Vec::new()
+            } else {
+                iter::successors(Some(vec![root]), |modules| {
+                    let children = modules.iter().flat_map(|it| it.children(db)).collect_vec();
+                    (!children.is_empty()).then_some(children)
+                })
+                .flatten()
+                .collect()
+            }
+        });
+
+        let mut num_broken = 0;
+        for def in doc_owners(db, modules) {
+            if !self.include_hidden && is_doc_hidden(db, def) {
+                continue;
+            }
+            for link in analysis.broken_doc_links(def)? {
+                num_broken += 1;
+                let item = full_name(db, def);
+                let message = failure_message(&link.target, &link.reason);
+                let file = link.range.map(|it| (vfs.file_path(it.file_id).to_string(), it));
+                match self.format.unwrap_or(DocLinksFormat::Text) {
+                    DocLinksFormat::Text => {
+                        let location = match &file {
+                            Some((path, range)) => {
+                                let LineCol { line, col } =
+                                    db.line_index(range.file_id).line_col(range.range.start());
+                                format!("{path}:{}:{}", line + 1, col + 1)
+                            }
+                            None => "<unknown>".to_owned(),
+                        };
+                        println!("{location}: in the docs of `{item}`: {message}");
+                    }
+                    DocLinksFormat::Json => {
+                        let record = json!({
+                            "file": file.as_ref().map(|(path, _)| path),
+                            "range": file.as_ref().map(|(_, it)| json!({
+                                "start": u32::from(it.range.start()),
+                                "end": u32::from(it.range.end()),
+                            })),
+                            "item": item,
+                            "target": link.target,
+                            "reason": failure_kind(&link.reason),
+                            "message": message,
+                        });
+                        println!("{record}");
+                    }
+                }
+            }
+        }
+
+        if self.coverage {
+            for crate_id in analysis.workspace_crates()? {
+                let name = analysis.crate_display_name(crate_id)?;
+                let name = name.as_deref().unwrap_or("<unnamed>");
+                let coverage = analysis.doc_coverage(crate_id)?;
+                match self.format.unwrap_or(DocLinksFormat::Text) {
+                    DocLinksFormat::Text => println!(
+                        "{name}: {}/{} public items documented, {}/{} intra-doc links broken",
+                        coverage.documented, coverage.items, coverage.broken_links, coverage.links
+                    ),
+                    DocLinksFormat::Json => {
+                        let record = json!({
+                            "crate": name,
+                            "items": coverage.items,
+                            "documented": coverage.documented,
+                            "links": coverage.links,
+                            "broken_links": coverage.broken_links,
+                        });
+                        println!("{record}");
+                    }
+                }
+            }
+        }
+
+        if num_broken > 0 {
+            anyhow::bail!("found {num_broken} broken doc links")
+        }
+        Ok(())
+    }
+}
+
+/// The items in `modules` that can be documented, including the modules themselves.
+pub(crate) fn doc_owners(
+    db: &RootDatabase,
+    modules: impl IntoIterator<Item = Module>,
+) -> Vec<Definition> {
+    let mut res = Vec::new();
+    for module in modules {
+        res.push(Definition::Module(module));
+        for decl in module.declarations(db) {
+            match decl {
+                // visited as one of the modules
+                ModuleDef::Module(_) | ModuleDef::BuiltinType(_) => {}
+                ModuleDef::Adt(it) => {
+                    res.push(it.into());
+                    let fields = match it {
+                        Adt::Struct(it) => it.fields(db),
+                        Adt::Union(it) => it.fields(db),
+                        Adt::Enum(it) => it
+                            .variants(db)
+                            .into_iter()
+                            .flat_map(|variant| {
+                                res.push(variant.into());
+                                variant.fields(db)
+                            })
+                            .collect(),
+                    };
+                    res.extend(fields.into_iter().map(Definition::from));
+                }
+                ModuleDef::Trait(it) => {
+                    res.push(it.into());
+                    res.extend(it.items(db).into_iter().map(Definition::from));
+                }
+                _ => res.push(decl.into()),
+            }
+        }
+        for impl_def in module.impl_defs(db) {
+            res.extend(impl_def.items(db).into_iter().map(Definition::from));
+        }
+    }
+    res
+}
+
+/// The item containing `def` if it is a field, variant or associated item.
+fn parent(db: &RootDatabase, def: Definition) -> Option<Definition> {
+    match def {
+        Definition::Field(it) => Some(match it.parent_def(db) {
+            hir::VariantDef::Struct(it) => it.into(),
+            hir::VariantDef::Union(it) => it.into(),
+            hir::VariantDef::Variant(it) => it.into(),
+        }),
+        Definition::Variant(it) => Some(Adt::from(it.parent_enum(db)).into()),
+        _ => match def.as_assoc_item(db)?.container(db) {
+            AssocItemContainer::Trait(it) => Some(it.into()),
+            AssocItemContainer::Impl(it) => Some(it.into()),
+        },
+    }
+}
+
+/// Whether `def`, its parent or one of the modules containing it is `#[doc(hidden)]`.
+fn is_doc_hidden(db: &RootDatabase, def: Definition) -> bool {
+    let is_hidden = |def: Definition| def.attrs(db).map_or(false, |it| it.has_doc_hidden());
+    let items = iter::successors(Some(def), |&it| parent(db, it)).collect_vec();
+    let module = match def {
+        Definition::Module(it) => it.parent(db),
+        _ => items.last().and_then(|it| it.module(db)),
+    };
+    items.into_iter().any(is_hidden)
+        || iter::successors(module, |it| it.parent(db)).any(|it| is_hidden(it.into()))
+}
+
+fn failure_kind(reason: &DocLinkResolutionFailure) -> &'static str {
+    match reason {
+        DocLinkResolutionFailure::NotInScope => "not-in-scope",
+        DocLinkResolutionFailure::NoSuchItem { .. } => "no-such-item",
+        DocLinkResolutionFailure::QualifierNotAType { .. } => "qualifier-not-a-type",
+        DocLinkResolutionFailure::WrongNamespace { .. } => "wrong-namespace",
+        DocLinkResolutionFailure::WrongFunctionKind { .. } => "wrong-function-kind",
+        DocLinkResolutionFailure::Ambiguous(_) => "ambiguous",
+        DocLinkResolutionFailure::Inactive(_) => "inactive",
+        DocLinkResolutionFailure::DocAlias { .. } => "doc-alias",
+    }
+}
+
+fn failure_message(target: &str, reason: &DocLinkResolutionFailure) -> String {
+    let ns_name = |ns: &Namespace| match ns {
+        Namespace::Types => "a type",
+        Namespace::Values => "a value",
+        Namespace::Macros => "a macro",
+    };
+    match reason {
+        DocLinkResolutionFailure::NotInScope => format!("no item named `{target}` is in scope"),
+        DocLinkResolutionFailure::NoSuchItem { resolved, segment } => {
+            format!("`{resolved}` has no item named `{segment}`")
+        }
+        DocLinkResolutionFailure::QualifierNotAType { qualifier } => {
+            format!("`{qualifier}` is not a type or module")
+        }
+        DocLinkResolutionFailure::WrongNamespace { expected } => {
+            format!("`{target}` is not {}", ns_name(expected))
+        }
+        DocLinkResolutionFailure::WrongFunctionKind { expected } => match expected {
+            FunctionKind::Free => format!("`{target}` is not a free function"),
+            FunctionKind::Associated => format!("`{target}` is not a method"),
+        },
+        DocLinkResolutionFailure::Ambiguous(namespaces) => {
+            format!(
+                "`{target}` is ambiguous, it names {}",
+                namespaces.iter().map(ns_name).join(" and ")
+            )
+        }
+        DocLinkResolutionFailure::Inactive(item) => {
+            format!("`{target}` is only enabled with `#[cfg({})]`", DnfExpr::new(item.cfg.clone()))
+        }
+        DocLinkResolutionFailure::DocAlias { name, .. } => {
+            let suggestion = match target.rsplit_once("::") {
+                Some((qualifier, _)) => format!("{qualifier}::{name}"),
+                None => name.clone(),
+            };
+            format!("`{target}` is a doc alias, link to `{suggestion}` instead")
+        }
+    }
+}
@@ -68,6 +68,8 @@ xflags::xflags! {
             optional --source-stats
             /// Only type check, skip lowering to mir
             optional --skip-mir-stats
+            /// Resolve the links in the documentation of all items and print how many resolve.
+            optional --doc-links
 
             /// Only analyze items matching this path.
             optional -o, --only path: String
@@ -94,6 +96,25 @@ xflags::xflags! {
             optional --disable-proc-macros
         }
 
+        /// Check the intra-doc links of a project and list the broken ones, exits with a non-zero
+        /// status code if there are any.
+        cmd doc-links {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+
+            /// Output format, `text` or `json` for one JSON object per line.
+            optional --format format: DocLinksFormat
+            /// Also check items hidden with `#[doc(hidden)]`.
+            optional --include-hidden
+            /// Activate all cargo features, checking the items behind them as well.
+            optional --all-features
+            /// Also print how much of the public API of each workspace crate is documented.
+            optional --coverage
+
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+        }
+
         cmd ssr {
             /// A structured search replace rule (`$a.foo($b) ==> bar($a, $b)`)
             repeated rule: SsrRule
@@ -137,6 +158,7 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     Diagnostics(Diagnostics),
+    DocLinks(DocLinks),
     Ssr(Ssr),
     Search(Search),
     Lsif(Lsif),
@@ -172,6 +194,7 @@ pub struct AnalysisStats {
     pub memory_usage: bool,
     pub source_stats: bool,
     pub skip_mir_stats: bool,
+    pub doc_links: bool,
     pub only: Option<String>,
     pub with_deps: bool,
     pub no_sysroot: bool,
@@ -188,6 +211,17 @@ pub struct Diagnostics {
     pub disable_proc_macros: bool,
 }
 
+#[derive(Debug)]
+pub struct DocLinks {
+    pub path: PathBuf,
+
+    pub format: Option<DocLinksFormat>,
+    pub include_hidden: bool,
+    pub all_features: bool,
+    pub coverage: bool,
+    pub disable_build_scripts: bool,
+}
+
 #[derive(Debug)]
 pub struct Ssr {
     pub rule: Vec<SsrRule>,
@@ -233,6 +267,12 @@ pub enum OutputFormat {
     Csv,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocLinksFormat {
+    Text,
+    Json,
+}
+
 impl RustAnalyzer {
     pub fn verbosity(&self) -> Verbosity {
         if self.quiet {
@@ -256,3 +296,15 @@ impl FromStr for OutputFormat {
         }
     }
 }
+
+impl FromStr for DocLinksFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown output format `{s}`")),
+        }
+    }
+}
@@ -162,8 +162,9 @@ impl LsifManager<'_> {
             let hover_id = self.add_vertex(lsif::Vertex::HoverResult {
                 result: lsp_types::Hover {
                     contents: lsp_types::HoverContents::Markup(to_proto::markup_content(
-                        hover.markup,
+                        hover,
                         ide::HoverDocFormat::Markdown,
+                        None,
                     )),
                     range: None,
                 },
@@ -173,6 +174,7 @@ impl LsifManager<'_> {
                 out_v: result_set_id.into(),
             }));
         }
+        let mut symbol_moniker = None;
         if let Some(moniker) = token.moniker {
             let package_id = self.get_package_id(moniker.package_information);
             let moniker_id = self.add_vertex(lsif::Vertex::Moniker(lsp_types::Moniker {
@@ -192,6 +194,24 @@ impl LsifManager<'_> {
                 in_v: moniker_id.into(),
                 out_v: result_set_id.into(),
             }));
+            symbol_moniker = Some(moniker_id);
+        }
+        if let Some(url) = token.docs_url {
+            // the documentation page is another name of the symbol, following its moniker
+            let docs_id = self.add_vertex(lsif::Vertex::Moniker(lsp_types::Moniker {
+                scheme: "url".to_string(),
+                identifier: url,
+                unique: lsp_types::UniquenessLevel::Global,
+                kind: None,
+            }));
+            let edge = lsif::EdgeData {
+                in_v: docs_id.into(),
+                out_v: symbol_moniker.unwrap_or(result_set_id).into(),
+            };
+            self.add_edge(match symbol_moniker {
+                Some(_) => lsif::Edge::NextMoniker(edge),
+                None => lsif::Edge::Moniker(edge),
+            });
         }
         if let Some(def) = token.definition {
             let result_id = self.add_vertex(lsif::Vertex::DefinitionResult);
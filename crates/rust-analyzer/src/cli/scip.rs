@@ -121,12 +121,14 @@ impl flags::Scip {
                         let documentation = token
                             .hover
                             .as_ref()
-                            .map(|hover| hover.markup.as_str())
+                            .map(|hover| hover.as_str())
                             .filter(|it| !it.is_empty())
-                            .map(|it| vec![it.to_owned()]);
+                            .map(|it| it.to_owned());
+                        let docs_url =
+                            token.docs_url.as_ref().map(|url| format!("[Documentation]({url})"));
                         let symbol_info = scip_types::SymbolInformation {
                             symbol: symbol.clone(),
-                            documentation: documentation.unwrap_or_default(),
+                            documentation: documentation.into_iter().chain(docs_url).collect(),
                             relationships: Vec::new(),
                             special_fields: Default::default(),
                         };
@@ -11,9 +11,10 @@ use std::{fmt, iter, ops::Not, path::PathBuf};
 
 use flycheck::FlycheckConfig;
 use ide::{
-    AssistConfig, CallableSnippets, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode,
-    HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayHintsConfig,
-    JoinLinesConfig, Snippet, SnippetScope,
+    AssistConfig, BrokenLinksMode, CallableSnippets, CompletionConfig, DiagnosticsConfig,
+    DocLinkOptions, DocLinksMode, ExprFillDefaultMode, HighlightConfig, HighlightRelatedConfig,
+    HoverConfig, HoverDocFormat, InlayHintsConfig, JoinLinesConfig, PageLinksMode, Snippet,
+    SnippetScope,
 };
 use ide_db::{
     imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -30,7 +31,7 @@ use serde::{de::DeserializeOwned, Deserialize};
 use vfs::{AbsPath, AbsPathBuf};
 
 use crate::{
-    caps::completion_item_edit_resolve,
+    caps::{completion_item_documentation_resolve, completion_item_edit_resolve},
     diagnostics::DiagnosticsMapConfig,
     line_index::PositionEncoding,
     lsp_ext::{self, negotiated_encoding, WorkspaceSymbolSearchKind, WorkspaceSymbolSearchScope},
@@ -293,6 +294,9 @@ config_data! {
         /// Whether to show `Debug` action. Only applies when
         /// `#rust-analyzer.hover.actions.enable#` is set.
         hover_actions_debug_enable: bool           = "true",
+        /// Whether to show `Open Docs` action. Only applies when
+        /// `#rust-analyzer.hover.actions.enable#` is set.
+        hover_actions_docs_enable: bool            = "true",
         /// Whether to show HoverActions in Rust files.
         hover_actions_enable: bool          = "true",
         /// Whether to show `Go to Type Definition` action. Only applies when
@@ -307,14 +311,62 @@ config_data! {
         /// Whether to show `Run` action. Only applies when
         /// `#rust-analyzer.hover.actions.enable#` is set.
         hover_actions_run_enable: bool             = "true",
+        /// Whether to show `Open Source` action for library items whose package has a GitHub or
+        /// GitLab `repository`. Only applies when `#rust-analyzer.hover.actions.enable#` is set.
+        hover_actions_source_enable: bool          = "true",
 
+        /// Whether to demote the level 1 and 2 headings of the documentation shown on hover by
+        /// one level, so the `# Examples` of crate documentation don't dwarf the popup.
+        hover_documentation_demoteHeadings_enable: bool = "false",
         /// Whether to show documentation on hover.
         hover_documentation_enable: bool           = "true",
+        /// Whether to include the documentation of `cfg_attr`s whose condition doesn't hold in the
+        /// current configuration, like `#[cfg_attr(feature = "docs", doc = "...")]`. Only applies
+        /// when `#rust-analyzer.hover.documentation.enable#` is set.
+        hover_documentation_inactiveCfgAttrs_enable: bool = "false",
         /// Whether to show keyword hover popups. Only applies when
         /// `#rust-analyzer.hover.documentation.enable#` is set.
         hover_documentation_keywords_enable: bool  = "true",
-        /// Use markdown syntax for links on hover.
-        hover_links_enable: bool = "true",
+        /// How many characters of documentation to show on hover, longer documentation is cut
+        /// off after the last paragraph that fits. Only applies when
+        /// `#rust-analyzer.hover.documentation.enable#` is set.
+        hover_documentation_maxLength: Option<usize> = "null",
+        /// The column at which to soft-wrap the lines of paragraphs shown on hover, for clients
+        /// with narrow hover popups. Code blocks, tables, headings and link destinations are never
+        /// wrapped. `null` leaves the lines as they are.
+        hover_documentation_wrapColumn: Option<usize> = "null",
+        /// Whether to turn bare URLs in the documentation shown on hover into links. Doesn't
+        /// apply when `#rust-analyzer.hover.links.mode#` is `raw`.
+        hover_links_bareUrls_enable: bool = "true",
+        /// How to render links that could not be resolved in the documentation shown on hover and
+        /// in completion items. Only applies when `#rust-analyzer.hover.links.mode#` rewrites
+        /// links. Links to URLs are always kept, as are the links left unresolved because of
+        /// `#rust-analyzer.hover.links.resolutionBudget#`.
+        hover_links_broken: BrokenLinksModeDef = "\"keep\"",
+        /// Whether intra-doc links that name nothing resolve to the item that has their last
+        /// segment as a `#[doc(alias = "...")]`, like `[Vec::delete]` for a `remove` method with
+        /// that alias. rustdoc doesn't resolve such links.
+        hover_links_docAliases_enable: bool = "false",
+        /// Whether to end long or truncated documentation shown on hover and in completion items
+        /// with a link to the item's documentation page.
+        hover_links_fullDocs_enable: bool = "true",
+        /// Whether intra-doc links to items of workspace crates without an `html_root_url`, whose
+        /// documentation is only available locally, point to the item's source as a `file://`
+        /// URI instead of a guessed docs.rs page.
+        hover_links_localSource_enable: bool = "false",
+        /// How to render links in the documentation shown on hover and in completion items.
+        hover_links_mode: DocLinksModeDef = "\"rewrite\"",
+        /// Whether to show the name of the hovered item as a link to its documentation page.
+        /// Only applies to clients rendering markdown.
+        hover_links_nameLink_enable: bool = "false",
+        /// How to render links to rustdoc pages, like `struct.Foo.html`, in the documentation
+        /// shown on hover and in completion items. Only applies when
+        /// `#rust-analyzer.hover.links.mode#` rewrites links.
+        hover_links_pages: PageLinksModeDef = "\"rewrite\"",
+        /// How many links to resolve at most in one piece of documentation on hover and in
+        /// completion items. Documentation with more links, usually generated, shows the links
+        /// past the budget as they are, with a note saying so.
+        hover_links_resolutionBudget: usize = "500",
         /// Whether to show memory layout data on hover.
         hover_memoryLayout_enable: bool = "true",
 
@@ -391,6 +443,9 @@ config_data! {
         /// Whether to show `Debug` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
         lens_debug_enable: bool            = "true",
+        /// Whether to show `Open Docs` lens above public items. Only applies when
+        /// `#rust-analyzer.lens.enable#` is set.
+        lens_docs_enable: bool             = "false",
         /// Whether to show CodeLens in Rust files.
         lens_enable: bool           = "true",
         /// Internal config: use custom client-side commands even when the
@@ -448,6 +503,8 @@ config_data! {
 
         /// Exclude imports from find-all-references.
         references_excludeImports: bool = "false",
+        /// Include intra-doc links in doc comments in find-all-references.
+        references_includeDocLinks: bool = "false",
 
         /// Command to be executed instead of 'cargo' for runnables.
         runnables_command: Option<String> = "null",
@@ -593,6 +650,9 @@ pub struct LensConfig {
     pub refs_trait: bool, // for Struct, Enum, Union and Trait
     pub enum_variant_refs: bool,
 
+    // documentation
+    pub docs: bool,
+
     // annotations
     pub location: AnnotationLocation,
 }
@@ -622,6 +682,7 @@ impl LensConfig {
             || self.refs_adt
             || self.refs_trait
             || self.enum_variant_refs
+            || self.docs
     }
 
     pub fn none(&self) -> bool {
@@ -644,6 +705,8 @@ pub struct HoverActionsConfig {
     pub run: bool,
     pub debug: bool,
     pub goto_type_def: bool,
+    pub docs: bool,
+    pub source: bool,
 }
 
 impl HoverActionsConfig {
@@ -653,10 +716,17 @@ impl HoverActionsConfig {
         run: false,
         debug: false,
         goto_type_def: false,
+        docs: false,
+        source: false,
     };
 
     pub fn any(&self) -> bool {
-        self.implementations || self.references || self.runnable() || self.goto_type_def
+        self.implementations
+            || self.references
+            || self.runnable()
+            || self.goto_type_def
+            || self.docs
+            || self.source
     }
 
     pub fn none(&self) -> bool {
@@ -717,6 +787,8 @@ pub struct ClientCommandsConfig {
     pub show_reference: bool,
     pub goto_location: bool,
     pub trigger_parameter_hints: bool,
+    pub open_docs_url: bool,
+    pub copy_to_clipboard: bool,
 }
 
 #[derive(Debug)]
@@ -1016,6 +1088,56 @@ impl Config {
         )
     }
 
+    pub fn signature_help_markdown(&self) -> bool {
+        try_or_def!(self
+            .caps
+            .text_document
+            .as_ref()?
+            .signature_help
+            .as_ref()?
+            .signature_information
+            .as_ref()?
+            .documentation_format
+            .as_ref()?
+            .as_slice())
+        .contains(&MarkupKind::Markdown)
+    }
+
+    pub fn doc_links_mode(&self) -> DocLinksMode {
+        match self.data.hover_links_mode {
+            DocLinksModeDef::Rewrite => DocLinksMode::Rewrite,
+            DocLinksModeDef::Strip => DocLinksMode::Strip,
+            DocLinksModeDef::Raw => DocLinksMode::Raw,
+            DocLinksModeDef::Navigate if self.experimental("sourceDocLinks") => {
+                DocLinksMode::Navigate
+            }
+            DocLinksModeDef::Navigate => DocLinksMode::Rewrite,
+        }
+    }
+
+    pub fn doc_link_options(&self) -> DocLinkOptions {
+        let page_links = match self.data.hover_links_pages {
+            PageLinksModeDef::Rewrite => PageLinksMode::Rewrite,
+            PageLinksModeDef::Ignore => PageLinksMode::Ignore,
+            PageLinksModeDef::Strip => PageLinksMode::Strip,
+        };
+        let broken_links = match self.data.hover_links_broken {
+            BrokenLinksModeDef::Keep => BrokenLinksMode::Keep,
+            BrokenLinksModeDef::Strip => BrokenLinksMode::Strip,
+            BrokenLinksModeDef::StripWithAmbiguous => BrokenLinksMode::StripWithAmbiguous,
+        };
+        DocLinkOptions::default()
+            .with_mode(self.doc_links_mode())
+            .with_page_links(page_links)
+            .with_full_docs_link(self.data.hover_links_fullDocs_enable)
+            .with_source_fallback(self.data.hover_links_localSource_enable)
+            .with_broken_links(broken_links)
+    }
+
+    pub fn completion_resolve_docs(&self) -> bool {
+        completion_item_documentation_resolve(&self.caps)
+    }
+
     pub fn completion_label_details_support(&self) -> bool {
         try_!(self
             .caps
@@ -1119,6 +1241,18 @@ impl Config {
         self.data.lru_query_capacities.is_empty().not().then(|| &self.data.lru_query_capacities)
     }
 
+    pub fn hover_wrap_column(&self) -> Option<usize> {
+        self.data.hover_documentation_wrapColumn
+    }
+
+    pub fn doc_link_resolution_budget(&self) -> usize {
+        self.data.hover_links_resolutionBudget
+    }
+
+    pub fn doc_link_alias_resolution(&self) -> bool {
+        self.data.hover_links_docAliases_enable
+    }
+
     pub fn proc_macro_srv(&self) -> Option<AbsPathBuf> {
         let path = self.data.procMacro_server.clone()?;
         Some(AbsPathBuf::try_from(path).unwrap_or_else(|path| self.root_path.join(&path)))
@@ -1408,6 +1542,10 @@ impl Config {
         self.data.references_excludeImports
     }
 
+    pub fn find_all_refs_include_doc_links(&self) -> bool {
+        self.data.references_includeDocLinks
+    }
+
     pub fn snippet_cap(&self) -> bool {
         self.experimental("snippetTextEdit")
     }
@@ -1419,6 +1557,7 @@ impl Config {
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
             assist_emit_must_use: self.data.assist_emitMustUse,
+            copy_to_clipboard: self.client_commands().copy_to_clipboard,
         }
     }
 
@@ -1451,6 +1590,7 @@ impl Config {
             refs_trait: self.data.lens_enable && self.data.lens_references_trait_enable,
             enum_variant_refs: self.data.lens_enable
                 && self.data.lens_references_enumVariant_enable,
+            docs: self.data.lens_enable && self.data.lens_docs_enable,
             location: self.data.lens_location,
         }
     }
@@ -1463,6 +1603,8 @@ impl Config {
             run: enable && self.data.hover_actions_run_enable,
             debug: enable && self.data.hover_actions_debug_enable,
             goto_type_def: enable && self.data.hover_actions_gotoTypeDef_enable,
+            docs: enable && self.data.hover_actions_docs_enable,
+            source: enable && self.data.hover_actions_source_enable,
         }
     }
 
@@ -1487,7 +1629,7 @@ impl Config {
 
     pub fn hover(&self) -> HoverConfig {
         HoverConfig {
-            links_in_hover: self.data.hover_links_enable,
+            links: self.doc_link_options(),
             memory_layout: self.data.hover_memoryLayout_enable,
             documentation: self.data.hover_documentation_enable,
             format: {
@@ -1508,6 +1650,12 @@ impl Config {
                 }
             },
             keywords: self.data.hover_documentation_keywords_enable,
+            max_doc_length: self.data.hover_documentation_maxLength,
+            inactive_cfg_docs: self.data.hover_documentation_inactiveCfgAttrs_enable,
+            name_link: self.data.hover_links_nameLink_enable,
+            linkify_urls: self.data.hover_links_bareUrls_enable,
+            demote_headings: self.data.hover_documentation_demoteHeadings_enable,
+            original_markup: self.experimental("hoverOriginalMarkup"),
         }
     }
 
@@ -1568,6 +1716,8 @@ impl Config {
             show_reference: get("rust-analyzer.showReferences"),
             goto_location: get("rust-analyzer.gotoLocation"),
             trigger_parameter_hints: get("editor.action.triggerParameterHints"),
+            open_docs_url: get("rust-analyzer.openDocsUrl"),
+            copy_to_clipboard: get("rust-analyzer.copyToClipboard"),
         }
     }
 
@@ -1783,6 +1933,31 @@ enum ImportGranularityDef {
     Module,
 }
 
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+enum DocLinksModeDef {
+    Rewrite,
+    Strip,
+    Raw,
+    Navigate,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+enum PageLinksModeDef {
+    Rewrite,
+    Ignore,
+    Strip,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+enum BrokenLinksModeDef {
+    Keep,
+    Strip,
+    StripWithAmbiguous,
+}
+
 #[derive(Deserialize, Debug, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 enum CallableCompletionDef {
@@ -2261,6 +2436,34 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 { "type": "null" }
             ],
         },
+        "DocLinksModeDef" => set! {
+            "type": "string",
+            "enum": ["rewrite", "strip", "raw", "navigate"],
+            "enumDescriptions": [
+                "Rewrite intra-doc links to point to the online documentation.",
+                "Remove links, keeping their text.",
+                "Leave the documentation markdown untouched.",
+                "Like `rewrite`, but links to items defined in the workspace open their source in the editor."
+            ]
+        },
+        "PageLinksModeDef" => set! {
+            "type": "string",
+            "enum": ["rewrite", "ignore", "strip"],
+            "enumDescriptions": [
+                "Rewrite links to rustdoc pages to point to the online documentation.",
+                "Leave links to rustdoc pages as written.",
+                "Remove links to rustdoc pages, keeping their text."
+            ]
+        },
+        "BrokenLinksModeDef" => set! {
+            "type": "string",
+            "enum": ["keep", "strip", "strip_with_ambiguous"],
+            "enumDescriptions": [
+                "Keep links that could not be resolved as written.",
+                "Remove links that could not be resolved, keeping their text.",
+                "Like `strip`, also removing ambiguous intra-doc links, which resolve to one of the items they name."
+            ]
+        },
         "CallableCompletionDef" => set! {
             "type": "string",
             "enum": [
@@ -72,6 +72,12 @@ pub(super) fn patch_json_for_outdated_configs(json: &mut Value) {
         rustfmt.enableRangeFormatting -> rustfmt.rangeFormatting.enable;
     }
 
+    // hover_links_enable -> hover_links_mode
+    let links_disabled = matches!(json.pointer("/hover/links/enable"), Some(Value::Bool(false)));
+    if links_disabled && copy.pointer("/hover/links/mode").is_none() {
+        merge(json, json!({ "hover": { "links": { "mode": "strip" } } }));
+    }
+
     // completion.snippets -> completion.snippets.custom;
     if let Some(Value::Object(obj)) = copy.pointer("/completion/snippets").cloned() {
         if obj.len() != 1 || obj.get("custom").is_none() {
@@ -168,3 +174,17 @@ fn check_on_save_patching_enable() {
         json!({ "checkOnSave": true, "check": { "enable": true, "overrideCommand": "foo" }})
     );
 }
+
+#[test]
+fn hover_links_enable_patching() {
+    let mut json = json!({ "hover": { "linksInHover": false }});
+    patch_json_for_outdated_configs(&mut json);
+    assert_eq!(
+        json,
+        json!({ "hover": { "linksInHover": false, "links": { "enable": false, "mode": "strip" } }})
+    );
+
+    let mut json = json!({ "hover": { "links": { "enable": false, "mode": "raw" } }});
+    patch_json_for_outdated_configs(&mut json);
+    assert_eq!(json, json!({ "hover": { "links": { "enable": false, "mode": "raw" } }}));
+}
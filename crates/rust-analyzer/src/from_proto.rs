@@ -134,6 +134,18 @@ pub(crate) fn annotation(
                 kind: AnnotationKind::HasReferences { pos, data: None },
             })
         }
+        lsp_ext::CodeLensResolveDataKind::Docs(params) => {
+            if snap.url_file_version(&params.text_document.uri) != Some(resolve.version) {
+                return Ok(None);
+            }
+            let pos @ FilePosition { file_id, .. } = file_position(snap, params)?;
+            let line_index = snap.file_line_index(file_id)?;
+
+            Ok(Annotation {
+                range: text_range(&line_index, code_lens.range)?,
+                kind: AnnotationKind::HasDocs { pos, data: None },
+            })
+        }
     }
     .map(Some)
 }
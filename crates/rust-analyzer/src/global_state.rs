@@ -146,6 +146,8 @@ impl GlobalState {
         if let Some(capacities) = config.lru_query_capacities() {
             analysis_host.update_lru_capacities(capacities);
         }
+        analysis_host.update_doc_link_resolution_budget(config.doc_link_resolution_budget());
+        analysis_host.update_doc_link_alias_resolution(config.doc_link_alias_resolution());
         let (flycheck_sender, flycheck_receiver) = unbounded();
         let mut this = GlobalState {
             sender,
@@ -9,9 +9,9 @@ use std::{
 
 use anyhow::Context;
 use ide::{
-    AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, FilePosition, FileRange,
-    HoverAction, HoverGotoTypeData, Query, RangeInfo, ReferenceCategory, Runnable, RunnableKind,
-    SingleResolve, SourceChange, TextEdit,
+    AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, DocLinksMode, Documentation,
+    FilePosition, FileRange, HoverAction, HoverGotoTypeData, Query, RangeInfo, ReferenceCategory,
+    Runnable, RunnableKind, SingleResolve, SourceChange, TextEdit,
 };
 use ide_db::SymbolKind;
 use lsp_server::ErrorCode;
@@ -844,7 +844,7 @@ pub(crate) fn handle_completion(
         params.context.and_then(|ctx| ctx.trigger_character).and_then(|s| s.chars().next());
 
     let completion_config = &snap.config.completion();
-    let items = match snap.analysis.completions(
+    let mut items = match snap.analysis.completions(
         completion_config,
         position,
         completion_trigger_character,
@@ -852,6 +852,14 @@ pub(crate) fn handle_completion(
         None => return Ok(None),
         Some(items) => items,
     };
+    // the documentation can't be resolved later, so its links are processed for the whole list
+    let links = snap.config.doc_link_options();
+    if !snap.config.completion_resolve_docs() && links.mode != DocLinksMode::Raw {
+        let docs = snap.analysis.completion_list_docs(&items, &links)?;
+        for (item, docs) in items.iter_mut().zip(docs) {
+            item.documentation = docs.map(Documentation::new);
+        }
+    }
     let line_index = snap.file_line_index(position.file_id)?;
 
     let items =
@@ -885,11 +893,33 @@ pub(crate) fn handle_completion_resolve(
     let line_index = snap.file_line_index(file_id)?;
     let offset = from_proto::offset(&line_index, resolve_data.position.position)?;
 
+    let position = FilePosition { file_id, offset };
+    let completion_config = snap.config.completion();
+
+    let doc_owner = resolve_data.doc_owner.as_deref().and_then(|it| it.parse().ok());
+    if let (Some(doc_label), Some(doc_owner)) = (&resolve_data.doc_label, doc_owner) {
+        let links = snap.config.doc_link_options();
+        if let Some(docs) = snap.analysis.resolve_completion_docs(
+            &completion_config,
+            position,
+            doc_label,
+            doc_owner,
+            &links,
+        )? {
+            original_completion.documentation =
+                Some(to_proto::documentation(Documentation::new(docs)));
+        }
+    }
+
+    if resolve_data.imports.is_empty() {
+        return Ok(original_completion);
+    }
+
     let additional_edits = snap
         .analysis
         .resolve_completion_edits(
-            &snap.config.completion(),
-            FilePosition { file_id, offset },
+            &completion_config,
+            position,
             resolve_data
                 .imports
                 .into_iter()
@@ -940,11 +970,12 @@ pub(crate) fn handle_signature_help(
 ) -> Result<Option<lsp_types::SignatureHelp>> {
     let _p = profile::span("handle_signature_help");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
-    let help = match snap.analysis.signature_help(position)? {
+    let config = snap.config.call_info();
+    let rewrite_doc_links = config.docs && snap.config.signature_help_markdown();
+    let help = match snap.analysis.signature_help(position, rewrite_doc_links)? {
         Some(it) => it,
         None => return Ok(None),
     };
-    let config = snap.config.call_info();
     let res = to_proto::signature_help(help, config, snap.config.signature_help_label_offsets());
     Ok(Some(res))
 }
@@ -960,19 +991,25 @@ pub(crate) fn handle_hover(
     };
 
     let file_range = from_proto::file_range(&snap, params.text_document, range)?;
-    let info = match snap.analysis.hover(&snap.config.hover(), file_range)? {
+    let config = snap.config.hover();
+    let info = match snap.analysis.hover(&config, file_range)? {
         None => return Ok(None),
         Some(info) => info,
     };
 
     let line_index = snap.file_line_index(file_range.file_id)?;
     let range = to_proto::range(&line_index, info.range);
-    let markup_kind = snap.config.hover().format;
+    let markup = snap.analysis.render_hover(&config, &info.info.markup)?;
+    let original = info
+        .info
+        .original
+        .map(|original| to_proto::hover_original_markup(original, snap.config.position_encoding()));
     let hover = lsp_ext::Hover {
         hover: lsp_types::Hover {
             contents: HoverContents::Markup(to_proto::markup_content(
-                info.info.markup,
-                markup_kind,
+                markup,
+                config.format,
+                snap.config.hover_wrap_column(),
             )),
             range: Some(range),
         },
@@ -981,6 +1018,7 @@ pub(crate) fn handle_hover(
         } else {
             prepare_hover_actions(&snap, &info.info.actions)
         },
+        original,
     };
 
     Ok(Some(hover))
@@ -1031,8 +1069,9 @@ pub(crate) fn handle_references(
     let position = from_proto::file_position(&snap, params.text_document_position)?;
 
     let exclude_imports = snap.config.find_all_refs_exclude_imports();
+    let include_doc_links = snap.config.find_all_refs_include_doc_links();
 
-    let refs = match snap.analysis.find_all_refs(position, None)? {
+    let refs = match snap.analysis.find_all_refs(position, None, include_doc_links)? {
         None => return Ok(None),
         Some(refs) => refs,
     };
@@ -1256,6 +1295,7 @@ pub(crate) fn handle_code_lens(
             annotate_references: lens_config.refs_adt,
             annotate_method_references: lens_config.method_refs,
             annotate_enum_variant_references: lens_config.enum_variant_refs,
+            annotate_docs: lens_config.docs,
             location: lens_config.location.into(),
         },
         file_id,
@@ -1273,7 +1313,9 @@ pub(crate) fn handle_code_lens_resolve(
     snap: GlobalStateSnapshot,
     code_lens: CodeLens,
 ) -> Result<CodeLens> {
-    let Some(annotation) = from_proto::annotation(&snap, code_lens.clone())? else { return Ok(code_lens) };
+    let Some(annotation) = from_proto::annotation(&snap, code_lens.clone())? else {
+        return Ok(code_lens);
+    };
     let annotation = snap.analysis.resolve_annotation(annotation)?;
 
     let mut acc = Vec::new();
@@ -1574,9 +1616,9 @@ pub(crate) fn handle_open_docs(
     let Ok(remote_urls) = snap.analysis.external_docs(position, target_dir, sysroot) else {
         return if snap.config.local_docs() {
             Ok(ExternalDocsResponse::WithLocal(Default::default()))
-            } else {
+        } else {
             Ok(ExternalDocsResponse::Simple(None))
-            }
+        };
     };
 
     let web = remote_urls.web_url.and_then(|it| Url::parse(&it).ok());
@@ -1589,6 +1631,16 @@ pub(crate) fn handle_open_docs(
     }
 }
 
+pub(crate) fn handle_docs_url(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<String> {
+    let _p = profile::span("handle_docs_url");
+    let position = from_proto::file_position(&snap, params)?;
+    let url = snap.analysis.web_doc_url(position)??;
+    Ok(url)
+}
+
 pub(crate) fn handle_open_cargo_toml(
     snap: GlobalStateSnapshot,
     params: lsp_ext::OpenCargoTomlParams,
@@ -1664,7 +1716,9 @@ fn show_ref_command_link(
     position: &FilePosition,
 ) -> Option<lsp_ext::CommandLinkGroup> {
     if snap.config.hover_actions().references && snap.config.client_commands().show_reference {
-        if let Some(ref_search_res) = snap.analysis.find_all_refs(*position, None).unwrap_or(None) {
+        if let Some(ref_search_res) =
+            snap.analysis.find_all_refs(*position, None, false).unwrap_or(None)
+        {
             let uri = to_proto::url(snap, position.file_id);
             let line_index = snap.file_line_index(position.file_id).ok()?;
             let position = to_proto::position(&line_index, position.offset);
@@ -1749,6 +1803,36 @@ fn goto_type_action_links(
     })
 }
 
+fn open_docs_command_link(
+    snap: &GlobalStateSnapshot,
+    url: &str,
+) -> Option<lsp_ext::CommandLinkGroup> {
+    if !snap.config.hover_actions().docs || !snap.config.client_commands().open_docs_url {
+        return None;
+    }
+
+    let command = to_proto::command::open_docs_url(url);
+    Some(lsp_ext::CommandLinkGroup {
+        commands: vec![to_command_link(command, url.to_owned())],
+        ..Default::default()
+    })
+}
+
+fn open_source_command_link(
+    snap: &GlobalStateSnapshot,
+    url: &str,
+) -> Option<lsp_ext::CommandLinkGroup> {
+    if !snap.config.hover_actions().source || !snap.config.client_commands().open_docs_url {
+        return None;
+    }
+
+    let command = to_proto::command::open_source_url(url);
+    Some(lsp_ext::CommandLinkGroup {
+        commands: vec![to_command_link(command, url.to_owned())],
+        ..Default::default()
+    })
+}
+
 fn prepare_hover_actions(
     snap: &GlobalStateSnapshot,
     actions: &[HoverAction],
@@ -1760,6 +1844,8 @@ fn prepare_hover_actions(
             HoverAction::Reference(position) => show_ref_command_link(snap, position),
             HoverAction::Runnable(r) => runnable_action_links(snap, r.clone()),
             HoverAction::GoToType(targets) => goto_type_action_links(snap, targets),
+            HoverAction::OpenDocs(url) => open_docs_command_link(snap, url),
+            HoverAction::OpenSource(url) => open_source_command_link(snap, url),
         })
         .collect()
 }
@@ -1950,6 +2036,37 @@ pub(crate) fn fetch_dependency_list(
     Ok(FetchDependencyListResult { crates: crate_infos })
 }
 
+pub(crate) fn handle_doc_coverage(
+    snap: GlobalStateSnapshot,
+    _params: lsp_ext::DocCoverageParams,
+) -> Result<Vec<lsp_ext::CrateDocCoverage>> {
+    let _p = profile::span("handle_doc_coverage");
+    let mut res = Vec::new();
+    for crate_id in snap.analysis.workspace_crates()? {
+        let coverage = snap.analysis.doc_coverage(crate_id)?;
+        res.push(lsp_ext::CrateDocCoverage {
+            name: snap.analysis.crate_display_name(crate_id)?,
+            root: to_proto::url(&snap, snap.analysis.crate_root(crate_id)?),
+            items: coverage.items,
+            documented: coverage.documented,
+            links: coverage.links,
+            broken_links: coverage.broken_links,
+        });
+    }
+    Ok(res)
+}
+
+pub(crate) fn handle_doc_links(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DocLinksParams,
+) -> Result<Vec<lsp_ext::ItemDocLinks>> {
+    let _p = profile::span("handle_doc_links");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let items = snap.analysis.file_doc_links(file_id)?;
+    Ok(items.into_iter().map(|it| to_proto::item_doc_links(&line_index, it)).collect())
+}
+
 /// Searches for the directory of a Rust crate given this crate's root file path.
 ///
 /// # Arguments
@@ -52,6 +52,97 @@ pub struct FetchDependencyListResult {
     pub crates: Vec<CrateInfoResult>,
 }
 
+pub enum DocCoverage {}
+
+impl Request for DocCoverage {
+    type Params = DocCoverageParams;
+    type Result = Vec<CrateDocCoverage>;
+    const METHOD: &'static str = "rust-analyzer/docCoverage";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DocCoverageParams {}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CrateDocCoverage {
+    pub name: Option<String>,
+    pub root: Url,
+    pub items: usize,
+    pub documented: usize,
+    pub links: usize,
+    pub broken_links: usize,
+}
+
+pub enum DocLinks {}
+
+impl Request for DocLinks {
+    type Params = DocLinksParams;
+    type Result = Vec<ItemDocLinks>;
+    const METHOD: &'static str = "rust-analyzer/docLinks";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DocLinksParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemDocLinks {
+    pub path: String,
+    pub range: Range,
+    pub links: Vec<DocLink>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocLink {
+    pub range: Option<Range>,
+    pub target_kind: DocLinkTargetKind,
+    pub url: Option<String>,
+    pub ambiguous: bool,
+    pub impl_ambiguous: bool,
+    pub candidates: Vec<DocLinkCandidate>,
+    pub error: Option<DocLinkError>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocLinkCandidate {
+    pub path: String,
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum DocLinkTargetKind {
+    Url,
+    Page,
+    Intra,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocLinkError {
+    pub kind: DocLinkErrorKind,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum DocLinkErrorKind {
+    UnparsablePath,
+    UnresolvedSegment,
+    WrongNamespace,
+    WrongFunctionKind,
+    NoDocBaseUrl,
+    UnsupportedDefinition,
+    BudgetExceeded,
+}
+
 pub enum MemoryUsage {}
 
 impl Request for MemoryUsage {
@@ -486,6 +577,30 @@ pub struct Hover {
     pub hover: lsp_types::Hover,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub actions: Vec<CommandLinkGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original: Option<HoverOriginalMarkup>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct HoverOriginalMarkup {
+    pub value: String,
+    pub links: Vec<HoverDocLink>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct HoverDocLink {
+    /// Offset of the link's start in `HoverOriginalMarkup::value`, in the negotiated position
+    /// encoding.
+    pub start: u32,
+    pub end: u32,
+    pub text: String,
+    pub target: Option<String>,
+    /// Whether the link was not resolved because the documentation has too many links.
+    pub skipped: bool,
+    /// Whether `target` points to the source of the linked item rather than its documentation.
+    pub source: bool,
+    /// Whether the link was removed from the hover's contents because it could not be resolved.
+    pub removed: bool,
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
@@ -526,6 +641,14 @@ pub struct ExternalDocsPair {
     pub local: Option<lsp_types::Url>,
 }
 
+pub enum DocsUrl {}
+
+impl Request for DocsUrl {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = String;
+    const METHOD: &'static str = "experimental/docsUrl";
+}
+
 pub enum OpenCargoToml {}
 
 impl Request for OpenCargoToml {
@@ -553,6 +676,7 @@ pub struct CodeLensResolveData {
 pub enum CodeLensResolveDataKind {
     Impls(lsp_types::request::GotoImplementationParams),
     References(lsp_types::TextDocumentPositionParams),
+    Docs(lsp_types::TextDocumentPositionParams),
 }
 
 pub fn negotiated_encoding(caps: &lsp_types::ClientCapabilities) -> PositionEncoding {
@@ -652,6 +776,13 @@ impl Request for OnTypeFormatting {
 pub struct CompletionResolveData {
     pub position: lsp_types::TextDocumentPositionParams,
     pub imports: Vec<CompletionImport>,
+    /// Label of the item whose documentation should be resolved with its links rewritten.
+    #[serde(default)]
+    pub doc_label: Option<String>,
+    /// The `CompletionItem::doc_owner_key` of that item, as a string since JSON numbers can't
+    /// hold all `u64`s.
+    #[serde(default)]
+    pub doc_owner: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
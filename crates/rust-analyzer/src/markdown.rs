@@ -43,10 +43,194 @@ fn code_line_ignored_by_rustdoc(line: &str) -> bool {
     trimmed == "#" || trimmed.starts_with("# ") || trimmed.starts_with("#\t")
 }
 
+/// Soft-wraps the paragraph lines of `src` that are longer than `width` characters at the last
+/// space that fits, for clients with narrow hover popups.
+///
+/// Lines are only ever split, never joined. Code blocks, tables, headings, reference definitions
+/// and HTML are left as they are, and lines are never split inside a code span, a link
+/// destination or an autolink, nor in front of a word that would start a new block, like `-`.
+/// The continuation lines of list items and block quotes keep their indentation and markers.
+pub(crate) fn wrap_docs(src: &str, width: usize) -> String {
+    let mut processed_lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in src.lines() {
+        let trimmed = line.trim_start();
+        if RUSTDOC_FENCES.into_iter().any(|fence| trimmed.starts_with(fence)) {
+            in_code_block ^= true;
+            processed_lines.push(line.to_owned());
+            continue;
+        }
+        let is_kept = in_code_block
+            || line.starts_with("    ")
+            || line.starts_with('\t')
+            || trimmed.starts_with(['#', '<'])
+            || line.contains('|')
+            || (trimmed.starts_with('[') && trimmed.contains("]:"));
+        if !is_kept && line.chars().count() > width {
+            wrap_line(line, width, &mut processed_lines);
+        } else {
+            processed_lines.push(line.to_owned());
+        }
+    }
+    processed_lines.join("\n")
+}
+
+fn wrap_line(line: &str, width: usize, lines: &mut Vec<String>) {
+    let (prefix, continuation) = line_prefixes(line);
+    let content = &line[prefix.len()..];
+    let breaks = break_points(content);
+    let mut start = 0;
+    let mut lead = prefix.to_owned();
+    loop {
+        let rest = &content[start..];
+        let available = width.saturating_sub(lead.chars().count());
+        let fits = |&&at: &&usize| content[start..at].chars().count() <= available;
+        let mut candidates = breaks.iter().filter(|&&at| at > start);
+        let at = match candidates.clone().take_while(fits).last() {
+            Some(at) => Some(at),
+            None => candidates.next(),
+        };
+        let Some(&at) = at.filter(|_| rest.chars().count() > available) else {
+            lines.push(format!("{lead}{rest}"));
+            return;
+        };
+        lines.push(format!("{lead}{}", content[start..at].trim_end()));
+        start = at + (content[at..].len() - content[at..].trim_start().len());
+        lead = continuation.clone();
+    }
+}
+
+/// Splits the block quote markers and list item marker off the start of `line`, returning them
+/// along with the prefix of the continuation lines the item or quote wraps onto.
+fn line_prefixes(line: &str) -> (&str, String) {
+    let mut rest = line;
+    let mut quotes = String::new();
+    loop {
+        let indented = rest.trim_start_matches(' ');
+        match indented.strip_prefix('>') {
+            Some(quoted) => {
+                let quoted = quoted.strip_prefix(' ').unwrap_or(quoted);
+                quotes.push_str(&rest[..rest.len() - quoted.len()]);
+                rest = quoted;
+            }
+            None => break,
+        }
+    }
+    let indented = rest.trim_start_matches(' ');
+    let digits = indented.len() - indented.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker = match indented.as_bytes().get(digits..digits + 2) {
+        Some([b'.' | b')', b' ']) if (1..10).contains(&digits) => digits + 2,
+        Some([b'-' | b'+' | b'*', b' ']) if digits == 0 => 2,
+        _ => 0,
+    };
+    let item = rest.len() - indented.len() + marker;
+    let prefix = &line[..line.len() - rest.len() + item];
+    (prefix, format!("{quotes}{}", " ".repeat(item)))
+}
+
+/// The byte offsets of the spaces of `content` it can be split at, see [`wrap_docs`].
+fn break_points(content: &str) -> Vec<usize> {
+    let mut res = Vec::new();
+    let mut code_span = None;
+    let mut destination_depth = 0;
+    let mut in_autolink = false;
+    let mut prev = None;
+    let mut chars = content.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '`' => {
+                let mut run = 1;
+                while chars.next_if(|&(_, c)| c == '`').is_some() {
+                    run += 1;
+                }
+                code_span = match code_span {
+                    None => Some(run),
+                    Some(open) if open == run => None,
+                    open => open,
+                };
+            }
+            _ if code_span.is_some() => (),
+            '\\' => {
+                chars.next();
+            }
+            '(' if prev == Some(']') || destination_depth > 0 => destination_depth += 1,
+            ')' if destination_depth > 0 => destination_depth -= 1,
+            '<' if destination_depth == 0 => in_autolink = true,
+            '>' if in_autolink => in_autolink = false,
+            ' ' if destination_depth == 0 && !in_autolink && prev != Some(' ') => {
+                let next = content[idx..].trim_start();
+                if !next.is_empty() && !starts_block(next) {
+                    res.push(idx);
+                }
+            }
+            _ => (),
+        }
+        prev = Some(c);
+    }
+    res
+}
+
+/// Whether a line starting with `text` would not continue the paragraph before it.
+fn starts_block(text: &str) -> bool {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let is_autolink = text
+        .strip_prefix('<')
+        .and_then(|it| it.split_once('>'))
+        .map_or(false, |(it, _)| it.contains([':', '@']));
+    text.starts_with(['#', '>', '-', '+', '*', '=', '|'])
+        || RUSTDOC_FENCES.into_iter().any(|fence| text.starts_with(fence))
+        || (text.starts_with('<') && !is_autolink)
+        || (digits > 0 && text[digits..].starts_with(['.', ')']))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_wrap_docs_wraps_paragraphs() {
+        let docs = "Connects to the gateway and waits for the first event.\n\nShort.";
+        assert_eq!(
+            wrap_docs(docs, 20),
+            "Connects to the\ngateway and waits\nfor the first event.\n\nShort."
+        );
+    }
+
+    #[test]
+    fn test_wrap_docs_does_not_split_links_and_code_spans() {
+        let docs = "See [the gateway docs](https://example.com/a%20b (title here)) and `a long code span` \
+                    or <https://example.com/some page> now.";
+        assert_eq!(
+            wrap_docs(docs, 10),
+            "See [the\ngateway\ndocs](https://example.com/a%20b (title here))\nand\n`a long code span`\nor\n<https://example.com/some page>\nnow."
+        );
+    }
+
+    #[test]
+    fn test_wrap_docs_keeps_blocks() {
+        let docs = "# A heading that is much too long\n\
+                    ```\nlet code = \"that is much too long\";\n```\n\
+                    | a table | that is much too long |\n\
+                    [label]: https://example.com/a long destination";
+        assert_eq!(wrap_docs(docs, 10), docs);
+    }
+
+    #[test]
+    fn test_wrap_docs_keeps_list_and_quote_markers() {
+        let docs = "- an item of a list\n12. a numbered item\n> > a quoted line of text";
+        assert_eq!(
+            wrap_docs(docs, 12),
+            "- an item of\n  a list\n12. a\n    numbered\n    item\n> > a quoted\n> > line of\n> > text"
+        );
+    }
+
+    #[test]
+    fn test_wrap_docs_does_not_start_blocks() {
+        let docs = "Has - dashes, # hashes and 1. numbers";
+        assert_eq!(wrap_docs(docs, 5), "Has -\ndashes, #\nhashes\nand 1.\nnumbers");
+    }
+
     #[test]
     fn test_format_docs_adds_rust() {
         let comment = "```\nfn some_rust() {}\n```";
@@ -83,6 +83,14 @@ impl GlobalState {
                 &self.config.lru_query_capacities().cloned().unwrap_or_default(),
             );
         }
+        if self.config.doc_link_resolution_budget() != old_config.doc_link_resolution_budget() {
+            self.analysis_host
+                .update_doc_link_resolution_budget(self.config.doc_link_resolution_budget());
+        }
+        if self.config.doc_link_alias_resolution() != old_config.doc_link_alias_resolution() {
+            self.analysis_host
+                .update_doc_link_alias_resolution(self.config.doc_link_alias_resolution());
+        }
         if self.config.linked_projects() != old_config.linked_projects() {
             self.fetch_workspaces_queue.request_op("linked projects changed".to_string(), ())
         } else if self.config.flycheck() != old_config.flycheck() {
@@ -7,11 +7,11 @@ use std::{
 
 use ide::{
     Annotation, AnnotationKind, Assist, AssistKind, Cancellable, CompletionItem,
-    CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
-    Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayKind, Markup, NavigationTarget, ReferenceCategory,
-    RenameError, Runnable, Severity, SignatureHelp, SourceChange, StructureNodeKind, SymbolKind,
-    TextEdit, TextRange, TextSize,
+    CompletionItemKind, CompletionRelevance, DocLinksMode, Documentation, FileId, FileRange,
+    FileSystemEdit, Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel,
+    InlayHint, InlayHintLabel, InlayHintLabelPart, InlayKind, Markup, NavigationTarget,
+    OriginalMarkup, ReferenceCategory, RenameError, Runnable, Severity, SignatureHelp,
+    SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
@@ -87,7 +87,7 @@ pub(crate) fn document_highlight_kind(
     match category {
         ReferenceCategory::Read => Some(lsp_types::DocumentHighlightKind::READ),
         ReferenceCategory::Write => Some(lsp_types::DocumentHighlightKind::WRITE),
-        ReferenceCategory::Import => None,
+        ReferenceCategory::Import | ReferenceCategory::DocLink => None,
     }
 }
 
@@ -238,6 +238,7 @@ fn completion_item(
     let insert_replace_support = config.insert_replace_support().then_some(tdpp.position);
     let ref_match = item.ref_match();
     let lookup = item.lookup().to_string();
+    let doc_owner = item.doc_owner_key();
 
     let mut additional_text_edits = Vec::new();
 
@@ -301,25 +302,38 @@ fn completion_item(
 
     set_score(&mut lsp_item, max_relevance, item.relevance);
 
+    let mut imports = Vec::new();
     if config.completion().enable_imports_on_the_fly {
-        if !item.import_to_add.is_empty() {
-            let imports: Vec<_> = item
-                .import_to_add
-                .into_iter()
-                .filter_map(|import_edit| {
-                    let import_path = &import_edit.import_path;
-                    let import_name = import_path.segments().last()?;
-                    Some(lsp_ext::CompletionImport {
-                        full_import_path: import_path.to_string(),
-                        imported_name: import_name.to_string(),
-                    })
+        imports = item
+            .import_to_add
+            .into_iter()
+            .filter_map(|import_edit| {
+                let import_path = &import_edit.import_path;
+                let import_name = import_path.segments().last()?;
+                Some(lsp_ext::CompletionImport {
+                    full_import_path: import_path.to_string(),
+                    imported_name: import_name.to_string(),
                 })
-                .collect();
-            if !imports.is_empty() {
-                let data = lsp_ext::CompletionResolveData { position: tdpp.clone(), imports };
-                lsp_item.data = Some(to_value(data).unwrap());
-            }
-        }
+            })
+            .collect();
+    }
+    // Processing the links in the documentation is too slow to do for every item of the list, so
+    // it is deferred to `completionItem/resolve` when the client supports resolving documentation.
+    let doc_owner = doc_owner.filter(|_| {
+        lsp_item.documentation.is_some()
+            && config.doc_links_mode() != DocLinksMode::Raw
+            && config.completion_resolve_docs()
+    });
+    let doc_label = doc_owner.map(|_| item.label.to_string());
+    let doc_owner = doc_owner.map(|it| it.to_string());
+    if !imports.is_empty() || doc_label.is_some() {
+        let data = lsp_ext::CompletionResolveData {
+            position: tdpp.clone(),
+            imports,
+            doc_label,
+            doc_owner,
+        };
+        lsp_item.data = Some(to_value(data).unwrap());
     }
 
     if let Some((label, indel, relevance)) = ref_match {
@@ -358,13 +372,21 @@ pub(crate) fn signature_help(
     config: CallInfoConfig,
     label_offsets: bool,
 ) -> lsp_types::SignatureHelp {
+    let parameter_doc = |idx: usize| {
+        let doc = call_info.parameter_doc(idx).filter(|_| config.docs)?;
+        Some(lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: doc.to_owned(),
+        }))
+    };
     let (label, parameters) = match (config.params_only, label_offsets) {
         (concise, false) => {
             let params = call_info
                 .parameter_labels()
-                .map(|label| lsp_types::ParameterInformation {
+                .enumerate()
+                .map(|(idx, label)| lsp_types::ParameterInformation {
                     label: lsp_types::ParameterLabel::Simple(label.to_string()),
-                    documentation: None,
+                    documentation: parameter_doc(idx),
                 })
                 .collect::<Vec<_>>();
             let label =
@@ -380,9 +402,10 @@ pub(crate) fn signature_help(
                     let end = call_info.signature[..it.end().into()].chars().count() as u32;
                     [start, end]
                 })
-                .map(|label_offsets| lsp_types::ParameterInformation {
+                .enumerate()
+                .map(|(idx, label_offsets)| lsp_types::ParameterInformation {
                     label: lsp_types::ParameterLabel::LabelOffsets(label_offsets),
-                    documentation: None,
+                    documentation: parameter_doc(idx),
                 })
                 .collect::<Vec<_>>();
             (call_info.signature, params)
@@ -391,7 +414,7 @@ pub(crate) fn signature_help(
             let mut params = Vec::new();
             let mut label = String::new();
             let mut first = true;
-            for param in call_info.parameter_labels() {
+            for (idx, param) in call_info.parameter_labels().enumerate() {
                 if !first {
                     label.push_str(", ");
                 }
@@ -401,7 +424,7 @@ pub(crate) fn signature_help(
                 let end = label.chars().count() as u32;
                 params.push(lsp_types::ParameterInformation {
                     label: lsp_types::ParameterLabel::LabelOffsets([start, end]),
-                    documentation: None,
+                    documentation: parameter_doc(idx),
                 });
             }
 
@@ -1101,6 +1124,11 @@ pub(crate) fn code_action(
     if assist.trigger_signature_help && snap.config.client_commands().trigger_parameter_hints {
         res.command = Some(command::trigger_parameter_hints());
     }
+    if let Some(text) = &assist.copy_to_clipboard {
+        if snap.config.client_commands().copy_to_clipboard {
+            res.command = Some(command::copy_to_clipboard(text));
+        }
+    }
 
     match (assist.source_change, resolve_data) {
         (Some(it), _) => res.edit = Some(snippet_workspace_edit(snap, it)?),
@@ -1287,6 +1315,35 @@ pub(crate) fn code_lens(
                 })(),
             })
         }
+        AnnotationKind::HasDocs { pos, data } => {
+            if !client_commands_config.open_docs_url {
+                return Ok(());
+            }
+            let line_index = snap.file_line_index(pos.file_id)?;
+            let annotation_range = range(&line_index, annotation.range);
+            let url = url(snap, pos.file_id);
+
+            let id = lsp_types::TextDocumentIdentifier { uri: url.clone() };
+
+            // resolve at the item's name, the lens itself may sit above the whole item
+            let doc_pos =
+                lsp_types::TextDocumentPositionParams::new(id, position(&line_index, pos.offset));
+
+            acc.push(lsp_types::CodeLens {
+                range: annotation_range,
+                command: data.as_deref().map(command::open_docs_url),
+                data: (|| {
+                    let version = snap.url_file_version(&url)?;
+                    Some(
+                        to_value(lsp_ext::CodeLensResolveData {
+                            version,
+                            kind: lsp_ext::CodeLensResolveDataKind::Docs(doc_pos),
+                        })
+                        .unwrap(),
+                    )
+                })(),
+            })
+        }
     }
     Ok(())
 }
@@ -1367,6 +1424,30 @@ pub(crate) mod command {
         })
     }
 
+    pub(crate) fn open_docs_url(url: &str) -> lsp_types::Command {
+        lsp_types::Command {
+            title: "Open Docs".into(),
+            command: "rust-analyzer.openDocsUrl".into(),
+            arguments: Some(vec![to_value(url).unwrap()]),
+        }
+    }
+
+    pub(crate) fn copy_to_clipboard(text: &str) -> lsp_types::Command {
+        lsp_types::Command {
+            title: "Copy to Clipboard".into(),
+            command: "rust-analyzer.copyToClipboard".into(),
+            arguments: Some(vec![to_value(text).unwrap()]),
+        }
+    }
+
+    pub(crate) fn open_source_url(url: &str) -> lsp_types::Command {
+        lsp_types::Command {
+            title: "Open Source".into(),
+            command: "rust-analyzer.openDocsUrl".into(),
+            arguments: Some(vec![to_value(url).unwrap()]),
+        }
+    }
+
     pub(crate) fn trigger_parameter_hints() -> lsp_types::Command {
         lsp_types::Command {
             title: "triggerParameterHints".into(),
@@ -1395,15 +1476,98 @@ pub(crate) fn reference_title(count: usize) -> String {
 pub(crate) fn markup_content(
     markup: Markup,
     kind: ide::HoverDocFormat,
+    wrap_column: Option<usize>,
 ) -> lsp_types::MarkupContent {
     let kind = match kind {
         ide::HoverDocFormat::Markdown => lsp_types::MarkupKind::Markdown,
         ide::HoverDocFormat::PlainText => lsp_types::MarkupKind::PlainText,
     };
     let value = crate::markdown::format_docs(markup.as_str());
+    let value = match wrap_column {
+        Some(width) => crate::markdown::wrap_docs(&value, width),
+        None => value,
+    };
     lsp_types::MarkupContent { kind, value }
 }
 
+pub(crate) fn hover_original_markup(
+    original: OriginalMarkup,
+    encoding: PositionEncoding,
+) -> lsp_ext::HoverOriginalMarkup {
+    let value = original.markup.as_str();
+    let offset = |offset: TextSize| {
+        let prefix = &value[..usize::from(offset)];
+        match encoding {
+            PositionEncoding::Utf8 => prefix.len() as u32,
+            PositionEncoding::Wide(enc) => enc.measure(prefix) as u32,
+        }
+    };
+    let links = original
+        .links
+        .into_iter()
+        .map(|link| lsp_ext::HoverDocLink {
+            start: offset(link.range.start()),
+            end: offset(link.range.end()),
+            text: link.text,
+            target: link.url.map(|it| it.to_string()),
+            skipped: link.error == Some(ide::ResolutionError::BudgetExceeded),
+            source: link.source_link,
+            removed: link.removed,
+        })
+        .collect();
+    lsp_ext::HoverOriginalMarkup { value: value.to_owned(), links }
+}
+
+pub(crate) fn item_doc_links(
+    line_index: &LineIndex,
+    item: ide::ItemDocLinks,
+) -> lsp_ext::ItemDocLinks {
+    let links = item
+        .links
+        .into_iter()
+        .map(|(range, link)| lsp_ext::DocLink {
+            range: range.map(|it| self::range(line_index, it)),
+            target_kind: match link.target_kind {
+                ide::DocLinkTargetKind::Url => lsp_ext::DocLinkTargetKind::Url,
+                ide::DocLinkTargetKind::Page => lsp_ext::DocLinkTargetKind::Page,
+                ide::DocLinkTargetKind::Intra => lsp_ext::DocLinkTargetKind::Intra,
+            },
+            url: link.url.map(|it| it.to_string()),
+            ambiguous: link.ambiguous,
+            impl_ambiguous: link.impl_ambiguous,
+            candidates: link
+                .candidates
+                .into_iter()
+                .map(|it| lsp_ext::DocLinkCandidate {
+                    path: it.path,
+                    url: it.url.map(|it| it.to_string()),
+                })
+                .collect(),
+            error: link.error.map(doc_link_error),
+        })
+        .collect();
+    lsp_ext::ItemDocLinks { path: item.path, range: range(line_index, item.range), links }
+}
+
+fn doc_link_error(error: ide::ResolutionError) -> lsp_ext::DocLinkError {
+    let kind = match error {
+        ide::ResolutionError::UnparsablePath => lsp_ext::DocLinkErrorKind::UnparsablePath,
+        ide::ResolutionError::UnresolvedSegment { .. } => {
+            lsp_ext::DocLinkErrorKind::UnresolvedSegment
+        }
+        ide::ResolutionError::WrongNamespace { .. } => lsp_ext::DocLinkErrorKind::WrongNamespace,
+        ide::ResolutionError::WrongFunctionKind { .. } => {
+            lsp_ext::DocLinkErrorKind::WrongFunctionKind
+        }
+        ide::ResolutionError::NoDocBaseUrl { .. } => lsp_ext::DocLinkErrorKind::NoDocBaseUrl,
+        ide::ResolutionError::UnsupportedDefinition => {
+            lsp_ext::DocLinkErrorKind::UnsupportedDefinition
+        }
+        ide::ResolutionError::BudgetExceeded => lsp_ext::DocLinkErrorKind::BudgetExceeded,
+    };
+    lsp_ext::DocLinkError { kind, message: error.to_string() }
+}
+
 pub(crate) fn rename_error(err: RenameError) -> crate::LspError {
     // This is wrong, but we don't have a better alternative I suppose?
     // https://github.com/microsoft/language-server-protocol/issues/1341
@@ -1413,6 +1577,7 @@ pub(crate) fn rename_error(err: RenameError) -> crate::LspError {
 #[cfg(test)]
 mod tests {
     use ide::Analysis;
+    use ide_db::line_index::WideEncoding;
     use triomphe::Arc;
 
     use super::*;
@@ -1453,6 +1618,36 @@ fn main() {
         }
     }
 
+    #[test]
+    fn hover_original_markup_link_offsets() {
+        let markup = "Größe: [`Foo`]";
+        let original = || OriginalMarkup {
+            markup: markup.to_owned().into(),
+            links: vec![ide::ResolvedDocLink {
+                range: TextRange::new(TextSize::of("Größe: "), TextSize::of(markup)),
+                url: Some("https://example.com/".into()),
+                target_kind: ide::DocLinkTargetKind::Intra,
+                definition: None,
+                text: "Foo".to_owned(),
+                ambiguous: false,
+                impl_ambiguous: false,
+                candidates: Vec::new(),
+                error: None,
+                inactive_cfg: None,
+                doc_alias: false,
+                reference_definition: false,
+                source_link: false,
+                removed: false,
+            }],
+        };
+
+        let utf8 = hover_original_markup(original(), PositionEncoding::Utf8);
+        assert_eq!((utf8.links[0].start, utf8.links[0].end), (9, 16));
+        let utf16 = hover_original_markup(original(), PositionEncoding::Wide(WideEncoding::Utf16));
+        assert_eq!((utf16.links[0].start, utf16.links[0].end), (7, 14));
+        assert_eq!(utf16.value, markup);
+    }
+
     // `Url` is not able to parse windows paths on unix machines.
     #[test]
     #[cfg(target_os = "windows")]
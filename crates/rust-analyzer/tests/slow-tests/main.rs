@@ -29,7 +29,7 @@ use lsp_types::{
     PartialResultParams, Position, Range, RenameFilesParams, TextDocumentItem,
     TextDocumentPositionParams, WorkDoneProgressParams,
 };
-use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
+use rust_analyzer::lsp_ext::{DocLinks, DocLinksParams, OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
 use test_utils::skip_slow_tests;
 
@@ -1116,3 +1116,83 @@ version = "0.0.0"
 
     server.request::<WorkspaceSymbolRequest>(Default::default(), json!([]));
 }
+
+#[test]
+fn doc_links_of_file() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+/// Connects through a [`Gateway`], see [run], [`Missing`] and the
+/// [guide](https://example.com/guide).
+pub struct Shard;
+pub struct Gateway;
+pub fn run() {}
+pub mod run {}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    let range = |(line, character): (u32, u32), (end_line, end_character): (u32, u32)| {
+        json!({
+            "start": { "line": line, "character": character },
+            "end": { "line": end_line, "character": end_character },
+        })
+    };
+    server.request::<DocLinks>(
+        DocLinksParams { text_document: server.doc_id("src/lib.rs") },
+        json!([{
+            "path": "foo::Shard",
+            "range": range((0, 0), (1, 39)),
+            "links": [
+                {
+                    "range": range((0, 23), (0, 34)),
+                    "targetKind": "intra",
+                    "url": "https://docs.rs/foo/0.0.0/foo/struct.Gateway.html",
+                    "ambiguous": false,
+                    "implAmbiguous": false,
+                    "candidates": [],
+                    "error": null
+                },
+                {
+                    "range": range((0, 40), (0, 45)),
+                    "targetKind": "intra",
+                    "url": "https://docs.rs/foo/0.0.0/foo/run/index.html",
+                    "ambiguous": true,
+                    "implAmbiguous": false,
+                    "candidates": [],
+                    "error": null
+                },
+                {
+                    "range": range((0, 47), (0, 58)),
+                    "targetKind": "intra",
+                    "url": null,
+                    "ambiguous": false,
+                    "implAmbiguous": false,
+                    "candidates": [],
+                    "error": {
+                        "kind": "unresolvedSegment",
+                        "message": "segment 0 of the path, `Missing`, names nothing"
+                    }
+                },
+                {
+                    "range": range((1, 4), (1, 38)),
+                    "targetKind": "url",
+                    "url": "https://example.com/guide",
+                    "ambiguous": false,
+                    "implAmbiguous": false,
+                    "candidates": [],
+                    "error": null
+                }
+            ]
+        }]),
+    );
+}
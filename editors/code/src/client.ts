@@ -382,6 +382,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
             serverStatusNotification: true,
             colorDiagnosticOutput: true,
             openServerLogs: true,
+            sourceDocLinks: true,
             commands: {
                 commands: [
                     "rust-analyzer.runSingle",
@@ -389,6 +390,8 @@ class ExperimentalFeatures implements lc.StaticFeature {
                     "rust-analyzer.showReferences",
                     "rust-analyzer.gotoLocation",
                     "editor.action.triggerParameterHints",
+                    "rust-analyzer.openDocsUrl",
+                    "rust-analyzer.copyToClipboard",
                 ],
             },
             ...capabilities.experimental,
@@ -953,6 +953,41 @@ export function openDocs(ctx: CtxInit): Cmd {
     };
 }
 
+export function copyDocsUrl(ctx: CtxInit): Cmd {
+    return async () => {
+        const editor = vscode.window.activeTextEditor;
+        if (!editor) {
+            return;
+        }
+        const client = ctx.client;
+
+        const position = editor.selection.active;
+        const textDocument = { uri: editor.document.uri.toString() };
+
+        try {
+            const url = await client.sendRequest(ra.docsUrl, { position, textDocument });
+            await vscode.env.clipboard.writeText(url);
+            await vscode.window.showInformationMessage(`Copied ${url} to the clipboard.`);
+        } catch (e) {
+            const message = e instanceof Error ? e.message : String(e);
+            await vscode.window.showErrorMessage(`No documentation URL: ${message}`);
+        }
+    };
+}
+
+export function openDocsUrl(_: CtxInit): Cmd {
+    return async (url: string) => {
+        await vscode.commands.executeCommand("vscode.open", vscode.Uri.parse(url));
+    };
+}
+
+export function copyToClipboard(_: CtxInit): Cmd {
+    return async (text: string) => {
+        await vscode.env.clipboard.writeText(text);
+        await vscode.window.showInformationMessage(`Copied ${text} to the clipboard.`);
+    };
+}
+
 export function cancelFlycheck(ctx: CtxInit): Cmd {
     return async () => {
         await ctx.client.sendNotification(ra.cancelFlycheck);
@@ -135,6 +135,9 @@ export const onEnter = new lc.RequestType<lc.TextDocumentPositionParams, lc.Text
 export const openCargoToml = new lc.RequestType<OpenCargoTomlParams, lc.Location, void>(
     "experimental/openCargoToml"
 );
+export const docsUrl = new lc.RequestType<lc.TextDocumentPositionParams, string, void>(
+    "experimental/docsUrl"
+);
 export const openDocs = new lc.RequestType<lc.TextDocumentPositionParams, string | void, void>(
     "experimental/externalDocs"
 );
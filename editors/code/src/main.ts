@@ -170,6 +170,7 @@ function createCommands(): Record<string, CommandFactory> {
         debug: { enabled: commands.debug },
         newDebugConfig: { enabled: commands.newDebugConfig },
         openDocs: { enabled: commands.openDocs },
+        copyDocsUrl: { enabled: commands.copyDocsUrl },
         openCargoToml: { enabled: commands.openCargoToml },
         peekTests: { enabled: commands.peekTests },
         moveItemUp: { enabled: commands.moveItemUp },
@@ -185,6 +186,8 @@ function createCommands(): Record<string, CommandFactory> {
         debugSingle: { enabled: commands.debugSingle },
         gotoLocation: { enabled: commands.gotoLocation },
         linkToCommand: { enabled: commands.linkToCommand },
+        openDocsUrl: { enabled: commands.openDocsUrl },
+        copyToClipboard: { enabled: commands.copyToClipboard },
         resolveCodeAction: { enabled: commands.resolveCodeAction },
         runSingle: { enabled: commands.runSingle },
         showReferences: { enabled: commands.showReferences },